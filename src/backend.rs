@@ -0,0 +1,919 @@
+//! Servers chats are streamed from: Ollama, or any server speaking the OpenAI chat
+//! completions API, like vLLM, llama.cpp or LM Studio.
+
+use crate::{
+    chat::TokenUsage,
+    i18n::{tr, tr_args},
+    widgets::{ModelDetails, ModelSettings},
+};
+use ollama_rs::{
+    generation::chat::ChatMessage,
+    models::{LocalModel, ModelInfo},
+};
+use std::time::{Duration, Instant};
+
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// API spoken by the server chats are sent to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum BackendKind {
+    #[default]
+    Ollama,
+    /// `/v1/chat/completions` and `/v1/models`
+    OpenAi,
+}
+
+impl BackendKind {
+    pub const ALL: [Self; 2] = [Self::Ollama, Self::OpenAi];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Ollama => "Ollama",
+            Self::OpenAi => "OpenAI-compatible",
+        }
+    }
+}
+
+/// Connection to an OpenAI-compatible server.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct OpenAiSettings {
+    /// API root, usually ending in `/v1`
+    pub base_url: String,
+    /// Sent as a bearer token, if not empty
+    pub api_key: String,
+}
+
+impl Default for OpenAiSettings {
+    fn default() -> Self {
+        Self {
+            base_url: "http://127.0.0.1:8000/v1".to_owned(),
+            api_key: String::new(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct OpenAiClient {
+    base_url: String,
+    api_key: String,
+    client: reqwest::Client,
+}
+
+impl OpenAiClient {
+    pub fn new(settings: &OpenAiSettings, client: reqwest::Client) -> Self {
+        Self {
+            base_url: settings.base_url.trim().trim_end_matches('/').to_owned(),
+            api_key: settings.api_key.trim().to_owned(),
+            client,
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self
+            .client
+            .request(method, format!("{}/{path}", self.base_url));
+        if self.api_key.is_empty() {
+            request
+        } else {
+            request.bearer_auth(&self.api_key)
+        }
+    }
+
+    async fn list_models(&self) -> Result<Vec<LocalModel>, BoxError> {
+        #[derive(serde::Deserialize)]
+        struct Models {
+            data: Vec<Model>,
+        }
+        #[derive(serde::Deserialize)]
+        struct Model {
+            id: String,
+            #[serde(default)]
+            created: i64,
+        }
+
+        let response = send(self.request(reqwest::Method::GET, "models")).await?;
+        let models: Models = serde_json::from_str(&response.text().await?)?;
+        // the API doesn't report sizes
+        Ok(models
+            .data
+            .into_iter()
+            .map(|model| LocalModel {
+                name: model.id,
+                modified_at: chrono::DateTime::from_timestamp(model.created, 0)
+                    .map(|t| t.to_rfc3339())
+                    .unwrap_or_default(),
+                size: 0,
+            })
+            .collect())
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, BoxError> {
+        self.stream("chat/completions", Self::chat_body(request))
+            .await
+    }
+
+    async fn completion_stream(&self, request: CompletionRequest) -> Result<ChatStream, BoxError> {
+        let mut body = serde_json::json!({
+            "model": request.model,
+            "prompt": request.prompt,
+            "stream": true,
+            "stream_options": { "include_usage": true },
+        });
+        Self::apply_settings(&mut body, request.settings);
+        self.stream("completions", body).await
+    }
+
+    /// Post `body` to `path` and read the streamed events of the response.
+    async fn stream(&self, path: &str, body: serde_json::Value) -> Result<ChatStream, BoxError> {
+        let response = send(
+            self.request(reqwest::Method::POST, path)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.to_string()),
+        )
+        .await?;
+        Ok(ChatStream {
+            body: redact_images(body),
+            reader: ReplyReader::OpenAi(SseReader {
+                lines: LineReader::new(response),
+                first_token: None,
+                done: false,
+            }),
+        })
+    }
+
+    /// Chat request body with the settings the API has an equivalent for.
+    fn chat_body(request: ChatRequest) -> serde_json::Value {
+        use ollama_rs::generation::chat::MessageRole;
+        use serde_json::json;
+
+        let messages: Vec<serde_json::Value> = request
+            .messages
+            .into_iter()
+            .map(|message| {
+                let role = match message.role {
+                    MessageRole::User => "user",
+                    MessageRole::Assistant => "assistant",
+                    _ => "system",
+                };
+                let images = message.images.unwrap_or_default();
+                if images.is_empty() {
+                    return json!({ "role": role, "content": message.content });
+                }
+                // images are base64 PNG or JPEG, the data URL needs a type but servers
+                // sniff the actual format
+                let mut content = vec![json!({ "type": "text", "text": message.content })];
+                content.extend(images.iter().map(|image| {
+                    json!({
+                        "type": "image_url",
+                        "image_url": { "url": format!("data:image/png;base64,{}", image.to_base64()) },
+                    })
+                }));
+                json!({ "role": role, "content": content })
+            })
+            .collect();
+
+        let mut body = json!({
+            "model": request.model,
+            "messages": messages,
+            "stream": true,
+            "stream_options": { "include_usage": true },
+        });
+        Self::apply_settings(&mut body, request.settings);
+        body
+    }
+
+    /// Add the settings the API has an equivalent for to a request `body`.
+    fn apply_settings(body: &mut serde_json::Value, settings: ModelSettings) {
+        use serde_json::json;
+
+        let mut set = |key: &str, value: serde_json::Value| {
+            body[key] = value;
+        };
+        if let Some(temperature) = settings.temperature {
+            set("temperature", json!(temperature));
+        }
+        if let Some(top_p) = settings.top_p {
+            set("top_p", json!(top_p));
+        }
+        if let Some(seed) = settings.seed {
+            set("seed", json!(seed));
+        }
+        if let Some(stop) = settings.stop {
+            let stop: Vec<String> = stop.into_iter().filter(|pat| !pat.is_empty()).collect();
+            if !stop.is_empty() {
+                set("stop", json!(stop));
+            }
+        }
+        // -1 and -2 mean no limit
+        if let Some(num_predict) = settings.num_predict.filter(|n| *n > 0) {
+            set("max_tokens", json!(num_predict));
+        }
+        if let Some(presence_penalty) = settings.presence_penalty {
+            set("presence_penalty", json!(presence_penalty));
+        }
+        if let Some(frequency_penalty) = settings.frequency_penalty {
+            set("frequency_penalty", json!(frequency_penalty));
+        }
+        if settings.json_mode {
+            set("response_format", json!({ "type": "json_object" }));
+        }
+    }
+}
+
+/// Client of the Ollama server, for chats as well as model management and embeddings.
+/// The requests are built here instead of with ollama-rs, whose `GenerationOptions` can't
+/// carry all settings, e.g. `min_p` or `num_batch`, and which can't send the credentials
+/// and proxy of the settings. Its types are still used for the responses.
+#[derive(Clone)]
+pub struct OllamaClient {
+    endpoint: url::Url,
+    client: reqwest::Client,
+}
+
+impl Default for OllamaClient {
+    fn default() -> Self {
+        Self::new(
+            url::Url::parse("http://127.0.0.1:11434").unwrap(),
+            reqwest::Client::new(),
+        )
+    }
+}
+
+impl OllamaClient {
+    pub fn new(endpoint: url::Url, client: reqwest::Client) -> Self {
+        Self { endpoint, client }
+    }
+
+    /// Post `body` to `path`, failing on error statuses.
+    async fn post(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+    ) -> Result<reqwest::Response, BoxError> {
+        send(
+            self.client
+                .post(self.endpoint.join(path)?)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.to_string()),
+        )
+        .await
+    }
+
+    /// Modelfile, parameters, template and license of the model `name`.
+    pub async fn model_info(&self, name: &str) -> Result<ModelInfo, BoxError> {
+        let response = self
+            .post("api/show", &serde_json::json!({ "name": name }))
+            .await?;
+        Ok(serde_json::from_str(&response.text().await?)?)
+    }
+
+    /// Parameter size, quantization and family of the model `name`.
+    pub async fn model_details(&self, name: &str) -> Result<ModelDetails, BoxError> {
+        #[derive(serde::Deserialize)]
+        struct ShowResponse {
+            #[serde(default)]
+            details: ModelDetails,
+        }
+
+        let response = self
+            .post("api/show", &serde_json::json!({ "name": name }))
+            .await?;
+        let show: ShowResponse = serde_json::from_str(&response.text().await?)?;
+        Ok(show.details)
+    }
+
+    pub async fn copy_model(&self, source: &str, destination: &str) -> Result<(), BoxError> {
+        let body = serde_json::json!({ "source": source, "destination": destination });
+        self.post("api/copy", &body).await?;
+        Ok(())
+    }
+
+    pub async fn delete_model(&self, name: &str) -> Result<(), BoxError> {
+        send(
+            self.client
+                .delete(self.endpoint.join("api/delete")?)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(serde_json::json!({ "name": name }).to_string()),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Download the model `name` from the Ollama library, streaming the progress.
+    pub async fn pull_model(&self, name: &str) -> Result<StatusStream, BoxError> {
+        let body = serde_json::json!({ "name": name, "stream": true });
+        let response = self.post("api/pull", &body).await?;
+        Ok(StatusStream {
+            lines: LineReader::new(response),
+            done: false,
+        })
+    }
+
+    /// Create the model `name` from a `modelfile`, streaming the progress.
+    pub async fn create_model(
+        &self,
+        name: &str,
+        modelfile: &str,
+    ) -> Result<StatusStream, BoxError> {
+        let body = serde_json::json!({ "name": name, "modelfile": modelfile, "stream": true });
+        let response = self.post("api/create", &body).await?;
+        Ok(StatusStream {
+            lines: LineReader::new(response),
+            done: false,
+        })
+    }
+
+    /// Embedding of `prompt` by the embedding model `model`.
+    pub async fn embeddings(&self, model: &str, prompt: &str) -> Result<Vec<f64>, BoxError> {
+        #[derive(serde::Deserialize)]
+        struct Embeddings {
+            embedding: Vec<f64>,
+        }
+
+        let body = serde_json::json!({ "model": model, "prompt": prompt });
+        let response = self.post("api/embeddings", &body).await?;
+        let embeddings: Embeddings = serde_json::from_str(&response.text().await?)?;
+        Ok(embeddings.embedding)
+    }
+
+    /// Models loaded into memory, `None` if the server is too old to tell.
+    pub async fn running_models(&self) -> Result<Option<Vec<RunningModel>>, BoxError> {
+        #[derive(serde::Deserialize)]
+        struct PsResponse {
+            models: Vec<RunningModel>,
+        }
+
+        let response = self
+            .client
+            .get(self.endpoint.join("api/ps")?)
+            .send()
+            .await?;
+        // servers older than 0.1.38 don't have this endpoint
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = response.error_for_status()?;
+        let ps: PsResponse = serde_json::from_str(&response.text().await?)?;
+        Ok(Some(ps.models))
+    }
+
+    /// Unload the model `name` from memory right away.
+    pub async fn unload_model(&self, name: &str) -> Result<(), BoxError> {
+        let body = serde_json::json!({ "model": name, "keep_alive": 0 });
+        self.post("api/generate", &body).await?;
+        Ok(())
+    }
+
+    async fn list_models(&self) -> Result<Vec<LocalModel>, BoxError> {
+        #[derive(serde::Deserialize)]
+        struct Tags {
+            models: Vec<LocalModel>,
+        }
+
+        let response = send(self.client.get(self.endpoint.join("api/tags")?)).await?;
+        let tags: Tags = serde_json::from_str(&response.text().await?)?;
+        Ok(tags.models)
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, BoxError> {
+        let mut body = serde_json::json!({
+            "model": request.model,
+            "messages": request.messages,
+        });
+        if let Some(template) = request.template {
+            body["template"] = template.into();
+        }
+        self.stream("api/chat", body, request.settings).await
+    }
+
+    async fn completion_stream(&self, request: CompletionRequest) -> Result<ChatStream, BoxError> {
+        // a template of just the prompt sends it verbatim, leaving out the system prompt of
+        // the modelfile too
+        let body = serde_json::json!({
+            "model": request.model,
+            "prompt": request.prompt,
+            "template": "{{ .Prompt }}",
+        });
+        self.stream("api/generate", body, request.settings).await
+    }
+
+    /// Post `body` with the `settings` to `path` and read the streamed lines of the response.
+    async fn stream(
+        &self,
+        path: &str,
+        mut body: serde_json::Value,
+        settings: ModelSettings,
+    ) -> Result<ChatStream, BoxError> {
+        body["stream"] = true.into();
+        body["options"] = settings.ollama_options();
+        if settings.json_mode {
+            body["format"] = "json".into();
+        }
+        if let Some(keep_alive) = settings.keep_alive() {
+            body["keep_alive"] = serde_json::to_value(keep_alive)?;
+        }
+        let response = self.post(path, &body).await?;
+        Ok(ChatStream {
+            body: redact_images(body),
+            reader: ReplyReader::Ollama {
+                lines: LineReader::new(response),
+                done: false,
+            },
+        })
+    }
+
+    /// Parse one line of a streamed `/api/chat` or `/api/generate` response.
+    fn parse_line(line: &str) -> Result<ChatChunk, BoxError> {
+        #[derive(serde::Deserialize)]
+        struct Line {
+            /// Set by `/api/chat`
+            message: Option<Message>,
+            /// Set by `/api/generate`
+            #[serde(default)]
+            response: String,
+            error: Option<String>,
+            #[serde(default)]
+            done: bool,
+            #[serde(default)]
+            total_duration: u64,
+            #[serde(default)]
+            prompt_eval_count: u64,
+            #[serde(default)]
+            prompt_eval_duration: u64,
+            #[serde(default)]
+            eval_count: u64,
+            #[serde(default)]
+            eval_duration: u64,
+        }
+        #[derive(serde::Deserialize)]
+        struct Message {
+            content: String,
+        }
+
+        let line: Line = serde_json::from_str(line)?;
+        if let Some(error) = line.error {
+            return Err(error.into());
+        }
+        let mut chunk = ChatChunk {
+            content: line.message.map_or(line.response, |msg| msg.content),
+            ..Default::default()
+        };
+        if line.done {
+            chunk.usage = Some(TokenUsage {
+                prompt_tokens: line.prompt_eval_count,
+                reply_tokens: line.eval_count,
+            });
+            chunk.timings = Some(ServerTimings {
+                total: Duration::from_nanos(line.total_duration),
+                prompt_eval: Duration::from_nanos(line.prompt_eval_duration),
+                eval: Duration::from_nanos(line.eval_duration),
+            });
+            // eval_duration is in nanoseconds
+            if line.eval_duration > 0 {
+                chunk.tokens_per_second =
+                    Some(line.eval_count as f64 / (line.eval_duration as f64 / 1e9));
+            }
+        }
+        Ok(chunk)
+    }
+}
+
+/// A model loaded into memory, as reported by `/api/ps`
+#[derive(Clone, serde::Deserialize)]
+pub struct RunningModel {
+    pub name: String,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub size_vram: u64,
+    #[serde(default)]
+    pub expires_at: String,
+}
+
+impl RunningModel {
+    /// "in 4m 12s", or "never" for models kept loaded forever
+    pub fn expires_in(&self) -> String {
+        let Ok(expires_at) = chrono::DateTime::parse_from_rfc3339(&self.expires_at) else {
+            return tr("common.unknown").to_owned();
+        };
+        let left = expires_at.signed_duration_since(chrono::Utc::now());
+        if left.num_days() > 365 {
+            tr("models.expires_never").to_owned()
+        } else if left.num_seconds() <= 0 {
+            tr("models.expires_now").to_owned()
+        } else if left.num_hours() > 0 {
+            tr_args(
+                "models.expires_hours",
+                &[&left.num_hours(), &(left.num_minutes() % 60)],
+            )
+        } else {
+            tr_args(
+                "models.expires_minutes",
+                &[&left.num_minutes(), &(left.num_seconds() % 60)],
+            )
+        }
+    }
+}
+
+/// Progress of a model pull or creation.
+pub struct ModelStatus {
+    pub message: String,
+    /// Bytes downloaded of the current layer, pulls only
+    pub completed: u64,
+    pub total: u64,
+}
+
+/// Streamed progress lines of a model pull or creation.
+pub struct StatusStream {
+    lines: LineReader,
+    /// The server reported success or an error
+    done: bool,
+}
+
+impl StatusStream {
+    /// The next status, `None` once the server is done. Cancel safe.
+    pub async fn next(&mut self) -> Option<Result<ModelStatus, BoxError>> {
+        #[derive(serde::Deserialize)]
+        struct Line {
+            #[serde(default)]
+            status: String,
+            error: Option<String>,
+            #[serde(default)]
+            completed: u64,
+            #[serde(default)]
+            total: u64,
+        }
+
+        loop {
+            let line = match self.lines.next().await {
+                Some(Ok(line)) => line,
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                None if self.done => return None,
+                None => {
+                    // a pull that was cut off must not pass as complete
+                    self.done = true;
+                    return Some(Err("the response stream ended unexpectedly".into()));
+                }
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let status = match serde_json::from_str::<Line>(&line) {
+                Ok(Line {
+                    error: Some(error), ..
+                }) => Err(error.into()),
+                Ok(line) => Ok(ModelStatus {
+                    message: line.status,
+                    completed: line.completed,
+                    total: line.total,
+                }),
+                Err(e) => Err(e.into()),
+            };
+            match &status {
+                Ok(status) => self.done |= status.message == "success",
+                Err(_) => {
+                    self.done = true;
+                    self.lines.finish();
+                }
+            }
+            return Some(status);
+        }
+    }
+}
+
+/// Send `request`, turning error statuses into errors with the server's message.
+async fn send(request: reqwest::RequestBuilder) -> Result<reqwest::Response, BoxError> {
+    let response = request.send().await?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|value| value.get("error").map(error_message))
+        .unwrap_or(body);
+    Err(format!("{status}: {}", message.trim()).into())
+}
+
+/// `message` of an API error object, or the whole object. Ollama sends just a string.
+fn error_message(error: &serde_json::Value) -> String {
+    error
+        .as_str()
+        .or_else(|| error.get("message").and_then(serde_json::Value::as_str))
+        .map_or_else(|| error.to_string(), ToOwned::to_owned)
+}
+
+/// Where chats and model lists come from.
+#[derive(Clone)]
+pub enum Backend {
+    Ollama(OllamaClient),
+    OpenAi(OpenAiClient),
+}
+
+impl Backend {
+    /// The OpenAI-compatible server if one is configured, otherwise Ollama.
+    pub fn pick(ollama: &OllamaClient, openai: Option<&OpenAiClient>) -> Self {
+        match openai {
+            Some(client) => Self::OpenAi(client.clone()),
+            None => Self::Ollama(ollama.clone()),
+        }
+    }
+
+    pub async fn list_models(&self) -> Result<Vec<LocalModel>, BoxError> {
+        match self {
+            Self::Ollama(client) => client.list_models().await,
+            Self::OpenAi(client) => client.list_models().await,
+        }
+    }
+
+    pub async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, BoxError> {
+        match self {
+            Self::Ollama(client) => client.chat_stream(request).await,
+            Self::OpenAi(client) => client.chat_stream(request).await,
+        }
+    }
+
+    /// Stream the continuation of a raw prompt, without any chat template.
+    pub async fn completion_stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<ChatStream, BoxError> {
+        match self {
+            Self::Ollama(client) => client.completion_stream(request).await,
+            Self::OpenAi(client) => client.completion_stream(request).await,
+        }
+    }
+}
+
+/// Chat completion to stream.
+#[derive(Clone)]
+pub struct ChatRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub settings: ModelSettings,
+    /// Prompt template override, Ollama only
+    pub template: Option<String>,
+}
+
+/// Raw completion to stream, the prompt is continued as it is.
+#[derive(Clone)]
+pub struct CompletionRequest {
+    pub model: String,
+    pub prompt: String,
+    pub settings: ModelSettings,
+}
+
+/// Piece of a streamed reply.
+#[derive(Default)]
+pub struct ChatChunk {
+    pub content: String,
+    /// Token counts, reported once at the end
+    pub usage: Option<TokenUsage>,
+    pub tokens_per_second: Option<f64>,
+    /// Reported once at the end by Ollama
+    pub timings: Option<ServerTimings>,
+}
+
+/// Time the server spent on a reply, as reported by Ollama.
+#[derive(Debug, Clone, Copy)]
+pub struct ServerTimings {
+    pub total: Duration,
+    pub prompt_eval: Duration,
+    pub eval: Duration,
+}
+
+/// Streamed reply to a chat or raw completion request.
+pub struct ChatStream {
+    /// The posted request body with the images left out, for the request inspector
+    pub body: serde_json::Value,
+    reader: ReplyReader,
+}
+
+enum ReplyReader {
+    /// One JSON object per line, the last one is marked `done`
+    Ollama {
+        lines: LineReader,
+        done: bool,
+    },
+    OpenAi(SseReader),
+}
+
+impl ChatStream {
+    /// The next piece of the reply, `None` once it's complete. Cancel safe.
+    pub async fn next(&mut self) -> Option<Result<ChatChunk, BoxError>> {
+        match &mut self.reader {
+            ReplyReader::Ollama { lines, done } => loop {
+                let line = match lines.next().await {
+                    Some(Ok(line)) => line,
+                    Some(Err(e)) => {
+                        *done = true;
+                        return Some(Err(e));
+                    }
+                    None if *done => return None,
+                    None => {
+                        // a reply that was cut off must not pass as complete
+                        *done = true;
+                        return Some(Err("the response stream ended unexpectedly".into()));
+                    }
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let chunk = OllamaClient::parse_line(&line);
+                match &chunk {
+                    Ok(chunk) => *done |= chunk.usage.is_some(),
+                    Err(_) => {
+                        *done = true;
+                        lines.finish();
+                    }
+                }
+                return Some(chunk);
+            },
+            ReplyReader::OpenAi(reader) => reader.next().await,
+        }
+    }
+}
+
+/// `body` with its images replaced by placeholders, both the base64 `images` of Ollama
+/// messages and the data URLs of OpenAI content parts.
+fn redact_images(mut body: serde_json::Value) -> serde_json::Value {
+    let Some(messages) = body.get_mut("messages").and_then(|m| m.as_array_mut()) else {
+        return body;
+    };
+    for message in messages {
+        if let Some(images) = message.get_mut("images").and_then(|i| i.as_array_mut()) {
+            let count = images.len();
+            for (n, image) in images.iter_mut().enumerate() {
+                *image = format!("<image {} of {count}>", n + 1).into();
+            }
+        }
+        if let Some(parts) = message.get_mut("content").and_then(|c| c.as_array_mut()) {
+            let urls: Vec<&mut serde_json::Value> = parts
+                .iter_mut()
+                .filter_map(|part| part.get_mut("image_url")?.get_mut("url"))
+                .collect();
+            let count = urls.len();
+            for (n, url) in urls.into_iter().enumerate() {
+                *url = format!("<image {} of {count}>", n + 1).into();
+            }
+        }
+    }
+    body
+}
+
+/// Splits a streamed response into lines.
+pub struct LineReader {
+    response: reqwest::Response,
+    /// Received bytes not split into lines yet
+    buffer: Vec<u8>,
+    done: bool,
+}
+
+impl LineReader {
+    fn new(response: reqwest::Response) -> Self {
+        Self {
+            response,
+            buffer: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// The next line, `None` at the end of the response. Cancel safe.
+    async fn next(&mut self) -> Option<Result<String, BoxError>> {
+        loop {
+            if let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=end).collect();
+                return Some(Ok(String::from_utf8_lossy(&line).into_owned()));
+            }
+            if self.done {
+                return None;
+            }
+            // the only await, so a dropped call loses nothing
+            match self.response.chunk().await {
+                Ok(Some(bytes)) => self.buffer.extend_from_slice(&bytes),
+                Ok(None) => {
+                    // flush a last line without a newline
+                    self.done = true;
+                    if !self.buffer.is_empty() {
+                        self.buffer.push(b'\n');
+                    }
+                }
+                Err(e) => {
+                    self.done = true;
+                    self.buffer.clear();
+                    return Some(Err(e.into()));
+                }
+            }
+        }
+    }
+
+    /// Drop the rest of the response.
+    fn finish(&mut self) {
+        self.done = true;
+        self.buffer.clear();
+    }
+}
+
+/// Reads the server-sent events of a streamed chat completion.
+pub struct SseReader {
+    lines: LineReader,
+    /// When the first token arrived, the API doesn't report the generation time
+    first_token: Option<Instant>,
+    /// `[DONE]` was received or the stream failed
+    done: bool,
+}
+
+impl SseReader {
+    async fn next(&mut self) -> Option<Result<ChatChunk, BoxError>> {
+        loop {
+            let line = match self.lines.next().await {
+                Some(Ok(line)) => line,
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                None if self.done => return None,
+                None => {
+                    // a reply that was cut off must not pass as complete
+                    self.done = true;
+                    return Some(Err("the response stream ended unexpectedly".into()));
+                }
+            };
+            // comments, event names and blank separators carry nothing for us
+            let Some(data) = line.trim().strip_prefix("data:") else {
+                continue;
+            };
+            let data = data.trim();
+            if data == "[DONE]" {
+                self.done = true;
+                self.lines.finish();
+                return None;
+            }
+            let chunk = self.parse_event(data);
+            if chunk.is_err() {
+                self.done = true;
+                self.lines.finish();
+            }
+            return Some(chunk);
+        }
+    }
+
+    fn parse_event(&mut self, data: &str) -> Result<ChatChunk, BoxError> {
+        #[derive(serde::Deserialize)]
+        struct Event {
+            #[serde(default)]
+            choices: Vec<Choice>,
+            usage: Option<Usage>,
+            error: Option<serde_json::Value>,
+        }
+        #[derive(serde::Deserialize)]
+        struct Choice {
+            #[serde(default)]
+            delta: Delta,
+            /// Set instead of `delta` by the completions endpoint
+            text: Option<String>,
+        }
+        #[derive(Default, serde::Deserialize)]
+        struct Delta {
+            content: Option<String>,
+        }
+        #[derive(serde::Deserialize)]
+        struct Usage {
+            prompt_tokens: u64,
+            completion_tokens: u64,
+        }
+
+        let event: Event = serde_json::from_str(data)?;
+        if let Some(error) = event.error {
+            return Err(error_message(&error).into());
+        }
+        let content: String = event
+            .choices
+            .into_iter()
+            .filter_map(|choice| choice.delta.content.or(choice.text))
+            .collect();
+        if !content.is_empty() {
+            self.first_token.get_or_insert_with(Instant::now);
+        }
+        let usage = event.usage.map(|usage| TokenUsage {
+            prompt_tokens: usage.prompt_tokens,
+            reply_tokens: usage.completion_tokens,
+        });
+        let tokens_per_second = usage
+            .as_ref()
+            .zip(self.first_token)
+            .map(|(usage, first)| usage.reply_tokens as f64 / first.elapsed().as_secs_f64())
+            .filter(|tps| tps.is_finite() && *tps > 0.0);
+        Ok(ChatChunk {
+            content,
+            usage,
+            tokens_per_second,
+            timings: None,
+        })
+    }
+}
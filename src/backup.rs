@@ -0,0 +1,98 @@
+//! Backups of all chats and settings in a single JSON file.
+
+use crate::{
+    chat::Chat,
+    i18n::{tr, tr_args},
+    widgets::Settings,
+};
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+/// Format version written to new backups, bumped on incompatible changes
+pub const BACKUP_VERSION: u32 = 1;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Backup {
+    pub version: u32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub chats: Vec<Chat>,
+    /// Preferences, including the shared model picker with its settings and presets.
+    /// Secrets are left out, see [`Settings::without_secrets`]
+    pub settings: Settings,
+}
+
+impl Backup {
+    /// Serialize a backup of `chats` and `settings`.
+    pub fn to_json(chats: &[Chat], settings: &Settings) -> Result<String> {
+        // same shape as `Backup`, without cloning every chat
+        #[derive(serde::Serialize)]
+        struct BackupRef<'a> {
+            version: u32,
+            created_at: chrono::DateTime<chrono::Utc>,
+            chats: &'a [Chat],
+            settings: Settings,
+        }
+        serde_json::to_string_pretty(&BackupRef {
+            version: BACKUP_VERSION,
+            created_at: chrono::Utc::now(),
+            chats,
+            settings: settings.without_secrets(),
+        })
+        .context("failed to serialize backup")
+    }
+
+    /// Read a backup, refusing ones written by a newer version of the app.
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| tr_args("sidebar.backup_read_failed", &[&path.display()]))?;
+        let value: serde_json::Value =
+            serde_json::from_str(&text).context(tr("sidebar.backup_not_json"))?;
+        let Some(version) = value.get("version").and_then(serde_json::Value::as_u64) else {
+            bail!(tr("sidebar.backup_not_ellama"));
+        };
+        if version > BACKUP_VERSION as u64 {
+            bail!(tr_args("sidebar.backup_newer", &[&version]));
+        }
+        serde_json::from_value(value).context(tr("sidebar.backup_damaged"))
+    }
+
+    /// Like "14 chats, 2 presets".
+    pub fn summary(&self) -> String {
+        let plural = |n: usize, one, many| tr_args(if n == 1 { one } else { many }, &[&n]);
+        format!(
+            "{}, {}",
+            plural(self.chats.len(), "sidebar.chats_one", "sidebar.chats_many"),
+            plural(
+                self.settings.model_picker.presets.len(),
+                "sidebar.presets_one",
+                "sidebar.presets_many"
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_json(name: &str, json: &str) -> Result<Backup> {
+        let path = std::env::temp_dir().join(format!("ellama-{name}-{}.json", std::process::id()));
+        std::fs::write(&path, json).unwrap();
+        let backup = Backup::read(&path);
+        std::fs::remove_file(&path).unwrap();
+        backup
+    }
+
+    #[test]
+    fn refuses_newer_versions() {
+        let json = Backup::to_json(&[], &Settings::default()).unwrap();
+        assert!(read_json("current-backup", &json).is_ok());
+
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["version"] = (BACKUP_VERSION + 1).into();
+        let err = read_json("newer-backup", &value.to_string()).unwrap_err();
+        assert!(err.to_string().contains("newer version"), "{err}");
+
+        assert!(read_json("not-a-backup", "{}").is_err());
+    }
+}
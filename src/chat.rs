@@ -1,37 +1,44 @@
 #[cfg(feature = "tts")]
 use crate::sessions::SharedTts;
+#[cfg(feature = "voice")]
+use crate::voice;
 
 use crate::{
+    backend::{
+        Backend, BoxError, ChatRequest, CompletionRequest, OllamaClient, OpenAiClient,
+        ServerTimings,
+    },
     easymark::MemoizedEasymarkHighlighter,
-    widgets::{self, ModelPicker},
+    golden::{self, GoldenPrompt},
+    html,
+    i18n::{tr, tr_args},
+    knowledge::{self, IndexFlower, Knowledge, Retrieved, SourcePick},
+    langdetect, math,
+    wake::WakeDetector,
+    widgets::{self, ModelPicker, ModelSettings, SelectedModel, SendShortcut, Timeouts},
 };
 use anyhow::{Context, Result};
 use eframe::egui::{
-    self, pos2, vec2, Align, Color32, Frame, Key, KeyboardShortcut, Layout, Margin, Modifiers,
-    Pos2, Rect, Rounding, Stroke,
+    self, pos2, vec2, Align, Frame, Key, Layout, Margin, Pos2, Rect, Rounding, Stroke,
 };
 use egui_commonmark::{CommonMarkCache, CommonMarkViewer};
 use egui_modal::{Icon, Modal};
-use egui_virtual_list::VirtualList;
 use flowync::{error::Compact, CompactFlower, CompactHandle};
 use ollama_rs::{
-    generation::{
-        chat::{request::ChatMessageRequest, ChatMessage, ChatMessageResponseStream},
-        images::Image,
-        options::GenerationOptions,
-    },
-    Ollama,
+    generation::{chat::ChatMessage, images::Image},
+    models::LocalModel,
 };
 use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     io::Write,
-    path::PathBuf,
+    ops::Range,
+    path::{Path, PathBuf},
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
-use tokio_stream::StreamExt;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 enum Role {
@@ -42,6 +49,10 @@ enum Role {
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
 #[serde(default)]
 pub struct Message {
+    /// Identifies the message while the app runs, for what is remembered about it across
+    /// insertions and deletions above it
+    #[serde(skip)]
+    row_id: u64,
     model_name: String,
     content: String,
     role: Role,
@@ -49,71 +60,962 @@ pub struct Message {
     is_generating: bool,
     #[serde(skip)]
     requested_at: Instant,
-    time: chrono::DateTime<chrono::Utc>,
+    /// When the message was sent or received, `None` for messages imported without one
+    #[serde(default, deserialize_with = "deserialize_time")]
+    time: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(skip)]
     clicked_copy: bool,
+    #[serde(skip)]
+    clicked_copy_code: bool,
     is_error: bool,
+    /// Why the request for this reply failed, as reported. Older failed messages have it in
+    /// `content` instead
+    error: Option<String>,
     #[serde(skip)]
     is_speaking: bool,
+    /// Show the text as emitted, in monospace, instead of rendering the markdown
+    #[serde(skip)]
+    show_source: bool,
     images: Vec<PathBuf>,
     is_prepending: bool,
+    /// User corrections for the languages of untagged code blocks, by block index
+    code_languages: BTreeMap<usize, String>,
+    #[serde(skip)]
+    tagged_content: Option<TaggedContent>,
+    #[serde(skip)]
+    json_view: Option<JsonView>,
+    /// Token counts reported by Ollama for the exchange that produced this reply
+    usage: Option<TokenUsage>,
+    /// Latency and speed of the generation that produced this reply
+    stats: Option<ResponseStats>,
+    /// Knowledge excerpts added to the prompt of this reply
+    sources: Vec<Retrieved>,
+    /// The user's annotation, never sent to the model
+    note: String,
+    /// The user's verdict on this reply
+    rating: Option<Rating>,
+    /// Inference settings this reply was generated with, `None` for replies generated
+    /// before they were recorded
+    settings: Option<ModelSettings>,
+    /// The reply was still being generated when last saved. Stays set if the app closed
+    /// before it completed, so it can be continued
+    incomplete: bool,
+    /// Reply of the compared model to the same prompt, shown next to this one
+    compare: Option<Box<Message>>,
+    /// What was sent for this reply and what the server reported, as pretty-printed JSON.
+    /// Not saved, the full context of every reply would bloat the chats
+    #[serde(skip)]
+    inspection: Option<String>,
+}
+
+/// Read [`Message::time`] both as an option and as the bare timestamp chats were saved with
+/// before it became optional.
+fn deserialize_time<'de, D>(
+    deserializer: D,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Time {
+        Bare(chrono::DateTime<chrono::Utc>),
+        Optional(Option<chrono::DateTime<chrono::Utc>>),
+    }
+
+    Ok(match serde::Deserialize::deserialize(deserializer)? {
+        Time::Bare(time) => Some(time),
+        Time::Optional(time) => time,
+    })
+}
+
+/// The user's verdict on a reply, for keeping track while tuning prompts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+enum Rating {
+    Good,
+    Bad,
+}
+
+impl Rating {
+    const fn icon(self) -> &'static str {
+        match self {
+            Self::Good => "👍",
+            Self::Bad => "👎",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Good => tr("chat.rating_good"),
+            Self::Bad => tr("chat.rating_bad"),
+        }
+    }
+}
+
+/// Token counts of a completed exchange, as reported by the server.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub reply_tokens: u64,
+}
+
+/// Latency and speed of a generated reply.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct ResponseStats {
+    /// Time from sending the request until the first token arrived
+    pub time_to_first_token: Duration,
+    /// Generation speed reported by the server, `None` if the generation was stopped
+    pub tokens_per_second: Option<f64>,
+    /// Time from sending the request until the reply was complete
+    #[serde(default)]
+    pub total_time: Duration,
+}
+
+impl ResponseStats {
+    fn describe(&self) -> String {
+        let mut text = tr_args(
+            "chat.time_to_first_token",
+            &[&format!("{:.2}", self.time_to_first_token.as_secs_f64())],
+        );
+        if let Some(tps) = self.tokens_per_second {
+            text.push('\n');
+            text += &tr_args("chat.speed", &[&format!("{tps:.1}")]);
+        }
+        // zero for replies generated before this was recorded
+        if !self.total_time.is_zero() {
+            text.push('\n');
+            text += &tr_args(
+                "chat.total_time",
+                &[&format!("{:.1}", self.total_time.as_secs_f64())],
+            );
+        }
+        text
+    }
+}
+
+/// Ollama's context window size if neither the settings nor the model set one
+pub const DEFAULT_NUM_CTX: u64 = 2048;
+
+/// Rough token count of `text`, for when Ollama doesn't report one
+fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+/// Format a number with thousands separators, like `8,192`
+fn group_digits(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Format a token count compactly, like `438` or `1.2k`
+pub fn compact_count(n: u64) -> String {
+    let (value, suffix) = match n {
+        0..=999 => return n.to_string(),
+        1_000..=999_999 => (n as f64 / 1_000.0, "k"),
+        _ => (n as f64 / 1_000_000.0, "M"),
+    };
+    let value = format!("{value:.1}");
+    format!("{}{suffix}", value.strip_suffix(".0").unwrap_or(&value))
+}
+
+/// Token usage of the exchange that produced the reply at `idx` in `messages`. Estimated if
+/// Ollama didn't report it, e.g. because the generation was stopped.
+fn usage_of(messages: &[Message], system_prompt: &str, idx: usize) -> (TokenUsage, bool) {
+    if let Some(usage) = messages[idx].usage {
+        return (usage, false);
+    }
+    let prompt_tokens = estimate_tokens(system_prompt)
+        + messages[..idx]
+            .iter()
+            .map(|m| estimate_tokens(&m.content))
+            .sum::<u64>();
+    let usage = TokenUsage {
+        prompt_tokens,
+        reply_tokens: estimate_tokens(&messages[idx].content),
+    };
+    (usage, true)
+}
+
+/// Hash of message contents, to tell whether what was built from them is still current.
+/// Unlike the length it changes when the contents are edited in place.
+fn content_hash(content: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Reply generated in JSON mode, pretty-printed in a code block for the markdown viewer.
+#[derive(Clone)]
+struct JsonView {
+    /// [`content_hash`] of the message contents this was built from
+    content_hash: u64,
+    /// The formatted JSON, or why the reply isn't valid JSON
+    formatted: Result<String, String>,
+}
+
+impl JsonView {
+    fn new(content: &str) -> Self {
+        let formatted = serde_json::from_str::<serde_json::Value>(content)
+            .and_then(|value| serde_json::to_string_pretty(&value))
+            .map(|pretty| format!("```json\n{pretty}\n```"))
+            .map_err(|e| e.to_string());
+        Self {
+            content_hash: content_hash(content),
+            formatted,
+        }
+    }
+}
+
+/// Message contents with languages filled in for untagged code blocks and, optionally, math
+/// rendered as Unicode text.
+#[derive(Clone)]
+struct TaggedContent {
+    /// [`content_hash`] of the message contents this was built from
+    content_hash: u64,
+    /// Whether math spans were rendered
+    math: bool,
+    text: String,
+    /// (block index, guessed language) of every untagged code block
+    untagged: Vec<(usize, Option<&'static str>)>,
+    /// Where the code block starts in `text`, if the message is a preamble + code block
+    preamble_split: Option<usize>,
+}
+
+impl TaggedContent {
+    fn new(content: &str, overrides: &BTreeMap<usize, String>, render_math: bool) -> Self {
+        let mut text = String::with_capacity(content.len());
+        let mut untagged = Vec::new();
+        let mut last = 0;
+        for (i, block) in langdetect::fenced_blocks(content).iter().enumerate() {
+            if !block.info.is_empty() {
+                continue;
+            }
+            let guess = langdetect::detect_language(&block.code);
+            untagged.push((i, guess));
+            if let Some(lang) = overrides.get(&i).map(String::as_str).or(guess) {
+                text.push_str(&content[last..block.info_pos]);
+                text.push_str(lang);
+                last = block.info_pos;
+            }
+        }
+        text.push_str(&content[last..]);
+        if render_math {
+            text = math::render_math(&text);
+        }
+        Self {
+            content_hash: content_hash(content),
+            math: render_math,
+            preamble_split: langdetect::preamble_split(&text),
+            text,
+            untagged,
+        }
+    }
+}
+
+/// Token counts and speed under a reply, returns whether it was clicked.
+fn show_reply_footer(
+    ui: &mut egui::Ui,
+    (usage, estimated): (TokenUsage, bool),
+    stats: Option<ResponseStats>,
+) -> bool {
+    let approx = if estimated { "~" } else { "" };
+    let mut footer = tr_args(
+        "chat.footer",
+        &[
+            &format!("{approx}{}", compact_count(usage.prompt_tokens)),
+            &format!("{approx}{}", compact_count(usage.reply_tokens)),
+        ],
+    );
+    if let Some(tps) = stats.and_then(|s| s.tokens_per_second) {
+        footer += &tr_args("chat.footer_speed", &[&format!("{tps:.1}")]);
+    }
+    ui.add(egui::Label::new(egui::RichText::new(footer).small().weak()).sense(egui::Sense::click()))
+        .on_hover_text(match stats {
+            Some(stats) => format!("{}\n{}", stats.describe(), tr("chat.click_for_info")),
+            None => tr("chat.show_info").to_owned(),
+        })
+        .clicked()
+}
+
+/// Faint line above the first message sent with the last request.
+fn show_context_divider(ui: &mut egui::Ui, summary: Option<&str>) {
+    ui.vertical_centered(|ui| {
+        let text = if summary.is_some() {
+            tr("chat.context_starts_summarized")
+        } else {
+            tr("chat.context_starts")
+        };
+        ui.label(egui::RichText::new(text).small().weak())
+            .on_hover_text(match summary {
+                Some(summary) => tr_args("chat.context_summary", &[&summary]),
+                None => tr("chat.context_not_sent").to_owned(),
+            });
+    });
+    ui.add_space(4.0);
+}
+
+/// Context menu listing the knowledge excerpts a reply was given.
+fn show_sources(ui: &mut egui::Ui, sources: &[Retrieved]) {
+    ui.set_max_width(420.0);
+    ui.label(tr_args("chat.sources", &[&sources.len()]));
+    ui.separator();
+    egui::ScrollArea::vertical()
+        .max_height(360.0)
+        .show(ui, |ui| {
+            for (i, chunk) in sources.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.strong(format!("[{}] {}", i + 1, chunk.source));
+                    ui.label(egui::RichText::new(format!("{:.2}", chunk.score)).weak())
+                        .on_hover_text(tr("chat.similarity"));
+                });
+                ui.label(egui::RichText::new(&chunk.text).small());
+                ui.add_space(4.0);
+            }
+        });
 }
 
 impl Default for Message {
     fn default() -> Self {
+        static NEXT_ROW_ID: AtomicU64 = AtomicU64::new(0);
         Self {
+            row_id: NEXT_ROW_ID.fetch_add(1, Ordering::Relaxed),
             content: String::new(),
             role: Role::User,
             is_generating: false,
             requested_at: Instant::now(),
-            time: chrono::Utc::now(),
+            time: Some(chrono::Utc::now()),
             clicked_copy: false,
+            clicked_copy_code: false,
             is_error: false,
+            error: None,
             is_speaking: false,
+            show_source: false,
             model_name: String::new(),
             images: Vec::new(),
             is_prepending: false,
+            code_languages: BTreeMap::new(),
+            tagged_content: None,
+            json_view: None,
+            usage: None,
+            stats: None,
+            sources: Vec::new(),
+            note: String::new(),
+            rating: None,
+            settings: None,
+            incomplete: false,
+            compare: None,
+            inspection: None,
         }
     }
 }
 
+#[cfg(feature = "tts")]
+enum TtsCommand {
+    /// Speak the text, interrupting what is being spoken if set
+    Speak {
+        text: String,
+        interrupt: bool,
+    },
+    Stop,
+}
+
+/// Hand `command` to the thread talking to the TTS engine. The engine can block, so it's
+/// kept off the UI thread, and a single thread keeps the commands in order: queued sentences
+/// must not overtake each other.
+#[cfg(feature = "tts")]
+fn tts_send(tts: SharedTts, command: TtsCommand) {
+    use std::sync::{mpsc, Mutex, OnceLock};
+
+    type Job = (Arc<parking_lot::RwLock<tts::Tts>>, TtsCommand);
+    static WORKER: OnceLock<Mutex<mpsc::Sender<Job>>> = OnceLock::new();
+
+    let Some(tts) = tts else {
+        return;
+    };
+    let worker = WORKER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        std::thread::spawn(move || {
+            for (tts, command) in receiver {
+                match command {
+                    TtsCommand::Speak { text, interrupt } => {
+                        let _ = tts
+                            .write()
+                            .speak(text, interrupt)
+                            .map_err(|e| log::error!("failed to speak: {e}"));
+                    }
+                    TtsCommand::Stop => {
+                        let _ = tts
+                            .write()
+                            .stop()
+                            .map_err(|e| log::error!("failed to stop tts: {e}"));
+                    }
+                }
+            }
+        });
+        Mutex::new(sender)
+    });
+    if let Ok(sender) = worker.lock() {
+        let _ = sender.send((tts, command));
+    }
+}
+
 #[cfg(feature = "tts")]
 fn tts_control(tts: SharedTts, text: String, speak: bool) {
-    std::thread::spawn(move || {
-        if let Some(tts) = tts {
-            if speak {
-                let _ = tts
-                    .write()
-                    .speak(text, true)
-                    .map_err(|e| log::error!("failed to speak: {e}"));
-            } else {
-                let _ = tts
-                    .write()
-                    .stop()
-                    .map_err(|e| log::error!("failed to stop tts: {e}"));
+    let command = if speak {
+        TtsCommand::Speak {
+            text,
+            interrupt: true,
+        }
+    } else {
+        TtsCommand::Stop
+    };
+    tts_send(tts, command);
+}
+
+/// Queue `text` after whatever is being spoken.
+#[cfg(feature = "tts")]
+fn tts_enqueue(tts: SharedTts, text: String) {
+    tts_send(
+        tts,
+        TtsCommand::Speak {
+            text,
+            interrupt: false,
+        },
+    );
+}
+
+/// Splits a streamed response into complete sentences, so speech can trail the generation.
+/// Code blocks are skipped.
+#[cfg(feature = "tts")]
+#[derive(Default)]
+struct SentenceSplitter {
+    /// Bytes of the response already handed out or skipped
+    consumed: usize,
+    in_code_block: bool,
+}
+
+#[cfg(feature = "tts")]
+impl SentenceSplitter {
+    /// Words that end with a period without ending the sentence
+    const ABBREVIATIONS: &'static [&'static str] = &[
+        "e.g", "i.e", "etc", "vs", "mr", "mrs", "ms", "dr", "prof", "st", "no", "fig", "approx",
+    ];
+
+    /// Returns the text of the sentences completed since the last call. With `finish`, the
+    /// incomplete tail is returned as well.
+    fn next_sentences(&mut self, content: &str, finish: bool) -> String {
+        let mut out = String::new();
+        while self.consumed < content.len() {
+            let rest = &content[self.consumed..];
+            let line_end = rest.find('\n').map(|i| i + 1);
+            let line = &rest[..line_end.unwrap_or(rest.len())];
+            let at_line_start = self.consumed == 0 || content[..self.consumed].ends_with('\n');
+
+            // code fences can only be told apart from prose once the line is complete
+            if self.in_code_block || (at_line_start && Self::may_be_fence(line, line_end.is_some()))
+            {
+                let Some(end) = line_end.or(finish.then_some(rest.len())) else {
+                    break;
+                };
+                let trimmed = line.trim();
+                if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                    self.in_code_block = !self.in_code_block;
+                } else if !self.in_code_block {
+                    out.push_str(line);
+                }
+                self.consumed += end;
+                continue;
             }
+
+            let Some(end) = Self::sentence_end(line, at_line_start)
+                .or(line_end)
+                .or(finish.then_some(rest.len()))
+            else {
+                break;
+            };
+            out.push_str(&rest[..end]);
+            self.consumed += end;
         }
-    });
+        out
+    }
+
+    fn may_be_fence(line: &str, complete: bool) -> bool {
+        let trimmed = line.trim();
+        trimmed.starts_with("```")
+            || trimmed.starts_with("~~~")
+            || (!complete && ("```".starts_with(trimmed) || "~~~".starts_with(trimmed)))
+    }
+
+    /// Byte offset just past the first sentence terminator in `line`, including the
+    /// whitespace after it.
+    fn sentence_end(line: &str, at_line_start: bool) -> Option<usize> {
+        let mut chars = line.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if !matches!(c, '.' | '!' | '?') {
+                continue;
+            }
+            // the terminator must be followed by whitespace, which also rules out decimals
+            let Some(&(next_i, next)) = chars.peek() else {
+                return None;
+            };
+            if !next.is_whitespace() {
+                continue;
+            }
+            if c == '.' {
+                let word_start = line[..i]
+                    .rfind(|c: char| c.is_whitespace() || c == '(')
+                    .map_or(0, |j| j + 1);
+                let word = line[word_start..i].to_lowercase();
+                let is_initial = word.chars().count() == 1;
+                let is_list_number = at_line_start
+                    && line[..word_start].trim().is_empty()
+                    && !word.is_empty()
+                    && word.chars().all(|c| c.is_ascii_digit());
+                if is_initial || is_list_number || Self::ABBREVIATIONS.contains(&word.as_str()) {
+                    continue;
+                }
+            }
+            return Some(next_i + next.len_utf8());
+        }
+        None
+    }
+}
+
+/// Strip markdown from `markdown`, leaving prose that reads well out loud. Code blocks
+/// are skipped entirely and links are replaced with their text.
+#[cfg(feature = "tts")]
+fn speakable_text(markdown: &str) -> String {
+    let mut out = String::with_capacity(markdown.len());
+    let mut in_code_block = false;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        // block-level markers: headings, quotes, list bullets, rules and table borders
+        let mut line = trimmed.trim_start_matches(['#', '>']).trim_start();
+        for bullet in ["- ", "* ", "+ "] {
+            if let Some(rest) = line.strip_prefix(bullet) {
+                line = rest;
+                break;
+            }
+        }
+        if line
+            .chars()
+            .all(|c| matches!(c, '-' | '*' | '_' | '=' | '|' | ':' | ' '))
+        {
+            out.push('\n');
+            continue;
+        }
+
+        let line = line.trim_matches(['|', ' ']);
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' | '_' | '`' | '~' => {}
+                '|' => out.push(','),
+                // images: `![alt](url)` reads as the alt text
+                '!' if chars.peek() == Some(&'[') => {}
+                '[' => {
+                    let text: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                    out.push_str(&text);
+                    if chars.peek() == Some(&'(') {
+                        chars.by_ref().take_while(|&c| c != ')').for_each(drop);
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+        out.push('\n');
+    }
+    out.trim().to_owned()
 }
 
 /// Convert a model name into a short name.
 ///
 /// # Example
 ///
-/// - nous-hermes2:latest -> Nous
+/// - nous-hermes2:latest -> Nous Hermes2
 /// - gemma:latest -> Gemma
-/// - starling-lm:7b-beta-q5_K_M -> Starling
-/// - bambucha/saiga-llama3 -> Saiga
+/// - starling-lm:7b-beta-q5_K_M -> Starling LM
+/// - bambucha/saiga-llama3 -> Saiga Llama3
+/// - :latest -> Llama
 fn make_short_name(name: &str) -> String {
-    let mut c = name
-        .split('/')
-        .nth(1)
-        .unwrap_or(name)
-        .chars()
-        .take_while(|c| c.is_alphanumeric());
-    match c.next() {
-        None => "Llama".to_string(),
-        Some(f) => f.to_uppercase().collect::<String>() + c.collect::<String>().as_str(),
+    let path = name.split(':').next().unwrap_or_default();
+    let base = path.rsplit('/').next().unwrap_or_default();
+    let words: Vec<String> = base
+        .split(['-', '_', ' '])
+        .filter(|w| !w.is_empty())
+        .map(title_case)
+        .collect();
+    if words.is_empty() {
+        return "Llama".to_owned();
+    }
+    words.join(" ")
+}
+
+/// Capitalize a word of a model name, parameter counts like "7b" and abbreviations like
+/// "lm" are written in capitals.
+fn title_case(word: &str) -> String {
+    const ABBREVIATIONS: &[&str] = &["ai", "gpt", "it", "llm", "lm", "moe", "sql", "vl"];
+    let is_size = word.len() > 1
+        && word.ends_with(['b', 'm', 'k'])
+        && word[..word.len() - 1]
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '.');
+    if is_size || ABBREVIATIONS.contains(&word.to_lowercase().as_str()) {
+        return word.to_uppercase();
+    }
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Short names of the models in `names`, with the tag or namespace appended where two
+/// models would otherwise get the same one, like "Llama3 (8b)" and "Llama3 (70b)".
+fn short_names<'a>(names: impl IntoIterator<Item = &'a str>) -> HashMap<String, String> {
+    let names: BTreeSet<&str> = names.into_iter().collect();
+    let mut by_short_name: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for name in names {
+        by_short_name
+            .entry(make_short_name(name))
+            .or_default()
+            .push(name);
+    }
+    fn tag(name: &str) -> &str {
+        name.split_once(':').map_or("latest", |(_, tag)| tag)
+    }
+    let mut short_names = HashMap::new();
+    for (short_name, names) in by_short_name {
+        if let [name] = names[..] {
+            short_names.insert(name.to_owned(), short_name);
+            continue;
+        }
+        for name in &names {
+            // the same model pulled from different namespaces has the same tag
+            let same_tag = names.iter().filter(|n| tag(n) == tag(name)).count() > 1;
+            let namespace = name.split(':').next().and_then(|p| p.rsplit_once('/'));
+            let suffix = match namespace {
+                Some((namespace, _)) if same_tag => namespace,
+                _ => tag(name),
+            };
+            short_names.insert((*name).to_owned(), format!("{short_name} ({suffix})"));
+        }
+    }
+    short_names
+}
+
+/// Round badge with the first letter of the model's short name on a color derived from
+/// it, so a model looks the same wherever it appears.
+pub fn model_avatar(ui: &mut egui::Ui, model_name: &str) -> egui::Response {
+    let short_name = make_short_name(model_name);
+    let letter: String = short_name.chars().take(1).collect();
+    let size = ui.text_style_height(&egui::TextStyle::Body);
+    let (rect, response) = ui.allocate_exact_size(vec2(size, size), egui::Sense::hover());
+    if ui.is_rect_visible(rect) {
+        let painter = ui.painter();
+        painter.circle_filled(rect.center(), size / 2.0, widgets::name_color(&short_name));
+        painter.text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            letter,
+            egui::FontId::proportional(size * 0.65),
+            egui::Color32::WHITE,
+        );
+    }
+    response.on_hover_text(model_name)
+}
+
+/// Find-in-chat matches in a single message
+#[derive(Clone, Copy)]
+struct FindHighlights<'a> {
+    /// Byte ranges into the raw message text
+    ranges: &'a [Range<usize>],
+    /// Index of the current match among `ranges`, if it's in this message
+    current: Option<usize>,
+    /// Scroll the current match into view
+    scroll: bool,
+}
+
+/// Lay out `text` with the matches highlighted, scrolling to the current one if requested.
+fn show_highlighted(ui: &mut egui::Ui, text: &str, highlights: FindHighlights) {
+    let visuals = ui.visuals();
+    let normal = egui::TextFormat {
+        font_id: egui::TextStyle::Body.resolve(ui.style()),
+        color: visuals.text_color(),
+        ..Default::default()
+    };
+    let mut job = egui::text::LayoutJob::default();
+    let mut end = 0;
+    for (i, range) in highlights.ranges.iter().enumerate() {
+        job.append(&text[end..range.start], 0.0, normal.clone());
+        let background = if highlights.current == Some(i) {
+            visuals.selection.bg_fill
+        } else {
+            visuals.selection.bg_fill.gamma_multiply(0.4)
+        };
+        job.append(
+            &text[range.clone()],
+            0.0,
+            egui::TextFormat {
+                background,
+                ..normal.clone()
+            },
+        );
+        end = range.end;
+    }
+    job.append(&text[end..], 0.0, normal);
+    job.wrap.max_width = ui.available_width();
+
+    let galley = ui.fonts(|f| f.layout_job(job));
+    let rect = ui.label(galley.clone()).rect;
+    if !highlights.scroll {
+        return;
+    }
+    if let Some(range) = highlights.current.and_then(|i| highlights.ranges.get(i)) {
+        let start = text[..range.start].chars().count();
+        let end = start + text[range.clone()].chars().count();
+        let match_rect = galley
+            .pos_from_ccursor(egui::text::CCursor::new(start))
+            .union(galley.pos_from_ccursor(egui::text::CCursor::new(end)))
+            .translate(rect.min.to_vec2());
+        ui.scroll_to_rect(match_rect, Some(Align::Center));
+    }
+}
+
+/// Measured heights of the message rows, so only the visible ones need to be laid out.
+#[derive(Default)]
+struct RowHeights {
+    /// Width the rows were measured at, they wrap differently at any other width
+    width: f32,
+    /// Heights by [`Message::row_id`], so they stay with their rows when rows are
+    /// inserted or deleted above
+    measured: HashMap<u64, f32>,
+    /// Heights of the rows in order, `None` for rows that weren't laid out yet
+    heights: Vec<Option<f32>>,
+    /// Scroll offset keeping the visible rows in place after a row above them turned out
+    /// to have a different height than estimated, applied on the next frame
+    scroll_to: Option<f32>,
+}
+
+impl RowHeights {
+    /// Height assumed for rows before any of them are measured
+    const DEFAULT_ESTIMATE: f32 = 96.0;
+
+    /// Forget the heights if the width changed and line them up with the rows of `ids`.
+    fn prepare(&mut self, width: f32, ids: &[u64]) {
+        if (self.width - width).abs() > 0.5 {
+            self.width = width;
+            self.measured.clear();
+        }
+        if self.measured.len() > ids.len() {
+            // some rows were deleted
+            let ids: HashSet<u64> = ids.iter().copied().collect();
+            self.measured.retain(|id, _| ids.contains(id));
+        }
+        self.heights = ids
+            .iter()
+            .map(|id| self.measured.get(id).copied())
+            .collect();
+    }
+
+    /// Remember the height of the row at `index` with `id`, returns the previous one.
+    fn measure(&mut self, index: usize, id: u64, height: f32) -> Option<f32> {
+        self.measured.insert(id, height);
+        self.heights[index].replace(height)
+    }
+
+    /// Height assumed for rows that haven't been laid out yet, the average of the
+    /// measured ones
+    fn estimate(&self) -> f32 {
+        let (sum, count) = self
+            .heights
+            .iter()
+            .flatten()
+            .fold((0.0, 0), |(sum, count), h| (sum + h, count + 1));
+        if count == 0 {
+            Self::DEFAULT_ESTIMATE
+        } else {
+            sum / count as f32
+        }
+    }
+
+    /// Index of the row at `y` from the top of the list and where that row starts.
+    fn row_at(&self, y: f32, estimate: f32) -> (usize, f32) {
+        let mut top = 0.0;
+        for (i, height) in self.heights.iter().enumerate() {
+            let height = height.unwrap_or(estimate);
+            if top + height > y {
+                return (i, top);
+            }
+            top += height;
+        }
+        (self.heights.len(), top)
+    }
+
+    /// Total height of the rows from `start` on.
+    fn height_from(&self, start: usize, estimate: f32) -> f32 {
+        self.heights[start.min(self.heights.len())..]
+            .iter()
+            .map(|h| h.unwrap_or(estimate))
+            .sum()
+    }
+}
+
+/// Find-in-chat bar, opened with its shortcut (Ctrl+F by default)
+#[derive(Default)]
+struct FindBar {
+    open: bool,
+    query: String,
+    /// Focus the query field on the next frame
+    focus: bool,
+    /// Matches by message index, offsets are into the raw message text
+    matches: Vec<(usize, Vec<Range<usize>>)>,
+    /// Total number of matches
+    count: usize,
+    /// Index of the current match among all of them
+    current: usize,
+    /// Scroll the current match into view on the next frame
+    scroll: bool,
+    /// Lowercase query and hash of the messages the matches were found for
+    searched: (String, u64),
+}
+
+impl FindBar {
+    /// Find the matches again if the query or the messages changed.
+    fn update(&mut self, messages: &[Message]) {
+        use std::hash::{Hash, Hasher};
+
+        // an edit can keep the length, e.g. "main" → "mine", so hash the text itself
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        for message in messages {
+            message.content.hash(&mut hasher);
+        }
+        let searched = (self.query.to_lowercase(), hasher.finish());
+        if searched == self.searched {
+            return;
+        }
+        let query_changed = searched.0 != self.searched.0;
+        self.matches = messages
+            .iter()
+            .enumerate()
+            .map(|(i, message)| (i, find_ignore_case(&message.content, &searched.0)))
+            .filter(|(_, ranges)| !ranges.is_empty())
+            .collect();
+        self.count = self.matches.iter().map(|(_, ranges)| ranges.len()).sum();
+        if query_changed {
+            self.current = 0;
+            self.scroll = self.count > 0;
+        } else {
+            self.current = self.current.min(self.count.saturating_sub(1));
+        }
+        self.searched = searched;
+    }
+
+    fn step(&mut self, forward: bool) {
+        if self.count == 0 {
+            return;
+        }
+        self.current = if forward {
+            (self.current + 1) % self.count
+        } else {
+            (self.current + self.count - 1) % self.count
+        };
+        self.scroll = true;
+    }
+
+    /// Matches to highlight in the message at `idx`.
+    fn highlights(&self, idx: usize) -> Option<FindHighlights<'_>> {
+        if !self.open {
+            return None;
+        }
+        let mut before = 0;
+        for (i, ranges) in &self.matches {
+            if *i == idx {
+                let current = self
+                    .current
+                    .checked_sub(before)
+                    .filter(|&c| c < ranges.len());
+                return Some(FindHighlights {
+                    ranges,
+                    current,
+                    scroll: self.scroll && current.is_some(),
+                });
+            }
+            before += ranges.len();
+        }
+        None
+    }
+
+    /// Returns `true` if the bar should be closed.
+    fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut close = false;
+        ui.horizontal(|ui| {
+            let resp = ui.add(
+                egui::TextEdit::singleline(&mut self.query)
+                    .hint_text(tr("chat.find"))
+                    .desired_width(240.0),
+            );
+            if std::mem::take(&mut self.focus) {
+                resp.request_focus();
+            }
+            if resp.lost_focus() {
+                let (enter, shift, escape) = ui.input(|i| {
+                    (
+                        i.key_pressed(Key::Enter),
+                        i.modifiers.shift,
+                        i.key_pressed(Key::Escape),
+                    )
+                });
+                if enter {
+                    self.step(!shift);
+                    resp.request_focus();
+                }
+                close |= escape;
+            }
+
+            if self.count > 0 {
+                ui.label(format!("{}/{}", self.current + 1, self.count));
+            } else if !self.query.is_empty() {
+                ui.weak(tr("chat.no_matches"));
+            }
+            ui.add_enabled_ui(self.count > 0, |ui| {
+                if ui
+                    .small_button("⬆")
+                    .on_hover_text(tr("chat.previous_match"))
+                    .clicked()
+                {
+                    self.step(false);
+                }
+                if ui
+                    .small_button("⬇")
+                    .on_hover_text(tr("chat.next_match"))
+                    .clicked()
+                {
+                    self.step(true);
+                }
+            });
+            close |= ui
+                .small_button("✖")
+                .on_hover_text(tr("chat.close_find"))
+                .clicked();
+        });
+        close
     }
 }
 
@@ -121,6 +1023,14 @@ enum MessageAction {
     None,
     Retry(usize),
     Regenerate(usize),
+    RegenerateWith { idx: usize, model: String },
+    SaveGolden(usize),
+    Branch(usize),
+    Delete(usize),
+    Continue(usize),
+    NoteEdited,
+    Rated,
+    ViewRequest(usize),
 }
 
 impl Message {
@@ -142,6 +1052,7 @@ impl Message {
             content,
             role: Role::Assistant,
             is_generating: true,
+            incomplete: true,
             model_name,
             ..Default::default()
         }
@@ -152,73 +1063,298 @@ impl Message {
         matches!(self.role, Role::User)
     }
 
-    fn show(
+    /// Guess languages for untagged code blocks and render math. This only runs once the
+    /// message is complete and is cached until the contents or `render_math` change.
+    fn update_tagged_content(&mut self, render_math: bool) {
+        if !self.is_generating
+            && self.tagged_content.as_ref().map_or(true, |t| {
+                t.content_hash != content_hash(&self.content) || t.math != render_math
+            })
+        {
+            self.tagged_content = Some(TaggedContent::new(
+                &self.content,
+                &self.code_languages,
+                render_math,
+            ));
+        }
+    }
+
+    /// Show the contents as formatted JSON, with a warning if they aren't valid JSON.
+    fn show_json(&mut self, ui: &mut egui::Ui, commonmark_cache: &mut CommonMarkCache, idx: usize) {
+        if self.json_view.as_ref().map_or(true, |view| {
+            view.content_hash != content_hash(&self.content)
+        }) {
+            self.json_view = Some(JsonView::new(&self.content));
+        }
+        let Some(view) = &self.json_view else {
+            return;
+        };
+        ui.vertical(|ui| {
+            // compensate for the space removed above markdown
+            ui.add_space(24.0);
+            match &view.formatted {
+                Ok(formatted) => {
+                    ui.label(egui::RichText::new(tr("chat.valid_json")).small().weak());
+                    CommonMarkViewer::new(ui.id().with(("message_json", idx))).show(
+                        ui,
+                        commonmark_cache,
+                        formatted,
+                    );
+                }
+                Err(e) => {
+                    let error_color = ui.visuals().error_fg_color;
+                    Frame::group(ui.style())
+                        .stroke(Stroke::new(1.0, error_color))
+                        .fill(error_color.gamma_multiply(0.08))
+                        .show(ui, |ui| {
+                            ui.label(
+                                egui::RichText::new(tr("chat.invalid_json"))
+                                    .color(error_color)
+                                    .strong(),
+                            );
+                            ui.label(egui::RichText::new(e).small().weak());
+                        });
+                    ui.add(
+                        egui::Label::new(egui::RichText::new(&self.content).monospace()).wrap(true),
+                    );
+                }
+            }
+        });
+    }
+
+    /// Note editor of the message context menu, returns whether the note changed.
+    fn edit_note(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.label(tr("chat.note"));
+        let mut changed = ui
+            .add(
+                egui::TextEdit::multiline(&mut self.note)
+                    .hint_text(tr("chat.note_hint"))
+                    .desired_rows(2),
+            )
+            .changed();
+        if !self.note.is_empty() && ui.button(tr("chat.remove_note")).clicked() {
+            self.note.clear();
+            changed = true;
+            ui.close_menu();
+        }
+        changed
+    }
+
+    /// Contents to render, with guessed languages for untagged code blocks and rendered math
+    fn display_content(&self) -> &str {
+        match &self.tagged_content {
+            Some(tagged) if !self.is_generating => &tagged.text,
+            _ => &self.content,
+        }
+    }
+
+    /// Where the code block starts in [`Self::display_content`], if the message is a short
+    /// preamble followed by a single code block
+    fn preamble_split(&self) -> Option<usize> {
+        if self.is_generating {
+            return None;
+        }
+        self.tagged_content.as_ref().and_then(|t| t.preamble_split)
+    }
+
+    fn show_code_languages(&mut self, ui: &mut egui::Ui) {
+        let Some(tagged) = &self.tagged_content else {
+            return;
+        };
+        if tagged.untagged.is_empty() {
+            return;
+        }
+        let untagged = tagged.untagged.clone();
+        let mut changed = false;
+        ui.horizontal_wrapped(|ui| {
+            for (block, guess) in untagged {
+                let mut lang = self.code_languages.get(&block).cloned().unwrap_or_default();
+                ui.add_enabled(
+                    false,
+                    egui::Label::new(
+                        egui::RichText::new(tr_args("chat.code_block", &[&(block + 1)])).small(),
+                    ),
+                );
+                let resp = ui
+                    .add(
+                        egui::TextEdit::singleline(&mut lang)
+                            .hint_text(guess.map_or_else(|| "text".to_owned(), |g| format!("{g}?")))
+                            .font(egui::TextStyle::Small)
+                            .desired_width(64.0),
+                    )
+                    .on_hover_text(tr("chat.code_language_help"));
+                if resp.changed() {
+                    if lang.trim().is_empty() {
+                        self.code_languages.remove(&block);
+                    } else {
+                        self.code_languages.insert(block, lang.trim().to_owned());
+                    }
+                    changed = true;
+                }
+            }
+        });
+        if changed {
+            self.tagged_content = None;
+        }
+    }
+
+    fn show(
         &mut self,
         ui: &mut egui::Ui,
         commonmark_cache: &mut CommonMarkCache,
         #[cfg(feature = "tts")] tts: SharedTts,
         idx: usize,
         prepend_buf: &mut String,
+        reply_options: ReplyOptions,
+        regenerate_models: Option<&[String]>,
+        highlights: Option<FindHighlights>,
+        author: &str,
     ) -> MessageAction {
+        self.update_tagged_content(reply_options.render_math);
+        let mut action = MessageAction::None;
+
         // message role
         let message_offset = ui
             .horizontal(|ui| {
-                if self.is_user() {
+                let offset = if self.is_user() {
                     let f = ui.label("👤").rect.left();
-                    ui.label("You").rect.left() - f
+                    let name = ui
+                        .add(egui::Label::new(author).sense(egui::Sense::click()))
+                        .on_hover_text(tr("chat.add_note_help"));
+                    name.context_menu(|ui| {
+                        if self.edit_note(ui) {
+                            action = MessageAction::NoteEdited;
+                        }
+                    });
+                    name.rect.left() - f
                 } else {
-                    let f = ui.label("🐱").rect.left();
-                    let offset = ui
-                        .label(make_short_name(&self.model_name))
-                        .on_hover_text(&self.model_name)
-                        .rect
-                        .left()
-                        - f;
+                    let f = model_avatar(ui, &self.model_name).rect.left();
+                    let name = ui
+                        .add(egui::Label::new(author).sense(egui::Sense::click()))
+                        .on_hover_text(format!(
+                            "{}\n{}",
+                            self.model_name,
+                            tr("chat.add_note_help")
+                        ));
+                    let offset = name.rect.left() - f;
+                    name.context_menu(|ui| {
+                        if self.edit_note(ui) {
+                            action = MessageAction::NoteEdited;
+                        }
+                        if !self.sources.is_empty() {
+                            ui.separator();
+                            show_sources(ui, &self.sources);
+                        }
+                        ui.separator();
+                        if ui
+                            .add_enabled(
+                                self.inspection.is_some(),
+                                egui::Button::new(tr("chat.view_request")),
+                            )
+                            .on_disabled_hover_text(tr("chat.view_request_unavailable"))
+                            .clicked()
+                        {
+                            action = MessageAction::ViewRequest(idx);
+                            ui.close_menu();
+                        }
+                    });
                     ui.add_enabled(false, egui::Label::new(&self.model_name));
+                    if !self.sources.is_empty() {
+                        ui.add(
+                            egui::Label::new(
+                                egui::RichText::new(format!("📚 {}", self.sources.len()))
+                                    .small()
+                                    .weak(),
+                            )
+                            .sense(egui::Sense::click()),
+                        )
+                        .on_hover_text(tr("chat.sources_help"))
+                        .context_menu(|ui| show_sources(ui, &self.sources));
+                    }
                     offset
+                };
+                if let Some(time) = self.time {
+                    let ago = timeago::Formatter::new().convert_chrono(time, chrono::Utc::now());
+                    ui.label(egui::RichText::new(ago).small().weak())
+                        .on_hover_text(time.to_rfc3339());
                 }
+                offset
             })
             .inner;
 
         // for some reason commonmark creates empty space above it when created,
         // compensate for that
-        let is_commonmark = !self.content.is_empty() && !self.is_error && !self.is_prepending;
+        let is_commonmark = !self.content.is_empty()
+            && !self.is_error
+            && !self.is_prepending
+            && !self.show_source
+            && highlights.is_none();
         if is_commonmark {
             ui.add_space(-24.0);
         }
 
         // message content / spinner
-        let mut action = MessageAction::None;
         ui.horizontal(|ui| {
             ui.add_space(message_offset);
             if self.content.is_empty() && self.is_generating && !self.is_error {
                 ui.horizontal(|ui| {
                     ui.add(egui::Spinner::new());
 
-                    // show time spent waiting for response
+                    // show time spent waiting for response, big models on CPU can take a while
+                    // to load
                     ui.add_enabled(
                         false,
-                        egui::Label::new(format!(
-                            "{:.1}s",
-                            self.requested_at.elapsed().as_secs_f64()
+                        egui::Label::new(tr_args(
+                            "chat.waiting",
+                            &[&format!("{:.1}", self.requested_at.elapsed().as_secs_f64())],
                         )),
-                    )
+                    );
+                    ui.ctx().request_repaint();
                 });
             } else if self.is_error {
-                ui.label("An error occurred while requesting completion");
-                if ui
-                    .button("Retry")
-                    .on_hover_text(
-                        "Try to generate a response again. Make sure you have Ollama running",
-                    )
-                    .clicked()
-                {
-                    action = MessageAction::Retry(idx);
-                }
+                let error_color = ui.visuals().error_fg_color;
+                Frame::group(ui.style())
+                    .stroke(Stroke::new(1.0, error_color))
+                    .fill(error_color.gamma_multiply(0.08))
+                    .show(ui, |ui| {
+                        // whatever was streamed before the failure
+                        if self.error.is_some() && !self.content.is_empty() {
+                            ui.add(
+                                egui::Label::new(egui::RichText::new(&self.content).weak())
+                                    .wrap(true),
+                            );
+                            ui.separator();
+                        }
+                        let details = self.error.as_deref().unwrap_or(&self.content);
+                        ui.label(
+                            egui::RichText::new(widgets::explain_request_error(details))
+                                .color(error_color)
+                                .strong(),
+                        );
+                        ui.add(
+                            egui::Label::new(egui::RichText::new(details).small().weak())
+                                .wrap(true),
+                        );
+                        ui.horizontal(|ui| {
+                            if ui
+                                .button(tr("chat.retry"))
+                                .on_hover_text(tr("chat.retry_help"))
+                                .clicked()
+                            {
+                                action = MessageAction::Retry(idx);
+                            }
+                            if ui
+                                .button(tr("chat.copy_error"))
+                                .on_hover_text(tr("chat.copy_error_help"))
+                                .clicked()
+                            {
+                                ui.ctx().copy_text(details.to_owned());
+                            }
+                        });
+                    });
             } else if self.is_prepending {
-                let textedit = ui.add(
-                    egui::TextEdit::multiline(prepend_buf).hint_text("Prepend text to response…"),
-                );
+                let textedit = ui
+                    .add(egui::TextEdit::multiline(prepend_buf).hint_text(tr("chat.prepend_hint")));
                 macro_rules! cancel_prepend {
                     () => {
                         self.is_prepending = false;
@@ -230,40 +1366,136 @@ impl Message {
                 }
                 ui.vertical(|ui| {
                     if ui
-                        .button("🔄 Regenerate")
-                        .on_hover_text(
-                            "Generate the response again, \
-                            the LLM will start after any prepended text",
-                        )
+                        .button(tr("chat.regenerate"))
+                        .on_hover_text(tr("chat.regenerate_prepended_help"))
                         .clicked()
                     {
                         self.content = prepend_buf.clone();
                         self.is_prepending = false;
                         self.is_generating = true;
+                        self.incomplete = true;
                         action = MessageAction::Regenerate(idx);
                     }
                     if !prepend_buf.is_empty()
                         && ui
-                            .button("\u{270f} Edit")
-                            .on_hover_text(
-                                "Edit the message in the context, but don't regenerate it",
-                            )
+                            .button(tr("chat.edit_message"))
+                            .on_hover_text(tr("chat.edit_message_help"))
                             .clicked()
                     {
                         self.content = prepend_buf.clone();
                         cancel_prepend!();
                     }
-                    if ui.button("❌ Cancel").clicked() {
+                    if ui.button(tr("common.cancel")).clicked() {
                         cancel_prepend!();
                     }
                 });
+            } else if let Some(highlights) = highlights {
+                // matches are found in the raw text, show it as is while searching
+                show_highlighted(ui, &self.content, highlights);
+            } else if self.show_source {
+                ui.add(egui::Label::new(egui::RichText::new(&self.content).monospace()).wrap(true));
+            } else if reply_options.json && !self.is_user() && !self.is_generating {
+                self.show_json(ui, commonmark_cache, idx);
             } else {
-                CommonMarkViewer::new(format!("message_{idx}_commonmark"))
-                    .max_image_width(Some(512))
-                    .show(ui, commonmark_cache, &self.content);
+                let content = self.display_content();
+                let split = if reply_options.collapse_preamble {
+                    self.preamble_split()
+                } else {
+                    None
+                };
+                if let Some(split) = split {
+                    ui.vertical(|ui| {
+                        ui.add_space(24.0);
+                        egui::CollapsingHeader::new(
+                            egui::RichText::new(tr("chat.preamble")).small(),
+                        )
+                        .id_source(("message_preamble", idx))
+                        .show(ui, |ui| {
+                            ui.label(content[..split].trim());
+                        });
+                        CommonMarkViewer::new(ui.id().with(("message_commonmark", idx)))
+                            .max_image_width(Some(512))
+                            .show(ui, commonmark_cache, &content[split..]);
+                    });
+                } else {
+                    CommonMarkViewer::new(ui.id().with(("message_commonmark", idx)))
+                        .max_image_width(Some(512))
+                        .show(ui, commonmark_cache, content);
+                }
             }
         });
 
+        if is_commonmark && !self.is_generating {
+            ui.horizontal(|ui| {
+                ui.add_space(message_offset);
+                self.show_code_languages(ui);
+            });
+        }
+
+        // streaming indicator
+        if self.is_generating && !self.content.is_empty() && !self.is_error {
+            ui.horizontal(|ui| {
+                ui.add_space(message_offset);
+                ui.add(egui::Spinner::new().size(10.0));
+                ui.add_enabled(
+                    false,
+                    egui::Label::new(
+                        egui::RichText::new(tr_args(
+                            "chat.generating",
+                            &[&format!("{:.1}", self.requested_at.elapsed().as_secs_f64())],
+                        ))
+                        .small(),
+                    ),
+                );
+            });
+        }
+
+        // the user's note, below the message it's about
+        if !self.note.is_empty() {
+            ui.horizontal(|ui| {
+                ui.add_space(message_offset);
+                let note_color = egui::Color32::from_rgb(0xf5, 0xd7, 0x6e);
+                Frame::none()
+                    .fill(note_color.gamma_multiply(0.2))
+                    .stroke(Stroke::new(1.0, note_color.gamma_multiply(0.6)))
+                    .rounding(Rounding::same(4.0))
+                    .inner_margin(Margin::symmetric(6.0, 2.0))
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::Label::new(
+                                egui::RichText::new(format!("📝 {}", self.note)).small(),
+                            )
+                            .wrap(true),
+                        )
+                        .on_hover_text(tr("chat.note_help"));
+                    });
+            });
+        }
+
+        // cut off by closing the app or a stalled connection
+        if self.incomplete && !self.is_generating && !self.is_error && !self.is_user() {
+            ui.horizontal(|ui| {
+                ui.add_space(message_offset);
+                ui.label(
+                    egui::RichText::new(tr("chat.interrupted"))
+                        .small()
+                        .color(ui.visuals().warn_fg_color),
+                )
+                .on_hover_text(self.error.as_deref().unwrap_or(tr("chat.interrupted_help")));
+                if ui
+                    .add_enabled(
+                        regenerate_models.is_some(),
+                        egui::Button::new(tr("chat.continue")),
+                    )
+                    .on_hover_text(tr("chat.continue_help"))
+                    .on_disabled_hover_text(tr("chat.wait_for_response"))
+                    .clicked()
+                {
+                    action = MessageAction::Continue(idx);
+                }
+            });
+        }
+
         // images
         if !self.images.is_empty() {
             if is_commonmark {
@@ -294,6 +1526,30 @@ impl Message {
             ui.add_space(-12.0);
             ui.horizontal(|ui| {
                 ui.add_space(message_offset);
+                if reply_options.copy_code_only && self.preamble_split().is_some() {
+                    let copy_code = ui
+                        .add(
+                            egui::Button::new(if self.clicked_copy_code {
+                                "✔"
+                            } else {
+                                tr("chat.copy_code_button")
+                            })
+                            .small()
+                            .fill(egui::Color32::TRANSPARENT),
+                        )
+                        .on_hover_text(if self.clicked_copy_code {
+                            tr("chat.copied")
+                        } else {
+                            tr("chat.copy_code")
+                        });
+                    if copy_code.clicked() {
+                        if let Some(block) = langdetect::fenced_blocks(&self.content).pop() {
+                            ui.ctx().copy_text(block.code);
+                            self.clicked_copy_code = true;
+                        }
+                    }
+                    self.clicked_copy_code = self.clicked_copy_code && copy_code.hovered();
+                }
                 let copy = ui
                     .add(
                         egui::Button::new(if self.clicked_copy { "✔" } else { "🗐" })
@@ -301,9 +1557,9 @@ impl Message {
                             .fill(egui::Color32::TRANSPARENT),
                     )
                     .on_hover_text(if self.clicked_copy {
-                        "Copied!"
+                        tr("chat.copied")
                     } else {
-                        "Copy message"
+                        tr("chat.copy_message")
                     });
                 if copy.clicked() {
                     ui.ctx().copy_text(self.content.clone());
@@ -311,15 +1567,68 @@ impl Message {
                 }
                 self.clicked_copy = self.clicked_copy && copy.hovered();
 
-                #[cfg(feature = "tts")]
+                if ui
+                    .add(
+                        egui::Button::new("</>")
+                            .small()
+                            .selected(self.show_source)
+                            .fill(egui::Color32::TRANSPARENT),
+                    )
+                    .on_hover_text(if self.show_source {
+                        tr("chat.show_rendered")
+                    } else {
+                        tr("chat.view_source")
+                    })
+                    .clicked()
+                {
+                    self.show_source = !self.show_source;
+                }
+
+                if !self.is_user() {
+                    for rating in [Rating::Good, Rating::Bad] {
+                        let rated = self.rating == Some(rating);
+                        if ui
+                            .add(
+                                egui::Button::new(rating.icon())
+                                    .small()
+                                    .selected(rated)
+                                    .fill(egui::Color32::TRANSPARENT),
+                            )
+                            .on_hover_text(rating.description())
+                            .clicked()
+                        {
+                            self.rating = (!rated).then_some(rating);
+                            action = MessageAction::Rated;
+                        }
+                    }
+                }
+
+                if self.is_user()
+                    && ui
+                        .add(
+                            egui::Button::new("⭐")
+                                .small()
+                                .fill(egui::Color32::TRANSPARENT),
+                        )
+                        .on_hover_text(tr("chat.save_golden"))
+                        .clicked()
                 {
+                    action = MessageAction::SaveGolden(idx);
+                }
+
+                #[cfg(feature = "tts")]
+                if !self.is_user() && !self.is_error {
                     let speak = ui
                         .add(
-                            egui::Button::new(if self.is_speaking { "…" } else { "🔊" })
+                            egui::Button::new(if self.is_speaking { "⏹" } else { "🔊" })
                                 .small()
                                 .fill(egui::Color32::TRANSPARENT),
                         )
-                        .on_hover_text("Read the message out loud. Right click to repeat");
+                        .on_hover_text(if self.is_speaking {
+                            tr("chat.stop_reading")
+                        } else {
+                            tr("chat.read_aloud")
+                        });
 
                     if speak.clicked() {
                         if self.is_speaking {
@@ -327,14 +1636,40 @@ impl Message {
                             tts_control(tts, String::new(), false);
                         } else {
                             self.is_speaking = true;
-                            tts_control(tts, self.content.clone(), true);
+                            tts_control(tts, speakable_text(&self.content), true);
                         }
                     } else if speak.secondary_clicked() {
                         self.is_speaking = true;
-                        tts_control(tts, self.content.clone(), true);
+                        tts_control(tts, speakable_text(&self.content), true);
                     }
                 }
 
+                if ui
+                    .add(
+                        egui::Button::new("↪")
+                            .small()
+                            .fill(egui::Color32::TRANSPARENT),
+                    )
+                    .on_hover_text(tr("chat.branch_help"))
+                    .clicked()
+                {
+                    action = MessageAction::Branch(idx);
+                }
+
+                if ui
+                    .add_enabled(
+                        regenerate_models.is_some(),
+                        egui::Button::new("🗑")
+                            .small()
+                            .fill(egui::Color32::TRANSPARENT),
+                    )
+                    .on_hover_text(tr("chat.delete_message"))
+                    .on_disabled_hover_text(tr("chat.wait_for_response"))
+                    .clicked()
+                {
+                    action = MessageAction::Delete(idx);
+                }
+
                 if !self.is_user()
                     && prepend_buf.is_empty()
                     && ui
@@ -343,12 +1678,33 @@ impl Message {
                                 .small()
                                 .fill(egui::Color32::TRANSPARENT),
                         )
-                        .on_hover_text("Regenerate")
+                        .on_hover_text(tr("chat.regenerate_help"))
                         .clicked()
                 {
                     prepend_buf.clear();
                     self.is_prepending = true;
                 }
+
+                if !self.is_user() {
+                    ui.add_enabled_ui(regenerate_models.is_some(), |ui| {
+                        ui.menu_button("🔄…", |ui| {
+                            ui.label(tr("chat.regenerate_with"));
+                            ui.separator();
+                            for model in regenerate_models.unwrap_or_default() {
+                                if ui.button(model).clicked() {
+                                    action = MessageAction::RegenerateWith {
+                                        idx,
+                                        model: model.clone(),
+                                    };
+                                    ui.close_menu();
+                                }
+                            }
+                        })
+                        .response
+                        .on_hover_text(tr("chat.regenerate_with_help"))
+                        .on_disabled_hover_text(tr("chat.wait_for_response"));
+                    });
+                }
             });
             ui.add_space(8.0);
         }
@@ -357,141 +1713,973 @@ impl Message {
     }
 }
 
-// <completion progress, final completion, error>
-type CompletionFlower = CompactFlower<(usize, String), (usize, String), (usize, String)>;
-type CompletionFlowerHandle = CompactHandle<(usize, String), (usize, String), (usize, String)>;
+/// Totals of a chat, shown in its stats popover.
+#[derive(Default)]
+struct ChatStats {
+    user_messages: usize,
+    replies: usize,
+    errors: usize,
+    words: usize,
+    characters: usize,
+    prompt_tokens: u64,
+    reply_tokens: u64,
+    /// Whether any token count is estimated
+    estimated: bool,
+    generation_time: Duration,
+    /// Speeds of the replies that reported one
+    speeds: Vec<f64>,
+    /// Number of replies by model, in the order the models first answered
+    models: Vec<(String, usize)>,
+}
 
-#[derive(serde::Deserialize, serde::Serialize)]
+impl ChatStats {
+    fn add_text(&mut self, text: &str) {
+        self.words += text.split_whitespace().count();
+        self.characters += text.chars().count();
+    }
+
+    fn add_reply(&mut self, reply: &Message, (usage, estimated): (TokenUsage, bool)) {
+        self.replies += 1;
+        self.prompt_tokens += usage.prompt_tokens;
+        self.reply_tokens += usage.reply_tokens;
+        self.estimated |= estimated;
+        if let Some(stats) = reply.stats {
+            self.generation_time += stats.total_time;
+            self.speeds.extend(stats.tokens_per_second);
+        }
+        match self
+            .models
+            .iter_mut()
+            .find(|(name, _)| *name == reply.model_name)
+        {
+            Some((_, count)) => *count += 1,
+            None => self.models.push((reply.model_name.clone(), 1)),
+        }
+    }
+}
+
+/// Per-chat presentation options for assistant replies. These never alter the stored messages.
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize)]
 #[serde(default)]
-pub struct Chat {
-    chatbox: String,
-    #[serde(skip)]
-    chatbox_height: f32,
-    pub messages: Vec<Message>,
-    #[serde(skip)]
-    flower: CompletionFlower,
+pub struct ReplyOptions {
+    /// Make "copy code only" the primary copy action of preamble + code block replies
+    copy_code_only: bool,
+    /// Collapse the preamble of preamble + code block replies
+    collapse_preamble: bool,
+    /// Show `$…$` and `$$…$$` LaTeX math as Unicode text
+    render_math: bool,
+    /// Include the system prompt when copying the whole conversation
+    copy_system_prompt: bool,
+    /// Show replies as formatted JSON, set from the chat's JSON mode while drawing
     #[serde(skip)]
-    retry_message_idx: Option<usize>,
-    pub summary: String,
-    #[serde(skip)]
-    chatbox_highlighter: MemoizedEasymarkHighlighter,
-    stop_generating: Arc<AtomicBool>,
-    #[serde(skip)]
-    virtual_list: VirtualList,
-    pub model_picker: ModelPicker,
-    pub images: Vec<PathBuf>,
-    prepend_buf: String,
+    json: bool,
 }
 
-impl Default for Chat {
+impl Default for ReplyOptions {
     fn default() -> Self {
         Self {
-            chatbox: String::new(),
-            chatbox_height: 0.0,
-            messages: Vec::new(),
-            flower: CompletionFlower::new(1),
-            retry_message_idx: None,
-            summary: String::new(),
-            chatbox_highlighter: MemoizedEasymarkHighlighter::default(),
-            stop_generating: Arc::new(AtomicBool::new(false)),
-            virtual_list: VirtualList::new(),
-            model_picker: ModelPicker::default(),
-            images: Vec::new(),
-            prepend_buf: String::new(),
+            copy_code_only: false,
+            collapse_preamble: false,
+            render_math: true,
+            copy_system_prompt: true,
+            json: false,
         }
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-async fn request_completion(
-    ollama: Ollama,
-    messages: Vec<ChatMessage>,
-    handle: &CompletionFlowerHandle,
-    stop_generating: Arc<AtomicBool>,
-    selected_model: String,
-    options: GenerationOptions,
-    template: Option<String>,
-    index: usize,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    log::info!(
-        "requesting completion... (history length: {})",
-        messages.len()
-    );
+/// How the conversation is fit into the model's context window.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum ContextStrategy {
+    /// Send the whole conversation, Ollama drops the start of it when it doesn't fit
+    #[default]
+    SendAll,
+    /// Send the latest messages and the system prompt
+    SlidingWindow,
+    /// Replace the oldest messages with a summary when the conversation gets close to the
+    /// context window
+    SummarizeOverflow,
+}
 
-    // if any assistant message was prepended, save it so we can prepend it
-    // to the final response
-    let prepend = {
-        if let Some(last) = messages.last() {
-            if last.role == ollama_rs::generation::chat::MessageRole::Assistant {
-                last.content.clone()
-            } else {
-                String::new()
-            }
-        } else {
-            String::new()
+impl ContextStrategy {
+    pub const ALL: [Self; 3] = [Self::SendAll, Self::SlidingWindow, Self::SummarizeOverflow];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::SendAll => tr("chat.strategy_send_all"),
+            Self::SlidingWindow => tr("chat.strategy_sliding_window"),
+            Self::SummarizeOverflow => tr("chat.strategy_summarize"),
         }
-    };
+    }
 
-    let mut request = ChatMessageRequest::new(selected_model, messages).options(options);
-    if let Some(template) = template {
-        request = request.template(template);
+    fn description(self) -> &'static str {
+        match self {
+            Self::SendAll => tr("chat.strategy_send_all_help"),
+            Self::SlidingWindow => tr("chat.strategy_sliding_window_help"),
+            Self::SummarizeOverflow => tr("chat.strategy_summarize_help"),
+        }
     }
-    let mut stream: ChatMessageResponseStream = ollama.send_chat_messages_stream(request).await?;
+}
 
-    log::info!("reading response...");
+/// Per-chat context window management.
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ContextOptions {
+    pub strategy: ContextStrategy,
+    /// Number of messages sent by [`ContextStrategy::SlidingWindow`]
+    pub window: usize,
+    /// Share of the context window the conversation may take before
+    /// [`ContextStrategy::SummarizeOverflow`] summarizes it
+    pub threshold: f32,
+}
 
-    let mut response = String::new();
-    let mut is_whitespace = true;
+impl Default for ContextOptions {
+    fn default() -> Self {
+        Self {
+            strategy: ContextStrategy::default(),
+            window: 20,
+            threshold: 0.75,
+        }
+    }
+}
 
-    while let Some(Ok(res)) = stream.next().await {
-        if let Some(msg) = res.message {
-            if is_whitespace && msg.content.trim().is_empty() {
-                continue;
+/// Summary of the first `covers` messages, sent in their place.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct ContextSummary {
+    text: String,
+    covers: usize,
+}
+
+impl ContextSummary {
+    fn to_message(text: &str) -> ChatMessage {
+        ChatMessage::system(format!("Summary of the earlier conversation:\n{text}"))
+    }
+}
+
+/// Messages to fold into a new summary before a request is sent.
+struct SummaryJob {
+    previous: Option<String>,
+    folded: Vec<ChatMessage>,
+    /// Number of messages the new summary covers
+    covers: usize,
+    /// Position of the summary in the request's messages
+    slot: usize,
+}
+
+/// Which messages the last request was sent with.
+#[derive(Debug, Clone, Copy)]
+struct SentContext {
+    /// Index of the first message sent as is
+    start: usize,
+    /// Whether the messages before it were sent as a summary
+    summarized: bool,
+}
+
+/// Estimated size of the next request, updated shortly after the input stops changing.
+#[derive(Default)]
+struct PromptEstimate {
+    /// (input length, attachments, messages, generating) the estimate was made for
+    source: (usize, usize, usize, bool),
+    /// When `source` last changed, `None` once estimated
+    changed_at: Option<Instant>,
+    tokens: u64,
+}
+
+/// Text file attached to the next prompt.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct FileAttachment {
+    pub name: String,
+    pub content: String,
+}
+
+impl FileAttachment {
+    /// Contents wrapped in a fenced block labeled with the file name
+    fn to_prompt(&self) -> String {
+        // the fence has to be longer than any backtick run inside the file
+        let longest_run = self
+            .content
+            .split(|c| c != '`')
+            .map(str::len)
+            .max()
+            .unwrap_or(0);
+        let fence = "`".repeat(longest_run.max(2) + 1);
+        let lang = Path::new(&self.name)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+        format!(
+            "File `{}`:\n{fence}{lang}\n{}\n{fence}",
+            self.name,
+            self.content.trim_end()
+        )
+    }
+
+    fn show(&self, ui: &mut egui::Ui, idx: usize) -> bool {
+        let mut remove = false;
+        ui.horizontal(|ui| {
+            if ui
+                .small_button("❌")
+                .on_hover_text(tr("chat.remove_attachment"))
+                .clicked()
+            {
+                remove = true;
             }
-            let content = if is_whitespace {
-                msg.content.trim_start()
-            } else {
-                &msg.content
-            };
-            is_whitespace = false;
+            egui::CollapsingHeader::new(format!(
+                "📄 {} ({})",
+                self.name,
+                bytesize::ByteSize(self.content.len() as u64)
+            ))
+            .id_source(("file_attachment", idx))
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        ui.add(egui::Label::new(
+                            egui::RichText::new(&self.content).monospace(),
+                        ));
+                    });
+            });
+        });
+        remove
+    }
+}
 
-            // send message to gui thread
-            handle.send((index, content.to_string()));
-            response += content;
+/// Number of sent prompts remembered per chat
+const PROMPT_HISTORY_LEN: usize = 100;
 
-            if stop_generating.load(Ordering::SeqCst) {
-                log::info!("stopping generation");
-                drop(stream);
-                stop_generating.store(false, Ordering::SeqCst);
-                break;
+/// Number of lines the chat input grows to before it starts scrolling
+const MAX_CHATBOX_LINES: f32 = 8.0;
+
+/// How long the input has to stay unchanged before the request size is estimated again
+const ESTIMATE_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Final result of a completion request.
+struct Completion {
+    /// Index of the reply
+    index: usize,
+    content: String,
+    usage: Option<TokenUsage>,
+    stats: Option<ResponseStats>,
+    /// Knowledge excerpts the reply was given
+    sources: Vec<Retrieved>,
+    /// Summary of the earlier messages made for the request
+    summary: Option<ContextSummary>,
+    /// Why the reply was cut off before it was complete, if it was
+    interrupted: Option<String>,
+    /// Request and response metadata as pretty-printed JSON
+    inspection: String,
+}
+
+/// Progress of a streaming reply.
+enum ReplyProgress {
+    /// Next piece of the reply
+    Text(String),
+    /// The reply stalled and is requested again, starting over from the given text
+    Restart(String),
+}
+
+// <completion progress, final completion, error>
+type CompletionFlower = CompactFlower<(usize, ReplyProgress), Completion, (usize, String)>;
+type CompletionFlowerHandle = CompactHandle<(usize, ReplyProgress), Completion, (usize, String)>;
+
+/// Streaming task of a chat, aborted when the chat is dropped so removed chats don't keep
+/// the server busy.
+#[derive(Default)]
+struct CompletionTask(Option<tokio::task::AbortHandle>);
+
+impl Drop for CompletionTask {
+    fn drop(&mut self) {
+        if let Some(task) = self.0.take() {
+            task.abort();
+        }
+    }
+}
+
+// <no progress, transcription, error>
+#[cfg(feature = "voice")]
+type TranscribeFlower = CompactFlower<(), String, String>;
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Chat {
+    /// Identifies the chat across saves and backups, unlike the flower id
+    pub uid: u64,
+    chatbox: String,
+    #[serde(skip)]
+    chatbox_height: f32,
+    /// Id of the chat input, to read and move its cursor
+    #[serde(skip)]
+    chatbox_id: Option<egui::Id>,
+    /// Key combination that sends the message, updated by [`crate::sessions::Sessions`]
+    /// each frame
+    #[serde(skip)]
+    pub send_shortcut: SendShortcut,
+    /// Name shown on the user's messages, updated by [`crate::sessions::Sessions`] each
+    /// frame
+    #[serde(skip)]
+    pub user_name: String,
+    /// Whether to show the temperature and seed controls, updated by
+    /// [`crate::sessions::Sessions`] each frame
+    #[serde(skip)]
+    pub quick_settings: bool,
+    pub messages: Vec<Message>,
+    #[serde(skip)]
+    flower: CompletionFlower,
+    /// Each chat streams its own responses, independently of which chat is shown
+    #[serde(skip)]
+    completion_task: CompletionTask,
+    #[serde(skip)]
+    retry_message_idx: Option<usize>,
+    pub summary: String,
+    /// Shown in its own section at the top of the chat list
+    pub pinned: bool,
+    /// Hidden from the chat list, in the "Archived" section
+    pub archived: bool,
+    pub tags: Vec<String>,
+    /// A response completed that hasn't been scrolled to yet
+    pub unread: bool,
+    #[serde(skip)]
+    chatbox_highlighter: MemoizedEasymarkHighlighter,
+    stop_generating: Arc<AtomicBool>,
+    #[serde(skip)]
+    row_heights: RowHeights,
+    pub model_picker: ModelPicker,
+    pub images: Vec<PathBuf>,
+    /// Text files attached to the next prompt
+    pub files: Vec<FileAttachment>,
+    /// Sent prompts, oldest first
+    prompt_history: Vec<String>,
+    /// Position in `prompt_history` while recalling, `None` while typing a new prompt
+    #[serde(skip)]
+    history_pos: Option<usize>,
+    /// Text typed before recalling the history, restored at the bottom of it
+    #[serde(skip)]
+    history_draft: String,
+    /// Whether the input cursor was on the first and last line last frame
+    #[serde(skip)]
+    cursor_rows: (bool, bool),
+    prepend_buf: String,
+    pub system_prompt: String,
+    /// Inference settings used instead of the shared ones, if set
+    pub settings_override: Option<ModelSettings>,
+    /// The settings of the chat's own model picker were moved into `settings_override`,
+    /// unset for chats saved before chats could override the shared settings
+    #[serde(default)]
+    picker_settings_migrated: bool,
+    reply_options: ReplyOptions,
+    /// Shared inference settings, updated by [`crate::sessions::Sessions`] each frame
+    #[serde(skip)]
+    pub inherited_settings: ModelSettings,
+    /// Proxy used for requests, if any, updated by [`crate::sessions::Sessions`] each frame
+    #[serde(skip)]
+    pub proxy: Option<widgets::ProxyInUse>,
+    /// Client of the OpenAI-compatible server, `None` when chatting through Ollama, updated
+    /// by [`crate::sessions::Sessions`] each frame
+    #[serde(skip)]
+    pub openai: Option<OpenAiClient>,
+    /// Client chats with Ollama are streamed from, updated by
+    /// [`crate::sessions::Sessions`] each frame
+    #[serde(skip)]
+    pub ollama_client: OllamaClient,
+    /// Request timeouts, updated by [`crate::sessions::Sessions`] each frame
+    #[serde(skip)]
+    pub timeouts: Timeouts,
+    #[serde(skip)]
+    show_info: bool,
+    /// Golden prompt being saved from a message
+    #[serde(skip)]
+    golden_draft: Option<GoldenPrompt>,
+    /// Reply whose request is shown in the inspector, and whether it's the compared reply
+    #[serde(skip)]
+    inspected: Option<(usize, bool)>,
+    /// "Clear conversation" was picked, ask for confirmation on the next frame
+    #[serde(skip)]
+    confirm_clear: bool,
+    /// Whether messages were added or completed since [`Chat::take_modified`] was called
+    #[serde(skip)]
+    modified: bool,
+    #[serde(skip)]
+    find: FindBar,
+    /// Message to branch a new chat from, reported by [`Chat::show`]
+    #[serde(skip)]
+    branch_from: Option<usize>,
+    /// Message to remove, reported by [`Chat::show`] so it can be restored
+    #[serde(skip)]
+    delete_message: Option<usize>,
+    /// Number of messages copied with "Copy conversation", reported by [`Chat::show`]
+    #[serde(skip)]
+    copied_messages: Option<usize>,
+    /// Message to scroll to and highlight, and when it was requested
+    #[serde(skip)]
+    reveal: Option<(usize, Instant)>,
+    /// Whether the revealed message has been scrolled to
+    #[serde(skip)]
+    revealed: bool,
+    /// Scroll to the end of the messages on the next frame
+    #[serde(skip)]
+    jump_to_bottom: bool,
+    /// Messages arrived below while scrolled up
+    #[serde(skip)]
+    new_below: bool,
+    /// Number of messages and length of the last one when last shown, to notice new content
+    #[serde(skip)]
+    shown_content: (usize, usize),
+    /// Read each response aloud while it's being generated
+    #[cfg(feature = "tts")]
+    auto_speak: bool,
+    /// Index of the message being read aloud as it streams in
+    #[cfg(feature = "tts")]
+    #[serde(skip)]
+    speech_stream: Option<(usize, SentenceSplitter)>,
+    /// Whisper model used for voice input, updated by [`crate::sessions::Sessions`] each frame
+    #[cfg(feature = "voice")]
+    #[serde(skip)]
+    pub whisper_model: String,
+    #[cfg(feature = "voice")]
+    #[serde(skip)]
+    recorder: Option<voice::Recorder>,
+    #[cfg(feature = "voice")]
+    #[serde(skip)]
+    transcribe_flower: TranscribeFlower,
+    /// Why the microphone can't be used
+    #[cfg(feature = "voice")]
+    #[serde(skip)]
+    mic_error: Option<String>,
+    #[cfg(feature = "voice")]
+    #[serde(skip)]
+    transcribe_error: Option<String>,
+    /// Documents answers are grounded in
+    pub knowledge: Knowledge,
+    #[serde(skip)]
+    index_flower: IndexFlower,
+    #[serde(skip)]
+    index_task: CompletionTask,
+    /// Embedded and total chunks of the running indexing
+    #[serde(skip)]
+    index_progress: Option<(usize, usize)>,
+    #[serde(skip)]
+    index_error: Option<String>,
+    /// Second model answering each prompt next to the selected one, in compare mode
+    pub compare_model: Option<SelectedModel>,
+    #[serde(skip)]
+    compare_flower: CompletionFlower,
+    #[serde(skip)]
+    compare_task: CompletionTask,
+    #[serde(skip)]
+    compare_stop: Arc<AtomicBool>,
+    context: ContextOptions,
+    /// Summary of the earliest messages, made by [`ContextStrategy::SummarizeOverflow`]
+    context_summary: Option<ContextSummary>,
+    #[serde(skip)]
+    sent_context: Option<SentContext>,
+    #[serde(skip)]
+    prompt_estimate: PromptEstimate,
+    /// Send the conversation as one raw prompt for the model to continue, without a chat
+    /// template or system prompt
+    pub completion_mode: bool,
+    /// Completion mode to switch to once confirmed, as the history is sent differently
+    #[serde(skip)]
+    confirm_mode_switch: Option<bool>,
+}
+
+impl Default for Chat {
+    fn default() -> Self {
+        Self {
+            uid: fastrand::u64(..),
+            chatbox: String::new(),
+            chatbox_height: 0.0,
+            chatbox_id: None,
+            send_shortcut: SendShortcut::default(),
+            user_name: String::new(),
+            quick_settings: true,
+            messages: Vec::new(),
+            flower: CompletionFlower::new(1),
+            completion_task: CompletionTask::default(),
+            retry_message_idx: None,
+            summary: String::new(),
+            pinned: false,
+            archived: false,
+            tags: Vec::new(),
+            unread: false,
+            chatbox_highlighter: MemoizedEasymarkHighlighter::default(),
+            stop_generating: Arc::new(AtomicBool::new(false)),
+            row_heights: RowHeights::default(),
+            model_picker: ModelPicker::default(),
+            images: Vec::new(),
+            files: Vec::new(),
+            prompt_history: Vec::new(),
+            history_pos: None,
+            history_draft: String::new(),
+            cursor_rows: (true, true),
+            prepend_buf: String::new(),
+            system_prompt: String::new(),
+            settings_override: None,
+            picker_settings_migrated: true,
+            reply_options: ReplyOptions::default(),
+            inherited_settings: ModelSettings::default(),
+            proxy: None,
+            openai: None,
+            ollama_client: OllamaClient::default(),
+            timeouts: Timeouts::default(),
+            show_info: false,
+            golden_draft: None,
+            inspected: None,
+            confirm_clear: false,
+            modified: false,
+            find: FindBar::default(),
+            branch_from: None,
+            delete_message: None,
+            copied_messages: None,
+            reveal: None,
+            revealed: false,
+            jump_to_bottom: false,
+            new_below: false,
+            shown_content: (0, 0),
+            #[cfg(feature = "tts")]
+            auto_speak: false,
+            #[cfg(feature = "tts")]
+            speech_stream: None,
+            #[cfg(feature = "voice")]
+            whisper_model: String::new(),
+            #[cfg(feature = "voice")]
+            recorder: None,
+            #[cfg(feature = "voice")]
+            transcribe_flower: TranscribeFlower::new(1),
+            #[cfg(feature = "voice")]
+            mic_error: None,
+            #[cfg(feature = "voice")]
+            transcribe_error: None,
+            knowledge: Knowledge::default(),
+            index_flower: IndexFlower::new(1),
+            index_task: CompletionTask::default(),
+            index_progress: None,
+            index_error: None,
+            compare_model: None,
+            compare_flower: CompletionFlower::new(1),
+            compare_task: CompletionTask::default(),
+            compare_stop: Arc::new(AtomicBool::new(false)),
+            context: ContextOptions::default(),
+            context_summary: None,
+            sent_context: None,
+            prompt_estimate: PromptEstimate::default(),
+            completion_mode: false,
+            confirm_mode_switch: None,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn request_completion(
+    ollama: OllamaClient,
+    backend: Backend,
+    messages: Vec<ChatMessage>,
+    handle: &CompletionFlowerHandle,
+    stop_generating: Arc<AtomicBool>,
+    selected_model: String,
+    settings: ModelSettings,
+    template: Option<String>,
+    knowledge: Option<Knowledge>,
+    summary_job: Option<SummaryJob>,
+    index: usize,
+    raw: bool,
+    timeouts: Timeouts,
+) -> Result<(), BoxError> {
+    log::info!(
+        "requesting completion... (history length: {})",
+        messages.len()
+    );
+    let mut messages = messages;
+
+    // fold the messages that don't fit anymore into the summary
+    let mut summary = None;
+    if let Some(job) = summary_job {
+        log::info!("summarizing {} earlier message(s)...", job.folded.len());
+        match summarize(&backend, &selected_model, &settings, &timeouts, &job).await {
+            Ok(text) => {
+                messages[job.slot] = ContextSummary::to_message(&text);
+                summary = Some(ContextSummary {
+                    text,
+                    covers: job.covers,
+                });
+            }
+            Err(e) => {
+                // better to answer without the oldest messages than not at all
+                log::warn!("failed to summarize the earlier messages, leaving them out: {e}");
+                match &job.previous {
+                    Some(previous) => messages[job.slot] = ContextSummary::to_message(previous),
+                    None => {
+                        messages.remove(job.slot);
+                    }
+                }
+            }
+        }
+    }
+
+    // ground the latest question in the excerpts closest to it, embeddings always come
+    // from Ollama
+    let mut retrieved = Vec::new();
+    if let Some(knowledge) = knowledge {
+        if let Some(question) = messages
+            .iter_mut()
+            .rev()
+            .find(|m| m.role == ollama_rs::generation::chat::MessageRole::User)
+        {
+            retrieved = knowledge::retrieve(&ollama, &knowledge, &question.content)
+                .await
+                .map_err(|e| tr_args("chat.knowledge_failed", &[&e]))?;
+            log::info!("adding {} knowledge excerpt(s)", retrieved.len());
+            question.content = knowledge::augment_prompt(&question.content, &retrieved);
+        }
+    }
+
+    // if any assistant message was prepended, save it so we can prepend it
+    // to the final response
+    let prepend = {
+        if let Some(last) = messages.last() {
+            if last.role == ollama_rs::generation::chat::MessageRole::Assistant {
+                last.content.clone()
+            } else {
+                String::new()
             }
+        } else {
+            String::new()
         }
+    };
+
+    let request = if raw {
+        // the messages are parts of one document
+        let prompt: String = messages
+            .iter()
+            .filter(|m| m.role != ollama_rs::generation::chat::MessageRole::System)
+            .map(|m| m.content.as_str())
+            .collect();
+        ReplyRequest::Completion(CompletionRequest {
+            model: selected_model,
+            prompt,
+            settings,
+        })
+    } else {
+        ReplyRequest::Chat(ChatRequest {
+            model: selected_model,
+            messages,
+            settings,
+            template,
+        })
+    };
+
+    // send the pieces to the gui thread
+    let on_text = |text| handle.send((index, ReplyProgress::Text(text)));
+    let started = Instant::now();
+    let mut reply = stream_reply(
+        &backend,
+        request.clone(),
+        raw,
+        &timeouts,
+        &stop_generating,
+        &on_text,
+    )
+    .await?;
+    let retried = reply.stalled && timeouts.retry_stalled;
+    if retried {
+        log::warn!("the reply stalled, requesting it once more");
+        handle.send((index, ReplyProgress::Restart(prepend.clone())));
+        reply = stream_reply(
+            &backend,
+            request,
+            raw,
+            &timeouts,
+            &stop_generating,
+            &on_text,
+        )
+        .await?;
     }
 
     log::info!(
         "completion request complete, response length: {}",
-        response.len()
+        reply.text.len()
     );
-    handle.success((index, prepend + response.trim()));
+    let stats = reply
+        .time_to_first_token
+        .map(|time_to_first_token| ResponseStats {
+            time_to_first_token,
+            tokens_per_second: reply.tokens_per_second,
+            total_time: started.elapsed(),
+        });
+    let inspection = serde_json::json!({
+        "backend": match backend {
+            Backend::Ollama(_) => "Ollama",
+            Backend::OpenAi(_) => "OpenAI-compatible",
+        },
+        "request": reply.request,
+        "response": {
+            "prompt_eval_count": reply.usage.map(|u| u.prompt_tokens),
+            "eval_count": reply.usage.map(|u| u.reply_tokens),
+            "total_duration_ms": reply.timings.map(|t| t.total.as_millis() as u64),
+            "prompt_eval_duration_ms": reply.timings.map(|t| t.prompt_eval.as_millis() as u64),
+            "eval_duration_ms": reply.timings.map(|t| t.eval.as_millis() as u64),
+            "tokens_per_second": reply.tokens_per_second,
+            "time_to_first_token_ms": reply
+                .time_to_first_token
+                .map(|t| t.as_millis() as u64),
+            "wall_time_ms": started.elapsed().as_millis() as u64,
+            "stalled": reply.stalled,
+            "retried": retried,
+        },
+    });
+    handle.success(Completion {
+        index,
+        content: if raw {
+            prepend + &reply.text
+        } else {
+            prepend + reply.text.trim()
+        },
+        inspection: serde_json::to_string_pretty(&inspection).unwrap_or_default(),
+        usage: reply.usage,
+        stats,
+        sources: retrieved,
+        summary,
+        interrupted: reply
+            .stalled
+            .then(|| tr_args("chat.stalled", &[&timeouts.stall_secs])),
+    });
     Ok(())
 }
 
+/// Request to stream a reply with, kept to send it again if the reply stalls.
+#[derive(Clone)]
+enum ReplyRequest {
+    Chat(ChatRequest),
+    Completion(CompletionRequest),
+}
+
+/// Reply read from one stream.
+struct StreamedReply {
+    /// The posted request body, images left out
+    request: serde_json::Value,
+    text: String,
+    usage: Option<TokenUsage>,
+    time_to_first_token: Option<Duration>,
+    tokens_per_second: Option<f64>,
+    timings: Option<ServerTimings>,
+    /// No token arrived within the stall timeout, the reply was cut off
+    stalled: bool,
+}
+
+/// Stream the reply to `request`, passing each piece to `on_text`. Fails if the reply
+/// doesn't start within the first token timeout, and stops early if it stalls for longer
+/// than the stall timeout.
+async fn stream_reply(
+    backend: &Backend,
+    request: ReplyRequest,
+    raw: bool,
+    timeouts: &Timeouts,
+    stop_generating: &AtomicBool,
+    on_text: impl Fn(String),
+) -> Result<StreamedReply, BoxError> {
+    // how often to check on a silent stream
+    const HEARTBEAT: Duration = Duration::from_secs(5);
+    // how long a stream may stay silent after the system wakes up before it's considered dead
+    const WAKE_GRACE: Duration = Duration::from_secs(15);
+
+    let started = Instant::now();
+    let first_token_timeout = || -> BoxError {
+        tr_args("chat.first_token_timeout", &[&timeouts.first_token_secs]).into()
+    };
+    let open = async {
+        match request {
+            ReplyRequest::Chat(request) => backend.chat_stream(request).await,
+            ReplyRequest::Completion(request) => backend.completion_stream(request).await,
+        }
+    };
+    let mut stream = tokio::time::timeout(timeouts.first_token(), open)
+        .await
+        .map_err(|_| first_token_timeout())??;
+
+    log::info!("reading response...");
+
+    let mut reply = StreamedReply {
+        request: std::mem::take(&mut stream.body),
+        text: String::new(),
+        usage: None,
+        time_to_first_token: None,
+        tokens_per_second: None,
+        timings: None,
+        stalled: false,
+    };
+    // a raw continuation may well start with a space or a new line
+    let mut is_whitespace = !raw;
+    let mut wake = WakeDetector::default();
+    let mut woke_at: Option<Instant> = None;
+    // when the last chunk arrived, `None` before the first one
+    let mut last_chunk: Option<Instant> = None;
+
+    loop {
+        let chunk = match tokio::time::timeout(HEARTBEAT, stream.next()).await {
+            Ok(Some(Ok(chunk))) => chunk,
+            Ok(Some(Err(e))) => return Err(e),
+            Ok(None) => break,
+            Err(_) => {
+                if stop_generating.load(Ordering::SeqCst) {
+                    log::info!("stopping generation");
+                    stop_generating.store(false, Ordering::SeqCst);
+                    break;
+                }
+                if let Some(slept) = wake.check() {
+                    log::warn!(
+                        "system woke up after ~{}s, waiting for the stream to resume",
+                        slept.as_secs()
+                    );
+                    woke_at = Some(Instant::now());
+                }
+                if let Some(woke_at) = woke_at {
+                    if woke_at.elapsed() >= WAKE_GRACE {
+                        return Err(tr("chat.lost_while_asleep").into());
+                    }
+                    // the time asleep doesn't count as a stall
+                    continue;
+                }
+                match last_chunk {
+                    None if started.elapsed() >= timeouts.first_token() => {
+                        return Err(first_token_timeout());
+                    }
+                    Some(t) if t.elapsed() >= timeouts.stall() => {
+                        log::warn!(
+                            "no token for {}s, cutting off the reply",
+                            timeouts.stall_secs
+                        );
+                        reply.stalled = true;
+                        break;
+                    }
+                    _ => continue,
+                }
+            }
+        };
+        // data still flows, the connection survived any sleep
+        wake = WakeDetector::default();
+        woke_at = None;
+        last_chunk = Some(Instant::now());
+
+        if chunk.usage.is_some() {
+            reply.usage = chunk.usage;
+            reply.tokens_per_second = chunk.tokens_per_second;
+            reply.timings = chunk.timings;
+        }
+        if is_whitespace && chunk.content.trim().is_empty() {
+            continue;
+        }
+        let content = if is_whitespace {
+            chunk.content.trim_start()
+        } else {
+            &chunk.content
+        };
+        is_whitespace = false;
+        reply
+            .time_to_first_token
+            .get_or_insert_with(|| started.elapsed());
+
+        on_text(content.to_string());
+        reply.text += content;
+
+        if stop_generating.load(Ordering::SeqCst) {
+            log::info!("stopping generation");
+            stop_generating.store(false, Ordering::SeqCst);
+            break;
+        }
+    }
+    Ok(reply)
+}
+
+/// Summary of the messages of `job`, continuing its previous summary. The model is run
+/// with the context size, hardware settings and keep alive of the chat, so that it isn't
+/// reloaded for the reply, and the summary is subject to the same timeouts as replies.
+async fn summarize(
+    backend: &Backend,
+    model: &str,
+    settings: &ModelSettings,
+    timeouts: &Timeouts,
+    job: &SummaryJob,
+) -> Result<String, BoxError> {
+    use ollama_rs::generation::chat::MessageRole;
+
+    let mut transcript = String::new();
+    if let Some(previous) = &job.previous {
+        transcript += &format!("Summary so far:\n{previous}\n\n");
+    }
+    for message in &job.folded {
+        let speaker = match message.role {
+            MessageRole::User => "User",
+            MessageRole::Assistant => "Assistant",
+            _ => "System",
+        };
+        transcript += &format!("{speaker}: {}\n\n", message.content.trim());
+    }
+    let request = ChatRequest {
+        model: model.to_owned(),
+        messages: vec![
+            ChatMessage::system(
+                "Summarize the conversation below for your own later reference, as compact \
+                notes. Keep facts, decisions, names, numbers, code identifiers and open \
+                questions."
+                    .to_owned(),
+            ),
+            ChatMessage::user(transcript),
+        ],
+        // the sampling settings of the chat are left out, a JSON mode or stop sequences
+        // would spoil the summary
+        settings: ModelSettings {
+            num_ctx: settings.num_ctx,
+            num_gqa: settings.num_gqa,
+            num_gpu: settings.num_gpu,
+            num_thread: settings.num_thread,
+            num_batch: settings.num_batch,
+            keep_alive: settings.keep_alive.clone(),
+            ..Default::default()
+        },
+        template: None,
+    };
+    let reply = stream_reply(
+        backend,
+        ReplyRequest::Chat(request),
+        false,
+        timeouts,
+        &AtomicBool::new(false),
+        |_| {},
+    )
+    .await?;
+    // a summary cut off by a stall would silently lose the rest of the folded messages
+    if reply.stalled {
+        return Err(format!("no token for {}s", timeouts.stall_secs).into());
+    }
+    Ok(reply.text.trim().to_owned())
+}
+
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, serde::Deserialize, serde::Serialize)]
 pub enum ChatExportFormat {
     #[default]
     Plaintext,
     Json,
     Ron,
+    Report,
+    Html,
 }
 
 impl ToString for ChatExportFormat {
     fn to_string(&self) -> String {
-        format!("{self:?}")
+        match self {
+            Self::Report => tr("chat.format_report").to_owned(),
+            Self::Html => tr("chat.format_html").to_owned(),
+            _ => format!("{self:?}"),
+        }
     }
 }
 
 impl ChatExportFormat {
-    pub const ALL: [Self; 3] = [Self::Plaintext, Self::Json, Self::Ron];
+    pub const ALL: [Self; 5] = [
+        Self::Plaintext,
+        Self::Json,
+        Self::Ron,
+        Self::Report,
+        Self::Html,
+    ];
 
     #[inline]
     pub const fn extensions(self) -> &'static [&'static str] {
@@ -499,55 +2687,425 @@ impl ChatExportFormat {
             Self::Plaintext => &["txt"],
             Self::Json => &["json"],
             Self::Ron => &["ron"],
+            Self::Report => &["md"],
+            Self::Html => &["html", "htm"],
         }
     }
 }
 
-pub async fn export_messages(
-    messages: Vec<Message>,
-    format: ChatExportFormat,
-    task: impl std::future::Future<Output = Option<rfd::FileHandle>>,
-) -> Result<egui_notify::Toast> {
-    let Some(file) = task.await else {
-        log::info!("export cancelled");
-        return Ok(egui_notify::Toast::info("Export cancelled"));
-    };
-    log::info!(
-        "exporting {} messages to {file:?} (format: {format:?})...",
-        messages.len()
-    );
-
-    let f = std::fs::File::create(file.path())?;
-    let mut f = std::io::BufWriter::new(f);
+/// Chat-level data that is included in [`ChatExportFormat::Report`] and
+/// [`ChatExportFormat::Html`] exports.
+#[derive(Default)]
+pub struct ReportInfo {
+    summary: String,
+    model_name: String,
+    system_prompt: String,
+    template: Option<String>,
+    settings: Vec<(&'static str, String)>,
+    /// The name set in the settings, empty for "You"
+    user_name: String,
+    /// Include the notes of the messages
+    pub notes: bool,
+}
 
-    match format {
-        ChatExportFormat::Plaintext => {
-            for msg in &messages {
-                writeln!(
-                    f,
-                    "{} - {:?} ({}): {}",
-                    msg.time.to_rfc3339(),
-                    msg.role,
-                    msg.model_name,
-                    msg.content
-                )?;
-            }
+impl ReportInfo {
+    fn user_name(&self) -> &str {
+        match self.user_name.trim() {
+            "" => tr("common.you"),
+            name => name,
         }
-        ChatExportFormat::Json => {
-            serde_json::to_writer_pretty(&mut f, &messages)?;
+    }
+}
+
+/// Escape a string so that it can be put into a single markdown table cell.
+fn escape_table_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Code fence for `text`, longer than any run of backticks in it so it can't close early.
+fn code_fence(text: &str) -> String {
+    let longest = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    "`".repeat(longest.max(2) + 1)
+}
+
+/// How the settings of a reply differ from the chat's, like "temperature 0.2, seed default".
+fn settings_difference(
+    settings: &[(&'static str, String)],
+    chat: &[(&'static str, String)],
+) -> Vec<String> {
+    let changed = settings
+        .iter()
+        .filter(|entry| !chat.contains(entry))
+        .map(|(name, value)| format!("{name} {value}"));
+    let unset = chat
+        .iter()
+        .filter(|(name, _)| !settings.iter().any(|(n, _)| n == name))
+        .map(|(name, _)| tr_args("chat.report_default", &[name]));
+    changed.chain(unset).collect()
+}
+
+/// Write a markdown report of the chat. Sections without any data are omitted.
+fn write_report(f: &mut impl Write, messages: &[Message], info: &ReportInfo) -> Result<()> {
+    if info.summary.is_empty() {
+        writeln!(f, "# {}\n", tr("chat.new_chat"))?;
+    } else {
+        writeln!(f, "# {}\n", info.summary)?;
+    }
+    writeln!(
+        f,
+        "_{}_\n",
+        tr_args("chat.exported_on", &[&chrono::Utc::now().to_rfc3339()])
+    )?;
+
+    if !info.model_name.is_empty() {
+        writeln!(
+            f,
+            "## {}\n\n`{}`\n",
+            tr("chat.report_model"),
+            info.model_name
+        )?;
+    }
+
+    if !info.system_prompt.trim().is_empty() {
+        let fence = code_fence(&info.system_prompt);
+        writeln!(
+            f,
+            "## {}\n\n{fence}\n{}\n{fence}\n",
+            tr("chat.system_prompt"),
+            info.system_prompt
+        )?;
+    }
+
+    if !info.settings.is_empty() {
+        writeln!(
+            f,
+            "## {}\n\n| {} | {} |\n| --- | --- |",
+            tr("chat.report_settings"),
+            tr("chat.report_setting"),
+            tr("chat.report_value"),
+        )?;
+        for (name, value) in &info.settings {
+            writeln!(f, "| {name} | {} |", escape_table_cell(value))?;
+        }
+        writeln!(f)?;
+    }
+
+    if let Some(template) = &info.template {
+        let fence = code_fence(template);
+        writeln!(
+            f,
+            "## {}\n\n{fence}\n{template}\n{fence}\n",
+            tr("chat.report_template")
+        )?;
+    }
+
+    if messages.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(
+        f,
+        "## {}\n\n| # | {} | {} | {} | {} | {} |\n| --- | --- | --- | --- | --- | --- |",
+        tr("chat.report_overview"),
+        tr("chat.report_role"),
+        tr("chat.report_model"),
+        tr("chat.report_time"),
+        tr("chat.report_characters"),
+        tr("chat.report_images"),
+    )?;
+    for (i, msg) in messages.iter().enumerate() {
+        writeln!(
+            f,
+            "| {} | {:?} | {} | {} | {} | {} |",
+            i + 1,
+            msg.role,
+            escape_table_cell(&msg.model_name),
+            msg.time.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            msg.content.chars().count(),
+            msg.images.len(),
+        )?;
+    }
+
+    let has_stats =
+        |msg: &Message| msg.usage.is_some() || msg.stats.is_some() || msg.rating.is_some();
+    if messages.iter().any(has_stats) {
+        writeln!(
+            f,
+            "\n## {}\n\n| # | {} | {} | {} | {} | {} | {} |\n\
+            | --- | --- | --- | --- | --- | --- | --- |",
+            tr("chat.report_stats"),
+            tr("chat.report_model"),
+            tr("chat.report_prompt_tokens"),
+            tr("chat.report_reply_tokens"),
+            tr("chat.report_first_token"),
+            tr("chat.report_speed"),
+            tr("chat.report_rating"),
+        )?;
+        for (i, msg) in messages
+            .iter()
+            .enumerate()
+            .filter(|(_, msg)| has_stats(msg))
+        {
+            let usage = msg.usage.as_ref();
+            let stats = msg.stats.as_ref();
+            writeln!(
+                f,
+                "| {} | {} | {} | {} | {} | {} | {} |",
+                i + 1,
+                escape_table_cell(&msg.model_name),
+                usage
+                    .map(|u| u.prompt_tokens.to_string())
+                    .unwrap_or_default(),
+                usage
+                    .map(|u| u.reply_tokens.to_string())
+                    .unwrap_or_default(),
+                stats
+                    .map(|s| format!("{:.2} s", s.time_to_first_token.as_secs_f64()))
+                    .unwrap_or_default(),
+                stats
+                    .and_then(|s| s.tokens_per_second)
+                    .map(|tps| format!("{tps:.1} tok/s"))
+                    .unwrap_or_default(),
+                msg.rating.map(Rating::icon).unwrap_or_default(),
+            )?;
+        }
+    }
+
+    writeln!(f, "\n## {}\n", tr("chat.report_conversation"))?;
+    let short_names = short_names(
+        messages
+            .iter()
+            .filter(|m| !m.is_user())
+            .map(|m| m.model_name.as_str()),
+    );
+    for (i, msg) in messages.iter().enumerate() {
+        let author = if msg.is_user() {
+            info.user_name()
+        } else {
+            short_names
+                .get(&msg.model_name)
+                .map_or("Llama", String::as_str)
+        };
+        match msg.rating {
+            Some(rating) => writeln!(f, "### {}. {author} {}\n", i + 1, rating.icon())?,
+            None => writeln!(f, "### {}. {author}\n", i + 1)?,
+        }
+        if let Some(settings) = &msg.settings {
+            let difference = settings_difference(&settings.overview(), &info.settings);
+            if !difference.is_empty() {
+                writeln!(
+                    f,
+                    "_{}_\n",
+                    tr_args("chat.report_reply_settings", &[&difference.join(", ")])
+                )?;
+            }
+        }
+        for image in &msg.images {
+            writeln!(f, "- {}", tr_args("chat.report_image", &[&image.display()]))?;
+        }
+        if !msg.images.is_empty() {
+            writeln!(f)?;
+        }
+        writeln!(f, "{}\n", msg.content)?;
+        if info.notes && !msg.note.is_empty() {
+            for line in msg.note.lines() {
+                writeln!(f, "> **📝 {}** {line}", tr("chat.report_note"))?;
+            }
+            writeln!(f)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Write a self-contained web page of the chat, with styles and images embedded.
+fn write_html(f: &mut impl Write, messages: &[Message], info: &ReportInfo) -> Result<()> {
+    let title = if info.summary.is_empty() {
+        tr("chat.new_chat")
+    } else {
+        info.summary.as_str()
+    };
+    writeln!(
+        f,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+        <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+        <title>{}</title>\n<style>{}</style>\n</head>\n<body>",
+        html::escape(title),
+        html::STYLE
+    )?;
+
+    let mut meta = Vec::new();
+    if !info.model_name.is_empty() {
+        meta.push(format!("<code>{}</code>", html::escape(&info.model_name)));
+    }
+    if let Some(first) = messages.iter().find_map(|m| m.time) {
+        meta.push(
+            first
+                .with_timezone(&chrono::Local)
+                .format("%B %-d, %Y")
+                .to_string(),
+        );
+    }
+    writeln!(
+        f,
+        "<header>\n<h1>{}</h1>\n<div class=\"meta\">{}</div>",
+        html::escape(title),
+        meta.join(" · ")
+    )?;
+    if !info.system_prompt.trim().is_empty() {
+        writeln!(
+            f,
+            "<details><summary>{}</summary><pre>{}</pre></details>",
+            html::escape(tr("chat.system_prompt")),
+            html::escape(&info.system_prompt)
+        )?;
+    }
+    writeln!(f, "</header>\n<main>")?;
+
+    let short_names = short_names(
+        messages
+            .iter()
+            .filter(|m| !m.is_user())
+            .map(|m| m.model_name.as_str()),
+    );
+    for msg in messages {
+        let (class, author) = if msg.is_user() {
+            ("user", info.user_name())
+        } else {
+            (
+                "assistant",
+                short_names
+                    .get(&msg.model_name)
+                    .map_or("Llama", String::as_str),
+            )
+        };
+        let time = msg
+            .time
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format(" · %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        writeln!(
+            f,
+            "<article class=\"message {class}\">\n<div class=\"author\">{}{time}</div>",
+            html::escape(author)
+        )?;
+        if msg.is_error {
+            let error = msg.error.as_deref().unwrap_or(&msg.content);
+            writeln!(
+                f,
+                "<div class=\"bubble error\">{}</div>",
+                html::escape(error)
+            )?;
+        } else {
+            write!(f, "<div class=\"bubble\">")?;
+            for image in &msg.images {
+                let name = image
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                match crate::image::data_url(image) {
+                    Ok(url) => writeln!(f, "<img src=\"{url}\" alt=\"{}\">", html::escape(&name))?,
+                    Err(e) => {
+                        log::warn!("failed to embed `{}`: {e}", image.display());
+                        let missing = tr_args("chat.missing_image", &[&name]);
+                        writeln!(f, "<p><em>{}</em></p>", html::escape(&missing))?;
+                    }
+                }
+            }
+            writeln!(
+                f,
+                "{}</div>",
+                html::markdown(&math::render_math(&msg.content))
+            )?;
+        }
+        if info.notes && !msg.note.is_empty() {
+            writeln!(
+                f,
+                "<div class=\"note\">📝 {}</div>",
+                html::escape(&msg.note)
+            )?;
+        }
+        writeln!(f, "</article>")?;
+    }
+
+    writeln!(
+        f,
+        "</main>\n<footer>{}</footer>\n</body>\n</html>",
+        html::escape(&tr_args(
+            "chat.exported_on",
+            &[&chrono::Local::now().format("%B %-d, %Y %H:%M")]
+        ))
+    )?;
+    Ok(())
+}
+
+pub async fn export_messages(
+    mut messages: Vec<Message>,
+    report: ReportInfo,
+    format: ChatExportFormat,
+    task: impl std::future::Future<Output = Option<rfd::FileHandle>>,
+) -> Result<egui_notify::Toast> {
+    if !report.notes {
+        for message in &mut messages {
+            message.note.clear();
+        }
+    }
+    let Some(file) = task.await else {
+        log::info!("export cancelled");
+        return Ok(egui_notify::Toast::info(tr("chat.export_cancelled")));
+    };
+    log::info!(
+        "exporting {} messages to {file:?} (format: {format:?})...",
+        messages.len()
+    );
+
+    let f = std::fs::File::create(file.path())?;
+    let mut f = std::io::BufWriter::new(f);
+
+    match format {
+        ChatExportFormat::Plaintext => {
+            for msg in &messages {
+                writeln!(
+                    f,
+                    "{} - {:?} ({}): {}",
+                    msg.time
+                        .map_or_else(|| "unknown time".to_owned(), |t| t.to_rfc3339()),
+                    msg.role,
+                    msg.model_name,
+                    msg.content
+                )?;
+                if !msg.note.is_empty() {
+                    writeln!(f, "    [Note: {}]", msg.note)?;
+                }
+            }
+        }
+        ChatExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut f, &messages)?;
         }
         ChatExportFormat::Ron => {
             ron::ser::to_writer_pretty(&mut f, &messages, ron::ser::PrettyConfig::default())?;
         }
+        ChatExportFormat::Report => {
+            write_report(&mut f, &messages, &report)?;
+        }
+        ChatExportFormat::Html => {
+            write_html(&mut f, &messages, &report)?;
+        }
     }
 
     f.flush().context("failed to flush writer")?;
 
     log::info!("export complete");
-    Ok(egui_notify::Toast::success(format!(
-        "Exported {} messages to {}",
-        messages.len(),
-        file.file_name(),
+    Ok(egui_notify::Toast::success(tr_args(
+        "chat.exported",
+        &[&messages.len(), &file.file_name()],
     )))
 }
 
@@ -571,10 +3129,75 @@ fn make_summary(prompt: &str) -> String {
     summary
 }
 
+/// How long a message revealed from search results stays highlighted
+const REVEAL_HIGHLIGHT_DURATION: Duration = Duration::from_secs(2);
+
+/// Byte ranges of all case-insensitive occurrences of `needle` in `haystack`. `needle`
+/// must already be lowercase.
+pub fn find_ignore_case(haystack: &str, needle: &str) -> Vec<Range<usize>> {
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    // lowercasing may change the length of a character, so remember where each byte of
+    // the lowercase text came from
+    let mut lower = String::with_capacity(haystack.len());
+    let mut origin = Vec::with_capacity(haystack.len() + 1);
+    for (i, ch) in haystack.char_indices() {
+        for lc in ch.to_lowercase() {
+            origin.extend(std::iter::repeat(i).take(lc.len_utf8()));
+            lower.push(lc);
+        }
+    }
+    origin.push(haystack.len());
+    lower
+        .match_indices(needle)
+        .map(|(start, m)| origin[start]..origin[start + m.len()])
+        .collect()
+}
+
+/// Text around `range` in `text` on a single line, for showing search results.
+fn snippet(text: &str, range: Range<usize>) -> String {
+    const CONTEXT_BEFORE: usize = 24;
+    const CONTEXT_AFTER: usize = 64;
+    let before: Vec<char> = text[..range.start]
+        .chars()
+        .rev()
+        .take(CONTEXT_BEFORE + 1)
+        .collect();
+    let after: Vec<char> = text[range.end..].chars().take(CONTEXT_AFTER + 1).collect();
+    let mut snippet = String::new();
+    if before.len() > CONTEXT_BEFORE {
+        snippet.push('…');
+    }
+    snippet.extend(before.iter().take(CONTEXT_BEFORE).rev());
+    snippet += &text[range];
+    snippet.extend(after.iter().take(CONTEXT_AFTER));
+    if after.len() > CONTEXT_AFTER {
+        snippet.push('…');
+    }
+    snippet.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum ChatAction {
     None,
-    PickImages { id: usize },
+    PickImages {
+        id: usize,
+    },
+    PasteImage,
+    /// Continue the conversation up to and including `message` in a new chat
+    Branch {
+        message: usize,
+    },
+    /// The conversation was copied to the clipboard
+    ConversationCopied {
+        messages: usize,
+    },
+    /// `message` was removed from `index`, it can be put back with [`Chat::restore_message`]
+    MessageDeleted {
+        index: usize,
+        message: Box<Message>,
+    },
 }
 
 impl Chat {
@@ -582,6 +3205,7 @@ impl Chat {
     pub fn new(id: usize, model_picker: ModelPicker) -> Self {
         Self {
             flower: CompletionFlower::new(id),
+            compare_flower: CompletionFlower::new(id),
             model_picker,
             ..Default::default()
         }
@@ -592,16 +3216,69 @@ impl Chat {
         self.flower.id()
     }
 
+    /// Chats used the settings of their own model picker before they could override the
+    /// shared ones. Keep the ones that differ from `shared` as this chat's override.
+    pub fn migrate_picker_settings(&mut self, shared: &ModelSettings) {
+        if std::mem::replace(&mut self.picker_settings_migrated, true) {
+            return;
+        }
+        let settings = self.model_picker.settings();
+        if self.settings_override.is_none() && settings != shared {
+            log::info!(
+                "keeping the settings of chat `{}` as its override",
+                self.uid
+            );
+            self.settings_override = Some(settings.clone());
+            self.modified = true;
+        }
+    }
+
+    /// Inference settings used for this chat
+    #[inline]
+    pub fn settings(&self) -> &ModelSettings {
+        self.settings_override
+            .as_ref()
+            .unwrap_or(&self.inherited_settings)
+    }
+
+    pub fn report_info(&self) -> ReportInfo {
+        ReportInfo {
+            summary: self.summary.clone(),
+            model_name: self.model_picker.selected_model().to_owned(),
+            system_prompt: self.system_prompt.clone(),
+            template: self.model_picker.template.clone(),
+            settings: self.settings().overview(),
+            user_name: self.user_name.clone(),
+            notes: false,
+        }
+    }
+
+    /// The conversation as the Markdown of [`ChatExportFormat::Report`] exports.
+    fn conversation_markdown(&self, with_system_prompt: bool) -> String {
+        let mut info = self.report_info();
+        if !with_system_prompt {
+            info.system_prompt.clear();
+        }
+        let mut markdown = Vec::new();
+        if let Err(e) = write_report(&mut markdown, &self.messages, &info) {
+            log::error!("failed to write the conversation: {e}");
+        }
+        String::from_utf8_lossy(&markdown).into_owned()
+    }
+
     fn convert_images(images: &[PathBuf]) -> Option<Vec<Image>> {
         if !images.is_empty() {
             Some(
                 images
                     .iter()
-                    // TODO: handle errors
-                    .map(|i| {
+                    // an unreadable image (e.g. moved since it was attached) is left out
+                    // instead of failing the whole request
+                    .filter_map(|i| {
                         crate::image::convert_image(i)
-                            .map_err(|e| log::error!("failed to convert image: {e}"))
-                            .unwrap()
+                            .map_err(|e| {
+                                log::error!("failed to convert image `{}`: {e}", i.display())
+                            })
+                            .ok()
                     })
                     .collect(),
             )
@@ -610,10 +3287,13 @@ impl Chat {
         }
     }
 
-    fn get_context_messages(messages: &[Message]) -> Vec<ChatMessage> {
-        messages
-            .iter()
-            .map(|m| {
+    /// Build the message history sent to Ollama, starting with the system prompt (if any)
+    fn get_context_messages(&self, messages: &[Message]) -> Vec<ChatMessage> {
+        let system = (!self.system_prompt.trim().is_empty())
+            .then(|| ChatMessage::system(self.system_prompt.clone()));
+        system
+            .into_iter()
+            .chain(messages.iter().map(|m| {
                 let mut message = match m.role {
                     Role::User => ChatMessage::user(m.content.clone()),
                     Role::Assistant => ChatMessage::assistant(m.content.clone()),
@@ -622,213 +3302,1917 @@ impl Chat {
                 // TODO: don't do this each time!
                 message.images = Self::convert_images(&m.images);
 
-                message
-            })
-            .collect()
+                message
+            }))
+            .collect()
+    }
+
+    /// Message history as seen by the compared model, with its own replies where it gave
+    /// one
+    fn get_compare_context_messages(&self, messages: &[Message]) -> Vec<ChatMessage> {
+        let messages: Vec<Message> = messages
+            .iter()
+            .map(|m| m.compare.as_deref().unwrap_or(m).clone())
+            .collect();
+        self.get_context_messages(&messages)
+    }
+
+    /// Fit the request `context`, built from the messages with the system prompt first, to
+    /// the context strategy. Returns which messages are sent and, if a new summary has to be
+    /// made for the request and `summarize` allows it, the messages to fold into it.
+    fn fit_context(
+        &self,
+        context: &mut Vec<ChatMessage>,
+        summarize: bool,
+    ) -> (SentContext, Option<SummaryJob>) {
+        use ollama_rs::generation::chat::MessageRole;
+
+        let offset = usize::from(
+            context
+                .first()
+                .is_some_and(|m| m.role == MessageRole::System),
+        );
+        let history_len = context.len() - offset;
+        let sent_as_is = |start| SentContext {
+            start,
+            summarized: false,
+        };
+        // the first message sent should be a question
+        let question_from = |context: &[ChatMessage], start: usize| {
+            (start..history_len)
+                .find(|&i| context[offset + i].role == MessageRole::User)
+                .unwrap_or(start)
+        };
+
+        match self.context.strategy {
+            ContextStrategy::SendAll => (sent_as_is(0), None),
+            ContextStrategy::SlidingWindow => {
+                let start = history_len.saturating_sub(self.context.window.max(1));
+                let start = if start == 0 {
+                    0
+                } else {
+                    question_from(context.as_slice(), start)
+                };
+                context.drain(offset..offset + start);
+                (sent_as_is(start), None)
+            }
+            ContextStrategy::SummarizeOverflow => {
+                let budget = (self.context_window() as f32 * self.context.threshold) as u64;
+                let tokens = |messages: &[ChatMessage]| -> u64 {
+                    messages.iter().map(|m| estimate_tokens(&m.content)).sum()
+                };
+                // a summary made before a regenerated message doesn't apply
+                let summary = self
+                    .context_summary
+                    .as_ref()
+                    .filter(|s| s.covers < history_len);
+                let covered = summary.map_or(0, |s| s.covers);
+                let used = tokens(&context[..offset])
+                    + summary.map_or(0, |s| estimate_tokens(&s.text))
+                    + tokens(&context[offset + covered..]);
+
+                // keep the latest messages within half the budget, the rest is summarized
+                let mut start = history_len;
+                let mut kept = 0;
+                while start > covered {
+                    let message_tokens = estimate_tokens(&context[offset + start - 1].content);
+                    if history_len - start >= 2 && kept + message_tokens > budget / 2 {
+                        break;
+                    }
+                    kept += message_tokens;
+                    start -= 1;
+                }
+                let start = question_from(context.as_slice(), start);
+
+                if used <= budget || start <= covered {
+                    // the existing summary, if any, is enough
+                    let Some(summary) = summary else {
+                        return (sent_as_is(0), None);
+                    };
+                    context.splice(
+                        offset..offset + covered,
+                        [ContextSummary::to_message(&summary.text)],
+                    );
+                    let sent = SentContext {
+                        start: covered,
+                        summarized: true,
+                    };
+                    return (sent, None);
+                }
+
+                let folded = context[offset + covered..offset + start].to_vec();
+                let previous = summary.map(|s| s.text.clone());
+                if !summarize {
+                    // leave the new overflow out
+                    let replacement = previous.as_deref().map(ContextSummary::to_message);
+                    let sent = SentContext {
+                        start,
+                        summarized: replacement.is_some(),
+                    };
+                    context.splice(offset..offset + start, replacement);
+                    return (sent, None);
+                }
+                // filled in by the request once the summary is made
+                context.splice(offset..offset + start, [ChatMessage::system(String::new())]);
+                let job = SummaryJob {
+                    previous,
+                    folded,
+                    covers: start,
+                    slot: offset,
+                };
+                let sent = SentContext {
+                    start,
+                    summarized: true,
+                };
+                (sent, Some(job))
+            }
+        }
+    }
+
+    /// Context window size of the model: the one set in the settings, the `num_ctx` parameter
+    /// of the model or Ollama's default.
+    fn context_window(&self) -> u64 {
+        if let Some(num_ctx) = self.settings().num_ctx {
+            return num_ctx.into();
+        }
+        self.model_picker
+            .info
+            .as_ref()
+            .and_then(|info| {
+                info.parameters
+                    .lines()
+                    .find_map(|line| line.strip_prefix("num_ctx")?.trim().parse().ok())
+            })
+            .unwrap_or(DEFAULT_NUM_CTX)
+    }
+
+    fn usage_of(&self, idx: usize) -> (TokenUsage, bool) {
+        usage_of(&self.messages, &self.system_prompt, idx)
+    }
+
+    /// Tokens of the context window taken by the latest completed exchange, and whether
+    /// the count is estimated.
+    fn context_used(&self) -> Option<(u64, bool)> {
+        let idx = self
+            .messages
+            .iter()
+            .rposition(|m| !m.is_user() && !m.is_generating && !m.is_error)?;
+        let (usage, estimated) = self.usage_of(idx);
+        Some((usage.prompt_tokens + usage.reply_tokens, estimated))
+    }
+
+    /// Running context usage, colored as it approaches the context window.
+    fn show_context_usage(&self, ui: &mut egui::Ui) {
+        let Some((used, estimated)) = self.context_used() else {
+            return;
+        };
+        let window = self.context_window();
+        let fraction = used as f32 / window.max(1) as f32;
+        let color = if fraction >= 0.9 {
+            ui.visuals().error_fg_color
+        } else if fraction >= 0.75 {
+            ui.visuals().warn_fg_color
+        } else {
+            ui.visuals().weak_text_color()
+        };
+        let approx = if estimated { "~" } else { "" };
+        ui.label(
+            egui::RichText::new(format!(
+                "{approx}{} / {} tok",
+                compact_count(used),
+                compact_count(window)
+            ))
+            .small()
+            .color(color),
+        )
+        .on_hover_text(tr_args(
+            "chat.context_used",
+            &[
+                &format!("{approx}{}", group_digits(used)),
+                &group_digits(window),
+                &format!("{:.0}", fraction * 100.0),
+            ],
+        ));
+    }
+
+    fn stats(&self) -> ChatStats {
+        let mut stats = ChatStats::default();
+        for (idx, message) in self.messages.iter().enumerate() {
+            stats.add_text(&message.content);
+            if message.is_user() {
+                stats.user_messages += 1;
+                continue;
+            }
+            if message.is_error {
+                stats.errors += 1;
+            } else {
+                stats.add_reply(message, self.usage_of(idx));
+            }
+            if let Some(reply) = message.compare.as_deref().filter(|r| !r.is_error) {
+                stats.add_text(&reply.content);
+                let usage = match reply.usage {
+                    Some(usage) => (usage, false),
+                    None => (
+                        TokenUsage {
+                            prompt_tokens: 0,
+                            reply_tokens: estimate_tokens(&reply.content),
+                        },
+                        true,
+                    ),
+                };
+                stats.add_reply(reply, usage);
+            }
+        }
+        stats
+    }
+
+    /// Totals of the conversation, computed every frame so they follow streaming replies.
+    fn show_stats(&self, ui: &mut egui::Ui) {
+        let stats = self.stats();
+        let approx = if stats.estimated { "~" } else { "" };
+        egui::Grid::new(("chat_stats_grid", self.id()))
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label(tr("chat.stats_messages"));
+                let mut counts = tr_args(
+                    "chat.stats_message_counts",
+                    &[&stats.user_messages, &stats.replies],
+                );
+                if stats.errors > 0 {
+                    counts += &tr_args("chat.stats_errors", &[&stats.errors]);
+                }
+                ui.label(counts);
+                ui.end_row();
+
+                ui.label(tr("chat.stats_text"));
+                ui.label(tr_args(
+                    "chat.stats_text_counts",
+                    &[
+                        &group_digits(stats.words as u64),
+                        &group_digits(stats.characters as u64),
+                    ],
+                ));
+                ui.end_row();
+
+                ui.label(tr("chat.stats_tokens"));
+                ui.label(tr_args(
+                    "chat.stats_token_counts",
+                    &[
+                        &format!("{approx}{}", group_digits(stats.prompt_tokens)),
+                        &format!("{approx}{}", group_digits(stats.reply_tokens)),
+                    ],
+                ))
+                .on_hover_text(tr("chat.stats_tokens_help"));
+                ui.end_row();
+
+                ui.label(tr("chat.stats_generation_time"));
+                ui.label(tr_args(
+                    "chat.seconds",
+                    &[&format!("{:.1}", stats.generation_time.as_secs_f64())],
+                ));
+                ui.end_row();
+
+                if !stats.speeds.is_empty() {
+                    let avg = stats.speeds.iter().sum::<f64>() / stats.speeds.len() as f64;
+                    ui.label(tr("chat.stats_avg_speed"));
+                    ui.label(tr_args("chat.tokens_per_second", &[&format!("{avg:.1}")]));
+                    ui.end_row();
+                }
+
+                if stats.models.len() > 1 {
+                    ui.label(tr("chat.stats_models"));
+                    ui.vertical(|ui| {
+                        for (model, replies) in &stats.models {
+                            let key = if *replies == 1 {
+                                "chat.stats_model_replies_one"
+                            } else {
+                                "chat.stats_model_replies_many"
+                            };
+                            ui.label(tr_args(key, &[&model, &replies]));
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn show_info_grid(&self, ui: &mut egui::Ui) {
+        egui::Grid::new(("chat_info_grid", self.id()))
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label(tr("chat.stats_messages"));
+                ui.label(self.messages.len().to_string());
+                ui.end_row();
+
+                ui.label(tr("chat.info_context_window"));
+                ui.label(tr_args(
+                    "chat.token_count",
+                    &[&group_digits(self.context_window())],
+                ));
+                ui.end_row();
+
+                let replies: Vec<usize> = (0..self.messages.len())
+                    .filter(|&i| !self.messages[i].is_user() && !self.messages[i].is_error)
+                    .collect();
+                if let Some(&last) = replies.last() {
+                    let (usage, estimated) = self.usage_of(last);
+                    let approx = if estimated { "~" } else { "" };
+                    ui.label(tr("chat.info_last_prompt"));
+                    ui.label(tr_args(
+                        "chat.token_count",
+                        &[&format!("{approx}{}", group_digits(usage.prompt_tokens))],
+                    ));
+                    ui.end_row();
+
+                    let generated: u64 = replies
+                        .iter()
+                        .map(|&i| self.usage_of(i).0.reply_tokens)
+                        .sum();
+                    let any_estimated = replies.iter().any(|&i| self.messages[i].usage.is_none());
+                    ui.label(tr("chat.info_generated"));
+                    ui.label(tr_args(
+                        "chat.info_generated_tokens",
+                        &[
+                            &format!(
+                                "{}{}",
+                                if any_estimated { "~" } else { "" },
+                                group_digits(generated)
+                            ),
+                            &replies.len(),
+                        ],
+                    ));
+                    ui.end_row();
+
+                    let stats: Vec<ResponseStats> = replies
+                        .iter()
+                        .filter_map(|&i| self.messages[i].stats)
+                        .collect();
+                    if !stats.is_empty() {
+                        let ttft = stats
+                            .iter()
+                            .map(|s| s.time_to_first_token.as_secs_f64())
+                            .sum::<f64>()
+                            / stats.len() as f64;
+                        ui.label(tr("chat.info_avg_ttft"));
+                        ui.label(tr_args("chat.seconds", &[&format!("{ttft:.2}")]));
+                        ui.end_row();
+                    }
+                    let speeds: Vec<f64> =
+                        stats.iter().filter_map(|s| s.tokens_per_second).collect();
+                    if !speeds.is_empty() {
+                        let avg = speeds.iter().sum::<f64>() / speeds.len() as f64;
+                        ui.label(tr("chat.stats_avg_speed"));
+                        ui.label(tr_args(
+                            "chat.info_avg_speed",
+                            &[&format!("{avg:.1}"), &speeds.len()],
+                        ));
+                        ui.end_row();
+                    }
+                }
+            });
+    }
+
+    fn send_message(&mut self) {
+        // don't send empty messages
+        if self.chatbox.is_empty() && self.images.is_empty() && self.files.is_empty() {
+            return;
+        }
+
+        // remove old error messages
+        self.messages.retain(|m| !m.is_error);
+
+        let typed = self.chatbox.trim_end();
+        let summary_source = if typed.is_empty() {
+            self.files.first().map_or("", |f| f.name.as_str())
+        } else {
+            typed
+        };
+        if self.summary.is_empty() {
+            self.summary = make_summary(summary_source);
+        }
+        let prompt = self
+            .files
+            .iter()
+            .map(FileAttachment::to_prompt)
+            .chain((!typed.is_empty()).then(|| typed.to_owned()))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let model_name = self.model_picker.selected_model().to_owned();
+        self.messages.push(Message::user(
+            prompt.clone(),
+            model_name.clone(),
+            self.images.clone(),
+        ));
+
+        if !typed.is_empty() && self.prompt_history.last().map(String::as_str) != Some(typed) {
+            self.prompt_history.push(typed.to_owned());
+            let excess = self.prompt_history.len().saturating_sub(PROMPT_HISTORY_LEN);
+            self.prompt_history.drain(..excess);
+        }
+        self.history_pos = None;
+        self.history_draft.clear();
+
+        // clear chatbox & attachments
+        self.chatbox.clear();
+        self.images.clear();
+        self.files.clear();
+
+        // get ready for assistant response
+        self.messages
+            .push(Message::assistant(String::new(), model_name.clone()));
+        self.modified = true;
+
+        self.spawn_completion(
+            self.get_context_messages(&self.messages),
+            model_name,
+            self.messages.len() - 1,
+        );
+
+        // the compared model answers the same prompt at the same time
+        if let Some(compare) = &self.compare_model {
+            let name = compare.name.clone();
+            let idx = self.messages.len() - 1;
+            self.messages[idx].compare =
+                Some(Box::new(Message::assistant(String::new(), name.clone())));
+            self.spawn_compare_completion(
+                self.get_compare_context_messages(&self.messages),
+                name,
+                idx,
+            );
+        }
+    }
+
+    /// spawn a new task to generate the completion into the message at `index`
+    fn spawn_completion(
+        &mut self,
+        mut context_messages: Vec<ChatMessage>,
+        model_name: String,
+        index: usize,
+    ) {
+        let handle = self.flower.handle(); // recv'd by gui thread
+        let stop_generation = self.stop_generating.clone();
+        let (sent, summary_job) = self.fit_context(&mut context_messages, !self.completion_mode);
+        self.sent_context = Some(sent);
+        self.messages[index].inspection = None;
+        self.messages[index].settings = Some(self.settings().clone());
+        self.completion_task = self.spawn_request(
+            context_messages,
+            model_name,
+            index,
+            handle,
+            stop_generation,
+            summary_job,
+        );
+    }
+
+    /// spawn a new task to generate the compared model's reply to the prompt before the
+    /// message at `index`
+    fn spawn_compare_completion(
+        &mut self,
+        mut context_messages: Vec<ChatMessage>,
+        model_name: String,
+        index: usize,
+    ) {
+        let handle = self.compare_flower.handle();
+        let stop_generation = self.compare_stop.clone();
+        // summaries are made from the selected model's replies only
+        self.fit_context(&mut context_messages, false);
+        let settings = self.settings().clone();
+        if let Some(reply) = self.messages[index].compare.as_deref_mut() {
+            reply.settings = Some(settings);
+        }
+        self.compare_task = self.spawn_request(
+            context_messages,
+            model_name,
+            index,
+            handle,
+            stop_generation,
+            None,
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_request(
+        &self,
+        context_messages: Vec<ChatMessage>,
+        model_name: String,
+        index: usize,
+        handle: CompletionFlowerHandle,
+        stop_generation: Arc<AtomicBool>,
+        summary_job: Option<SummaryJob>,
+    ) -> CompletionTask {
+        let settings = self.settings().clone();
+        let template = self.model_picker.template.clone();
+        let proxy = self.proxy.clone();
+        // a raw prompt is continued as written, so there's no question to ground
+        let knowledge =
+            (self.knowledge.is_indexed() && !self.completion_mode).then(|| self.knowledge.clone());
+        let raw = self.completion_mode;
+        let timeouts = self.timeouts;
+        // client clones share the HTTP client, which handles concurrent requests, so
+        // chats can stream at the same time
+        let backend = Backend::pick(&self.ollama_client, self.openai.as_ref());
+        // embeddings for the knowledge always come from Ollama
+        let ollama = self.ollama_client.clone();
+        let task = tokio::spawn(async move {
+            handle.activate();
+            let _ = request_completion(
+                ollama,
+                backend,
+                context_messages,
+                &handle,
+                stop_generation,
+                model_name,
+                settings,
+                template,
+                knowledge,
+                summary_job,
+                index,
+                raw,
+                timeouts,
+            )
+            .await
+            .map_err(|e| {
+                log::error!("failed to request completion: {e}");
+                handle.error((
+                    index,
+                    widgets::describe_request_error(widgets::error_chain(&*e), proxy.as_ref()),
+                ));
+            });
+        });
+        CompletionTask(Some(task.abort_handle()))
+    }
+
+    fn regenerate_response(&mut self, idx: usize) {
+        // remake context history to make the message we want to regenerate last
+        let mut messages = self.get_context_messages(&self.messages[..idx]);
+
+        // start with the prepended message and update it in the displayed messages
+        messages.push(ChatMessage::assistant(self.prepend_buf.clone()));
+        self.messages[idx].content = self.prepend_buf.clone();
+        self.prepend_buf.clear();
+
+        // start completing the message
+        self.spawn_completion(messages, self.messages[idx].model_name.clone(), idx);
+    }
+
+    /// Let the model continue the interrupted reply at `idx`, appending to it.
+    fn continue_response(&mut self, idx: usize) {
+        let mut messages = self.get_context_messages(&self.messages[..idx]);
+        let message = &mut self.messages[idx];
+        messages.push(ChatMessage::assistant(message.content.clone()));
+        message.is_generating = true;
+        message.requested_at = Instant::now();
+        message.tagged_content = None;
+        message.error = None;
+        let model = message.model_name.clone();
+        self.spawn_completion(messages, model, idx);
+    }
+
+    /// Replace the reply at `idx` with a new one generated by `model`.
+    fn regenerate_with(&mut self, idx: usize, model: String) {
+        let messages = self.get_context_messages(&self.messages[..idx]);
+        let compare = self.messages[idx].compare.take();
+        self.messages[idx] = Message::assistant(String::new(), model.clone());
+        self.messages[idx].compare = compare;
+        self.spawn_completion(messages, model, idx);
+    }
+
+    /// Replace the compared model's reply at `idx` with a new one by `model`, or the same
+    /// model. A regenerated reply continues from any text prepended to it.
+    fn regenerate_compare(&mut self, idx: usize, model: Option<String>) {
+        let Some(reply) = self.messages[idx].compare.as_deref_mut() else {
+            return;
+        };
+        let (prepend, model) = match model {
+            Some(model) => (String::new(), model),
+            None if reply.is_error => (String::new(), reply.model_name.clone()),
+            None => (reply.content.clone(), reply.model_name.clone()),
+        };
+        *reply = Message::assistant(prepend.clone(), model.clone());
+        let mut messages = self.get_compare_context_messages(&self.messages[..idx]);
+        messages.push(ChatMessage::assistant(prepend));
+        self.spawn_compare_completion(messages, model, idx);
+    }
+
+    /// Leave compare mode, continuing with the compared model if `compared`, else with the
+    /// selected one. The replies of the model continued with take the place of the other
+    /// model's, which stay next to them.
+    fn promote(&mut self, compared: bool) {
+        let Some(compare_model) = self.compare_model.take() else {
+            return;
+        };
+        if compared {
+            for message in &mut self.messages {
+                let Some(mut other) = message.compare.take() else {
+                    continue;
+                };
+                if other.model_name == compare_model.name {
+                    std::mem::swap(message, &mut *other);
+                }
+                message.compare = Some(other);
+            }
+            self.model_picker.selected = compare_model;
+            self.model_picker.info = None;
+        }
+        self.modified = true;
+    }
+
+    /// Models offered by "Regenerate with…": the ones used in this chat first, then the most
+    /// recently modified local models.
+    fn regenerate_models(&self, models: &[LocalModel]) -> Vec<String> {
+        const MAX_MODELS: usize = 10;
+
+        let mut local: Vec<&LocalModel> = models.iter().collect();
+        local.sort_unstable_by(|a, b| b.modified_at.cmp(&a.modified_at));
+
+        let mut names: Vec<String> = Vec::new();
+        let used = self
+            .messages
+            .iter()
+            .rev()
+            .filter(|m| !m.is_user())
+            .map(|m| m.model_name.as_str());
+        for name in std::iter::once(self.model_picker.selected_model())
+            .chain(used)
+            .chain(local.iter().map(|m| m.name.as_str()))
+        {
+            if names.len() >= MAX_MODELS {
+                break;
+            }
+            if !name.is_empty() && !names.iter().any(|n| n == name) {
+                names.push(name.to_owned());
+            }
+        }
+        names
+    }
+
+    /// Put a golden prompt into the chatbox, replacing a slash command, the selected text if
+    /// the prompt has a place for it, or appending to the text. The cursor is moved to the
+    /// first placeholder.
+    fn insert_golden_prompt(&mut self, ctx: &egui::Context, prompt: &mut GoldenPrompt) {
+        let state = self
+            .chatbox_id
+            .and_then(|id| egui::text_edit::TextEditState::load(ctx, id));
+        let byte = |chatbox: &str, c: usize| {
+            chatbox
+                .char_indices()
+                .nth(c)
+                .map_or(chatbox.len(), |(i, _)| i)
+        };
+        let selected = state
+            .and_then(|state| state.cursor.char_range())
+            .filter(|_| !self.chatbox.starts_with('/'))
+            .map(|range| {
+                let [a, b] = [range.primary.index, range.secondary.index];
+                byte(&self.chatbox, a.min(b))..byte(&self.chatbox, a.max(b))
+            })
+            .filter(|range| !range.is_empty());
+
+        let (text, placeholder) = prompt.expand(selected.clone().map_or("", |r| &self.chatbox[r]));
+        let replaced = if self.chatbox.trim().is_empty() || self.chatbox.starts_with('/') {
+            0..self.chatbox.len()
+        } else if let Some(range) = selected.filter(|_| prompt.has_selection_placeholder()) {
+            range
+        } else {
+            if !self.chatbox.ends_with('\n') {
+                self.chatbox.push('\n');
+            }
+            self.chatbox.len()..self.chatbox.len()
+        };
+        let offset = self.chatbox[..replaced.start].chars().count();
+        let cursor = match placeholder {
+            Some(range) => offset + range.start..offset + range.end,
+            None => {
+                let end = offset + text.chars().count();
+                end..end
+            }
+        };
+        self.chatbox.replace_range(replaced, &text);
+        prompt.record_use(self.model_picker.selected_model());
+
+        if let Some(id) = self.chatbox_id {
+            let mut state = egui::text_edit::TextEditState::load(ctx, id).unwrap_or_default();
+            state
+                .cursor
+                .set_char_range(Some(egui::text::CCursorRange::two(
+                    egui::text::CCursor::new(cursor.start),
+                    egui::text::CCursor::new(cursor.end),
+                )));
+            state.store(ctx, id);
+            ctx.memory_mut(|m| m.request_focus(id));
+        }
+    }
+
+    fn show_save_golden_modal_inner(
+        &mut self,
+        ui: &mut egui::Ui,
+        modal: &Modal,
+        golden_prompts: &mut Vec<GoldenPrompt>,
+    ) {
+        let Some(draft) = &mut self.golden_draft else {
+            modal.close();
+            return;
+        };
+        modal.title(ui, tr("chat.save_golden_title"));
+        modal.frame(ui, |ui| {
+            egui::Grid::new("save_golden_prompt_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label(tr("chat.golden_name"));
+                    ui.text_edit_singleline(&mut draft.name);
+                    ui.end_row();
+
+                    ui.label(tr("chat.golden_tags"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut draft.tags)
+                            .hint_text(tr("chat.golden_tags_hint")),
+                    );
+                    ui.end_row();
+                });
+            egui::ScrollArea::vertical()
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    ui.add(egui::Label::new(&draft.text).wrap(true));
+                });
+        });
+        let mut close = false;
+        modal.buttons(ui, |ui| {
+            if modal.button(ui, tr("common.cancel")).clicked() {
+                close = true;
+            }
+            if modal.suggested_button(ui, tr("common.save")).clicked() {
+                if let Some(draft) = self.golden_draft.take() {
+                    golden_prompts.push(draft);
+                }
+                close = true;
+            }
+        });
+        if close {
+            self.golden_draft = None;
+            modal.close();
+        }
+    }
+
+    fn show_chatbox(
+        &mut self,
+        ui: &mut egui::Ui,
+        is_max_height: bool,
+        is_generating: bool,
+        golden_prompts: &mut [GoldenPrompt],
+    ) -> ChatAction {
+        let mut action = ChatAction::None;
+        if let Some(idx) = self.retry_message_idx.take() {
+            // same conversation and model as the failed request
+            let model = self.messages[idx].model_name.clone();
+            self.regenerate_with(idx, model);
+        }
+
+        if is_max_height {
+            ui.add_space(8.0);
+        }
+
+        let images_height = if !self.images.is_empty() {
+            ui.add_space(8.0);
+            let height = ui
+                .horizontal(|ui| {
+                    crate::image::show_images(ui, &mut self.images, true);
+                    if self.model_picker.has_selection() && !self.model_picker.likely_multimodal() {
+                        ui.label(egui::RichText::new("⚠").color(ui.visuals().warn_fg_color))
+                            .on_hover_text(tr_args(
+                                "chat.not_multimodal",
+                                &[&self.model_picker.selected_model()],
+                            ));
+                    }
+                })
+                .response
+                .rect
+                .height();
+            height + 16.0
+        } else {
+            0.0
+        };
+
+        let files_height = if !self.files.is_empty() {
+            ui.add_space(8.0);
+            let mut remove = None;
+            let height = ui
+                .vertical(|ui| {
+                    for (i, file) in self.files.iter().enumerate() {
+                        if file.show(ui, i) {
+                            remove = Some(i);
+                        }
+                    }
+                })
+                .response
+                .rect
+                .height();
+            if let Some(i) = remove {
+                self.files.remove(i);
+            }
+            height + 8.0
+        } else {
+            0.0
+        };
+
+        // "/query" lists the matching golden prompts
+        let slash_query = self
+            .chatbox
+            .strip_prefix('/')
+            .filter(|q| !q.contains('\n'))
+            .map(str::to_owned);
+        let mut insert_golden = None;
+        // the best match is inserted with the send key
+        let mut best_golden = None;
+        let slash_height = if let Some(query) = slash_query {
+            best_golden = golden::matching(golden_prompts, &query).first().copied();
+            ui.add_space(8.0);
+            let height = ui
+                .horizontal_wrapped(|ui| {
+                    ui.label(tr("chat.golden_prompts"));
+                    insert_golden = golden::show_prompt_list(ui, golden_prompts, &query);
+                })
+                .response
+                .rect
+                .height();
+            height + 8.0
+        } else {
+            0.0
+        };
+
+        let overflow_height = if self.chatbox.trim().is_empty() && self.files.is_empty() {
+            0.0
+        } else {
+            let tokens = self.prompt_tokens(ui.ctx());
+            let window = self.context_window();
+            if tokens > window {
+                ui.add_space(8.0);
+                let height = ui
+                    .horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(tr_args(
+                                "chat.overflow",
+                                &[&compact_count(tokens), &compact_count(window)],
+                            ))
+                            .color(ui.visuals().warn_fg_color),
+                        );
+                        ui.menu_button(tr("chat.context_strategy"), |ui| {
+                            self.show_context_options(ui)
+                        })
+                        .response
+                        .on_hover_text(tr("chat.context_strategy_help"));
+                    })
+                    .response
+                    .rect
+                    .height();
+                height + 8.0
+            } else {
+                0.0
+            }
+        };
+
+        ui.horizontal_centered(|ui| {
+            if ui
+                .add(
+                    egui::Button::new("📎")
+                        .min_size(vec2(32.0, 32.0))
+                        .rounding(Rounding::same(f32::INFINITY)),
+                )
+                .on_hover_text_at_pointer(tr("chat.attach_images"))
+                .clicked()
+            {
+                action = ChatAction::PickImages { id: self.id() };
+            }
+            if ui
+                .add(
+                    egui::Button::new("📋")
+                        .min_size(vec2(32.0, 32.0))
+                        .rounding(Rounding::same(f32::INFINITY)),
+                )
+                .on_hover_text_at_pointer(tr("chat.paste_image"))
+                .clicked()
+            {
+                action = ChatAction::PasteImage;
+            }
+            ui.menu_button("⭐", |ui| {
+                if let Some(i) = golden::show_prompt_list(ui, golden_prompts, "") {
+                    insert_golden = Some(i);
+                    ui.close_menu();
+                }
+            })
+            .response
+            .on_hover_text(tr("chat.insert_golden"));
+            #[cfg(feature = "voice")]
+            self.show_voice_input(ui);
+            let shortcut = self.send_shortcut;
+            let mut send = false;
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                send |= ui
+                    .add_enabled(
+                        !is_generating,
+                        egui::Button::new("➤")
+                            .min_size(vec2(32.0, 32.0))
+                            .rounding(Rounding::same(f32::INFINITY)),
+                    )
+                    .on_hover_text(tr_args(
+                        "chat.send_help",
+                        &[&shortcut.send_hint(), &shortcut.newline_hint()],
+                    ))
+                    .clicked();
+
+                ui.with_layout(
+                    Layout::left_to_right(Align::Center).with_main_justify(true),
+                    |ui| {
+                        let Self {
+                            chatbox_highlighter: highlighter,
+                            ..
+                        } = self;
+                        let mut layouter = |ui: &egui::Ui, easymark: &str, wrap_width: f32| {
+                            let mut layout_job = highlighter.highlight(ui.style(), easymark);
+                            layout_job.wrap.max_width = wrap_width;
+                            ui.fonts(|f| f.layout_job(layout_job))
+                        };
+
+                        // grow with the text up to a few lines, then scroll
+                        let row_height = ui.text_style_height(&egui::TextStyle::Body);
+                        let max_height =
+                            row_height * MAX_CHATBOX_LINES + ui.spacing().button_padding.y * 2.0;
+                        let scroll = egui::ScrollArea::vertical()
+                            .id_source("chatbox_input_scroll")
+                            .max_height(max_height)
+                            .stick_to_bottom(true)
+                            .show(ui, |ui| {
+                                egui::TextEdit::multiline(&mut self.chatbox)
+                                    .return_key(shortcut.newline_shortcut())
+                                    .desired_rows(1)
+                                    .hint_text(tr("chat.ask"))
+                                    .layouter(&mut layouter)
+                                    .show(ui)
+                            });
+                        self.chatbox_height = scroll.inner_rect.height()
+                            + images_height
+                            + files_height
+                            + slash_height
+                            + overflow_height;
+                        self.chatbox_id = Some(scroll.inner.response.id);
+                        // Enter in other fields, like the find bar, must not send the draft
+                        let focused = scroll.inner.response.has_focus();
+                        self.handle_history_keys(ui, scroll.inner);
+                        send |= focused && ui.input(|i| shortcut.send_pressed(i));
+                    },
+                );
+            });
+            if send && best_golden.is_some() {
+                insert_golden = best_golden;
+            } else if send && !is_generating {
+                self.send_message();
+            }
+        });
+
+        if let Some(prompt) = insert_golden.and_then(|i| golden_prompts.get_mut(i)) {
+            self.insert_golden_prompt(ui.ctx(), prompt);
+        }
+
+        if is_max_height {
+            ui.add_space(8.0);
+        }
+
+        action
+    }
+
+    /// Microphone button that records while toggled on, then transcribes the recording
+    /// into the chatbox.
+    #[cfg(feature = "voice")]
+    fn show_voice_input(&mut self, ui: &mut egui::Ui) {
+        if self.transcribe_flower.is_active() {
+            ui.ctx().request_repaint();
+            self.transcribe_flower
+                .extract(|()| ())
+                .finalize(|result| match result {
+                    Ok(text) => {
+                        if !self.chatbox.is_empty() && !self.chatbox.ends_with(char::is_whitespace)
+                        {
+                            self.chatbox.push(' ');
+                        }
+                        self.chatbox.push_str(&text);
+                    }
+                    Err(e) => {
+                        let e = match e {
+                            Compact::Panicked(e) => tr_args("common.task_panicked", &[&e]),
+                            Compact::Suppose(e) => e,
+                        };
+                        log::error!("{e}");
+                        self.transcribe_error = Some(e);
+                    }
+                });
+        }
+        if self.transcribe_flower.is_active() {
+            ui.spinner().on_hover_text(tr("chat.transcribing"));
+            return;
+        }
+
+        let button = egui::Button::new(if self.recorder.is_some() {
+            egui::RichText::new("⏺").color(ui.visuals().error_fg_color)
+        } else {
+            egui::RichText::new("🎤")
+        })
+        .min_size(vec2(32.0, 32.0))
+        .rounding(Rounding::same(f32::INFINITY));
+        let unavailable = if self.whisper_model.trim().is_empty() {
+            Some(tr("chat.voice_needs_model"))
+        } else {
+            self.mic_error.as_deref()
+        };
+        let resp = ui
+            .add_enabled(unavailable.is_none(), button)
+            .on_disabled_hover_text(unavailable.unwrap_or_default());
+
+        if self.recorder.is_some() {
+            ui.ctx().request_repaint();
+            ui.label(egui::RichText::new(tr("chat.recording")).color(ui.visuals().error_fg_color));
+        } else if let Some(e) = &self.transcribe_error {
+            ui.label(egui::RichText::new("⚠").color(ui.visuals().error_fg_color))
+                .on_hover_text(tr_args("chat.transcription_failed", &[e]));
+        }
+
+        let resp = resp.on_hover_text(if self.recorder.is_some() {
+            tr("chat.stop_recording")
+        } else {
+            tr("chat.record")
+        });
+        if !resp.clicked() {
+            return;
+        }
+
+        if let Some(recorder) = self.recorder.take() {
+            let samples = recorder.finish();
+            if samples.is_empty() {
+                return;
+            }
+            let handle = self.transcribe_flower.handle();
+            let model = PathBuf::from(self.whisper_model.trim());
+            tokio::task::spawn_blocking(move || {
+                handle.activate();
+                match voice::transcribe(&model, &samples) {
+                    Ok(text) => handle.success(text),
+                    Err(e) => handle.error(format!("{e:#}")),
+                }
+            });
+        } else {
+            self.transcribe_error = None;
+            match voice::Recorder::start() {
+                Ok(recorder) => self.recorder = Some(recorder),
+                Err(e) => {
+                    log::error!("failed to start recording: {e:#}");
+                    self.mic_error = Some(tr_args("chat.mic_unavailable", &[&format!("{e:#}")]));
+                }
+            }
+        }
+    }
+
+    /// Recall sent prompts with Up/Down while the cursor is on the first/last line of the
+    /// input.
+    fn handle_history_keys(&mut self, ui: &egui::Ui, output: egui::text_edit::TextEditOutput) {
+        if !output.response.has_focus() {
+            return;
+        }
+        let (up, down) = ui.input(|i| {
+            (
+                i.key_pressed(Key::ArrowUp) && i.modifiers.is_none(),
+                i.key_pressed(Key::ArrowDown) && i.modifiers.is_none(),
+            )
+        });
+
+        // the cursor already moved this frame, so use where it was before the key press
+        let (on_first_row, on_last_row) = self.cursor_rows;
+        let recalled = if up && (self.chatbox.is_empty() || on_first_row) {
+            self.recall_history(true)
+        } else if down && on_last_row {
+            self.recall_history(false)
+        } else {
+            false
+        };
+
+        if recalled {
+            let mut state = output.state;
+            let end = egui::text::CCursor::new(self.chatbox.chars().count());
+            state
+                .cursor
+                .set_char_range(Some(egui::text::CCursorRange::one(end)));
+            state.store(ui.ctx(), output.response.id);
+            self.cursor_rows = (!self.chatbox.contains('\n'), true);
+        } else if let Some(range) = output.cursor_range {
+            let row = range.primary.rcursor.row;
+            self.cursor_rows = (row == 0, row + 1 >= output.galley.rows.len());
+        }
+    }
+
+    /// Step through the prompt history, returns whether the input changed.
+    fn recall_history(&mut self, older: bool) -> bool {
+        let len = self.prompt_history.len();
+        let pos = match (self.history_pos, older) {
+            (None, true) if len > 0 => {
+                self.history_draft = std::mem::take(&mut self.chatbox);
+                len - 1
+            }
+            (Some(pos), true) if pos > 0 => pos - 1,
+            (Some(pos), false) if pos + 1 < len => pos + 1,
+            // back at the bottom, restore what was being typed
+            (Some(_), false) => {
+                self.history_pos = None;
+                self.chatbox = std::mem::take(&mut self.history_draft);
+                return true;
+            }
+            _ => return false,
+        };
+        self.history_pos = Some(pos);
+        self.chatbox.clone_from(&self.prompt_history[pos]);
+        true
+    }
+
+    fn show_header(
+        &mut self,
+        ui: &mut egui::Ui,
+        models: &[LocalModel],
+        #[cfg(feature = "tts")] tts_available: bool,
+    ) {
+        ui.horizontal(|ui| {
+            if self.model_picker.has_selection() {
+                model_avatar(ui, self.model_picker.selected_model());
+                ui.label(egui::RichText::new(self.model_picker.selected_model()).strong())
+                    .on_hover_text(tr("chat.model_help"));
+            } else {
+                ui.label(tr("chat.no_model"));
+            }
+            if let Some(compare) = &self.compare_model {
+                ui.label(tr("chat.versus"));
+                model_avatar(ui, &compare.name);
+                ui.label(egui::RichText::new(&compare.name).strong())
+                    .on_hover_text(tr("chat.compare_model_help"));
+            }
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                ui.toggle_value(&mut self.show_info, "ℹ")
+                    .on_hover_text(tr("chat.info"));
+                ui.menu_button("📊", |ui| self.show_stats(ui))
+                    .response
+                    .on_hover_text(tr("chat.stats"));
+                ui.add_enabled_ui(!self.flower_active(), |ui| {
+                    ui.menu_button(
+                        if self.compare_model.is_some() {
+                            egui::RichText::new("⚖").color(ui.visuals().selection.stroke.color)
+                        } else {
+                            egui::RichText::new("⚖")
+                        },
+                        |ui| {
+                            ui.label(tr("chat.compare_with"));
+                            ui.separator();
+                            let selected = self.model_picker.selected_model();
+                            for model in models.iter().filter(|m| m.name != selected) {
+                                let current = self
+                                    .compare_model
+                                    .as_ref()
+                                    .is_some_and(|m| m.name == model.name);
+                                if ui.selectable_label(current, &model.name).clicked() {
+                                    self.compare_model = Some(model.clone().into());
+                                    ui.close_menu();
+                                }
+                            }
+                            if self.compare_model.is_some() {
+                                ui.separator();
+                                if ui.button(tr("chat.stop_comparing")).clicked() {
+                                    self.compare_model = None;
+                                    ui.close_menu();
+                                }
+                            }
+                        },
+                    )
+                    .response
+                    .on_hover_text(tr("chat.compare_help"))
+                    .on_disabled_hover_text(tr("chat.wait_for_response"));
+                });
+                self.show_context_usage(ui);
+                ui.menu_button("☰", |ui| {
+                    let mut completion_mode = self.completion_mode;
+                    if ui
+                        .checkbox(&mut completion_mode, tr("chat.completion_mode"))
+                        .on_hover_text(tr("chat.completion_mode_help"))
+                        .changed()
+                    {
+                        if self.messages.is_empty() {
+                            self.completion_mode = completion_mode;
+                        } else {
+                            self.confirm_mode_switch = Some(completion_mode);
+                            ui.close_menu();
+                        }
+                    }
+                    ui.separator();
+                    ui.checkbox(
+                        &mut self.reply_options.copy_code_only,
+                        tr("chat.copy_code_only"),
+                    );
+                    ui.checkbox(
+                        &mut self.reply_options.collapse_preamble,
+                        tr("chat.collapse_preamble"),
+                    );
+                    ui.checkbox(&mut self.reply_options.render_math, tr("chat.render_math"))
+                        .on_hover_text(tr("chat.render_math_help"));
+                    ui.separator();
+                    self.show_context_options(ui);
+                    #[cfg(feature = "tts")]
+                    ui.add_enabled(
+                        tts_available,
+                        egui::Checkbox::new(&mut self.auto_speak, tr("chat.auto_speak")),
+                    )
+                    .on_disabled_hover_text(tr("chat.tts_unavailable"));
+                    ui.separator();
+                    if ui
+                        .add_enabled(
+                            !self.messages.is_empty(),
+                            egui::Button::new(tr("chat.copy_conversation")),
+                        )
+                        .on_hover_text(tr("chat.copy_conversation_help"))
+                        .clicked()
+                    {
+                        ui.ctx().copy_text(
+                            self.conversation_markdown(self.reply_options.copy_system_prompt),
+                        );
+                        self.copied_messages = Some(self.messages.len());
+                        ui.close_menu();
+                    }
+                    ui.checkbox(
+                        &mut self.reply_options.copy_system_prompt,
+                        tr("chat.copy_system_prompt"),
+                    );
+                    ui.separator();
+                    let generating = self.flower_active();
+                    if ui
+                        .add_enabled(
+                            !generating && !self.messages.is_empty(),
+                            egui::Button::new(tr("chat.clear")),
+                        )
+                        .on_hover_text(tr("chat.clear_help"))
+                        .on_disabled_hover_text(if generating {
+                            tr("chat.wait_for_response")
+                        } else {
+                            tr("chat.no_messages")
+                        })
+                        .clicked()
+                    {
+                        self.confirm_clear = true;
+                        ui.close_menu();
+                    }
+                })
+                .response
+                .on_hover_text(tr("chat.options"));
+            });
+        });
+        if self.quick_settings {
+            // edits override the shared settings for this chat, like the full editor
+            let mut settings = self.settings().clone();
+            if settings.show_quick(ui) {
+                self.settings_override = Some(settings);
+                self.modified = true;
+            }
+        }
+        // there's no system prompt in a raw completion
+        if !self.completion_mode {
+            egui::CollapsingHeader::new(tr("chat.system_prompt"))
+                .id_source(("chat_system_prompt", self.id()))
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.system_prompt)
+                            .hint_text(tr("chat.system_prompt_hint"))
+                            .desired_rows(3)
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+        }
+        if self.show_info {
+            ui.separator();
+            self.show_info_grid(ui);
+        }
+    }
+
+    /// How the conversation is fit into the context window.
+    fn show_context_options(&mut self, ui: &mut egui::Ui) {
+        ui.label(tr("chat.context"));
+        for strategy in ContextStrategy::ALL {
+            ui.radio_value(&mut self.context.strategy, strategy, strategy.label())
+                .on_hover_text(strategy.description());
+        }
+        match self.context.strategy {
+            ContextStrategy::SendAll => (),
+            ContextStrategy::SlidingWindow => {
+                ui.add(
+                    egui::DragValue::new(&mut self.context.window)
+                        .clamp_range(2..=1000)
+                        .prefix(tr("chat.window_prefix"))
+                        .suffix(tr("chat.window_suffix")),
+                );
+            }
+            ContextStrategy::SummarizeOverflow => {
+                ui.add(
+                    egui::Slider::new(&mut self.context.threshold, 0.3..=0.95)
+                        .custom_formatter(|v, _| format!("{:.0}%", v * 100.0))
+                        .text(tr("chat.threshold")),
+                )
+                .on_hover_text(tr_args("chat.threshold_help", &[&self.context_window()]));
+                if self.context_summary.is_some()
+                    && ui
+                        .button(tr("chat.forget_summary"))
+                        .on_hover_text(tr("chat.forget_summary_help"))
+                        .clicked()
+                {
+                    self.context_summary = None;
+                    self.modified = true;
+                }
+            }
+        }
     }
 
-    fn send_message(&mut self, ollama: &Ollama) {
-        // don't send empty messages
-        if self.chatbox.is_empty() && self.images.is_empty() {
-            return;
+    /// Estimated tokens of the request that would send the current input, after the
+    /// context strategy left out what it can. Only updated once the input stops changing.
+    fn prompt_tokens(&mut self, ctx: &egui::Context) -> u64 {
+        let source = (
+            self.chatbox.len(),
+            self.files.len(),
+            self.messages.len(),
+            self.flower_active(),
+        );
+        let estimate = &mut self.prompt_estimate;
+        if source != estimate.source {
+            estimate.source = source;
+            estimate.changed_at = Some(Instant::now());
+        }
+        if let Some(changed_at) = estimate.changed_at {
+            let waited = changed_at.elapsed();
+            if waited < ESTIMATE_DEBOUNCE {
+                ctx.request_repaint_after(ESTIMATE_DEBOUNCE - waited);
+            } else {
+                estimate.changed_at = None;
+                self.prompt_estimate.tokens = self.estimate_request_tokens();
+            }
         }
+        self.prompt_estimate.tokens
+    }
 
-        // remove old error messages
-        self.messages.retain(|m| !m.is_error);
+    fn estimate_request_tokens(&self) -> u64 {
+        let prompt = self
+            .files
+            .iter()
+            .map(FileAttachment::to_prompt)
+            .chain([self.chatbox.clone()])
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        // only the text counts, without loading the images
+        let mut context: Vec<ChatMessage> = (!self.system_prompt.trim().is_empty())
+            .then(|| ChatMessage::system(self.system_prompt.clone()))
+            .into_iter()
+            .chain(self.messages.iter().map(|m| match m.role {
+                Role::User => ChatMessage::user(m.content.clone()),
+                Role::Assistant => ChatMessage::assistant(m.content.clone()),
+            }))
+            .chain([ChatMessage::user(prompt)])
+            .collect();
+        self.fit_context(&mut context, false);
+        context.iter().map(|m| estimate_tokens(&m.content)).sum()
+    }
 
-        let prompt = self.chatbox.trim_end().to_string();
-        let model_name = self.model_picker.selected_model().to_owned();
-        self.messages.push(Message::user(
-            prompt.clone(),
-            model_name.clone(),
-            self.images.clone(),
-        ));
+    /// Index of the message being read aloud, if any.
+    #[cfg(feature = "tts")]
+    pub fn speaking_message(&self) -> Option<usize> {
+        self.messages.iter().position(|m| m.is_speaking)
+    }
 
-        if self.summary.is_empty() {
-            self.summary = make_summary(&prompt);
+    /// Reset the speaking icons after speech stopped.
+    #[cfg(feature = "tts")]
+    pub fn clear_speaking(&mut self) {
+        for msg in self.messages.iter_mut() {
+            msg.is_speaking = false;
         }
+    }
 
-        // clear chatbox & images
-        self.chatbox.clear();
-        self.images.clear();
+    /// Whether a reply is being generated, by either model in compare mode
+    #[inline]
+    pub fn flower_active(&self) -> bool {
+        self.flower.is_active() || self.compare_flower.is_active()
+    }
 
-        // get ready for assistant response
-        self.messages
-            .push(Message::assistant(String::new(), model_name.clone()));
+    #[inline]
+    pub fn is_indexing(&self) -> bool {
+        self.index_flower.is_active()
+    }
 
-        self.spawn_completion(
-            ollama.clone(),
-            Self::get_context_messages(&self.messages),
-            model_name,
-        );
+    pub fn poll_index(&mut self) {
+        self.index_flower
+            .extract(|progress| self.index_progress = Some(progress))
+            .finalize(|result| {
+                self.index_progress = None;
+                match result {
+                    Ok((model, sources, chunks)) => {
+                        self.knowledge.indexed_model = model;
+                        self.knowledge.indexed_sources = sources;
+                        self.knowledge.chunks = chunks;
+                        self.modified = true;
+                    }
+                    Err(Compact::Panicked(e)) => {
+                        self.index_error = Some(tr_args("chat.indexing_panicked", &[&e]));
+                    }
+                    Err(Compact::Suppose(e)) => self.index_error = Some(e),
+                }
+            });
     }
 
-    /// spawn a new task to generate the completion
-    fn spawn_completion(
-        &self,
-        ollama: Ollama,
-        context_messages: Vec<ChatMessage>,
-        model_name: String,
-    ) {
-        let handle = self.flower.handle(); // recv'd by gui thread
-        let stop_generation = self.stop_generating.clone();
-        let generation_options = self.model_picker.get_generation_options();
-        let template = self.model_picker.template.clone();
-        let index = self.messages.len() - 1;
-        tokio::spawn(async move {
+    fn start_indexing(&mut self, ollama: &OllamaClient) {
+        self.index_error = None;
+        self.index_progress = None;
+        let handle = self.index_flower.handle();
+        let ollama = ollama.clone();
+        let model = self.knowledge.embedding_model.clone();
+        let sources = self.knowledge.sources.clone();
+        let task = tokio::spawn(async move {
             handle.activate();
-            let _ = request_completion(
-                ollama,
-                context_messages,
-                &handle,
-                stop_generation,
-                model_name,
-                generation_options,
-                template,
-                index,
-            )
-            .await
-            .map_err(|e| {
-                log::error!("failed to request completion: {e}");
-                handle.error((index, e.to_string()));
-            });
+            knowledge::index(ollama, model, sources, &handle).await;
         });
+        self.index_task = CompletionTask(Some(task.abort_handle()));
     }
 
-    fn regenerate_response(&mut self, ollama: &Ollama, idx: usize) {
-        // remake context history to make the message we want to regenerate last
-        let mut messages = Self::get_context_messages(&self.messages[..idx]);
-
-        // start with the prepended message and update it in the displayed messages
-        messages.push(ChatMessage::assistant(self.prepend_buf.clone()));
-        self.messages[idx].content = self.prepend_buf.clone();
-        self.prepend_buf.clear();
-
-        // start completing the message
-        self.spawn_completion(
-            ollama.clone(),
-            messages,
-            self.messages[idx].model_name.clone(),
-        );
+    /// Add files or directories to index, skipping ones already added.
+    pub fn add_knowledge_sources(&mut self, paths: Vec<PathBuf>) {
+        for path in paths {
+            if !self.knowledge.sources.contains(&path) {
+                self.knowledge.sources.push(path);
+            }
+        }
+        self.modified = true;
     }
 
-    fn show_chatbox(
+    /// Sources, embedding model and indexing of the chat's knowledge. Returns the kind of
+    /// sources to ask for if "Add" was clicked.
+    pub fn show_knowledge(
         &mut self,
         ui: &mut egui::Ui,
-        is_max_height: bool,
-        is_generating: bool,
-        ollama: &Ollama,
-    ) -> ChatAction {
-        let mut action = ChatAction::None;
-        if let Some(idx) = self.retry_message_idx.take() {
-            self.chatbox = self.messages[idx - 1].content.clone();
-            self.messages.remove(idx); // remove assistant message
-            self.messages.remove(idx - 1); // remove user message
-            self.send_message(ollama);
-        }
+        ollama: &OllamaClient,
+        models: &[LocalModel],
+    ) -> Option<SourcePick> {
+        let mut pick = None;
+        ui.label(tr("chat.knowledge_help"));
 
-        if is_max_height {
-            ui.add_space(8.0);
+        let mut remove = None;
+        for (i, path) in self.knowledge.sources.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui
+                    .small_button("❌")
+                    .on_hover_text(tr("chat.remove_source"))
+                    .clicked()
+                {
+                    remove = Some(i);
+                }
+                ui.label(path.display().to_string());
+            });
         }
-
-        let images_height = if !self.images.is_empty() {
-            ui.add_space(8.0);
-            let height = ui
-                .horizontal(|ui| {
-                    crate::image::show_images(ui, &mut self.images, true);
-                })
-                .response
-                .rect
-                .height();
-            height + 16.0
-        } else {
-            0.0
-        };
-
-        ui.horizontal_centered(|ui| {
+        if let Some(i) = remove {
+            self.knowledge.sources.remove(i);
+            self.modified = true;
+        }
+        ui.horizontal(|ui| {
+            if ui.button(tr("chat.add_files")).clicked() {
+                pick = Some(SourcePick::Files);
+            }
             if ui
-                .add(
-                    egui::Button::new("➕")
-                        .min_size(vec2(32.0, 32.0))
-                        .rounding(Rounding::same(f32::INFINITY)),
-                )
-                .on_hover_text_at_pointer("Pick Images")
+                .button(tr("chat.add_folder"))
+                .on_hover_text(tr("chat.add_folder_help"))
                 .clicked()
             {
-                action = ChatAction::PickImages { id: self.id() };
+                pick = Some(SourcePick::Folder);
             }
-            ui.with_layout(
-                Layout::left_to_right(Align::Center).with_main_justify(true),
-                |ui| {
-                    let Self {
-                        chatbox_highlighter: highlighter,
-                        ..
-                    } = self;
-                    let mut layouter = |ui: &egui::Ui, easymark: &str, wrap_width: f32| {
-                        let mut layout_job = highlighter.highlight(ui.style(), easymark);
-                        layout_job.wrap.max_width = wrap_width;
-                        ui.fonts(|f| f.layout_job(layout_job))
-                    };
-
-                    self.chatbox_height = egui::TextEdit::multiline(&mut self.chatbox)
-                        .return_key(KeyboardShortcut::new(Modifiers::SHIFT, Key::Enter))
-                        .hint_text("Ask me anything…")
-                        .layouter(&mut layouter)
-                        .show(ui)
-                        .response
-                        .rect
-                        .height()
-                        + images_height;
-                    if !is_generating
-                        && ui.input(|i| i.key_pressed(Key::Enter) && i.modifiers.is_none())
-                    {
-                        self.send_message(ollama);
-                    }
-                },
-            );
         });
 
-        if is_max_height {
-            ui.add_space(8.0);
+        // embedding models first
+        let mut names: Vec<&str> = models.iter().map(|m| m.name.as_str()).collect();
+        names.sort_by_key(|name| !name.contains("embed"));
+        let embedding_model = &mut self.knowledge.embedding_model;
+        if embedding_model.is_empty() {
+            if let Some(name) = names.iter().find(|name| name.contains("embed")) {
+                *embedding_model = (*name).to_owned();
+            }
+        }
+        egui::ComboBox::from_label(tr("chat.embedding_model"))
+            .selected_text(if embedding_model.is_empty() {
+                tr("chat.select")
+            } else {
+                embedding_model.as_str()
+            })
+            .show_ui(ui, |ui| {
+                for name in &names {
+                    ui.selectable_value(embedding_model, (*name).to_owned(), *name);
+                }
+            });
+        if !names.iter().any(|name| name.contains("embed")) {
+            ui.label(egui::RichText::new(tr("chat.no_embedding_model")).weak());
         }
+        ui.add(
+            egui::Slider::new(&mut self.knowledge.top_k, 1..=16)
+                .text(tr("chat.excerpts_per_question")),
+        );
 
-        action
-    }
+        if self.is_indexing() {
+            match self.index_progress {
+                Some((done, total)) => {
+                    ui.add(
+                        egui::ProgressBar::new(done as f32 / total.max(1) as f32)
+                            .text(tr_args("chat.embedding_progress", &[&done, &total])),
+                    );
+                }
+                None => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(tr("chat.reading_files"));
+                    });
+                }
+            }
+            return pick;
+        }
 
-    #[inline]
-    pub fn flower_active(&self) -> bool {
-        self.flower.is_active()
+        if let Some(e) = &self.index_error {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+        if self.knowledge.is_indexed() {
+            ui.label(tr_args(
+                "chat.indexed",
+                &[&self.knowledge.chunks.len(), &self.knowledge.indexed_model],
+            ));
+            if self.knowledge.is_stale() {
+                ui.colored_label(ui.visuals().warn_fg_color, tr("chat.index_stale"));
+            }
+        }
+        ui.horizontal(|ui| {
+            let can_index =
+                !self.knowledge.sources.is_empty() && !self.knowledge.embedding_model.is_empty();
+            if ui
+                .add_enabled(
+                    can_index,
+                    egui::Button::new(if self.knowledge.is_indexed() {
+                        tr("chat.index_again")
+                    } else {
+                        tr("chat.index")
+                    }),
+                )
+                .on_disabled_hover_text(tr("chat.index_disabled"))
+                .clicked()
+            {
+                self.start_indexing(ollama);
+            }
+            if self.knowledge.is_indexed()
+                && ui
+                    .button(tr("chat.clear_index"))
+                    .on_hover_text(tr("chat.clear_index_help"))
+                    .clicked()
+            {
+                self.knowledge.clear_index();
+                self.modified = true;
+            }
+        });
+        pick
     }
 
-    pub fn poll_flower(&mut self, modal: &mut Modal) {
+    /// Returns whether a response was completed.
+    pub fn poll_flower(&mut self, #[cfg(feature = "tts")] tts: &SharedTts) -> bool {
+        let mut completed = false;
         self.flower
             .extract(|(idx, progress)| {
-                self.messages[idx].content += progress.as_str();
+                // saved with the next autosave, in case the app is closed before the end
+                self.modified = true;
+                match progress {
+                    ReplyProgress::Text(text) => self.messages[idx].content += text.as_str(),
+                    ReplyProgress::Restart(prepend) => {
+                        self.messages[idx].content = prepend;
+                        self.messages[idx].tagged_content = None;
+                        #[cfg(feature = "tts")]
+                        {
+                            self.speech_stream = None;
+                        }
+                        return;
+                    }
+                }
+                #[cfg(feature = "tts")]
+                if self.auto_speak && tts.is_some() {
+                    let (speech_idx, splitter) = self
+                        .speech_stream
+                        .get_or_insert_with(|| (idx, SentenceSplitter::default()));
+                    if *speech_idx != idx {
+                        *speech_idx = idx;
+                        *splitter = SentenceSplitter::default();
+                    }
+                    let text = speakable_text(
+                        &splitter.next_sentences(&self.messages[idx].content, false),
+                    );
+                    if !text.is_empty() {
+                        self.messages[idx].is_speaking = true;
+                        tts_enqueue(tts.clone(), text);
+                    }
+                }
             })
             .finalize(|result| {
-                if let Ok((idx, content)) = result {
+                self.modified = true;
+                if let Ok(completion) = result {
+                    let idx = completion.index;
+                    if completion.summary.is_some() {
+                        self.context_summary = completion.summary;
+                    }
                     let message = &mut self.messages[idx];
-                    message.content = content.clone();
+                    message.content = completion.content;
+                    message.tagged_content = None;
+                    message.usage = completion.usage;
+                    message.stats = completion.stats;
+                    message.sources = completion.sources;
+                    message.inspection = Some(completion.inspection);
+                    message.time = Some(chrono::Utc::now());
                     message.is_generating = false;
+                    // a stalled reply can be continued like one cut off by closing the app
+                    message.incomplete = completion.interrupted.is_some();
+                    message.error = completion.interrupted;
+                    self.unread = true;
+                    completed = true;
+                    #[cfg(feature = "tts")]
+                    if let Some((speech_idx, mut splitter)) = self.speech_stream.take() {
+                        let text = speakable_text(&splitter.next_sentences(&message.content, true));
+                        if speech_idx == idx && !text.is_empty() {
+                            message.is_speaking = true;
+                            tts_enqueue(tts.clone(), text);
+                        }
+                    }
                 } else if let Err(e) = result {
                     let (idx, msg) = match e {
-                        Compact::Panicked(e) => {
-                            (self.messages.len() - 1, format!("Tokio task panicked: {e}"))
-                        }
+                        Compact::Panicked(e) => (
+                            self.messages.len() - 1,
+                            tr_args("common.task_panicked", &[&e]),
+                        ),
                         Compact::Suppose((idx, e)) => (idx, e),
                     };
+                    // shown in place of the reply, keeping whatever was streamed before
+                    // the failure
                     let message = &mut self.messages[idx];
-                    message.content = msg.clone();
+                    message.tagged_content = None;
                     message.is_error = true;
-                    modal
-                        .dialog()
-                        .with_body(msg)
-                        .with_title("Failed to generate completion!")
-                        .with_icon(Icon::Error)
-                        .open();
+                    message.error = Some(msg);
                     message.is_generating = false;
+                    message.incomplete = false;
+                    self.unread = true;
+                    #[cfg(feature = "tts")]
+                    {
+                        self.speech_stream = None;
+                    }
+                }
+            });
+        self.compare_flower
+            .extract(|(idx, progress)| {
+                self.modified = true;
+                if let Some(reply) = self.messages[idx].compare.as_deref_mut() {
+                    match progress {
+                        ReplyProgress::Text(text) => reply.content += text.as_str(),
+                        ReplyProgress::Restart(prepend) => reply.content = prepend,
+                    }
+                }
+            })
+            .finalize(|result| {
+                self.modified = true;
+                self.unread = true;
+                let (idx, error) = match result {
+                    Ok(completion) => {
+                        let idx = completion.index;
+                        if let Some(reply) = self.messages[idx].compare.as_deref_mut() {
+                            reply.content = completion.content;
+                            reply.tagged_content = None;
+                            reply.usage = completion.usage;
+                            reply.stats = completion.stats;
+                            reply.sources = completion.sources;
+                            reply.inspection = Some(completion.inspection);
+                            reply.time = Some(chrono::Utc::now());
+                            reply.is_generating = false;
+                            reply.incomplete = completion.interrupted.is_some();
+                            reply.error = completion.interrupted;
+                        }
+                        completed = true;
+                        return;
+                    }
+                    Err(Compact::Panicked(e)) => (
+                        self.messages.len() - 1,
+                        tr_args("common.task_panicked", &[&e]),
+                    ),
+                    Err(Compact::Suppose((idx, e))) => (idx, e),
+                };
+                if let Some(reply) = self.messages[idx].compare.as_deref_mut() {
+                    reply.tagged_content = None;
+                    reply.is_error = true;
+                    reply.error = Some(error);
+                    reply.is_generating = false;
+                    reply.incomplete = false;
                 }
             });
+        completed
+    }
+
+    /// Feed the text chat search looks at to `hasher`.
+    pub fn hash_searchable(&self, hasher: &mut impl std::hash::Hasher) {
+        use std::hash::Hash;
+
+        self.summary.hash(hasher);
+        for message in &self.messages {
+            message.content.hash(hasher);
+        }
+    }
+
+    /// Index of the first message containing `query` (lowercase) and a snippet around the
+    /// match.
+    pub fn find_message(&self, query: &str) -> Option<(usize, String)> {
+        self.messages.iter().enumerate().find_map(|(i, message)| {
+            let range = find_ignore_case(&message.content, query)
+                .into_iter()
+                .next()?;
+            Some((i, snippet(&message.content, range)))
+        })
+    }
+
+    /// The chatbox had focus in the last frame.
+    pub fn chatbox_has_focus(&self, ctx: &egui::Context) -> bool {
+        self.chatbox_id
+            .is_some_and(|id| ctx.memory(|m| m.has_focus(id)))
+    }
+
+    pub fn is_revealing(&self) -> bool {
+        self.reveal.is_some()
+    }
+
+    /// Jump to the end of the messages, sticking to the bottom again.
+    pub fn scroll_to_bottom(&mut self) {
+        self.jump_to_bottom = true;
+        self.new_below = false;
+    }
+
+    /// Scroll to the message at `idx` and briefly highlight it.
+    pub fn reveal_message(&mut self, idx: usize) {
+        self.reveal = Some((idx, Instant::now()));
+        self.revealed = false;
+    }
+
+    /// Copy of the conversation and its settings as a new chat with its own `id`. Replies
+    /// still being generated are copied as they are so far.
+    pub fn duplicate(&self, id: usize) -> Self {
+        let mut messages = self.messages.clone();
+        for message in &mut messages {
+            message.is_generating = false;
+            message.is_speaking = false;
+            if let Some(reply) = &mut message.compare {
+                reply.is_generating = false;
+            }
+        }
+        Self {
+            chatbox: self.chatbox.clone(),
+            messages,
+            summary: format!("{} (copy)", self.summary),
+            images: self.images.clone(),
+            files: self.files.clone(),
+            prompt_history: self.prompt_history.clone(),
+            system_prompt: self.system_prompt.clone(),
+            settings_override: self.settings_override.clone(),
+            reply_options: self.reply_options,
+            #[cfg(feature = "tts")]
+            auto_speak: self.auto_speak,
+            knowledge: self.knowledge.clone(),
+            context: self.context,
+            completion_mode: self.completion_mode,
+            ..Self::new(id, self.model_picker.clone())
+        }
+    }
+
+    /// Put a deleted message back at `index`, or at the end if the chat got shorter since.
+    pub fn restore_message(&mut self, index: usize, message: Message) {
+        let index = index.min(self.messages.len());
+        self.messages.insert(index, message);
+        self.messages_changed_at(index);
+    }
+
+    /// Forget what was derived from the messages from `index` on.
+    fn messages_changed_at(&mut self, index: usize) {
+        if self
+            .context_summary
+            .as_ref()
+            .is_some_and(|s| s.covers > index)
+        {
+            self.context_summary = None;
+        }
+        self.sent_context = None;
+        self.retry_message_idx = None;
+        self.reveal = None;
+        self.modified = true;
+    }
+
+    /// Remove all messages and what was derived from them, keeping the configuration. The
+    /// title is made again from the next prompt.
+    fn clear_conversation(&mut self) {
+        self.messages.clear();
+        self.summary.clear();
+        self.context_summary = None;
+        self.sent_context = None;
+        self.retry_message_idx = None;
+        self.row_heights = RowHeights::default();
+        self.reveal = None;
+        self.branch_from = None;
+        self.new_below = false;
+        self.unread = false;
+        #[cfg(feature = "tts")]
+        {
+            self.speech_stream = None;
+        }
+        self.modified = true;
+    }
+
+    fn show_mode_switch_modal_inner(&mut self, ui: &mut egui::Ui, modal: &Modal) {
+        let Some(completion_mode) = self.confirm_mode_switch else {
+            modal.close();
+            return;
+        };
+        modal.title(ui, tr("chat.switch_mode_title"));
+        modal.frame(ui, |ui| {
+            modal.body_and_icon(
+                ui,
+                if completion_mode {
+                    tr("chat.switch_to_completion")
+                } else {
+                    tr("chat.switch_to_chat")
+                },
+                Icon::Warning,
+            );
+        });
+        modal.buttons(ui, |ui| {
+            if modal.button(ui, tr("common.cancel")).clicked() {
+                self.confirm_mode_switch = None;
+                modal.close();
+            }
+            if modal.suggested_button(ui, tr("chat.switch")).clicked() {
+                self.completion_mode = completion_mode;
+                self.confirm_mode_switch = None;
+                self.context_summary = None;
+                self.sent_context = None;
+                self.modified = true;
+                modal.close();
+            }
+        });
+    }
+
+    fn show_clear_modal_inner(&mut self, ui: &mut egui::Ui, modal: &Modal) {
+        modal.title(ui, tr("chat.clear_title"));
+        modal.frame(ui, |ui| {
+            modal.body_and_icon(
+                ui,
+                tr_args("chat.clear_body", &[&self.messages.len()]),
+                Icon::Warning,
+            );
+        });
+        modal.buttons(ui, |ui| {
+            if modal.button(ui, tr("common.cancel")).clicked() {
+                modal.close();
+            }
+            // a response may have been started from the keyboard in the meantime
+            if ui
+                .add_enabled_ui(!self.flower_active(), |ui| {
+                    modal.caution_button(ui, tr("chat.clear_button"))
+                })
+                .inner
+                .clicked()
+            {
+                self.clear_conversation();
+                modal.close();
+            }
+        });
+    }
+
+    /// New chat with `id` continuing the conversation from the message at `idx`, with the
+    /// same model and settings.
+    pub fn branch(&self, idx: usize, id: usize) -> Self {
+        let mut branch = self.duplicate(id);
+        branch.messages.truncate(idx + 1);
+        branch.summary = tr_args("chat.branch_of", &[&self.summary]);
+        branch.chatbox.clear();
+        branch.images.clear();
+        branch.files.clear();
+        branch
+    }
+
+    /// Returns whether messages were added or completed since the last call.
+    pub fn take_modified(&mut self) -> bool {
+        std::mem::take(&mut self.modified)
+    }
+
+    /// When a message was last sent or received
+    pub fn last_activity(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.messages.iter().rev().find_map(|m| m.time)
+    }
+
+    /// Total size of the message contents in bytes
+    pub fn content_size(&self) -> usize {
+        self.messages.iter().map(|m| m.content.len()).sum()
     }
 
     pub fn last_message_contents(&self) -> Option<String> {
@@ -837,7 +5221,7 @@ impl Chat {
                 continue;
             }
             return Some(if message.is_user() {
-                format!("You: {}", message.content)
+                format!("{}: {}", tr("common.you"), message.content)
             } else {
                 message.content.to_string()
             });
@@ -845,7 +5229,25 @@ impl Chat {
         None
     }
 
-    fn stop_generating_button(&self, ui: &mut egui::Ui, radius: f32, pos: Pos2) {
+    /// Stop the replies being generated, if any.
+    pub fn stop_generating(&self) {
+        // a flag left set would stop the next reply of its model right away
+        if self.flower.is_active() {
+            self.stop_generating.store(true, Ordering::SeqCst);
+        }
+        if self.compare_flower.is_active() {
+            self.compare_stop.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Show the find bar and focus its query.
+    pub fn open_find(&mut self) {
+        self.find.open = true;
+        self.find.focus = true;
+    }
+
+    /// Returns `true` if the button was clicked.
+    fn stop_generating_button(&self, ui: &mut egui::Ui, radius: f32, pos: Pos2) -> bool {
         let rect = Rect::from_min_max(pos + vec2(-radius, -radius), pos + vec2(radius, radius));
         let (hovered, primary_clicked) = ui.input(|i| {
             (
@@ -857,19 +5259,15 @@ impl Chat {
             )
         });
         if hovered && primary_clicked {
-            self.stop_generating.store(true, Ordering::SeqCst);
+            self.stop_generating();
+            true
         } else {
             ui.painter().circle(
                 pos,
                 radius,
                 if hovered {
                     ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
-                    if ui.style().visuals.dark_mode {
-                        let c = ui.style().visuals.faint_bg_color;
-                        Color32::from_rgb(c.r(), c.g(), c.b())
-                    } else {
-                        Color32::WHITE
-                    }
+                    ui.visuals().widgets.hovered.weak_bg_fill
                 } else {
                     ui.style().visuals.window_fill
                 },
@@ -878,71 +5276,479 @@ impl Chat {
             ui.painter().rect_stroke(
                 rect.shrink(radius / 2.0 + 1.2),
                 2.0,
-                Stroke::new(2.0, Color32::DARK_GRAY),
+                Stroke::new(2.0, ui.visuals().widgets.inactive.fg_stroke.color),
             );
+            false
+        }
+    }
+
+    /// Show the messages of a completion mode chat as one continuous text, with the
+    /// generated parts highlighted.
+    fn show_document(&mut self, ui: &mut egui::Ui) {
+        let font_id = egui::TextStyle::Body.resolve(ui.style());
+        let written = egui::TextFormat::simple(font_id.clone(), ui.visuals().text_color());
+        let generated = egui::TextFormat {
+            background: ui.visuals().selection.bg_fill.gamma_multiply(0.25),
+            ..egui::TextFormat::simple(font_id, ui.visuals().strong_text_color())
+        };
+        let mut job = egui::text::LayoutJob::default();
+        for message in self.messages.iter().filter(|m| !m.is_error) {
+            let format = if message.is_user() {
+                &written
+            } else {
+                &generated
+            };
+            job.append(&message.content, 0.0, format.clone());
         }
+
+        let last = self.messages.len() - 1;
+        egui::ScrollArea::vertical()
+            .stick_to_bottom(true)
+            .auto_shrink(false)
+            .show(ui, |ui| {
+                ui.add_space(16.0);
+                ui.add(egui::Label::new(job).wrap(true));
+                let message = &self.messages[last];
+                if message.is_generating && !message.is_error {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::Spinner::new().size(10.0));
+                        ui.add_enabled(
+                            false,
+                            egui::Label::new(
+                                egui::RichText::new(tr_args(
+                                    "chat.generating",
+                                    &[&format!(
+                                        "{:.1}",
+                                        message.requested_at.elapsed().as_secs_f64()
+                                    )],
+                                ))
+                                .small(),
+                            ),
+                        );
+                    });
+                    ui.ctx().request_repaint();
+                } else if message.is_error {
+                    let details = message.error.as_deref().unwrap_or(&message.content);
+                    let error_color = ui.visuals().error_fg_color;
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(widgets::explain_request_error(details))
+                                .color(error_color),
+                        )
+                        .on_hover_text(details);
+                        if ui.small_button(tr("chat.retry")).clicked() {
+                            self.retry_message_idx = Some(last);
+                        }
+                    });
+                }
+                // room for the stop button
+                ui.add_space(48.0);
+            });
     }
 
     fn show_chat_scrollarea(
         &mut self,
         ui: &mut egui::Ui,
-        ollama: &Ollama,
         commonmark_cache: &mut CommonMarkCache,
         #[cfg(feature = "tts")] tts: SharedTts,
+        models: &[LocalModel],
     ) -> Option<usize> {
         let mut new_speaker: Option<usize> = None;
         let mut any_prepending = false;
         let mut regenerate_response_idx = None;
-        egui::ScrollArea::both()
-            .stick_to_bottom(true)
-            .auto_shrink(false)
-            .show(ui, |ui| {
-                ui.add_space(16.0);
-                self.virtual_list
-                    .ui_custom_layout(ui, self.messages.len(), |ui, index| {
-                        let Some(message) = self.messages.get_mut(index) else {
-                            return 0;
-                        };
-                        let prev_speaking = message.is_speaking;
-                        if any_prepending && message.is_prepending {
-                            message.is_prepending = false;
+        let mut regenerate_with = None;
+        let mut continue_idx = None;
+        let regenerate_models = self.regenerate_models(models);
+        // the selected model too, so the next reply doesn't get a clashing name
+        let short_names = short_names(
+            self.messages
+                .iter()
+                .filter(|m| !m.is_user())
+                .map(|m| m.model_name.as_str())
+                .chain([self.model_picker.selected_model()])
+                .filter(|name| !name.is_empty()),
+        );
+        let reply_options = ReplyOptions {
+            json: self.settings().json_mode,
+            ..self.reply_options
+        };
+        let user_name = match self.user_name.trim() {
+            "" => tr("common.you").to_owned(),
+            name => name.to_owned(),
+        };
+        let is_generating = self.flower_active();
+        let mut open_info = false;
+        let mut save_golden = None;
+        let mut promote = None;
+        let mut compare_action = None;
+        // where the messages sent with the last request start, if not at the top
+        let context_start = self.sent_context.filter(|sent| sent.start > 0);
+        let context_summary = context_start
+            .filter(|sent| sent.summarized)
+            .and_then(|_| self.context_summary.as_ref().map(|s| s.text.clone()));
+        if self
+            .reveal
+            .is_some_and(|(_, at)| at.elapsed() > REVEAL_HIGHLIGHT_DURATION)
+        {
+            self.reveal = None;
+        }
+        let reveal = self.reveal.map(|(idx, at)| (idx, at.elapsed()));
+        let scroll_to_reveal = reveal.is_some() && !self.revealed;
+        let scroll_to_match = self.find.open && self.find.scroll;
+        let len = self.messages.len();
+        let mut scroll_area = egui::ScrollArea::both()
+            .stick_to_bottom(reveal.is_none() && !self.find.open)
+            .auto_shrink(false);
+        if let Some(offset) = self.row_heights.scroll_to.take() {
+            scroll_area = scroll_area.vertical_scroll_offset(offset);
+        }
+        let jumping = self.jump_to_bottom;
+        let row_ids: Vec<u64> = self.messages.iter().map(|m| m.row_id).collect();
+        let output = scroll_area.show_viewport(ui, |ui, viewport| {
+            const TOP_PADDING: f32 = 16.0;
+            ui.add_space(TOP_PADDING);
+            let mut show_row = |ui: &mut egui::Ui, index: usize| {
+                let Some(message) = self.messages.get(index) else {
+                    return;
+                };
+                // for the footers, counted before the message is borrowed mutably
+                let usage = (!message.is_user())
+                    .then(|| usage_of(&self.messages, &self.system_prompt, index));
+                let message = &mut self.messages[index];
+                if context_start.is_some_and(|sent| sent.start == index) {
+                    show_context_divider(ui, context_summary.as_deref());
+                }
+                let highlight = ui.painter().add(egui::Shape::Noop);
+                let top = ui.cursor().top();
+                let prev_speaking = message.is_speaking;
+                if any_prepending && message.is_prepending {
+                    message.is_prepending = false;
+                }
+                let short_name = |message: &Message| {
+                    if message.is_user() {
+                        return user_name.as_str();
+                    }
+                    short_names
+                        .get(&message.model_name)
+                        .map_or("", String::as_str)
+                };
+                let name = short_name(message);
+                let mut compare = message.compare.take();
+                let action = match (compare.as_deref_mut(), usage) {
+                    (Some(reply), Some(usage)) => {
+                        // the compared replies side by side, each with its own stats
+                        if any_prepending && reply.is_prepending {
+                            reply.is_prepending = false;
                         }
-                        let action = message.show(
-                            ui,
-                            commonmark_cache,
-                            #[cfg(feature = "tts")]
-                            tts.clone(),
-                            index,
-                            &mut self.prepend_buf,
-                        );
-                        match action {
-                            MessageAction::None => (),
-                            MessageAction::Retry(idx) => {
-                                self.retry_message_idx = Some(idx);
-                            }
-                            MessageAction::Regenerate(idx) => {
-                                regenerate_response_idx = Some(idx);
+                        let reply_usage = match reply.usage {
+                            Some(usage) => (usage, false),
+                            None => (
+                                TokenUsage {
+                                    prompt_tokens: usage.0.prompt_tokens,
+                                    reply_tokens: estimate_tokens(&reply.content),
+                                },
+                                true,
+                            ),
+                        };
+                        let reply_name = short_name(reply);
+                        let promotable =
+                            index + 1 == len && self.compare_model.is_some() && !is_generating;
+                        let mut actions = [MessageAction::None, MessageAction::None];
+                        ui.columns(2, |columns| {
+                            let shown = [
+                                (&mut *message, name, usage),
+                                (&mut *reply, reply_name, reply_usage),
+                            ];
+                            for (column, (ui, (shown, name, usage))) in
+                                columns.iter_mut().zip(shown).enumerate()
+                            {
+                                // both columns show the same message index
+                                ui.push_id(("compare_column", column), |ui| {
+                                    actions[column] = shown.show(
+                                        ui,
+                                        commonmark_cache,
+                                        #[cfg(feature = "tts")]
+                                        tts.clone(),
+                                        index,
+                                        &mut self.prepend_buf,
+                                        reply_options,
+                                        (!is_generating).then_some(regenerate_models.as_slice()),
+                                        (column == 0)
+                                            .then(|| self.find.highlights(index))
+                                            .flatten(),
+                                        name,
+                                    );
+                                    if shown.is_generating || shown.is_error {
+                                        return;
+                                    }
+                                    open_info |= show_reply_footer(ui, usage, shown.stats);
+                                    if promotable
+                                        && ui
+                                            .button(tr_args("chat.promote", &[&name]))
+                                            .on_hover_text(tr("chat.promote_help"))
+                                            .clicked()
+                                    {
+                                        promote = Some(column == 1);
+                                    }
+                                    ui.add_space(8.0);
+                                });
                             }
+                        });
+                        any_prepending |= reply.is_prepending;
+                        let [action, reply_action] = actions;
+                        if !matches!(reply_action, MessageAction::None) {
+                            compare_action = Some(reply_action);
                         }
-                        any_prepending |= message.is_prepending;
-                        if !prev_speaking && message.is_speaking {
-                            new_speaker = Some(index);
-                        }
-                        1 // 1 rendered item per row
-                    });
-            });
+                        action
+                    }
+                    _ => message.show(
+                        ui,
+                        commonmark_cache,
+                        #[cfg(feature = "tts")]
+                        tts.clone(),
+                        index,
+                        &mut self.prepend_buf,
+                        reply_options,
+                        (!is_generating).then_some(regenerate_models.as_slice()),
+                        self.find.highlights(index),
+                        name,
+                    ),
+                };
+                let compared = compare.is_some();
+                message.compare = compare;
+                match action {
+                    MessageAction::None => (),
+                    MessageAction::Retry(idx) => {
+                        self.retry_message_idx = Some(idx);
+                    }
+                    MessageAction::Regenerate(idx) => {
+                        regenerate_response_idx = Some(idx);
+                    }
+                    MessageAction::RegenerateWith { idx, model } => {
+                        regenerate_with = Some((idx, model));
+                    }
+                    MessageAction::SaveGolden(idx) => {
+                        save_golden = Some(idx);
+                    }
+                    MessageAction::Branch(idx) => {
+                        self.branch_from = Some(idx);
+                    }
+                    MessageAction::Delete(idx) => {
+                        self.delete_message = Some(idx);
+                    }
+                    MessageAction::Continue(idx) => {
+                        continue_idx = Some(idx);
+                    }
+                    MessageAction::NoteEdited | MessageAction::Rated => self.modified = true,
+                    MessageAction::ViewRequest(idx) => self.inspected = Some((idx, false)),
+                }
+                any_prepending |= message.is_prepending;
+                if !prev_speaking && message.is_speaking {
+                    new_speaker = Some(index);
+                }
+                if let Some(usage) = usage {
+                    if !compared && !message.is_generating && !message.is_error {
+                        open_info |= show_reply_footer(ui, usage, message.stats);
+                        ui.add_space(8.0);
+                    }
+                }
+                if let Some((_, elapsed)) = reveal.filter(|(idx, _)| *idx == index) {
+                    let rows = top..=ui.cursor().top();
+                    let fade =
+                        1.0 - elapsed.as_secs_f32() / REVEAL_HIGHLIGHT_DURATION.as_secs_f32();
+                    ui.painter().set(
+                        highlight,
+                        egui::Shape::rect_filled(
+                            Rect::from_x_y_ranges(ui.max_rect().x_range(), rows.clone()),
+                            Rounding::same(6.0),
+                            ui.visuals().selection.bg_fill.gamma_multiply(0.5 * fade),
+                        ),
+                    );
+                    if scroll_to_reveal {
+                        ui.scroll_to_rect(
+                            Rect::from_x_y_ranges(ui.clip_rect().x_range(), rows),
+                            Some(Align::Center),
+                        );
+                    }
+                    ui.ctx().request_repaint();
+                }
+            };
+
+            // only lay out the visible rows and leave space for the rest. Lay out all
+            // of them when the revealed message or match has to be scrolled to, its
+            // position isn't known otherwise
+            let layout_all = scroll_to_reveal || scroll_to_match;
+            let heights = &mut self.row_heights;
+            heights.prepare(ui.available_width(), &row_ids);
+            let estimate = heights.estimate();
+            let visible_top = viewport.top() - TOP_PADDING;
+            let (first, first_top) = if layout_all {
+                (0, 0.0)
+            } else {
+                heights.row_at(visible_top.max(0.0), estimate)
+            };
+            ui.add_space(first_top);
+            let mut index = first;
+            let mut bottom = first_top;
+            let mut shift = 0.0;
+            while index < len && (layout_all || bottom < viewport.bottom() - TOP_PADDING) {
+                let row_top = ui.cursor().top();
+                show_row(ui, index);
+                let height = ui.cursor().top() - row_top;
+                let old = heights
+                    .measure(index, row_ids[index], height)
+                    .unwrap_or(estimate);
+                if bottom < visible_top {
+                    // the row starts above the viewport, growing or shrinking it
+                    // would move everything visible below it
+                    shift += height - old;
+                }
+                bottom += height;
+                index += 1;
+            }
+            ui.add_space(heights.height_from(index, estimate));
+            if std::mem::take(&mut self.jump_to_bottom) {
+                ui.scroll_to_cursor(Some(Align::BOTTOM));
+            } else if shift.abs() > 0.5 && !layout_all {
+                heights.scroll_to = Some(viewport.top() + shift);
+                ui.ctx().request_repaint();
+            }
+        });
+
+        // the scroll area sticks to the bottom until scrolled up, then stays put and new
+        // content is pointed out instead
+        let at_bottom =
+            output.state.offset.y + output.inner_rect.height() >= output.content_size.y - 1.0;
+        let content = (
+            self.messages.len(),
+            self.messages.last().map_or(0, |m| m.content.len()),
+        );
+        if at_bottom {
+            // the new response has been seen once it's scrolled to
+            self.unread = false;
+            self.new_below = false;
+        } else if content != self.shown_content && !jumping {
+            self.new_below = true;
+        }
+        self.shown_content = content;
+        if self.new_below {
+            egui::Area::new(ui.id().with(("new_messages_pill", self.id())))
+                .order(egui::Order::Foreground)
+                .pivot(egui::Align2::CENTER_BOTTOM)
+                .fixed_pos(pos2(
+                    output.inner_rect.center().x,
+                    output.inner_rect.bottom() - 12.0,
+                ))
+                .show(ui.ctx(), |ui| {
+                    if ui
+                        .add(
+                            egui::Button::new(tr("chat.new_messages"))
+                                .rounding(Rounding::same(f32::INFINITY)),
+                        )
+                        .on_hover_text(tr("chat.jump_to_bottom"))
+                        .clicked()
+                    {
+                        self.scroll_to_bottom();
+                    }
+                });
+        }
+        self.revealed |= scroll_to_reveal;
+        if scroll_to_match {
+            self.find.scroll = false;
+        }
         if let Some(regenerate_idx) = regenerate_response_idx {
-            self.regenerate_response(ollama, regenerate_idx);
+            self.regenerate_response(regenerate_idx);
+        }
+        if let Some(idx) = continue_idx {
+            self.continue_response(idx);
+        }
+        if let Some((idx, model)) = regenerate_with {
+            self.regenerate_with(idx, model);
+        }
+        match compare_action {
+            Some(MessageAction::Retry(idx)) => self.regenerate_compare(idx, None),
+            Some(MessageAction::Regenerate(idx)) => {
+                let prepend = std::mem::take(&mut self.prepend_buf);
+                if let Some(reply) = self.messages[idx].compare.as_deref_mut() {
+                    reply.content = prepend;
+                }
+                self.regenerate_compare(idx, None);
+            }
+            Some(MessageAction::RegenerateWith { idx, model }) => {
+                self.regenerate_compare(idx, Some(model));
+            }
+            Some(MessageAction::Branch(idx)) => self.branch_from = Some(idx),
+            // the compared replies are shown as one row, deleted together
+            Some(MessageAction::Delete(idx)) => self.delete_message = Some(idx),
+            // a regeneration continues from the text so far
+            Some(MessageAction::Continue(idx)) => self.regenerate_compare(idx, None),
+            Some(MessageAction::NoteEdited | MessageAction::Rated) => self.modified = true,
+            Some(MessageAction::ViewRequest(idx)) => self.inspected = Some((idx, true)),
+            Some(MessageAction::SaveGolden(_) | MessageAction::None) | None => (),
+        }
+        if let Some(compared) = promote {
+            self.promote(compared);
+        }
+        if open_info {
+            self.show_info = true;
+        }
+        if let Some(idx) = save_golden {
+            self.golden_draft = Some(GoldenPrompt::new(self.messages[idx].content.clone()));
         }
         new_speaker
     }
 
-    fn send_text(&mut self, ollama: &Ollama, text: &str) {
+    /// Window with the request and response metadata of the inspected reply.
+    fn show_request_inspector(&mut self, ctx: &egui::Context) {
+        let Some((idx, compared)) = self.inspected else {
+            return;
+        };
+        let message = self.messages.get(idx).and_then(|m| {
+            if compared {
+                m.compare.as_deref()
+            } else {
+                Some(m)
+            }
+        });
+        // the reply may have been deleted or regenerated since
+        let Some((model, json)) =
+            message.and_then(|m| Some((m.model_name.as_str(), m.inspection.as_deref()?)))
+        else {
+            self.inspected = None;
+            return;
+        };
+        let mut open = true;
+        egui::Window::new(tr_args("chat.request_title", &[&model]))
+            .id(egui::Id::new(("request_inspector", self.uid)))
+            .open(&mut open)
+            .default_size([560.0, 480.0])
+            .show(ctx, |ui| {
+                if ui.button(tr("chat.copy_json")).clicked() {
+                    ui.ctx().copy_text(json.to_owned());
+                }
+                ui.separator();
+                egui::ScrollArea::both()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut { json })
+                                .code_editor()
+                                .desired_width(f32::INFINITY),
+                        );
+                    });
+            });
+        if !open {
+            self.inspected = None;
+        }
+    }
+
+    fn send_text(&mut self, text: &str) {
         self.chatbox = text.to_owned();
-        self.send_message(ollama);
+        self.send_message();
     }
 
-    fn show_suggestions(&mut self, ui: &mut egui::Ui, ollama: &Ollama) {
+    fn show_suggestions(&mut self, ui: &mut egui::Ui) {
         egui::ScrollArea::both().auto_shrink(false).show(ui, |ui| {
             widgets::centerer(ui, |ui| {
                 ui.horizontal(|ui| {
@@ -957,34 +5763,20 @@ impl Chat {
                     .num_columns(3)
                     .spacing(vec2(6.0, 6.0))
                     .show(ui, |ui| {
-                        if widgets::suggestion(ui, "Tell me a fun fact", "about the Roman empire")
-                            .clicked()
-                        {
-                            self.send_text(ollama, "Tell me a fun fact about the Roman empire");
-                        }
-                        if widgets::suggestion(
-                            ui,
-                            "Show me a code snippet",
-                            "of a web server in Rust",
-                        )
-                        .clicked()
-                        {
-                            self.send_text(
-                                ollama,
-                                "Show me a code snippet of a web server in Rust",
-                            );
-                        }
+                        // the prompt sent is the two lines joined
+                        let mut suggest = |ui: &mut egui::Ui, text, subtext| {
+                            let (text, subtext) = (tr(text), tr(subtext));
+                            if widgets::suggestion(ui, text, subtext).clicked() {
+                                self.send_text(&format!("{text} {subtext}"));
+                            }
+                        };
+                        suggest(ui, "chat.suggest_fact", "chat.suggest_fact_about");
+                        suggest(ui, "chat.suggest_code", "chat.suggest_code_about");
                         widgets::dummy(ui);
                         ui.end_row();
 
-                        if widgets::suggestion(ui, "Tell me a joke", "about crabs").clicked() {
-                            self.send_text(ollama, "Tell me a joke about crabs");
-                        }
-                        if widgets::suggestion(ui, "Give me ideas", "for a birthday present")
-                            .clicked()
-                        {
-                            self.send_text(ollama, "Give me ideas for a birthday present");
-                        }
+                        suggest(ui, "chat.suggest_joke", "chat.suggest_joke_about");
+                        suggest(ui, "chat.suggest_ideas", "chat.suggest_ideas_about");
                         widgets::dummy(ui);
                         ui.end_row();
                     });
@@ -995,10 +5787,11 @@ impl Chat {
     pub fn show(
         &mut self,
         ctx: &egui::Context,
-        ollama: &Ollama,
         #[cfg(feature = "tts")] tts: SharedTts,
         #[cfg(feature = "tts")] stopped_speaking: bool,
         commonmark_cache: &mut CommonMarkCache,
+        models: &[LocalModel],
+        golden_prompts: &mut Vec<GoldenPrompt>,
     ) -> ChatAction {
         let avail = ctx.available_rect();
         let max_height = avail.height() * 0.4 + 24.0;
@@ -1006,6 +5799,11 @@ impl Chat {
         let actual_chatbox_panel_height = chatbox_panel_height.min(max_height);
         let is_generating = self.flower_active();
         let mut action = ChatAction::None;
+        let golden_modal = Modal::new(ctx, "save_golden_prompt_modal");
+        let had_golden_draft = self.golden_draft.is_some();
+        let clear_modal = Modal::new(ctx, "clear_conversation_modal");
+        let mode_modal = Modal::new(ctx, "completion_mode_modal");
+        let had_mode_switch = self.confirm_mode_switch.is_some();
 
         egui::TopBottomPanel::bottom("chatbox_panel")
             .exact_height(actual_chatbox_panel_height)
@@ -1015,11 +5813,29 @@ impl Chat {
                         ui,
                         chatbox_panel_height >= max_height,
                         is_generating,
-                        ollama,
+                        golden_prompts,
                     );
                 });
             });
 
+        egui::TopBottomPanel::top("chat_header_panel").show(ctx, |ui| {
+            self.show_header(
+                ui,
+                models,
+                #[cfg(feature = "tts")]
+                tts.is_some(),
+            );
+        });
+
+        if self.find.open {
+            self.find.update(&self.messages);
+            egui::TopBottomPanel::top("chat_find_panel").show(ctx, |ui| {
+                if self.find.show(ui) {
+                    self.find.open = false;
+                }
+            });
+        }
+
         #[cfg(feature = "tts")]
         let mut new_speaker: Option<usize> = None;
 
@@ -1032,15 +5848,17 @@ impl Chat {
             }))
             .show(ctx, |ui| {
                 if self.messages.is_empty() {
-                    self.show_suggestions(ui, ollama);
+                    self.show_suggestions(ui);
                 } else {
                     #[allow(unused_variables)]
-                    if let Some(new) = self.show_chat_scrollarea(
+                    if self.completion_mode {
+                        self.show_document(ui);
+                    } else if let Some(new) = self.show_chat_scrollarea(
                         ui,
-                        ollama,
                         commonmark_cache,
                         #[cfg(feature = "tts")]
-                        tts,
+                        tts.clone(),
+                        models,
                     ) {
                         #[cfg(feature = "tts")]
                         {
@@ -1049,8 +5867,9 @@ impl Chat {
                     }
 
                     // stop generating button
-                    if is_generating {
-                        self.stop_generating_button(
+                    #[allow(unused_variables)]
+                    let stopped = is_generating
+                        && self.stop_generating_button(
                             ui,
                             16.0,
                             pos2(
@@ -1058,10 +5877,51 @@ impl Chat {
                                 avail.height() - 32.0 - actual_chatbox_panel_height,
                             ),
                         );
+
+                    // drop the sentences queued from the cancelled response
+                    #[cfg(feature = "tts")]
+                    if stopped && self.speech_stream.take().is_some() {
+                        tts_control(tts.clone(), String::new(), false);
                     }
                 }
             });
 
+        if let Some(message) = self.branch_from.take() {
+            action = ChatAction::Branch { message };
+        }
+        if let Some(messages) = self.copied_messages.take() {
+            action = ChatAction::ConversationCopied { messages };
+        }
+        if let Some(index) = self.delete_message.take() {
+            if index < self.messages.len() && !self.flower_active() {
+                let message = self.messages.remove(index);
+                self.messages_changed_at(index);
+                action = ChatAction::MessageDeleted {
+                    index,
+                    message: Box::new(message),
+                };
+            }
+        }
+
+        if !had_golden_draft && self.golden_draft.is_some() {
+            golden_modal.open();
+        }
+        golden_modal.show(|ui| {
+            self.show_save_golden_modal_inner(ui, &golden_modal, golden_prompts);
+        });
+
+        if std::mem::take(&mut self.confirm_clear) {
+            clear_modal.open();
+        }
+        clear_modal.show(|ui| self.show_clear_modal_inner(ui, &clear_modal));
+
+        if !had_mode_switch && self.confirm_mode_switch.is_some() {
+            mode_modal.open();
+        }
+        mode_modal.show(|ui| self.show_mode_switch_modal_inner(ui, &mode_modal));
+
+        self.show_request_inspector(ctx);
+
         #[cfg(feature = "tts")]
         {
             if let Some(new_idx) = new_speaker {
@@ -1075,12 +5935,374 @@ impl Chat {
             }
             if stopped_speaking {
                 log::debug!("TTS stopped speaking, updating message icons");
-                for msg in self.messages.iter_mut() {
-                    msg.is_speaking = false;
-                }
+                self.clear_speaking();
             }
         }
 
         action
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_messages_with_bare_timestamps() {
+        // as saved before the timestamp was optional
+        let saved = r#"(
+            model_name: "llama3:latest",
+            content: "Hello!",
+            role: Assistant,
+            time: "2024-05-01T12:30:00Z",
+            is_error: false,
+            images: [],
+            is_prepending: false,
+        )"#;
+        let message: Message = ron::from_str(saved).unwrap();
+        assert_eq!(message.content, "Hello!");
+        assert_eq!(
+            message.time.map(|t| t.to_rfc3339()).as_deref(),
+            Some("2024-05-01T12:30:00+00:00")
+        );
+
+        let message: Message = ron::from_str(r#"(content: "Hi", time: None)"#).unwrap();
+        assert!(message.time.is_none());
+        let message: Message = ron::from_str(r#"(content: "Hi")"#).unwrap();
+        assert!(message.time.is_none());
+    }
+
+    #[test]
+    fn find_bar_rescans_same_length_edits() {
+        let mut messages = vec![Message {
+            content: "fn main() {}".to_owned(),
+            ..Default::default()
+        }];
+        let mut find = FindBar {
+            query: "main".to_owned(),
+            ..Default::default()
+        };
+        find.update(&messages);
+        assert_eq!(find.count, 1);
+
+        messages[0].content = "fn mine() {}".to_owned();
+        find.update(&messages);
+        assert_eq!(find.count, 0);
+        assert!(find.matches.is_empty());
+    }
+
+    #[test]
+    fn timestamps_round_trip() {
+        let message = Message {
+            time: Some(chrono::Utc::now()),
+            ..Default::default()
+        };
+        let saved = ron::to_string(&message).unwrap();
+        let loaded: Message = ron::from_str(&saved).unwrap();
+        assert_eq!(loaded.time, message.time);
+    }
+
+    #[test]
+    fn streaming_reply_survives_crash() {
+        let mut chat = Chat::default();
+        chat.messages.push(Message::user(
+            "Tell me a story".to_owned(),
+            "llama3:latest".to_owned(),
+            Vec::new(),
+        ));
+        chat.messages.push(Message::assistant(
+            String::new(),
+            "llama3:latest".to_owned(),
+        ));
+        chat.messages[1].content += "Once upon a time";
+        chat.modified = true;
+        assert!(chat.take_modified());
+
+        // the autosave is the last thing written before the process is killed
+        let saved = ron::to_string(&chat).unwrap();
+        drop(chat);
+        let chat: Chat = ron::from_str(&saved).unwrap();
+
+        assert_eq!(chat.messages.len(), 2);
+        assert_eq!(chat.messages[0].content, "Tell me a story");
+        let reply = &chat.messages[1];
+        assert_eq!(reply.content, "Once upon a time");
+        assert!(reply.incomplete && !reply.is_generating);
+    }
+
+    #[test]
+    fn tagged_content_follows_same_length_edits() {
+        let mut message = Message {
+            role: Role::Assistant,
+            content: "```\nfn main() {}\n```".to_owned(),
+            ..Default::default()
+        };
+        message.update_tagged_content(false);
+        message.content = message.content.replace("main", "mine");
+        message.update_tagged_content(false);
+        let tagged = message.tagged_content.as_ref().unwrap();
+        assert!(tagged.text.contains("fn mine()"));
+    }
+
+    #[test]
+    fn row_heights_follow_their_messages() {
+        let mut heights = RowHeights::default();
+        heights.prepare(400.0, &[1, 2, 3]);
+        heights.measure(0, 1, 10.0);
+        heights.measure(1, 2, 20.0);
+        heights.measure(2, 3, 30.0);
+
+        // the first message was deleted
+        heights.prepare(400.0, &[2, 3]);
+        assert_eq!(heights.heights, [Some(20.0), Some(30.0)]);
+        assert_eq!(heights.measured.len(), 2);
+
+        // a message was inserted at the top
+        heights.prepare(400.0, &[4, 2, 3]);
+        assert_eq!(heights.heights, [None, Some(20.0), Some(30.0)]);
+
+        // rows wrap differently at another width
+        heights.prepare(300.0, &[4, 2, 3]);
+        assert_eq!(heights.heights, [None, None, None]);
+    }
+
+    #[test]
+    fn report() {
+        let info = ReportInfo {
+            system_prompt: "Answer with\n```rust\ncode\n```".to_owned(),
+            settings: vec![("Temperature", "0.8".to_owned())],
+            ..Default::default()
+        };
+        let messages = vec![
+            Message::user("Hi".to_owned(), "llama3".to_owned(), Vec::new()),
+            Message {
+                role: Role::Assistant,
+                content: "Hello!".to_owned(),
+                model_name: "llama3".to_owned(),
+                rating: Some(Rating::Good),
+                usage: Some(TokenUsage {
+                    prompt_tokens: 12,
+                    reply_tokens: 3,
+                }),
+                settings: Some(ModelSettings {
+                    temperature: Some(0.2),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ];
+        let mut report = Vec::new();
+        write_report(&mut report, &messages, &info).unwrap();
+        let report = String::from_utf8(report).unwrap();
+
+        // the fence outlasts the one in the prompt
+        assert!(report.contains("````\nAnswer with\n```rust\ncode\n```\n````"));
+        assert!(report.contains("| 2 | llama3 | 12 | 3 |  |  | 👍 |"));
+        assert!(report.contains("### 2. Llama3 👍"));
+        assert!(report.contains("_Settings: Temperature 0.2_"));
+    }
+
+    #[test]
+    fn exports_use_user_name() {
+        let messages = vec![Message::user(
+            "Hi".to_owned(),
+            "llama3".to_owned(),
+            Vec::new(),
+        )];
+        let export = |user_name: &str| {
+            let info = ReportInfo {
+                user_name: user_name.to_owned(),
+                ..Default::default()
+            };
+            let mut html = Vec::new();
+            write_html(&mut html, &messages, &info).unwrap();
+            let mut report = Vec::new();
+            write_report(&mut report, &messages, &info).unwrap();
+            (
+                String::from_utf8(html).unwrap(),
+                String::from_utf8(report).unwrap(),
+            )
+        };
+
+        let (html, report) = export("<Ada>");
+        assert!(html.contains("<div class=\"author\">&lt;Ada&gt;"));
+        assert!(report.contains("### 1. <Ada>"));
+        let (html, report) = export(" ");
+        assert!(html.contains("<div class=\"author\">You"));
+        assert!(report.contains("### 1. You"));
+    }
+
+    #[test]
+    fn picker_settings_become_override() {
+        let shared = ModelSettings {
+            temperature: Some(0.9),
+            ..Default::default()
+        };
+        let mut chat = Chat::default();
+        chat.migrate_picker_settings(&shared);
+        assert!(chat.settings_override.is_none());
+
+        // saved before chats could override the shared settings, it used its own
+        let saved = ron::to_string(&chat)
+            .unwrap()
+            .replace("picker_settings_migrated:true,", "");
+        assert!(!saved.contains("picker_settings_migrated"));
+        let mut chat: Chat = ron::from_str(&saved).unwrap();
+        chat.migrate_picker_settings(&shared);
+        assert!(chat.settings_override == Some(ModelSettings::default()));
+
+        // only once, resetting the override sticks
+        chat.settings_override = None;
+        chat.migrate_picker_settings(&shared);
+        assert!(chat.settings_override.is_none());
+    }
+
+    #[test]
+    fn short_name_of_model() {
+        assert_eq!(make_short_name("nous-hermes2:latest"), "Nous Hermes2");
+        assert_eq!(make_short_name("starling-lm:7b-beta-q5_K_M"), "Starling LM");
+        assert_eq!(make_short_name("bambucha/saiga-llama3"), "Saiga Llama3");
+        assert_eq!(make_short_name("qwen2.5-coder:1.5b"), "Qwen2.5 Coder");
+        assert_eq!(make_short_name("4o-mini"), "4o Mini");
+        assert_eq!(make_short_name("ümlaut-modell"), "Ümlaut Modell");
+        assert_eq!(make_short_name(":latest"), "Llama");
+        assert_eq!(make_short_name(""), "Llama");
+    }
+
+    #[test]
+    fn title_case_words() {
+        assert_eq!(title_case("llama"), "Llama");
+        assert_eq!(title_case("7b"), "7B");
+        assert_eq!(title_case("1.5b"), "1.5B");
+        assert_eq!(title_case("270m"), "270M");
+        assert_eq!(title_case("b"), "B");
+        assert_eq!(title_case("3dmodel"), "3dmodel");
+        assert_eq!(title_case("gpt"), "GPT");
+        assert_eq!(title_case("ëlan"), "Ëlan");
+        assert_eq!(title_case(""), "");
+    }
+
+    #[test]
+    fn short_names_are_unique() {
+        let names = short_names(["llama3:8b", "llama3:70b", "gemma:latest"]);
+        assert_eq!(names["llama3:8b"], "Llama3 (8b)");
+        assert_eq!(names["llama3:70b"], "Llama3 (70b)");
+        assert_eq!(names["gemma:latest"], "Gemma");
+
+        // a missing tag is ":latest"
+        let names = short_names(["mistral", "mistral:7b"]);
+        assert_eq!(names["mistral"], "Mistral (latest)");
+        assert_eq!(names["mistral:7b"], "Mistral (7b)");
+
+        // the same model from two namespaces
+        let names = short_names(["alice/phi3:latest", "bob/phi3:latest"]);
+        assert_eq!(names["alice/phi3:latest"], "Phi3 (alice)");
+        assert_eq!(names["bob/phi3:latest"], "Phi3 (bob)");
+
+        assert!(short_names([]).is_empty());
+    }
+
+    /// Splitting streamed replies into sentences for auto-speak
+    #[cfg(feature = "tts")]
+    mod sentences {
+        use super::super::SentenceSplitter;
+
+        /// Feed `content` in pieces of `step` bytes like a streamed reply, collecting what is
+        /// handed out after each piece.
+        fn split_streamed(content: &str, step: usize) -> Vec<String> {
+            let mut splitter = SentenceSplitter::default();
+            let mut out = Vec::new();
+            let mut end = 0;
+            while end < content.len() {
+                end = (end + step).min(content.len());
+                while !content.is_char_boundary(end) {
+                    end += 1;
+                }
+                let text = splitter.next_sentences(&content[..end], end == content.len());
+                if !text.is_empty() {
+                    out.push(text);
+                }
+            }
+            out
+        }
+
+        #[test]
+        fn splits_complete_sentences() {
+            let mut splitter = SentenceSplitter::default();
+            assert_eq!(
+                splitter.next_sentences("Hello there. How are", false),
+                "Hello there. "
+            );
+            assert_eq!(
+                splitter.next_sentences("Hello there. How are you? Fine", false),
+                "How are you? "
+            );
+            assert_eq!(
+                splitter.next_sentences("Hello there. How are you? Fine", true),
+                "Fine"
+            );
+        }
+
+        #[test]
+        fn abbreviations_and_initials_dont_end_sentences() {
+            let mut splitter = SentenceSplitter::default();
+            assert_eq!(
+                splitter.next_sentences("Fruit, e.g. apples, vs. pears by J. Doe. Next", false),
+                "Fruit, e.g. apples, vs. pears by J. Doe. "
+            );
+            let mut splitter = SentenceSplitter::default();
+            assert_eq!(
+                splitter.next_sentences("Ask Dr. Smith. Then", false),
+                "Ask Dr. Smith. "
+            );
+        }
+
+        #[test]
+        fn decimals_and_list_numbers_dont_end_sentences() {
+            let mut splitter = SentenceSplitter::default();
+            assert_eq!(
+                splitter.next_sentences("Pi is about 3.14 here. More", false),
+                "Pi is about 3.14 here. "
+            );
+            let mut splitter = SentenceSplitter::default();
+            assert_eq!(
+                splitter.next_sentences("1. First step is done. Sec", false),
+                "1. First step is done. "
+            );
+        }
+
+        #[test]
+        fn code_blocks_are_skipped() {
+            let content = "Run this.\n```rust\nlet x = 1. Not prose.\n```\nDone now.";
+            let mut splitter = SentenceSplitter::default();
+            assert_eq!(
+                splitter.next_sentences(content, true),
+                "Run this.\nDone now."
+            );
+            // a fence is only recognized once its line is complete
+            let mut splitter = SentenceSplitter::default();
+            assert_eq!(
+                splitter.next_sentences("Run this.\n``", false),
+                "Run this.\n"
+            );
+            assert_eq!(
+                splitter.next_sentences("Run this.\n```\nx. y. \n", false),
+                ""
+            );
+        }
+
+        #[test]
+        fn streaming_keeps_order_and_loses_nothing() {
+            let content =
+                "First one. Second, e.g. this one! Third?\n```\ncode. here.\n```\nLast 2.5 bit.";
+            for step in 1..8 {
+                let pieces = split_streamed(content, step);
+                assert_eq!(
+                    pieces.concat(),
+                    "First one. Second, e.g. this one! Third?\nLast 2.5 bit.",
+                    "step {step}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Command-line arguments.
+
+use crate::widgets::Settings;
+
+const USAGE: &str = "\
+Usage: ellama [OPTIONS]
+
+Options:
+      --host <URL>     Ollama server to connect to, overrides OLLAMA_HOST and the saved setting
+      --model <NAME>   Model to select on startup
+      --new-chat       Start with a new chat
+  -h, --help           Print this help";
+
+#[derive(Debug, Default)]
+pub struct Args {
+    pub host: Option<String>,
+    pub model: Option<String>,
+    pub new_chat: bool,
+}
+
+impl Args {
+    /// Parse the process arguments. Prints the usage and exits on `--help` or invalid
+    /// arguments.
+    pub fn parse() -> Self {
+        match Self::parse_from(std::env::args().skip(1)) {
+            Ok(Some(args)) => args,
+            Ok(None) => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("error: {e}\n\n{USAGE}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    /// Returns `None` if help was requested.
+    fn parse_from(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut parsed = Self::default();
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_owned())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .filter(|v| !v.is_empty())
+                    .ok_or_else(|| format!("`{flag}` requires a value"))
+            };
+            match flag {
+                "-h" | "--help" => return Ok(None),
+                "--host" => {
+                    let host = Settings::parse_ollama_host(&value()?)
+                        .map_err(|e| format!("invalid host: {e}"))?;
+                    parsed.host = Some(host);
+                }
+                "--model" => parsed.model = Some(value()?),
+                "--new-chat" if inline_value.is_none() => parsed.new_chat = true,
+                _ => return Err(format!("unexpected argument `{arg}`")),
+            }
+        }
+        Ok(Some(parsed))
+    }
+}
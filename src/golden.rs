@@ -0,0 +1,241 @@
+//! "Golden" prompts: carefully crafted user messages saved for reuse across chats.
+
+use crate::i18n::{tr, tr_args};
+use eframe::egui::{self, Color32, RichText};
+use std::{collections::BTreeMap, ops::Range};
+
+/// Replaced by the text selected in the chat input when a prompt is inserted
+const SELECTION: &str = "{selection}";
+/// Replaced by the text on the clipboard when a prompt is inserted
+const CLIPBOARD: &str = "{clipboard}";
+
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct GoldenPrompt {
+    pub name: String,
+    pub text: String,
+    /// Comma separated tags
+    pub tags: String,
+    /// Number of times the prompt was inserted into a chat
+    pub uses: u32,
+    /// Number of uses by model name
+    pub model_uses: BTreeMap<String, u32>,
+}
+
+impl GoldenPrompt {
+    pub fn new(text: String) -> Self {
+        let name = text
+            .lines()
+            .find(|l| !l.trim().is_empty())
+            .unwrap_or_default()
+            .chars()
+            .take(48)
+            .collect::<String>()
+            .trim()
+            .to_owned();
+        Self {
+            name,
+            text,
+            ..Default::default()
+        }
+    }
+
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.tags
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+    }
+
+    /// Case-insensitive fuzzy match on the name and tags, lower is better. `None` if the
+    /// query doesn't match.
+    pub fn match_score(&self, query: &str) -> Option<usize> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Some(0);
+        }
+        std::iter::once(self.name.as_str())
+            .chain(self.tags())
+            .filter_map(|s| fuzzy_score(&s.to_lowercase(), &query))
+            .min()
+    }
+
+    pub fn has_selection_placeholder(&self) -> bool {
+        self.text.contains(SELECTION)
+    }
+
+    /// The text with its placeholders replaced, and the char range of the first replaced
+    /// placeholder. The clipboard is only read if the text refers to it.
+    pub fn expand(&self, selection: &str) -> (String, Option<Range<usize>>) {
+        let mut text = String::with_capacity(self.text.len());
+        let mut first = None;
+        let mut clipboard = None;
+        let mut rest = self.text.as_str();
+        while let Some((i, placeholder)) = [SELECTION, CLIPBOARD]
+            .into_iter()
+            .filter_map(|p| Some((rest.find(p)?, p)))
+            .min()
+        {
+            text += &rest[..i];
+            let value = if placeholder == SELECTION {
+                selection
+            } else {
+                clipboard.get_or_insert_with(read_clipboard).as_str()
+            };
+            if first.is_none() {
+                let start = text.chars().count();
+                first = Some(start..start + value.chars().count());
+            }
+            text += value;
+            rest = &rest[i + placeholder.len()..];
+        }
+        text += rest;
+        (text, first)
+    }
+
+    /// Count an insertion of the prompt into a chat using `model`
+    pub fn record_use(&mut self, model: &str) {
+        self.uses += 1;
+        *self.model_uses.entry(model.to_owned()).or_default() += 1;
+    }
+
+    fn show_summary(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(&self.name).strong());
+            for tag in self.tags() {
+                ui.label(RichText::new(format!("#{tag}")).small().weak());
+            }
+        });
+    }
+}
+
+/// Substring matches score their position, others with the characters of `needle` in order
+/// score after them, fewer skipped characters first.
+fn fuzzy_score(haystack: &str, needle: &str) -> Option<usize> {
+    if let Some(pos) = haystack.find(needle) {
+        return Some(pos);
+    }
+    let mut skipped = 0;
+    let mut chars = haystack.chars();
+    for c in needle.chars() {
+        loop {
+            if chars.next()? == c {
+                break;
+            }
+            skipped += 1;
+        }
+    }
+    Some(haystack.len() + skipped)
+}
+
+fn read_clipboard() -> String {
+    arboard::Clipboard::new()
+        .and_then(|mut c| c.get_text())
+        .unwrap_or_else(|e| {
+            log::warn!("failed to read text from the clipboard: {e}");
+            String::new()
+        })
+}
+
+/// Indices of the prompts matching `query`, best matches first.
+pub fn matching(prompts: &[GoldenPrompt], query: &str) -> Vec<usize> {
+    let mut matches: Vec<(usize, usize)> = prompts
+        .iter()
+        .enumerate()
+        .filter_map(|(i, prompt)| Some((prompt.match_score(query)?, i)))
+        .collect();
+    matches.sort_unstable();
+    matches.into_iter().map(|(_, i)| i).collect()
+}
+
+/// List golden prompts matching `query` as buttons, best matches first, returns the index
+/// of the clicked one.
+pub fn show_prompt_list(ui: &mut egui::Ui, prompts: &[GoldenPrompt], query: &str) -> Option<usize> {
+    let mut clicked = None;
+    let matches = matching(prompts, query);
+    let any = !matches.is_empty();
+    for i in matches {
+        let prompt = &prompts[i];
+        let resp = ui
+            .add(egui::Button::new(&prompt.name).fill(Color32::TRANSPARENT))
+            .on_hover_ui(|ui| {
+                prompt.show_summary(ui);
+                ui.label(&prompt.text);
+            });
+        if resp.clicked() {
+            clicked = Some(i);
+        }
+    }
+    if !any {
+        ui.label(if prompts.is_empty() {
+            tr("settings.golden_none")
+        } else {
+            tr("settings.golden_none_match")
+        });
+    }
+    clicked
+}
+
+/// Show the editable golden prompt library.
+pub fn show_library(ui: &mut egui::Ui, prompts: &mut Vec<GoldenPrompt>) {
+    ui.label(tr_args("settings.golden_help", &[&SELECTION, &CLIPBOARD]));
+    if ui.button(tr("settings.golden_new")).clicked() {
+        prompts.push(GoldenPrompt {
+            name: tr("settings.golden_new_name").to_owned(),
+            ..Default::default()
+        });
+    }
+    if prompts.is_empty() {
+        ui.label(tr("settings.golden_none"));
+        return;
+    }
+
+    let mut remove = None;
+    for (i, prompt) in prompts.iter_mut().enumerate() {
+        let key = if prompt.uses == 1 {
+            "settings.golden_uses_one"
+        } else {
+            "settings.golden_uses_many"
+        };
+        egui::CollapsingHeader::new(tr_args(key, &[&prompt.name, &prompt.uses]))
+            .id_source(("golden_prompt", i))
+            .show(ui, |ui| {
+                egui::Grid::new(("golden_prompt_grid", i))
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label(tr("chat.golden_name"));
+                        ui.text_edit_singleline(&mut prompt.name);
+                        ui.end_row();
+
+                        ui.label(tr("chat.golden_tags"));
+                        ui.add(
+                            egui::TextEdit::singleline(&mut prompt.tags)
+                                .hint_text(tr("settings.golden_tags_hint")),
+                        );
+                        ui.end_row();
+
+                        ui.label(tr("settings.golden_used_with"));
+                        if prompt.model_uses.is_empty() {
+                            ui.label(tr("settings.golden_never_used"));
+                        } else {
+                            ui.label(
+                                prompt
+                                    .model_uses
+                                    .iter()
+                                    .map(|(model, n)| format!("{model} ({n})"))
+                                    .collect::<Vec<_>>()
+                                    .join(", "),
+                            );
+                        }
+                        ui.end_row();
+                    });
+                ui.add(egui::TextEdit::multiline(&mut prompt.text).desired_width(f32::INFINITY));
+                if ui.button(tr("common.delete")).clicked() {
+                    remove = Some(i);
+                }
+            });
+    }
+    if let Some(i) = remove {
+        prompts.remove(i);
+    }
+}
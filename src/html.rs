@@ -0,0 +1,534 @@
+//! Rendering chats to standalone HTML pages: a small markdown to HTML converter with
+//! keyword-based code highlighting, and the stylesheet embedded in exported pages.
+
+use crate::langdetect;
+
+/// Stylesheet of exported pages, following the browser's light or dark preference.
+pub const STYLE: &str = r#"
+:root {
+    color-scheme: light dark;
+    --bg: #f6f7f9; --fg: #1f2328; --muted: #656d76; --border: #d0d7de;
+    --user: #dbeafe; --assistant: #ffffff; --code-bg: #eef1f4;
+    --kw: #cf222e; --str: #0a3069; --com: #6e7781; --num: #0550ae;
+    --note: #fff8c5; --error: #ffebe9; --link: #0969da;
+}
+@media (prefers-color-scheme: dark) {
+    :root {
+        --bg: #0d1117; --fg: #e6edf3; --muted: #8d96a0; --border: #30363d;
+        --user: #1c3353; --assistant: #161b22; --code-bg: #0b0f14;
+        --kw: #ff7b72; --str: #a5d6ff; --com: #8b949e; --num: #79c0ff;
+        --note: #3a3000; --error: #4b1113; --link: #4493f8;
+    }
+}
+* { box-sizing: border-box; }
+body {
+    margin: 0; padding: 2rem 1rem; background: var(--bg); color: var(--fg);
+    font: 15px/1.55 system-ui, -apple-system, "Segoe UI", Roboto, sans-serif;
+}
+header, main, footer { max-width: 52rem; margin: 0 auto; }
+header { border-bottom: 1px solid var(--border); margin-bottom: 1.5rem; }
+header h1 { margin: 0 0 .25rem; font-size: 1.6rem; }
+.meta, .author, footer { color: var(--muted); font-size: .85rem; }
+header .meta { margin-bottom: 1rem; }
+details { margin-bottom: 1rem; }
+details pre { white-space: pre-wrap; }
+.message { display: flex; flex-direction: column; margin: 1rem 0; }
+.message.user { align-items: flex-end; }
+.author { margin: 0 .5rem .2rem; }
+.bubble {
+    max-width: 100%; padding: .6rem 1rem; border-radius: 12px;
+    border: 1px solid var(--border); overflow-wrap: anywhere;
+}
+.user .bubble { background: var(--user); border-bottom-right-radius: 4px; }
+.assistant .bubble { background: var(--assistant); border-bottom-left-radius: 4px; }
+.bubble > :first-child { margin-top: 0; }
+.bubble > :last-child { margin-bottom: 0; }
+.bubble img { display: block; max-width: 100%; max-height: 24rem; border-radius: 8px; margin: .4rem 0; }
+.error { background: var(--error); }
+.note {
+    max-width: 100%; margin-top: .3rem; padding: .2rem .6rem; border-radius: 6px;
+    background: var(--note); font-size: .85rem; white-space: pre-wrap;
+}
+a { color: var(--link); }
+code, pre { font-family: ui-monospace, "SF Mono", Menlo, Consolas, monospace; font-size: .9em; }
+:not(pre) > code { background: var(--code-bg); padding: .1em .3em; border-radius: 4px; }
+.code { margin: .6rem 0; border: 1px solid var(--border); border-radius: 8px; overflow: hidden; }
+.code .lang { padding: .15rem .6rem; color: var(--muted); font-size: .75rem; border-bottom: 1px solid var(--border); }
+pre { margin: 0; padding: .6rem; background: var(--code-bg); overflow-x: auto; }
+.kw { color: var(--kw); } .str { color: var(--str); } .com { color: var(--com); font-style: italic; } .num { color: var(--num); }
+blockquote { margin: .5rem 0; padding-left: .8rem; border-left: 3px solid var(--border); color: var(--muted); }
+table { border-collapse: collapse; margin: .5rem 0; }
+th, td { border: 1px solid var(--border); padding: .25rem .6rem; }
+hr { border: none; border-top: 1px solid var(--border); }
+footer { margin-top: 2rem; text-align: center; }
+"#;
+
+/// Words highlighted as keywords in code blocks of any language
+const KEYWORDS: &[&str] = &[
+    "as",
+    "async",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "def",
+    "default",
+    "defer",
+    "del",
+    "do",
+    "elif",
+    "else",
+    "enum",
+    "except",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "fn",
+    "for",
+    "from",
+    "func",
+    "function",
+    "go",
+    "if",
+    "impl",
+    "import",
+    "in",
+    "interface",
+    "is",
+    "lambda",
+    "let",
+    "loop",
+    "match",
+    "mod",
+    "mut",
+    "new",
+    "nil",
+    "None",
+    "not",
+    "null",
+    "or",
+    "and",
+    "package",
+    "pass",
+    "private",
+    "protected",
+    "pub",
+    "public",
+    "raise",
+    "return",
+    "self",
+    "Self",
+    "static",
+    "struct",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "trait",
+    "True",
+    "False",
+    "true",
+    "try",
+    "type",
+    "typeof",
+    "use",
+    "var",
+    "void",
+    "where",
+    "while",
+    "with",
+    "yield",
+    "SELECT",
+    "FROM",
+    "WHERE",
+    "INSERT",
+    "UPDATE",
+    "DELETE",
+    "JOIN",
+    "CREATE",
+    "TABLE",
+];
+
+/// Escape text for use in HTML content and attribute values.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Line comment marker of a language
+fn line_comment(language: &str) -> &'static str {
+    match language {
+        "python" | "py" | "bash" | "sh" | "shell" | "zsh" | "ruby" | "perl" | "yaml" | "yml"
+        | "toml" | "r" | "dockerfile" | "makefile" | "powershell" => "#",
+        "sql" | "lua" | "haskell" => "--",
+        _ => "//",
+    }
+}
+
+/// Highlight keywords, strings, comments and numbers of `code` as escaped HTML.
+fn highlight(code: &str, language: &str) -> String {
+    let comment = line_comment(language);
+    // `'` starts lifetimes in Rust rather than strings
+    let quotes: &[char] = if language == "rust" || language == "rs" {
+        &['"']
+    } else {
+        &['"', '\'', '`']
+    };
+    let span = |class: &str, text: &str| format!("<span class=\"{class}\">{}</span>", escape(text));
+
+    let mut html = String::with_capacity(code.len() * 2);
+    let mut rest = code;
+    while let Some(ch) = rest.chars().next() {
+        let token_len = if rest.starts_with(comment) {
+            let len = rest.find('\n').unwrap_or(rest.len());
+            html.push_str(&span("com", &rest[..len]));
+            len
+        } else if comment == "//" && rest.starts_with("/*") {
+            let len = rest[2..].find("*/").map_or(rest.len(), |end| end + 4);
+            html.push_str(&span("com", &rest[..len]));
+            len
+        } else if quotes.contains(&ch) {
+            let mut escaped = false;
+            let end = rest[1..].char_indices().find(|&(_, c)| {
+                let closes = !escaped && c == ch;
+                escaped = !escaped && c == '\\';
+                closes || c == '\n'
+            });
+            let len = end.map_or(rest.len(), |(i, c)| i + 1 + c.len_utf8());
+            html.push_str(&span("str", &rest[..len]));
+            len
+        } else if ch.is_alphanumeric() || ch == '_' {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            let word = &rest[..len];
+            if ch.is_ascii_digit() {
+                html.push_str(&span("num", word));
+            } else if KEYWORDS.contains(&word) {
+                html.push_str(&span("kw", word));
+            } else {
+                html.push_str(&escape(word));
+            }
+            len
+        } else {
+            html.push_str(&escape(&rest[..ch.len_utf8()]));
+            ch.len_utf8()
+        };
+        rest = &rest[token_len..];
+    }
+    html
+}
+
+/// `(inner, rest)` if `text` starts with `open` and `close` follows non-blank contents.
+fn delimited<'a>(text: &'a str, open: &str, close: &str) -> Option<(&'a str, &'a str)> {
+    let after = text.strip_prefix(open)?;
+    let end = after.find(close)?;
+    let inner = &after[..end];
+    (!inner.trim().is_empty() && !inner.starts_with(char::is_whitespace))
+        .then_some((inner, &after[end + close.len()..]))
+}
+
+/// Inline markdown of a single line: code spans, emphasis, strikethrough and links.
+fn inline(text: &str) -> String {
+    let mut html = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(ch) = rest.chars().next() {
+        if let Some((code, after)) = delimited(rest, "`", "`") {
+            html.push_str(&format!("<code>{}</code>", escape(code)));
+            rest = after;
+            continue;
+        }
+        let styled = [
+            ("**", "strong"),
+            ("__", "strong"),
+            ("~~", "del"),
+            ("*", "em"),
+        ]
+        .into_iter()
+        .find_map(|(marker, tag)| {
+            delimited(rest, marker, marker).map(|(inner, after)| (tag, inner, after))
+        });
+        if let Some((tag, inner, after)) = styled {
+            html.push_str(&format!("<{tag}>{}</{tag}>", inline(inner)));
+            rest = after;
+            continue;
+        }
+        if let Some((label, after)) = delimited(rest, "[", "](") {
+            if let Some((url, after)) = after.split_once(')').filter(|(url, _)| {
+                ["http://", "https://", "mailto:"]
+                    .iter()
+                    .any(|scheme| url.starts_with(scheme))
+            }) {
+                html.push_str(&format!(
+                    "<a href=\"{}\">{}</a>",
+                    escape(url),
+                    inline(label)
+                ));
+                rest = after;
+                continue;
+            }
+        }
+        html.push_str(&escape(&rest[..ch.len_utf8()]));
+        rest = &rest[ch.len_utf8()..];
+    }
+    html
+}
+
+/// `(ordered, contents)` if `line` is a list item.
+fn list_item(line: &str) -> Option<(bool, &str)> {
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(item) = line.strip_prefix(marker) {
+            return Some((false, item));
+        }
+    }
+    let digits = line.find(|c: char| !c.is_ascii_digit())?;
+    if digits == 0 {
+        return None;
+    }
+    line[digits..].strip_prefix(". ").map(|item| (true, item))
+}
+
+fn is_rule(line: &str) -> bool {
+    let marks: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3 && matches!(marks[0], '-' | '*' | '_') && marks.iter().all(|&c| c == marks[0])
+}
+
+fn table_cells(line: &str) -> Vec<&str> {
+    let line = line.trim().trim_start_matches('|').trim_end_matches('|');
+    line.split('|').map(str::trim).collect()
+}
+
+fn flush_paragraph(html: &mut String, paragraph: &mut Vec<&str>) {
+    if paragraph.is_empty() {
+        return;
+    }
+    let lines: Vec<String> = paragraph.drain(..).map(inline).collect();
+    html.push_str(&format!("<p>{}</p>\n", lines.join("<br>\n")));
+}
+
+/// Convert the markdown of a message to HTML. Covers what models commonly write: fenced
+/// code, headings, lists, quotes, tables, rules and inline styling. Raw HTML is escaped.
+pub fn markdown(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut html = String::with_capacity(text.len() * 2);
+    let mut paragraph = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim_start();
+        i += 1;
+
+        if let Some(fence) = ["```", "~~~"].into_iter().find(|f| trimmed.starts_with(f)) {
+            flush_paragraph(&mut html, &mut paragraph);
+            let info = trimmed[fence.len()..].trim();
+            let mut code = Vec::new();
+            while i < lines.len() && lines[i].trim() != fence {
+                code.push(lines[i]);
+                i += 1;
+            }
+            i += 1;
+            let code = code.join("\n");
+            let language = info
+                .split_whitespace()
+                .next()
+                .map(str::to_owned)
+                .or_else(|| langdetect::detect_language(&code).map(str::to_owned))
+                .unwrap_or_default();
+            html.push_str("<div class=\"code\">");
+            if !language.is_empty() {
+                html.push_str(&format!("<div class=\"lang\">{}</div>", escape(&language)));
+            }
+            html.push_str(&format!(
+                "<pre><code>{}</code></pre></div>\n",
+                highlight(&code, &language.to_ascii_lowercase())
+            ));
+        } else if trimmed.is_empty() {
+            flush_paragraph(&mut html, &mut paragraph);
+        } else if let Some(level) = trimmed
+            .find(|c: char| c != '#')
+            .filter(|&n| (1..=6).contains(&n) && trimmed[n..].starts_with(' '))
+        {
+            flush_paragraph(&mut html, &mut paragraph);
+            // the page title is the only h1
+            let tag = (level + 1).min(6);
+            html.push_str(&format!(
+                "<h{tag}>{}</h{tag}>\n",
+                inline(trimmed[level..].trim())
+            ));
+        } else if is_rule(trimmed) {
+            flush_paragraph(&mut html, &mut paragraph);
+            html.push_str("<hr>\n");
+        } else if trimmed.starts_with('>') {
+            flush_paragraph(&mut html, &mut paragraph);
+            let mut quote = vec![trimmed[1..].strip_prefix(' ').unwrap_or(&trimmed[1..])];
+            while let Some(next) = lines.get(i).and_then(|l| l.trim_start().strip_prefix('>')) {
+                quote.push(next.strip_prefix(' ').unwrap_or(next));
+                i += 1;
+            }
+            html.push_str(&format!(
+                "<blockquote>{}</blockquote>\n",
+                markdown(&quote.join("\n"))
+            ));
+        } else if let Some((ordered, first)) = list_item(trimmed) {
+            flush_paragraph(&mut html, &mut paragraph);
+            let mut items = vec![first.to_owned()];
+            while i < lines.len() {
+                let next = lines[i];
+                let next_trimmed = next.trim_start();
+                if next_trimmed.is_empty() {
+                    // a blank line only continues the list if more of it follows
+                    let continues = lines.get(i + 1).is_some_and(|l| {
+                        l.starts_with(char::is_whitespace)
+                            || list_item(l).is_some_and(|(o, _)| o == ordered)
+                    });
+                    if !continues {
+                        break;
+                    }
+                    items.last_mut().unwrap().push('\n');
+                } else if next.starts_with(char::is_whitespace) {
+                    let item = items.last_mut().unwrap();
+                    item.push('\n');
+                    item.push_str(next_trimmed);
+                } else if let Some((_, item)) = list_item(next).filter(|(o, _)| *o == ordered) {
+                    items.push(item.to_owned());
+                } else {
+                    break;
+                }
+                i += 1;
+            }
+            let tag = if ordered { "ol" } else { "ul" };
+            html.push_str(&format!("<{tag}>\n"));
+            for item in items {
+                if item.contains('\n') {
+                    html.push_str(&format!("<li>{}</li>\n", markdown(&item)));
+                } else {
+                    html.push_str(&format!("<li>{}</li>\n", inline(&item)));
+                }
+            }
+            html.push_str(&format!("</{tag}>\n"));
+        } else if trimmed.starts_with('|')
+            && lines.get(i).is_some_and(|next| {
+                next.contains('-')
+                    && next
+                        .trim()
+                        .chars()
+                        .all(|c| matches!(c, '|' | '-' | ':' | ' '))
+            })
+        {
+            flush_paragraph(&mut html, &mut paragraph);
+            html.push_str("<table>\n<tr>");
+            for cell in table_cells(trimmed) {
+                html.push_str(&format!("<th>{}</th>", inline(cell)));
+            }
+            html.push_str("</tr>\n");
+            i += 1;
+            while let Some(row) = lines.get(i).filter(|l| l.trim_start().starts_with('|')) {
+                html.push_str("<tr>");
+                for cell in table_cells(row) {
+                    html.push_str(&format!("<td>{}</td>", inline(cell)));
+                }
+                html.push_str("</tr>\n");
+                i += 1;
+            }
+            html.push_str("</table>\n");
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+    flush_paragraph(&mut html, &mut paragraph);
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaping() {
+        assert_eq!(
+            escape(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;"
+        );
+        assert_eq!(
+            markdown("<script>alert(1)</script>"),
+            "<p>&lt;script&gt;alert(1)&lt;/script&gt;</p>\n"
+        );
+        assert_eq!(
+            markdown("`<b>` **<i>**"),
+            "<p><code>&lt;b&gt;</code> <strong>&lt;i&gt;</strong></p>\n"
+        );
+    }
+
+    #[test]
+    fn links_cant_inject() {
+        // only web and mail links become anchors
+        assert_eq!(
+            markdown("[click](javascript:alert(1))"),
+            "<p>[click](javascript:alert(1))</p>\n"
+        );
+        assert_eq!(
+            markdown(r#"[x](https://a.b/"onmouseover="alert(1))"#),
+            "<p><a href=\"https://a.b/&quot;onmouseover=&quot;alert(1\">x</a>)</p>\n"
+        );
+        assert_eq!(
+            markdown("[<img src=x>](https://a.b)"),
+            "<p><a href=\"https://a.b\">&lt;img src=x&gt;</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn code_fences() {
+        assert_eq!(
+            markdown("```\"><script>\nlet x = \"</pre>\";\n```"),
+            "<div class=\"code\"><div class=\"lang\">&quot;&gt;&lt;script&gt;</div>\
+             <pre><code><span class=\"kw\">let</span> x = <span class=\"str\">&quot;&lt;/pre&gt;&quot;</span>;</code></pre></div>\n"
+        );
+        assert_eq!(
+            markdown("~~~python\n# hi\nreturn 1\n~~~\nafter"),
+            "<div class=\"code\"><div class=\"lang\">python</div>\
+             <pre><code><span class=\"com\"># hi</span>\n\
+             <span class=\"kw\">return</span> <span class=\"num\">1</span></code></pre></div>\n\
+             <p>after</p>\n"
+        );
+        // an unclosed fence runs to the end
+        assert!(markdown("```text\n**bold**").contains("<code>**bold**</code>"));
+    }
+
+    #[test]
+    fn lists() {
+        assert_eq!(
+            markdown("- a\n- *b*\n\n1. one\n2. two"),
+            "<ul>\n<li>a</li>\n<li><em>b</em></li>\n</ul>\n\
+             <ol>\n<li>one</li>\n<li>two</li>\n</ol>\n"
+        );
+        assert_eq!(
+            markdown("1. first\n   more\n\n   still first\n2. second"),
+            "<ol>\n<li><p>first<br>\nmore</p>\n<p>still first</p>\n</li>\n<li>second</li>\n</ol>\n"
+        );
+    }
+
+    #[test]
+    fn tables() {
+        assert_eq!(
+            markdown("| a | <b> |\n| --- | :-: |\n| `1` | 2 |\nafter"),
+            "<table>\n<tr><th>a</th><th>&lt;b&gt;</th></tr>\n\
+             <tr><td><code>1</code></td><td>2</td></tr>\n</table>\n<p>after</p>\n"
+        );
+        // without the separator row it's just text
+        assert_eq!(markdown("| a | b |"), "<p>| a | b |</p>\n");
+    }
+}
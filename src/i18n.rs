@@ -0,0 +1,1970 @@
+//! Translations of the user interface. Strings are looked up by key in tables compiled into
+//! the binary; keys missing from the table of the chosen language fall back to English.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Language of the user interface.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+/// Language strings are looked up in, as the index into [`Language::ALL`]
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+impl Language {
+    pub const ALL: [Self; 2] = [Self::English, Self::German];
+
+    /// Name of the language in itself, so it can be found without understanding the
+    /// current one
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::German => "Deutsch",
+        }
+    }
+
+    const fn table(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::English => ENGLISH,
+            Self::German => GERMAN,
+        }
+    }
+
+    /// Use this language for all following lookups.
+    pub fn apply(self) {
+        let idx = Self::ALL.iter().position(|&l| l == self).unwrap_or(0);
+        CURRENT.store(idx as u8, Ordering::Relaxed);
+    }
+
+    fn current() -> Self {
+        Self::ALL
+            .get(CURRENT.load(Ordering::Relaxed) as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+fn lookup(table: &'static [(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    table.iter().find(|(k, _)| *k == key).map(|(_, text)| *text)
+}
+
+/// The text of `key` in the current language, or in English if it isn't translated.
+pub fn tr(key: &'static str) -> &'static str {
+    lookup(Language::current().table(), key)
+        .or_else(|| lookup(ENGLISH, key))
+        .unwrap_or_else(|| {
+            log::warn!("no English text for `{key}`");
+            key
+        })
+}
+
+/// [`tr`] with each `{}` replaced by the next of `args`.
+pub fn tr_args(key: &'static str, args: &[&dyn std::fmt::Display]) -> String {
+    let mut args = args.iter();
+    let mut text = String::new();
+    for (i, part) in tr(key).split("{}").enumerate() {
+        if i > 0 {
+            if let Some(arg) = args.next() {
+                text.push_str(&arg.to_string());
+            }
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+const ENGLISH: &[(&str, &str)] = &[
+    // shared
+    ("common.apply", "Apply"),
+    ("common.cancel", "❌ Cancel"),
+    ("common.close", "Close"),
+    ("common.delete", "🗑 Delete"),
+    ("common.edit", "Edit"),
+    ("common.load", "Load"),
+    ("common.loading", "Loading…"),
+    ("common.optional", "Optional"),
+    ("common.reset", "Reset"),
+    ("common.reset_to_default", "Reset to default"),
+    ("common.save", "Save"),
+    ("common.unknown", "unknown"),
+    ("common.no", "No"),
+    ("common.yes", "Yes"),
+    ("common.you", "You"),
+    ("common.task_panicked", "Task panicked: {}"),
+    // settings
+    ("settings.server", "Server"),
+    ("settings.connection", "Connection settings"),
+    ("settings.backend", "Backend"),
+    (
+        "settings.backend_help",
+        "API used for chats and the model list. Knowledge embeddings and model management \
+        always go to the Ollama server.",
+    ),
+    ("settings.host", "Ollama host"),
+    ("settings.port", "Port"),
+    (
+        "settings.apply_endpoint_help",
+        "Connect to this server and reload the model list",
+    ),
+    ("settings.connected_to", "Currently connected to {}"),
+    ("settings.proxy", "Proxy"),
+    ("settings.manual_proxy", "Manual proxy"),
+    (
+        "settings.manual_proxy_help",
+        "When disabled, the HTTP_PROXY, HTTPS_PROXY, ALL_PROXY and NO_PROXY environment \
+        variables are used",
+    ),
+    ("settings.auth", "Authentication"),
+    ("settings.auth_token", "Token"),
+    (
+        "settings.auth_apply_help",
+        "Use these credentials for every request to the Ollama server and reconnect",
+    ),
+    ("settings.auth_in_keyring", "Stored in the OS keyring"),
+    (
+        "settings.auth_in_settings",
+        "No keyring available, saved with the settings",
+    ),
+    ("settings.connect_timeout", "Connect timeout"),
+    (
+        "settings.connect_timeout_help",
+        "How long to wait for a connection to the server",
+    ),
+    ("settings.first_token_timeout", "First token timeout"),
+    (
+        "settings.first_token_timeout_help",
+        "How long to wait for a reply to start, or for the model list and model info. \
+        Loading a large model can take a while",
+    ),
+    ("settings.stall_timeout", "Stall timeout"),
+    (
+        "settings.stall_timeout_help",
+        "How long a reply may stop streaming before it's cut off and marked as interrupted",
+    ),
+    ("settings.retry_stalled", "Retry stalled replies"),
+    (
+        "settings.retry_stalled_help",
+        "Send the request once more when a reply stalls, replacing the partial text",
+    ),
+    ("auth.none", "None"),
+    ("auth.bearer", "Bearer token"),
+    ("auth.basic", "Username and password"),
+    ("settings.proxy_url", "Proxy URL"),
+    ("settings.username", "Username"),
+    ("settings.password", "Password"),
+    ("settings.remember", "Remember"),
+    (
+        "settings.remember_help",
+        "Keep the password in the OS keyring, or with the settings if there is none",
+    ),
+    ("settings.bypass_localhost", "Bypass for localhost"),
+    ("settings.model", "Model"),
+    ("settings.default_model", "Default model for new chats"),
+    ("settings.inherit", "Inherit from chats"),
+    ("settings.inherit_help", "Inherit model changes from chats"),
+    ("settings.golden_prompts", "Golden prompts"),
+    ("settings.misc", "Miscellaneous"),
+    ("settings.language", "Language"),
+    ("settings.theme", "Theme"),
+    ("settings.theme_system", "Follow system"),
+    ("settings.theme_dark", "Dark"),
+    ("settings.theme_light", "Light"),
+    (
+        "settings.quick_settings",
+        "Show temperature and seed in the chat header",
+    ),
+    ("settings.your_name", "Your name"),
+    ("settings.send_with", "Send messages with"),
+    (
+        "settings.send_enter",
+        "Enter sends, Shift+Enter for a new line",
+    ),
+    (
+        "settings.send_ctrl_enter",
+        "Ctrl+Enter sends, Enter for a new line",
+    ),
+    ("settings.retention", "Keep deleted chats for"),
+    (
+        "settings.retention_help",
+        "Deleted chats are purged after this many days",
+    ),
+    ("settings.days", " days"),
+    ("settings.max_image", "Max. pasted image size"),
+    (
+        "settings.max_image_help",
+        "Pasted images are downscaled to fit, to keep requests small",
+    ),
+    ("settings.max_attachment", "Max. attached file size"),
+    (
+        "settings.max_attachment_help",
+        "Larger text files dropped into a chat are refused",
+    ),
+    ("settings.autosave", "Autosave every"),
+    (
+        "settings.autosave_help",
+        "Changes are saved at most this often, and when the app is closed",
+    ),
+    ("settings.reset_label", "Reset global settings to defaults"),
+    ("settings.save_load_label", "Save and load settings as JSON"),
+    (
+        "settings.backup_label",
+        "Back up all chats and settings to a single file",
+    ),
+    ("settings.export_backup", "Export backup…"),
+    // keyboard shortcuts
+    ("keys.heading", "Keyboard shortcuts"),
+    ("keys.new_chat", "New chat"),
+    ("keys.close_chat", "Delete chat"),
+    ("keys.next_chat", "Next chat"),
+    ("keys.previous_chat", "Previous chat"),
+    ("keys.toggle_sidebar", "Toggle sidebar"),
+    ("keys.search_chats", "Search chats"),
+    ("keys.find_in_chat", "Find in chat"),
+    ("keys.stop_generation", "Stop generating"),
+    ("keys.undo_deletion", "Undo deletion"),
+    ("keys.toggle_logs", "Show logs"),
+    ("keys.jump_to_chat", "Jump to chat {}"),
+    ("keys.press", "Press keys…"),
+    (
+        "keys.press_help",
+        "Press the new combination, Esc or click to cancel",
+    ),
+    ("keys.change_help", "Click to change"),
+    ("keys.conflict", "Also used by: {}"),
+    (
+        "keys.needs_modifier",
+        "Hold Ctrl or Alt with the key, plain keys are needed for typing",
+    ),
+    ("keys.reset_one", "Reset to {}"),
+    ("keys.reset_all", "Reset all to defaults"),
+    ("settings.restore_backup", "Restore backup…"),
+    // model picker
+    ("models.filter", "Filter models…"),
+    ("models.sort", "Sort"),
+    ("models.sort_name", "name"),
+    ("models.sort_size", "size"),
+    ("models.sort_modified", "modified"),
+    ("models.server_order", "Server order"),
+    (
+        "models.none_found",
+        "No models found, is the server running?",
+    ),
+    ("models.none_match", "No models match"),
+    ("models.refresh", "Refresh model list"),
+    ("models.loading", "Loading model list…"),
+    ("models.size", "Size"),
+    ("models.modified", "Modified"),
+    ("models.parameters", "Parameters"),
+    ("models.quantization", "Quantization"),
+    ("models.memory", "Memory"),
+    ("memory.fits", "Should fit comfortably"),
+    ("memory.tight", "Tight, may be slow or spill to system RAM"),
+    ("memory.too_large", "Likely won't fit"),
+    (
+        "memory.reason",
+        "model {} + ~{} context cache ({} tokens) ≈ {}, system RAM {}",
+    ),
+    ("memory.reason_gpu", ", GPU memory {}"),
+    (
+        "memory.estimate_note",
+        "This is a rough estimate, other apps and the model architecture change the real usage.",
+    ),
+    ("models.default", "★ Default model"),
+    ("models.unset", "Unset"),
+    ("models.unset_help", "Pick a model automatically on startup"),
+    ("models.use_default", "☆ Use as default"),
+    (
+        "models.use_default_help",
+        "Select this model on startup whenever it's installed",
+    ),
+    ("models.running", "Running"),
+    ("models.installed", "Installed"),
+    ("models.template_preview", "Template preview"),
+    (
+        "models.template_preview_help",
+        "What the model receives for a sample conversation of a system prompt, a user \
+        message and a reply. Parts of the template the preview doesn't understand are shown \
+        as written.",
+    ),
+    ("models.template_preview_empty", "The model has no template"),
+    ("model_table.name", "Name"),
+    ("model_table.family", "Family"),
+    ("model_table.filter", "Filter by name or family"),
+    ("model_table.select_all", "Select all shown models"),
+    (
+        "model_table.select_help",
+        "Use this model in the current chat",
+    ),
+    ("model_table.count", "{} models"),
+    ("model_table.delete", "🗑 Delete {} selected"),
+    ("model_table.clear", "Clear selection"),
+    (
+        "model_table.confirm_delete",
+        "Delete {} models from the server? This can't be undone.",
+    ),
+    ("model_table.deleting", "Deleting models…"),
+    (
+        "models.running_unsupported",
+        "Not supported by the server, update Ollama to see loaded models.",
+    ),
+    ("models.none_loaded", "No models are loaded."),
+    ("models.vram_help", "Memory used in VRAM / total"),
+    ("models.unloads", "Unloads {}"),
+    ("models.unload", "Unload"),
+    ("models.unload_help", "Free the memory used by this model"),
+    ("models.name_empty", "Name is empty"),
+    ("models.name_spaces", "Name must not contain spaces"),
+    ("models.name_format", "Name must look like `model` or `model:tag`"),
+    ("models.name_chars", "Name may only contain letters, digits and `._-/`"),
+    ("models.pulling", "Pulling {}: {}"),
+    ("models.pull", "⬇ Pull"),
+    ("models.pull_help", "Download a model from the Ollama library"),
+    ("models.pull_failed", "Failed to pull {}: {}"),
+    ("models.license", "License"),
+    ("models.modelfile", "Modelfile"),
+    ("models.template", "Template"),
+    (
+        "models.template_help",
+        "Prompt template to be passed into the model. It may include (optionally) a system message, a user's message and the response from the model. Note: syntax may be model specific. Templates use Go ",
+    ),
+    ("models.template_syntax", "template syntax"),
+    (
+        "models.template_help_end",
+        ". This overrides what is defined in the Modelfile. The default template is shown in the Template header.",
+    ),
+    ("models.variable", "Variable"),
+    ("models.description", "Description"),
+    ("models.var_system", "The system message used to specify custom behavior."),
+    ("models.var_prompt", "The user prompt message."),
+    (
+        "models.var_response",
+        "The response from the model. When generating a response, text after this variable is omitted.",
+    ),
+    ("models.docs", "Ollama Documentation"),
+    ("models.template_override", "Override (overrides the template set in the Modelfile)"),
+    ("models.modelfile_template", "Modelfile template:"),
+    ("models.refresh_info", "⟳ Refresh info"),
+    ("models.refresh_info_help", "Fetch the license, Modelfile and template of the model again"),
+    ("models.loading_info", "Loading model info…"),
+    ("models.duplicate", "Duplicate…"),
+    ("models.duplicate_help", "Copy the selected model under a new name"),
+    ("models.copy", "Copy"),
+    ("models.overwritten", "{} already exists and will be overwritten"),
+    ("models.create", "Create model"),
+    ("models.create_help", "Create a new model from this Modelfile"),
+    ("models.reset_modelfile", "Reset to the model's Modelfile"),
+    ("models.creating", "Creating {}: {}"),
+    ("models.starting", "starting"),
+    ("models.create_failed", "Failed to create {}: {}"),
+    ("models.created", "Created {}"),
+    ("models.build_log", "Build log ({})"),
+    ("models.expires_never", "never"),
+    ("models.expires_now", "now"),
+    ("models.expires_hours", "in {}h {}m"),
+    ("models.expires_minutes", "in {}m {}s"),
+    // sidebar
+    ("sidebar.chats", "Chats"),
+    ("sidebar.model", "Model"),
+    ("sidebar.settings", "Settings"),
+    ("sidebar.logs", "Logs ({})"),
+    ("settings.logging", "Logging"),
+    ("settings.log_file", "Write a log file"),
+    (
+        "settings.log_file_help",
+        "Keep a log for bug reports. The file is rotated at 5 MiB, the last 3 files are kept",
+    ),
+    ("settings.log_level", "Level"),
+    ("settings.log_path", "File"),
+    ("settings.open_log_folder", "🗁 Open log folder"),
+    ("settings.from_environment", "{} (from environment)"),
+    ("settings.proxy_error", "Proxy error (via {}): {}"),
+    ("settings.speech_unsupported", "{} can't be changed on this platform"),
+    ("settings.speech_reset", "Reset to the normal value"),
+    (
+        "settings.startup_model_help",
+        "Selected on startup whenever it's installed, set it with \"Use as default\" in a model picker",
+    ),
+    ("settings.startup_model", "Startup model"),
+    ("settings.picked_automatically", "picked automatically"),
+    (
+        "settings.prefer_chat_help",
+        "When picking automatically, prefer models tagged \"instruct\" or \"chat\" over larger ones",
+    ),
+    ("settings.prefer_chat", "Prefer chat models"),
+    (
+        "settings.size_limit_help",
+        "When picking automatically, skip models larger than this, e.g. the ones that don't fit in memory",
+    ),
+    ("settings.size_limit", "Size limit"),
+    ("settings.reset_title", "Reset Settings"),
+    (
+        "settings.reset_body",
+        "Are you sure you want to reset global settings? This action cannot be undone!",
+    ),
+    ("settings.speech", "Speech"),
+    ("settings.voice", "Voice"),
+    ("settings.default_voice", "Default"),
+    ("settings.rate", "Rate"),
+    ("settings.pitch", "Pitch"),
+    ("settings.volume", "Volume"),
+    ("settings.base_url", "Base URL"),
+    ("settings.api_key", "API key"),
+    ("settings.apply_server_help", "Connect to this server and reload the model list"),
+    ("settings.voice_input", "Voice input"),
+    (
+        "settings.whisper_model_help",
+        "Path to a whisper.cpp model (ggml .bin) used to transcribe the microphone",
+    ),
+    ("settings.whisper_model", "Whisper model"),
+    ("settings.invalid_host", "invalid host"),
+    ("settings.host_empty", "host is empty"),
+    ("settings.port_range", "port must be a number between 0 and 65535"),
+    ("settings.invalid_port", "invalid port"),
+    (
+        "settings.golden_none",
+        "No golden prompts yet. Hold Shift over one of your messages to save it",
+    ),
+    ("settings.golden_none_match", "No golden prompts match"),
+    (
+        "settings.golden_help",
+        "{} and {} in a prompt are replaced by the text selected in the chat input and the text on the clipboard, the cursor is placed at the first of them.",
+    ),
+    ("settings.golden_new", "➕ New prompt"),
+    ("settings.golden_new_name", "New prompt"),
+    ("settings.golden_uses_one", "{} ({} use)"),
+    ("settings.golden_uses_many", "{} ({} uses)"),
+    ("settings.golden_tags_hint", "writing, review"),
+    ("settings.golden_used_with", "Used with"),
+    ("settings.golden_never_used", "Never used"),
+    ("logs.title", "Logs"),
+    ("logs.filter", "Filter"),
+    ("logs.copy_all", "📋 Copy all"),
+    ("logs.clear", "🗑 Clear"),
+    ("logs.empty", "Nothing logged yet"),
+    ("logs.no_matches", "No entries match the filters"),
+    ("logs.count", "{} of {} entries"),
+    ("sidebar.expand", "Expand sidebar ({})"),
+    ("sidebar.collapse", "Collapse sidebar ({})"),
+    ("sidebar.new_chat", "New chat ({})"),
+    ("sidebar.create_chat", "Create a new chat ({})"),
+    ("sidebar.search", "🔍 Search chats"),
+    ("sidebar.clear_search", "Clear search"),
+    (
+        "sidebar.bin_help",
+        "Restore or permanently delete removed chats",
+    ),
+    ("sidebar.order_manual", "Manual order"),
+    ("sidebar.order_recent", "Recent first"),
+    ("sidebar.back", "⬅ Back"),
+    ("sidebar.bin_empty", "No recently deleted chats"),
+    ("sidebar.delete_permanently", "Delete permanently"),
+    ("sidebar.restore", "Restore"),
+    ("sidebar.generating", "Generating…"),
+    ("sidebar.new_response", "New response"),
+    ("sidebar.remove_chat", "Remove chat"),
+    ("sidebar.duplicate", "Duplicate"),
+    ("sidebar.menu_edit", "⚙ Edit…"),
+    ("sidebar.menu_duplicate", "🗐 Duplicate"),
+    ("sidebar.menu_export", "💾 Export…"),
+    ("sidebar.chats_one", "{} chat"),
+    ("sidebar.chats_many", "{} chats"),
+    ("sidebar.messages_one", "{} message"),
+    ("sidebar.messages_many", "{} messages"),
+    ("sidebar.text_size", "{} of text"),
+    ("sidebar.editing_chat", "Editing Chat \"{}\""),
+    ("sidebar.export_label", "Export chat history to a file"),
+    ("sidebar.save_as", "Save As…"),
+    ("sidebar.include_notes", "Include notes"),
+    ("sidebar.new_chat_button", "➕ New Chat"),
+    ("sidebar.recently_deleted", "🗑 Recently deleted ({})"),
+    ("sidebar.empty_bin", "Empty bin"),
+    ("sidebar.kept_for", "Deleted chats are kept for {} days"),
+    ("sidebar.rename", "✏ Rename…"),
+    ("sidebar.export", "Export"),
+    ("sidebar.export_format", "Export Format"),
+    ("sidebar.no_clipboard_image", "There is no image on the clipboard"),
+    ("sidebar.image_unsupported", "Image attached, but {} may not support images"),
+    ("sidebar.paste_failed", "Failed to paste image: {}"),
+    ("sidebar.copied_one", "Copied {} message"),
+    ("sidebar.copied_many", "Copied {} messages"),
+    ("sidebar.undo_chat_gone", "The chat is no longer in Recently deleted"),
+    ("sidebar.undo_message_chat_gone", "The chat of the message was deleted"),
+    ("sidebar.undo_wait_for_response", "Wait for the response to finish to restore the message"),
+    ("sidebar.chat_deleted", "Chat deleted"),
+    ("sidebar.message_deleted", "Message deleted"),
+    ("sidebar.undo", "Undo"),
+    ("sidebar.undo_help", "Undo ({})"),
+    ("sidebar.pinned", "📌 Pinned ({})"),
+    ("sidebar.archived", "🗄 Archived ({})"),
+    ("sidebar.uses_shared_settings", "This chat uses the shared inference settings from the global Settings."),
+    ("sidebar.override_settings", "Override for this chat"),
+    ("sidebar.override_settings_help", "Start from the shared settings and edit them for this chat only"),
+    ("sidebar.all_inherited", "All values are inherited from the shared settings."),
+    ("sidebar.overridden", "Overridden: {}"),
+    ("sidebar.use_shared_settings", "Use shared settings"),
+    ("sidebar.use_shared_settings_help", "Remove the override and use the shared settings again"),
+    ("sidebar.request_timeout", "The server didn't respond within {}s (request timed out)"),
+    ("sidebar.image_filter", "Image"),
+    ("sidebar.json_filter", "JSON file"),
+    ("sidebar.no_file_selected", "No file selected"),
+    ("sidebar.backup_saved", "Backup saved"),
+    ("sidebar.unload_failed", "Failed to unload {}: {}"),
+    ("sidebar.dropping_files", "Dropping files:"),
+    ("sidebar.presets_one", "{} preset"),
+    ("sidebar.presets_many", "{} presets"),
+    ("sidebar.model_created", "Created model {}"),
+    ("sidebar.restored_one", "Restored {} chat"),
+    ("sidebar.restored_many", "Restored {} chats"),
+    ("sidebar.merged", "Added {} and {}"),
+    ("sidebar.restore_title", "Restore Backup"),
+    ("sidebar.restore_body", "{} — merge them with the current ones or replace everything? Replacing discards all current chats and settings."),
+    ("sidebar.replace", "Replace"),
+    ("sidebar.merge", "Merge"),
+    ("sidebar.title_generating", "(generating…)"),
+    ("sidebar.model_pulled", "Pulled model {}"),
+    ("sidebar.pull_cancelled", "Cancelled pulling {}"),
+    ("sidebar.remove_title", "Remove Chat"),
+    ("sidebar.remove_body", "Do you really want to remove this chat? It will be kept in Recently deleted for {} days.\nHold Shift to surpass this warning."),
+    ("sidebar.remove_chat_help", "Remove chat \"{}\""),
+    ("sidebar.knowledge", "📚 Knowledge"),
+    ("sidebar.model_for", "Model for \"{}\""),
+    ("sidebar.stop_speaking", "Stop speaking"),
+    ("sidebar.reading", "Reading \"{}\", message {}"),
+    ("sidebar.go_to_chat", "Go to chat"),
+    ("sidebar.speaking", "Speaking"),
+    ("sidebar.connecting", "Connecting to {}…"),
+    ("sidebar.connected", "Connected to {}"),
+    ("sidebar.connection_failed", "Failed to connect to {}: {}\n\nRetrying in {}s"),
+    ("sidebar.unauthorized", "Authentication failed at {}: {}\n\nCheck the credentials in the connection settings"),
+    ("sidebar.retry_now", "Retry now"),
+    ("sidebar.model_not_installed", "Model {} is not installed"),
+    ("sidebar.model_deleted", "Deleted model {}"),
+    ("sidebar.models_deleted", "Deleted {} models"),
+    ("sidebar.delete_models_failed", "Failed to delete models"),
+    ("sidebar.request_failed", "Ollama request failed"),
+    ("sidebar.delete_permanently_title", "Delete Permanently"),
+    ("sidebar.delete_all_permanently_body", "Do you really want to permanently delete all chats in the bin? You cannot undo this action later."),
+    ("sidebar.delete_permanently_body", "Do you really want to permanently delete this chat? You cannot undo this action later."),
+    ("sidebar.deleted_info", "Deleted {} • {} • {} messages"),
+    ("sidebar.no_recent_messages", "No recent messages"),
+    ("sidebar.unarchive_help", "Restore to the chat list"),
+    ("sidebar.archive_help", "Archive"),
+    ("sidebar.unpin_help", "Unpin"),
+    ("sidebar.pin_help", "Pin to the top"),
+    ("sidebar.no_chats_found", "No chats found"),
+    ("sidebar.sort", "Sort:"),
+    ("sidebar.all_tags", "All tags"),
+    ("sidebar.tag_filter_help", "Only show chats with all of the selected tags"),
+    ("sidebar.unpin", "📌 Unpin"),
+    ("sidebar.pin", "📌 Pin"),
+    ("sidebar.unarchive", "📤 Unarchive"),
+    ("sidebar.archive", "🗄 Archive"),
+    ("sidebar.delete_chat", "❌ Delete…"),
+    ("sidebar.add_tag", "🏷 Add tag…"),
+    ("sidebar.new_tag", "New tag"),
+    ("sidebar.add", "Add"),
+    ("sidebar.remove_tag", "✖ Remove {}"),
+    ("sidebar.request_panicked", "Ollama request task panicked"),
+    ("sidebar.backup_read_failed", "failed to read `{}`"),
+    ("sidebar.backup_not_json", "the file is not valid JSON"),
+    ("sidebar.backup_not_ellama", "the file is not an Ellama backup"),
+    (
+        "sidebar.backup_newer",
+        "the backup was made by a newer version of Ellama (format {}), update Ellama to restore it",
+    ),
+    ("sidebar.backup_damaged", "the backup is damaged"),
+    ("sidebar.attach_too_large", "`{}` is too large to attach ({}, the limit is {})"),
+    ("sidebar.attach_binary", "`{}` looks like a binary file and can't be attached"),
+    ("sidebar.attach_not_utf8", "`{}` is not a UTF-8 text file"),
+    ("sidebar.export_filter", "{} file"),
+    // chat view
+    ("chat.new_chat", "New Chat"),
+    ("chat.ask", "Ask me anything…"),
+    ("chat.find", "Find in chat"),
+    ("chat.no_model", "No model selected"),
+    ("chat.model_help", "Model answering in this chat"),
+    ("chat.info", "Chat info"),
+    ("chat.stats", "Chat statistics"),
+    ("chat.options", "Chat options"),
+    ("chat.note", "📝 Note"),
+    ("chat.note_hint", "Not sent to the model…"),
+    ("chat.remove_note", "🗑 Remove note"),
+    ("chat.view_request", "🔍 View request"),
+    (
+        "chat.view_request_unavailable",
+        "Only replies generated since the app was started can be inspected",
+    ),
+    ("chat.request_title", "Request for {}"),
+    ("chat.copy_json", "📋 Copy as JSON"),
+    ("chat.add_note_help", "Right-click to add a note"),
+    ("chat.retry", "🔄 Retry"),
+    ("chat.retry_help", "Send the same request again"),
+    ("chat.copy_error", "🗐 Copy error"),
+    ("chat.copy_error_help", "Copy the error for a bug report"),
+    ("chat.regenerate", "🔄 Regenerate"),
+    ("chat.delete_message", "Delete message"),
+    ("chat.jump_to_bottom", "Jump to the bottom"),
+    ("chat.rating_good", "Good reply"),
+    ("chat.rating_bad", "Bad reply"),
+    ("chat.time_to_first_token", "Time to first token: {}s"),
+    ("chat.speed", "Speed: {} tok/s"),
+    ("chat.total_time", "Total time: {}s"),
+    ("chat.click_for_info", "Click to show chat info"),
+    ("chat.show_info", "Show chat info"),
+    ("chat.context_starts_summarized", "— earlier messages summarized, context starts here —"),
+    ("chat.context_starts", "— context starts here —"),
+    ("chat.context_summary", "Sent instead of the messages above:\n\n{}"),
+    ("chat.context_not_sent", "The messages above weren't sent with the last request"),
+    ("chat.sources", "Knowledge excerpts ({})"),
+    ("chat.similarity", "Similarity to the question"),
+    ("chat.footer", "prompt: {} tok, response: {} tok"),
+    ("chat.footer_speed", ", {} tok/s"),
+    ("chat.no_matches", "No matches"),
+    ("chat.previous_match", "Previous match (Shift+Enter)"),
+    ("chat.next_match", "Next match (Enter)"),
+    ("chat.close_find", "Close (Esc)"),
+    ("chat.valid_json", "✔ Valid JSON"),
+    ("chat.invalid_json", "The reply is not valid JSON"),
+    ("chat.code_block", "Block {}"),
+    ("chat.code_language_help", "Language of this untagged code block, used for highlighting"),
+    ("chat.sources_help", "Knowledge excerpts added to the prompt, right-click to view"),
+    ("chat.waiting", "Waiting for model… {} s"),
+    ("chat.wait_for_response", "Wait for the response to finish"),
+    ("chat.prepend_hint", "Prepend text to response…"),
+    ("chat.regenerate_prepended_help", "Generate the response again, the LLM will start after any prepended text"),
+    ("chat.edit_message", "✏ Edit"),
+    ("chat.edit_message_help", "Edit the message in the context, but don't regenerate it"),
+    ("chat.preamble", "Preamble"),
+    ("chat.generating", "Generating… {} s"),
+    ("chat.note_help", "Your note, not sent to the model. Right-click the name above to edit"),
+    ("chat.interrupted", "⚠ Interrupted"),
+    ("chat.interrupted_help", "The app was closed before the reply was complete"),
+    ("chat.continue", "⏩ Continue"),
+    ("chat.continue_help", "Let the model continue the reply from where it stops"),
+    ("chat.copied", "Copied!"),
+    ("chat.copy_code", "Copy only the code block"),
+    ("chat.copy_message", "Copy message"),
+    ("chat.copy_code_button", "🗐 Code"),
+    ("chat.show_rendered", "Show rendered"),
+    ("chat.view_source", "View source"),
+    ("chat.save_golden", "Save as golden prompt"),
+    ("chat.stop_reading", "Stop reading. Right click to start over"),
+    ("chat.read_aloud", "Read the message out loud"),
+    ("chat.branch_help", "Branch from here: continue in a new chat ending at this message"),
+    ("chat.regenerate_help", "Regenerate"),
+    ("chat.regenerate_with", "Regenerate with"),
+    ("chat.regenerate_with_help", "Regenerate with another model"),
+    ("chat.strategy_send_all", "Send everything"),
+    ("chat.strategy_sliding_window", "Latest messages only"),
+    ("chat.strategy_summarize", "Summarize overflow"),
+    ("chat.strategy_send_all_help", "Send the whole conversation. Once it exceeds the context window the model forgets its start, system prompt included"),
+    ("chat.strategy_sliding_window_help", "Send the system prompt and the latest messages"),
+    ("chat.strategy_summarize_help", "Replace the oldest messages with a summary written by the model when the conversation gets close to the context window"),
+    ("chat.remove_attachment", "Remove attachment"),
+    ("chat.knowledge_failed", "Searching the knowledge failed: {}"),
+    ("chat.stalled", "The reply stalled for more than {}s and was cut off"),
+    ("chat.first_token_timeout", "The model didn't start replying within {}s (request timed out)"),
+    ("chat.lost_while_asleep", "The connection was lost while the system was asleep"),
+    ("chat.format_report", "Report (Markdown)"),
+    ("chat.format_html", "Web Page (HTML)"),
+    ("chat.export_cancelled", "Export cancelled"),
+    ("chat.exported", "Exported {} messages to {}"),
+    ("chat.stats_messages", "Messages"),
+    ("chat.stats_message_counts", "{} from you, {} replies"),
+    ("chat.stats_errors", ", {} errors"),
+    ("chat.stats_text", "Text"),
+    ("chat.stats_text_counts", "{} words, {} characters"),
+    ("chat.stats_tokens", "Tokens"),
+    ("chat.stats_token_counts", "{} prompt, {} generated"),
+    ("chat.stats_tokens_help", "Summed over all replies, each prompt includes the history"),
+    ("chat.stats_generation_time", "Generation time"),
+    ("chat.seconds", "{}s"),
+    ("chat.stats_avg_speed", "Avg. speed"),
+    ("chat.tokens_per_second", "{} tok/s"),
+    ("chat.stats_models", "Models"),
+    ("chat.stats_model_replies_one", "{}: {} reply"),
+    ("chat.stats_model_replies_many", "{}: {} replies"),
+    ("chat.info_context_window", "Context window"),
+    ("chat.token_count", "{} tokens"),
+    ("chat.info_last_prompt", "Last prompt"),
+    ("chat.info_generated", "Generated"),
+    ("chat.info_generated_tokens", "{} tokens in {} replies"),
+    ("chat.info_avg_ttft", "Avg. time to first token"),
+    ("chat.info_avg_speed", "{} tok/s over {} replies"),
+    ("chat.context_used", "Context used: {} of {} tokens ({}%)"),
+    ("chat.save_golden_title", "Save Golden Prompt"),
+    ("chat.golden_name", "Name"),
+    ("chat.golden_tags", "Tags"),
+    ("chat.golden_tags_hint", "Comma separated, e.g. writing, review"),
+    ("chat.golden_prompts", "Golden prompts:"),
+    ("chat.overflow", "⚠ ~{} tokens, context is {}; older messages will be dropped"),
+    ("chat.context_strategy", "Context strategy…"),
+    ("chat.context_strategy_help", "Choose how the conversation is fit into the context"),
+    ("chat.attach_images", "Attach images"),
+    ("chat.paste_image", "Paste an image from the clipboard"),
+    ("chat.insert_golden", "Insert a golden prompt, or type / in the chatbox"),
+    ("chat.send_help", "Send ({}, {} for a new line)"),
+    ("chat.transcribing", "Transcribing…"),
+    ("chat.voice_needs_model", "Set a whisper model in the settings to use voice input"),
+    ("chat.recording", "Recording…"),
+    ("chat.transcription_failed", "Transcription failed: {}"),
+    ("chat.stop_recording", "Stop recording and transcribe"),
+    ("chat.record", "Record voice input"),
+    ("chat.mic_unavailable", "Microphone unavailable: {}"),
+    ("chat.compare_model_help", "Model answering each prompt next to it"),
+    ("chat.versus", "vs"),
+    ("chat.compare_with", "Compare with"),
+    ("chat.stop_comparing", "Stop comparing"),
+    ("chat.compare_help", "Compare mode: a second model answers each prompt too"),
+    ("chat.completion_mode", "Completion mode"),
+    ("chat.completion_mode_help", "Send the conversation as one text for the model to continue, without a chat template or system prompt. For base models"),
+    ("chat.copy_code_only", "Copy code only from preamble + code replies"),
+    ("chat.collapse_preamble", "Collapse preambles like \"Sure! Here's…\""),
+    ("chat.render_math", "Render LaTeX math"),
+    ("chat.render_math_help", "Show $…$ and $$…$$ formulas as text with math symbols, or as code when they are too complex"),
+    ("chat.auto_speak", "Auto-speak responses"),
+    ("chat.tts_unavailable", "Text-to-speech failed to initialize"),
+    ("chat.copy_conversation", "📋 Copy conversation"),
+    ("chat.copy_conversation_help", "Copy the conversation as Markdown, like the report export"),
+    ("chat.copy_system_prompt", "Include the system prompt when copying"),
+    ("chat.clear", "🗑 Clear conversation"),
+    ("chat.clear_help", "Remove all messages, keeping the system prompt, model and settings"),
+    ("chat.no_messages", "There are no messages yet"),
+    ("chat.system_prompt", "System prompt"),
+    ("chat.system_prompt_hint", "Standing instructions for the model, e.g. \"Answer in Spanish\"…"),
+    ("chat.context", "Context"),
+    ("chat.window_prefix", "Last "),
+    ("chat.window_suffix", " messages"),
+    ("chat.threshold", "of the context window"),
+    ("chat.threshold_help", "Summarize when the conversation takes more than this of the {} token context window"),
+    ("chat.forget_summary", "Forget summary"),
+    ("chat.forget_summary_help", "Send the summarized messages as they are again"),
+    ("chat.knowledge_help", "Excerpts of these documents closest to each question are added to the prompt."),
+    ("chat.remove_source", "Remove"),
+    ("chat.add_files", "➕ Files…"),
+    ("chat.add_folder", "➕ Folder…"),
+    ("chat.add_folder_help", "Text files in the folder and its subfolders are indexed"),
+    ("chat.embedding_model", "Embedding model"),
+    ("chat.select", "Select…"),
+    ("chat.no_embedding_model", "Pull an embedding model like nomic-embed-text to index with"),
+    ("chat.excerpts_per_question", "Excerpts per question"),
+    ("chat.embedding_progress", "Embedding chunks {}/{}"),
+    ("chat.reading_files", "Reading files…"),
+    ("chat.indexed", "{} chunks indexed with `{}`"),
+    ("chat.index_stale", "The sources or the model changed, index again to use them"),
+    ("chat.index_again", "⟳ Index again"),
+    ("chat.index", "Index"),
+    ("chat.index_disabled", "Add sources and select an embedding model first"),
+    ("chat.clear_index", "Clear index"),
+    ("chat.clear_index_help", "Stop adding excerpts to prompts"),
+    ("chat.switch_mode_title", "Switch Mode"),
+    ("chat.switch_to_completion", "In completion mode the messages are sent as one text for the model to continue, without the chat template, system prompt, summaries or knowledge. Replies to the existing messages may read differently."),
+    ("chat.switch_to_chat", "In chat mode the messages are sent as a conversation through the model's chat template. Text written as one document may not read like a conversation to the model."),
+    ("chat.switch", "Switch"),
+    ("chat.clear_title", "Clear Conversation"),
+    ("chat.clear_body", "Remove all {} messages of this chat? The system prompt, model, settings and knowledge are kept."),
+    ("chat.clear_button", "Clear"),
+    ("chat.branch_of", "Branch of {}"),
+    ("chat.suggest_fact", "Tell me a fun fact"),
+    ("chat.suggest_fact_about", "about the Roman empire"),
+    ("chat.suggest_code", "Show me a code snippet"),
+    ("chat.suggest_code_about", "of a web server in Rust"),
+    ("chat.suggest_joke", "Tell me a joke"),
+    ("chat.suggest_joke_about", "about crabs"),
+    ("chat.suggest_ideas", "Give me ideas"),
+    ("chat.suggest_ideas_about", "for a birthday present"),
+    ("chat.promote", "⬆ Continue with {}"),
+    ("chat.promote_help", "Stop comparing and continue the conversation with only this model"),
+    ("chat.new_messages", "⬇ New messages"),
+    (
+        "chat.explain_auth",
+        "Authentication failed. Check the token or username and password in the connection settings",
+    ),
+    (
+        "chat.explain_timeout",
+        "The request timed out. The server may still be loading the model, try again",
+    ),
+    (
+        "chat.explain_refused",
+        "Couldn't connect to the Ollama server. Make sure it's running at the configured address",
+    ),
+    (
+        "chat.explain_model_missing",
+        "The model isn't available on the server. Pull it or pick another model",
+    ),
+    (
+        "chat.explain_connection_lost",
+        "The connection to the server was lost while the reply was streaming",
+    ),
+    ("chat.explain_proxy", "The request failed at the proxy"),
+    ("chat.explain_failed", "The request failed"),
+    ("chat.exported_on", "Exported from Ellama on {}"),
+    ("chat.report_model", "Model"),
+    ("chat.report_settings", "Settings"),
+    ("chat.report_setting", "Setting"),
+    ("chat.report_value", "Value"),
+    ("chat.report_template", "Template"),
+    ("chat.report_overview", "Overview"),
+    ("chat.report_role", "Role"),
+    ("chat.report_time", "Time"),
+    ("chat.report_characters", "Characters"),
+    ("chat.report_images", "Images"),
+    ("chat.report_stats", "Stats"),
+    ("chat.report_prompt_tokens", "Prompt tokens"),
+    ("chat.report_reply_tokens", "Reply tokens"),
+    ("chat.report_first_token", "Time to first token"),
+    ("chat.report_speed", "Speed"),
+    ("chat.report_rating", "Rating"),
+    ("chat.report_conversation", "Conversation"),
+    ("chat.report_reply_settings", "Settings: {}"),
+    ("chat.report_image", "Image: `{}`"),
+    ("chat.report_note", "Note:"),
+    ("chat.report_default", "{} default"),
+    ("chat.missing_image", "Missing image: {}"),
+    (
+        "chat.not_multimodal",
+        "{} doesn't look like a multimodal model, it may fail to process images",
+    ),
+    ("chat.indexing_panicked", "Indexing panicked: {}"),
+    ("chat.knowledge_no_text", "No text found in the sources"),
+    ("chat.knowledge_read_failed", "Reading the sources failed: {}"),
+    ("chat.knowledge_empty_embedding", "`{}` returned an empty embedding"),
+    ("chat.knowledge_embed_failed", "Embedding with `{}` failed: {}"),
+    ("chat.voice_no_microphone", "no microphone found"),
+    ("chat.voice_microphone_config", "failed to query the microphone configuration"),
+    ("chat.voice_whisper_path", "whisper model path is not valid UTF-8"),
+    ("chat.voice_whisper_load", "failed to load the whisper model"),
+    ("chat.voice_whisper_state", "failed to create whisper state"),
+    ("chat.voice_transcribe", "failed to transcribe the recording"),
+    ("chat.voice_sample_format", "unsupported sample format {}"),
+    ("chat.voice_open", "failed to open the microphone"),
+    ("chat.voice_record", "failed to start recording"),
+    // inference settings
+    ("inference.quick_temperature_help", "Temperature, higher values make the model answer more creatively"),
+    ("inference.seed", "Seed"),
+    ("inference.quick_seed_help", "The same seed makes the model generate the same text for the same prompt"),
+    ("inference.random_seed", "Random seed"),
+    ("inference.enable", "Enable"),
+    ("inference.json_mode", "JSON Mode"),
+    ("inference.json_mode_help", "Force the model to reply with valid JSON. Replies are shown formatted, ask for JSON in the prompt too or the model may ramble on with whitespace."),
+    ("inference.suggest_stops", "Suggest from model template"),
+    ("inference.suggest_stops_help", "Add {}"),
+    ("inference.template_not_loaded", "The model's template isn't loaded"),
+    ("inference.no_turn_markers", "No known turn markers in the model's template"),
+    ("inference.stops_added", "All the suggested stop sequences are added"),
+    ("inference.disabled", "Disabled"),
+    ("inference.keep_alive_default", "5m (default)"),
+    ("inference.keep_alive_forever", "Forever (-1)"),
+    ("inference.keep_alive_unload", "Unload immediately (0)"),
+    ("inference.keep_alive_30m", "30m"),
+    ("inference.warn_mirostat", "Mirostat eta and tau have no effect while Mirostat is disabled"),
+    ("inference.warn_top_p", "Top-P of 0 leaves no tokens to sample from"),
+    ("inference.warn_repeat_last_n", "Repeat Last N ({}) is larger than the context window ({})"),
+    ("inference.warn_empty_stop", "Empty stop sequences are ignored"),
+    ("inference.warn_keep_alive", "Keep Alive is not a valid duration and is ignored"),
+    ("inference.header", "Inference Settings"),
+    ("inference.mirostat_eta", "Mirostat eta"),
+    ("inference.mirostat_tau", "Mirostat tau"),
+    ("inference.num_ctx", "Context Window"),
+    ("inference.num_gqa", "Number of GQA Groups"),
+    ("inference.num_gpu", "GPU Layers"),
+    ("inference.num_thread", "Number of Threads"),
+    ("inference.repeat_last_n", "Repeat Last N"),
+    ("inference.repeat_penalty", "Repeat Penalty"),
+    ("inference.temperature", "Temperature"),
+    ("inference.stop", "Stop Sequence"),
+    ("inference.tfs_z", "Tail-Free Sampling Z"),
+    ("inference.num_predict", "Number to Predict"),
+    ("inference.top_k", "Top-K"),
+    ("inference.top_p", "Top-P"),
+    ("inference.min_p", "Min-P"),
+    ("inference.typical_p", "Typical-P"),
+    ("inference.presence_penalty", "Presence Penalty"),
+    ("inference.frequency_penalty", "Frequency Penalty"),
+    ("inference.num_batch", "Batch Size"),
+    ("inference.penalize_newline", "Penalize Newline"),
+    ("inference.keep_alive", "Keep Alive"),
+    ("inference.on", "on"),
+    (
+        "inference.keep_alive_help",
+        "How long the model stays loaded in memory after a request. Keeping it loaded makes the first reply after a pause faster.",
+    ),
+    ("inference.custom", "Custom"),
+    ("inference.keep_alive_hint", "e.g. 90s, 2h, -1"),
+    ("inference.keep_alive_invalid", "Expected a number of seconds or a duration like 10m or 1h"),
+    ("inference.max", "max"),
+    ("inference.max_help", "Set maximum value"),
+    ("inference.min", "min"),
+    ("inference.min_help", "Set minimum value"),
+    ("inference.rand", "rand"),
+    ("inference.rand_help", "Set random value"),
+    ("inference.reset", "reset"),
+    ("inference.reset_help", "Set default value"),
+    ("inference.ollama_only", "Not supported by OpenAI-compatible servers"),
+    ("inference.preset", "Preset"),
+    ("inference.no_presets", "No presets saved yet"),
+    ("inference.save_as", "Save as…"),
+    ("inference.preset_name", "Preset name"),
+    ("inference.overwrite", "Overwrite"),
+    ("inference.manage_presets", "Manage Presets"),
+    ("inference.rename", "Rename"),
+    ("inference.delete_preset", "Delete preset"),
+    ("inference.reset_settings", "Reset Settings"),
+    ("inference.mirostat_help", "Enable Mirostat sampling for controlling perplexity."),
+    (
+        "inference.mirostat_eta_help",
+        "Influences how quickly the algorithm responds to feedback from the generated text. A lower learning rate will result in slower adjustments, while a higher learning rate will make the algorithm more responsive.",
+    ),
+    (
+        "inference.mirostat_tau_help",
+        "Controls the balance between coherence and diversity of the output. A lower value will result in more focused and coherent text.",
+    ),
+    (
+        "inference.num_ctx_help",
+        "Sets the size of the context window used to generate the next token.",
+    ),
+    (
+        "inference.num_gqa_help",
+        "The number of GQA groups in the transformer layer. Required for some models, for example it is 8 for llama2:70b.",
+    ),
+    (
+        "inference.num_gpu_help",
+        "The number of layers to send to the GPU(s). On macOS it defaults to 1 to enable metal support, 0 to disable.",
+    ),
+    (
+        "inference.num_thread_help",
+        "Sets the number of threads to use during computation. By default, Ollama will detect this for optimal performance. It is recommended to set this value to the number of physical CPU cores your system has (as opposed to the logical number of cores).",
+    ),
+    (
+        "inference.repeat_last_n_help",
+        "Sets how far back for the model to look back to prevent repetition.",
+    ),
+    (
+        "inference.repeat_penalty_help",
+        "Sets how strongly to penalize repetitions. A higher value (e.g., 1.5) will penalize repetitions more strongly, while a lower value (e.g., 0.9) will be more lenient.",
+    ),
+    (
+        "inference.temperature_help",
+        "The temperature of the model. Increasing the temperature will make the model answer more creatively.",
+    ),
+    (
+        "inference.seed_help",
+        "Sets the random number seed to use for generation. Setting this to a specific number will make the model generate the same text for the same prompt.",
+    ),
+    (
+        "inference.stop_help",
+        "Sets the stop sequences to use. When this pattern is encountered the LLM will stop generating text and return.",
+    ),
+    (
+        "inference.tfs_z_help",
+        "Tail free sampling is used to reduce the impact of less probable tokens from the output. A higher value (e.g., 2.0) will reduce the impact more, while a value of 1.0 disables this setting.",
+    ),
+    (
+        "inference.num_predict_help",
+        "Maximum number of tokens to predict when generating text. (Default: 128, -1 = infinite generation, -2 = fill context)",
+    ),
+    (
+        "inference.top_k_help",
+        "Reduces the probability of generating nonsense. A higher value (e.g. 100) will give more diverse answers, while a lower value (e.g. 10) will be more conservative.",
+    ),
+    (
+        "inference.top_p_help",
+        "Works together with top-k. A higher value (e.g., 0.95) will lead to more diverse text, while a lower value (e.g., 0.5) will generate more focused and conservative text.",
+    ),
+    (
+        "inference.min_p_help",
+        "Alternative to the top_p, and aims to ensure a balance of quality and variety. The parameter p represents the minimum probability for a token to be considered, relative to the probability of the most likely token. For example, with p=0.05 and the most likely token having a probability of 0.9, logits with a value less than 0.045 are filtered out.",
+    ),
+    (
+        "inference.typical_p_help",
+        "Enables locally typical sampling with parameter p, which keeps the tokens whose probability is close to the expected information content. A value of 1.0 disables this setting.",
+    ),
+    (
+        "inference.presence_penalty_help",
+        "Penalizes tokens that have already appeared in the text, encouraging the model to talk about new topics.",
+    ),
+    (
+        "inference.frequency_penalty_help",
+        "Penalizes tokens proportionally to how often they have appeared in the text so far, reducing verbatim repetition.",
+    ),
+    (
+        "inference.num_batch_help",
+        "Sets the number of prompt tokens processed in parallel. Larger values speed up prompt processing at the cost of memory.",
+    ),
+    (
+        "inference.penalize_newline_help",
+        "Whether the repetition penalties also apply to newline tokens.",
+    ),
+    ("inference.no_stops", "No stop sequences set, add one."),
+    ("inference.add", "➕ Add"),
+    ("inference.clear", "Clear"),
+];
+
+const GERMAN: &[(&str, &str)] = &[
+    // shared
+    ("common.apply", "Übernehmen"),
+    ("common.cancel", "❌ Abbrechen"),
+    ("common.close", "Schließen"),
+    ("common.delete", "🗑 Löschen"),
+    ("common.edit", "Bearbeiten"),
+    ("common.load", "Laden"),
+    ("common.loading", "Wird geladen…"),
+    ("common.optional", "Optional"),
+    ("common.reset", "Zurücksetzen"),
+    ("common.reset_to_default", "Auf Standard zurücksetzen"),
+    ("common.save", "Speichern"),
+    ("common.unknown", "unbekannt"),
+    ("common.no", "Nein"),
+    ("common.yes", "Ja"),
+    ("common.you", "Du"),
+    ("common.task_panicked", "Aufgabe abgestürzt: {}"),
+    // settings
+    ("settings.server", "Server"),
+    ("settings.connection", "Verbindungseinstellungen"),
+    ("settings.backend", "Backend"),
+    (
+        "settings.backend_help",
+        "API für Chats und die Modellliste. Wissens-Embeddings und die Modellverwaltung \
+        laufen immer über den Ollama-Server.",
+    ),
+    ("settings.host", "Ollama-Host"),
+    ("settings.port", "Port"),
+    (
+        "settings.apply_endpoint_help",
+        "Mit diesem Server verbinden und die Modellliste neu laden",
+    ),
+    ("settings.connected_to", "Derzeit verbunden mit {}"),
+    ("settings.proxy", "Proxy"),
+    ("settings.manual_proxy", "Manueller Proxy"),
+    (
+        "settings.manual_proxy_help",
+        "Wenn deaktiviert, werden die Umgebungsvariablen HTTP_PROXY, HTTPS_PROXY, ALL_PROXY \
+        und NO_PROXY verwendet",
+    ),
+    ("settings.auth", "Authentifizierung"),
+    ("settings.auth_token", "Token"),
+    (
+        "settings.auth_apply_help",
+        "Diese Zugangsdaten für jede Anfrage an den Ollama-Server verwenden und neu verbinden",
+    ),
+    (
+        "settings.auth_in_keyring",
+        "Im Schlüsselbund des Systems gespeichert",
+    ),
+    (
+        "settings.auth_in_settings",
+        "Kein Schlüsselbund verfügbar, mit den Einstellungen gespeichert",
+    ),
+    ("settings.connect_timeout", "Verbindungs-Timeout"),
+    (
+        "settings.connect_timeout_help",
+        "Wie lange auf eine Verbindung zum Server gewartet wird",
+    ),
+    (
+        "settings.first_token_timeout",
+        "Timeout bis zum ersten Token",
+    ),
+    (
+        "settings.first_token_timeout_help",
+        "Wie lange auf den Beginn einer Antwort, die Modellliste oder Modellinfos gewartet \
+        wird. Das Laden eines großen Modells kann dauern",
+    ),
+    ("settings.stall_timeout", "Stillstands-Timeout"),
+    (
+        "settings.stall_timeout_help",
+        "Wie lange eine Antwort stocken darf, bevor sie abgebrochen und als unterbrochen \
+        markiert wird",
+    ),
+    ("settings.retry_stalled", "Stockende Antworten wiederholen"),
+    (
+        "settings.retry_stalled_help",
+        "Die Anfrage einmal erneut senden, wenn eine Antwort stockt, und den bisherigen Text \
+        ersetzen",
+    ),
+    ("auth.none", "Keine"),
+    ("auth.bearer", "Bearer-Token"),
+    ("auth.basic", "Benutzername und Passwort"),
+    ("settings.proxy_url", "Proxy-URL"),
+    ("settings.username", "Benutzername"),
+    ("settings.password", "Passwort"),
+    ("settings.remember", "Merken"),
+    (
+        "settings.remember_help",
+        "Das Passwort im Schlüsselbund des Systems speichern, ohne Schlüsselbund mit den Einstellungen",
+    ),
+    ("settings.bypass_localhost", "Für localhost umgehen"),
+    ("settings.model", "Modell"),
+    ("settings.default_model", "Standardmodell für neue Chats"),
+    ("settings.inherit", "Von Chats übernehmen"),
+    ("settings.inherit_help", "Modellwechsel in Chats übernehmen"),
+    ("settings.golden_prompts", "Goldene Prompts"),
+    ("settings.misc", "Sonstiges"),
+    ("settings.language", "Sprache"),
+    ("settings.theme", "Design"),
+    ("settings.theme_system", "Wie das System"),
+    ("settings.theme_dark", "Dunkel"),
+    ("settings.theme_light", "Hell"),
+    (
+        "settings.quick_settings",
+        "Temperatur und Seed in der Kopfzeile des Chats anzeigen",
+    ),
+    ("settings.your_name", "Dein Name"),
+    ("settings.send_with", "Nachrichten senden mit"),
+    (
+        "settings.send_enter",
+        "Enter sendet, Umschalt+Enter für eine neue Zeile",
+    ),
+    (
+        "settings.send_ctrl_enter",
+        "Strg+Enter sendet, Enter für eine neue Zeile",
+    ),
+    ("settings.retention", "Gelöschte Chats behalten für"),
+    (
+        "settings.retention_help",
+        "Gelöschte Chats werden nach so vielen Tagen endgültig entfernt",
+    ),
+    ("settings.days", " Tage"),
+    ("settings.max_image", "Max. Größe eingefügter Bilder"),
+    (
+        "settings.max_image_help",
+        "Eingefügte Bilder werden passend verkleinert, damit Anfragen klein bleiben",
+    ),
+    ("settings.max_attachment", "Max. Größe angehängter Dateien"),
+    (
+        "settings.max_attachment_help",
+        "Größere Textdateien werden beim Ablegen in einem Chat abgelehnt",
+    ),
+    ("settings.autosave", "Automatisch speichern alle"),
+    (
+        "settings.autosave_help",
+        "Änderungen werden höchstens so oft gespeichert, und beim Schließen der App",
+    ),
+    (
+        "settings.reset_label",
+        "Globale Einstellungen auf Standardwerte zurücksetzen",
+    ),
+    (
+        "settings.save_load_label",
+        "Einstellungen als JSON speichern und laden",
+    ),
+    (
+        "settings.backup_label",
+        "Alle Chats und Einstellungen in einer Datei sichern",
+    ),
+    ("settings.export_backup", "Sicherung exportieren…"),
+    // keyboard shortcuts
+    ("keys.heading", "Tastenkürzel"),
+    ("keys.new_chat", "Neuer Chat"),
+    ("keys.close_chat", "Chat löschen"),
+    ("keys.next_chat", "Nächster Chat"),
+    ("keys.previous_chat", "Vorheriger Chat"),
+    ("keys.toggle_sidebar", "Seitenleiste ein-/ausblenden"),
+    ("keys.search_chats", "Chats durchsuchen"),
+    ("keys.find_in_chat", "Im Chat suchen"),
+    ("keys.stop_generation", "Generierung stoppen"),
+    ("keys.undo_deletion", "Löschen rückgängig machen"),
+    ("keys.toggle_logs", "Protokoll anzeigen"),
+    ("keys.jump_to_chat", "Zu Chat {} springen"),
+    ("keys.press", "Tasten drücken…"),
+    (
+        "keys.press_help",
+        "Neue Kombination drücken, Esc oder Klick zum Abbrechen",
+    ),
+    ("keys.change_help", "Klicken zum Ändern"),
+    ("keys.conflict", "Auch belegt durch: {}"),
+    (
+        "keys.needs_modifier",
+        "Strg oder Alt zusammen mit der Taste halten, einfache Tasten werden zum Tippen gebraucht",
+    ),
+    ("keys.reset_one", "Auf {} zurücksetzen"),
+    ("keys.reset_all", "Alle auf Standard zurücksetzen"),
+    ("settings.restore_backup", "Sicherung wiederherstellen…"),
+    // model picker
+    ("models.filter", "Modelle filtern…"),
+    ("models.sort", "Sortieren"),
+    ("models.sort_name", "Name"),
+    ("models.sort_size", "Größe"),
+    ("models.sort_modified", "geändert"),
+    ("models.server_order", "Reihenfolge des Servers"),
+    (
+        "models.none_found",
+        "Keine Modelle gefunden, läuft der Server?",
+    ),
+    ("models.none_match", "Keine passenden Modelle"),
+    ("models.refresh", "Modellliste aktualisieren"),
+    ("models.loading", "Modellliste wird geladen…"),
+    ("models.size", "Größe"),
+    ("models.modified", "Geändert"),
+    ("models.parameters", "Parameter"),
+    ("models.quantization", "Quantisierung"),
+    ("models.memory", "Speicher"),
+    ("memory.fits", "Sollte problemlos passen"),
+    ("memory.tight", "Knapp, evtl. langsam oder teilweise im Arbeitsspeicher"),
+    ("memory.too_large", "Passt wahrscheinlich nicht"),
+    (
+        "memory.reason",
+        "Modell {} + ~{} Kontext-Cache ({} Tokens) ≈ {}, Arbeitsspeicher {}",
+    ),
+    ("memory.reason_gpu", ", GPU-Speicher {}"),
+    (
+        "memory.estimate_note",
+        "Nur eine grobe Schätzung, andere Programme und die Modellarchitektur verändern den echten Bedarf.",
+    ),
+    ("models.default", "★ Standardmodell"),
+    ("models.unset", "Entfernen"),
+    (
+        "models.unset_help",
+        "Beim Start automatisch ein Modell wählen",
+    ),
+    ("models.use_default", "☆ Als Standard verwenden"),
+    (
+        "models.use_default_help",
+        "Dieses Modell beim Start wählen, sofern es installiert ist",
+    ),
+    ("models.running", "Geladen"),
+    ("models.installed", "Installiert"),
+    ("models.template_preview", "Vorlagenvorschau"),
+    (
+        "models.template_preview_help",
+        "Was das Modell für ein Beispielgespräch aus Systemprompt, Nutzernachricht und \
+        Antwort erhält. Teile der Vorlage, die die Vorschau nicht versteht, werden \
+        unverändert angezeigt.",
+    ),
+    ("models.template_preview_empty", "Das Modell hat keine Vorlage"),
+    ("model_table.name", "Name"),
+    ("model_table.family", "Familie"),
+    ("model_table.filter", "Nach Name oder Familie filtern"),
+    ("model_table.select_all", "Alle angezeigten Modelle auswählen"),
+    ("model_table.select_help", "Dieses Modell im aktuellen Chat verwenden"),
+    ("model_table.count", "{} Modelle"),
+    ("model_table.delete", "🗑 {} ausgewählte löschen"),
+    ("model_table.clear", "Auswahl aufheben"),
+    (
+        "model_table.confirm_delete",
+        "{} Modelle vom Server löschen? Das kann nicht rückgängig gemacht werden.",
+    ),
+    ("model_table.deleting", "Modelle werden gelöscht…"),
+    (
+        "models.running_unsupported",
+        "Vom Server nicht unterstützt, aktualisiere Ollama, um geladene Modelle zu sehen.",
+    ),
+    ("models.none_loaded", "Keine Modelle geladen."),
+    ("models.vram_help", "Belegter Speicher im VRAM / insgesamt"),
+    ("models.unloads", "Entladen {}"),
+    ("models.unload", "Entladen"),
+    (
+        "models.unload_help",
+        "Den von diesem Modell belegten Speicher freigeben",
+    ),
+    ("models.name_empty", "Der Name ist leer"),
+    ("models.name_spaces", "Der Name darf keine Leerzeichen enthalten"),
+    ("models.name_format", "Der Name muss wie `modell` oder `modell:tag` aussehen"),
+    ("models.name_chars", "Der Name darf nur Buchstaben, Ziffern und `._-/` enthalten"),
+    ("models.pulling", "{} wird heruntergeladen: {}"),
+    ("models.pull", "⬇ Herunterladen"),
+    ("models.pull_help", "Ein Modell aus der Ollama-Bibliothek herunterladen"),
+    ("models.pull_failed", "{} konnte nicht heruntergeladen werden: {}"),
+    ("models.license", "Lizenz"),
+    ("models.modelfile", "Modelfile"),
+    ("models.template", "Vorlage"),
+    (
+        "models.template_help",
+        "Prompt-Vorlage, die an das Modell übergeben wird. Sie kann (optional) eine Systemnachricht, eine Nachricht des Nutzers und die Antwort des Modells enthalten. Hinweis: Die Syntax kann modellspezifisch sein. Vorlagen verwenden die Go-",
+    ),
+    ("models.template_syntax", "Vorlagensyntax"),
+    (
+        "models.template_help_end",
+        ". Sie ersetzt, was im Modelfile festgelegt ist. Die Standardvorlage wird unter „Vorlage“ angezeigt.",
+    ),
+    ("models.variable", "Variable"),
+    ("models.description", "Beschreibung"),
+    ("models.var_system", "Die Systemnachricht, die eigenes Verhalten festlegt."),
+    ("models.var_prompt", "Die Nachricht des Nutzers."),
+    (
+        "models.var_response",
+        "Die Antwort des Modells. Beim Generieren einer Antwort wird Text nach dieser Variable weggelassen.",
+    ),
+    ("models.docs", "Ollama-Dokumentation"),
+    ("models.template_override", "Überschreiben (ersetzt die Vorlage aus dem Modelfile)"),
+    ("models.modelfile_template", "Vorlage im Modelfile:"),
+    ("models.refresh_info", "⟳ Infos neu laden"),
+    ("models.refresh_info_help", "Lizenz, Modelfile und Vorlage des Modells erneut abrufen"),
+    ("models.loading_info", "Modellinfos werden geladen…"),
+    ("models.duplicate", "Duplizieren…"),
+    ("models.duplicate_help", "Das ausgewählte Modell unter neuem Namen kopieren"),
+    ("models.copy", "Kopieren"),
+    ("models.overwritten", "{} existiert bereits und wird überschrieben"),
+    ("models.create", "Modell erstellen"),
+    ("models.create_help", "Ein neues Modell aus diesem Modelfile erstellen"),
+    ("models.reset_modelfile", "Auf das Modelfile des Modells zurücksetzen"),
+    ("models.creating", "{} wird erstellt: {}"),
+    ("models.starting", "startet"),
+    ("models.create_failed", "{} konnte nicht erstellt werden: {}"),
+    ("models.created", "{} erstellt"),
+    ("models.build_log", "Erstellungsprotokoll ({})"),
+    ("models.expires_never", "nie"),
+    ("models.expires_now", "jetzt"),
+    ("models.expires_hours", "in {} h {} min"),
+    ("models.expires_minutes", "in {} min {} s"),
+    // sidebar
+    ("sidebar.chats", "Chats"),
+    ("sidebar.model", "Modell"),
+    ("sidebar.settings", "Einstellungen"),
+    ("sidebar.logs", "Protokoll ({})"),
+    ("settings.logging", "Protokollierung"),
+    ("settings.log_file", "Protokolldatei schreiben"),
+    (
+        "settings.log_file_help",
+        "Ein Protokoll für Fehlerberichte führen. Die Datei wird bei 5 MiB rotiert, die \
+        letzten 3 Dateien bleiben erhalten",
+    ),
+    ("settings.log_level", "Stufe"),
+    ("settings.log_path", "Datei"),
+    ("settings.open_log_folder", "🗁 Protokollordner öffnen"),
+    ("settings.from_environment", "{} (aus der Umgebung)"),
+    ("settings.proxy_error", "Proxy-Fehler (über {}): {}"),
+    ("settings.speech_unsupported", "{} lässt sich auf dieser Plattform nicht ändern"),
+    ("settings.speech_reset", "Auf den normalen Wert zurücksetzen"),
+    (
+        "settings.startup_model_help",
+        "Wird beim Start gewählt, sofern es installiert ist. Lege es mit „Als Standard verwenden“ in einer Modellauswahl fest",
+    ),
+    ("settings.startup_model", "Startmodell"),
+    ("settings.picked_automatically", "automatisch gewählt"),
+    (
+        "settings.prefer_chat_help",
+        "Beim automatischen Wählen Modelle mit dem Tag „instruct“ oder „chat“ größeren vorziehen",
+    ),
+    ("settings.prefer_chat", "Chatmodelle bevorzugen"),
+    (
+        "settings.size_limit_help",
+        "Beim automatischen Wählen größere Modelle überspringen, z. B. solche, die nicht in den Speicher passen",
+    ),
+    ("settings.size_limit", "Größenlimit"),
+    ("settings.reset_title", "Einstellungen zurücksetzen"),
+    (
+        "settings.reset_body",
+        "Willst du die globalen Einstellungen wirklich zurücksetzen? Das lässt sich nicht rückgängig machen!",
+    ),
+    ("settings.speech", "Sprachausgabe"),
+    ("settings.voice", "Stimme"),
+    ("settings.default_voice", "Standard"),
+    ("settings.rate", "Tempo"),
+    ("settings.pitch", "Tonhöhe"),
+    ("settings.volume", "Lautstärke"),
+    ("settings.base_url", "Basis-URL"),
+    ("settings.api_key", "API-Schlüssel"),
+    ("settings.apply_server_help", "Mit diesem Server verbinden und die Modellliste neu laden"),
+    ("settings.voice_input", "Spracheingabe"),
+    (
+        "settings.whisper_model_help",
+        "Pfad zu einem whisper.cpp-Modell (ggml .bin), mit dem das Mikrofon transkribiert wird",
+    ),
+    ("settings.whisper_model", "Whisper-Modell"),
+    ("settings.invalid_host", "ungültiger Host"),
+    ("settings.host_empty", "der Host ist leer"),
+    ("settings.port_range", "der Port muss eine Zahl zwischen 0 und 65535 sein"),
+    ("settings.invalid_port", "ungültiger Port"),
+    (
+        "settings.golden_none",
+        "Noch keine Golden Prompts. Halte Shift über einer deiner Nachrichten gedrückt, um sie zu speichern",
+    ),
+    ("settings.golden_none_match", "Keine Golden Prompts passen"),
+    (
+        "settings.golden_help",
+        "{} und {} werden in einem Prompt durch den im Chat-Eingabefeld markierten Text und den Text der Zwischenablage ersetzt, der Cursor steht danach beim ersten davon.",
+    ),
+    ("settings.golden_new", "➕ Neuer Prompt"),
+    ("settings.golden_new_name", "Neuer Prompt"),
+    ("settings.golden_uses_one", "{} ({} Verwendung)"),
+    ("settings.golden_uses_many", "{} ({} Verwendungen)"),
+    ("settings.golden_tags_hint", "Schreiben, Review"),
+    ("settings.golden_used_with", "Verwendet mit"),
+    ("settings.golden_never_used", "Nie verwendet"),
+    ("logs.title", "Protokoll"),
+    ("logs.filter", "Filtern"),
+    ("logs.copy_all", "📋 Alles kopieren"),
+    ("logs.clear", "🗑 Leeren"),
+    ("logs.empty", "Noch nichts protokolliert"),
+    ("logs.no_matches", "Keine Einträge passen zu den Filtern"),
+    ("logs.count", "{} von {} Einträgen"),
+    ("sidebar.expand", "Seitenleiste ausklappen ({})"),
+    ("sidebar.collapse", "Seitenleiste einklappen ({})"),
+    ("sidebar.new_chat", "Neuer Chat ({})"),
+    ("sidebar.create_chat", "Neuen Chat erstellen ({})"),
+    ("sidebar.search", "🔍 Chats durchsuchen"),
+    ("sidebar.clear_search", "Suche leeren"),
+    (
+        "sidebar.bin_help",
+        "Entfernte Chats wiederherstellen oder endgültig löschen",
+    ),
+    ("sidebar.order_manual", "Eigene Reihenfolge"),
+    ("sidebar.order_recent", "Neueste zuerst"),
+    ("sidebar.back", "⬅ Zurück"),
+    ("sidebar.bin_empty", "Keine kürzlich gelöschten Chats"),
+    ("sidebar.delete_permanently", "Endgültig löschen"),
+    ("sidebar.restore", "Wiederherstellen"),
+    ("sidebar.generating", "Wird generiert…"),
+    ("sidebar.new_response", "Neue Antwort"),
+    ("sidebar.remove_chat", "Chat entfernen"),
+    ("sidebar.duplicate", "Duplizieren"),
+    ("sidebar.menu_edit", "⚙ Bearbeiten…"),
+    ("sidebar.menu_duplicate", "🗐 Duplizieren"),
+    ("sidebar.menu_export", "💾 Exportieren…"),
+    ("sidebar.chats_one", "{} Chat"),
+    ("sidebar.chats_many", "{} Chats"),
+    ("sidebar.messages_one", "{} Nachricht"),
+    ("sidebar.messages_many", "{} Nachrichten"),
+    ("sidebar.text_size", "{} Text"),
+    ("sidebar.editing_chat", "Chat „{}“ bearbeiten"),
+    (
+        "sidebar.export_label",
+        "Chatverlauf in eine Datei exportieren",
+    ),
+    ("sidebar.save_as", "Speichern unter…"),
+    ("sidebar.include_notes", "Notizen einschließen"),
+    ("sidebar.new_chat_button", "➕ Neuer Chat"),
+    ("sidebar.recently_deleted", "🗑 Kürzlich gelöscht ({})"),
+    ("sidebar.empty_bin", "Papierkorb leeren"),
+    (
+        "sidebar.kept_for",
+        "Gelöschte Chats werden {} Tage aufbewahrt",
+    ),
+    ("sidebar.rename", "✏ Umbenennen…"),
+    ("sidebar.export", "Exportieren"),
+    ("sidebar.export_format", "Exportformat"),
+    ("sidebar.no_clipboard_image", "In der Zwischenablage ist kein Bild"),
+    ("sidebar.image_unsupported", "Bild angehängt, aber {} unterstützt womöglich keine Bilder"),
+    ("sidebar.paste_failed", "Das Bild konnte nicht eingefügt werden: {}"),
+    ("sidebar.copied_one", "{} Nachricht kopiert"),
+    ("sidebar.copied_many", "{} Nachrichten kopiert"),
+    ("sidebar.undo_chat_gone", "Der Chat ist nicht mehr unter „Kürzlich gelöscht“"),
+    ("sidebar.undo_message_chat_gone", "Der Chat der Nachricht wurde gelöscht"),
+    ("sidebar.undo_wait_for_response", "Warte, bis die Antwort fertig ist, um die Nachricht wiederherzustellen"),
+    ("sidebar.chat_deleted", "Chat gelöscht"),
+    ("sidebar.message_deleted", "Nachricht gelöscht"),
+    ("sidebar.undo", "Rückgängig"),
+    ("sidebar.undo_help", "Rückgängig ({})"),
+    ("sidebar.pinned", "📌 Angeheftet ({})"),
+    ("sidebar.archived", "🗄 Archiviert ({})"),
+    ("sidebar.uses_shared_settings", "Dieser Chat verwendet die gemeinsamen Inferenz-Einstellungen aus den globalen Einstellungen."),
+    ("sidebar.override_settings", "Für diesen Chat überschreiben"),
+    ("sidebar.override_settings_help", "Von den gemeinsamen Einstellungen ausgehen und sie nur für diesen Chat ändern"),
+    ("sidebar.all_inherited", "Alle Werte stammen aus den gemeinsamen Einstellungen."),
+    ("sidebar.overridden", "Überschrieben: {}"),
+    ("sidebar.use_shared_settings", "Gemeinsame Einstellungen verwenden"),
+    ("sidebar.use_shared_settings_help", "Die Überschreibung entfernen und wieder die gemeinsamen Einstellungen verwenden"),
+    ("sidebar.request_timeout", "Der Server hat nicht innerhalb von {} s geantwortet (Zeitüberschreitung)"),
+    ("sidebar.image_filter", "Bild"),
+    ("sidebar.json_filter", "JSON-Datei"),
+    ("sidebar.no_file_selected", "Keine Datei ausgewählt"),
+    ("sidebar.backup_saved", "Sicherung gespeichert"),
+    ("sidebar.unload_failed", "{} konnte nicht entladen werden: {}"),
+    ("sidebar.dropping_files", "Dateien ablegen:"),
+    ("sidebar.presets_one", "{} Voreinstellung"),
+    ("sidebar.presets_many", "{} Voreinstellungen"),
+    ("sidebar.model_created", "Modell {} erstellt"),
+    ("sidebar.restored_one", "{} Chat wiederhergestellt"),
+    ("sidebar.restored_many", "{} Chats wiederhergestellt"),
+    ("sidebar.merged", "{} und {} hinzugefügt"),
+    ("sidebar.restore_title", "Sicherung wiederherstellen"),
+    ("sidebar.restore_body", "{} — mit den aktuellen zusammenführen oder alles ersetzen? Beim Ersetzen gehen alle aktuellen Chats und Einstellungen verloren."),
+    ("sidebar.replace", "Ersetzen"),
+    ("sidebar.merge", "Zusammenführen"),
+    ("sidebar.title_generating", "(generiert…)"),
+    ("sidebar.model_pulled", "Modell {} heruntergeladen"),
+    ("sidebar.pull_cancelled", "Herunterladen von {} abgebrochen"),
+    ("sidebar.remove_title", "Chat entfernen"),
+    ("sidebar.remove_body", "Willst du diesen Chat wirklich entfernen? Er bleibt {} Tage unter „Kürzlich gelöscht“.\nHalte Umschalt gedrückt, um diese Warnung zu überspringen."),
+    ("sidebar.remove_chat_help", "Chat „{}“ entfernen"),
+    ("sidebar.knowledge", "📚 Wissen"),
+    ("sidebar.model_for", "Modell für „{}“"),
+    ("sidebar.stop_speaking", "Vorlesen beenden"),
+    ("sidebar.reading", "Lese „{}“ vor, Nachricht {}"),
+    ("sidebar.go_to_chat", "Zum Chat"),
+    ("sidebar.speaking", "Spricht"),
+    ("sidebar.connecting", "Verbinde mit {}…"),
+    ("sidebar.connected", "Verbunden mit {}"),
+    ("sidebar.connection_failed", "Verbindung mit {} fehlgeschlagen: {}\n\nNeuer Versuch in {} s"),
+    ("sidebar.unauthorized", "Anmeldung bei {} fehlgeschlagen: {}\n\nPrüfe die Zugangsdaten in den Verbindungseinstellungen"),
+    ("sidebar.retry_now", "Jetzt erneut versuchen"),
+    ("sidebar.model_not_installed", "Modell {} ist nicht installiert"),
+    ("sidebar.model_deleted", "Modell {} gelöscht"),
+    ("sidebar.models_deleted", "{} Modelle gelöscht"),
+    ("sidebar.delete_models_failed", "Modelle konnten nicht gelöscht werden"),
+    ("sidebar.request_failed", "Ollama-Anfrage fehlgeschlagen"),
+    ("sidebar.delete_permanently_title", "Endgültig löschen"),
+    ("sidebar.delete_all_permanently_body", "Möchtest du wirklich alle Chats im Papierkorb endgültig löschen? Das kann nicht rückgängig gemacht werden."),
+    ("sidebar.delete_permanently_body", "Möchtest du diesen Chat wirklich endgültig löschen? Das kann nicht rückgängig gemacht werden."),
+    ("sidebar.deleted_info", "Gelöscht {} • {} • {} Nachrichten"),
+    ("sidebar.no_recent_messages", "Keine neuen Nachrichten"),
+    ("sidebar.unarchive_help", "In die Chatliste zurückholen"),
+    ("sidebar.archive_help", "Archivieren"),
+    ("sidebar.unpin_help", "Lösen"),
+    ("sidebar.pin_help", "Oben anheften"),
+    ("sidebar.no_chats_found", "Keine Chats gefunden"),
+    ("sidebar.sort", "Sortierung:"),
+    ("sidebar.all_tags", "Alle Tags"),
+    ("sidebar.tag_filter_help", "Nur Chats mit allen ausgewählten Tags anzeigen"),
+    ("sidebar.unpin", "📌 Lösen"),
+    ("sidebar.pin", "📌 Anheften"),
+    ("sidebar.unarchive", "📤 Aus dem Archiv holen"),
+    ("sidebar.archive", "🗄 Archivieren"),
+    ("sidebar.delete_chat", "❌ Löschen…"),
+    ("sidebar.add_tag", "🏷 Tag hinzufügen…"),
+    ("sidebar.new_tag", "Neuer Tag"),
+    ("sidebar.add", "Hinzufügen"),
+    ("sidebar.remove_tag", "✖ {} entfernen"),
+    ("sidebar.request_panicked", "Ollama-Anfrage abgestürzt"),
+    ("sidebar.backup_read_failed", "`{}` konnte nicht gelesen werden"),
+    ("sidebar.backup_not_json", "die Datei ist kein gültiges JSON"),
+    ("sidebar.backup_not_ellama", "die Datei ist keine Ellama-Sicherung"),
+    (
+        "sidebar.backup_newer",
+        "die Sicherung stammt von einer neueren Ellama-Version (Format {}), aktualisiere Ellama, um sie wiederherzustellen",
+    ),
+    ("sidebar.backup_damaged", "die Sicherung ist beschädigt"),
+    ("sidebar.attach_too_large", "`{}` ist zu groß zum Anhängen ({}, die Grenze liegt bei {})"),
+    (
+        "sidebar.attach_binary",
+        "`{}` scheint eine Binärdatei zu sein und kann nicht angehängt werden",
+    ),
+    ("sidebar.attach_not_utf8", "`{}` ist keine UTF-8-Textdatei"),
+    ("sidebar.export_filter", "{}-Datei"),
+    // chat view
+    ("chat.new_chat", "Neuer Chat"),
+    ("chat.ask", "Frag mich etwas…"),
+    ("chat.find", "Im Chat suchen"),
+    ("chat.no_model", "Kein Modell ausgewählt"),
+    ("chat.model_help", "Modell, das in diesem Chat antwortet"),
+    ("chat.info", "Chat-Info"),
+    ("chat.stats", "Chat-Statistik"),
+    ("chat.options", "Chat-Optionen"),
+    ("chat.note", "📝 Notiz"),
+    ("chat.note_hint", "Wird nicht an das Modell gesendet…"),
+    ("chat.remove_note", "🗑 Notiz entfernen"),
+    ("chat.view_request", "🔍 Anfrage anzeigen"),
+    (
+        "chat.view_request_unavailable",
+        "Nur Antworten seit dem Start der App können untersucht werden",
+    ),
+    ("chat.request_title", "Anfrage an {}"),
+    ("chat.copy_json", "📋 Als JSON kopieren"),
+    (
+        "chat.add_note_help",
+        "Rechtsklick, um eine Notiz hinzuzufügen",
+    ),
+    ("chat.retry", "🔄 Erneut versuchen"),
+    ("chat.retry_help", "Dieselbe Anfrage noch einmal senden"),
+    ("chat.copy_error", "🗐 Fehler kopieren"),
+    (
+        "chat.copy_error_help",
+        "Den Fehler für einen Fehlerbericht kopieren",
+    ),
+    ("chat.regenerate", "🔄 Neu generieren"),
+    ("chat.delete_message", "Nachricht löschen"),
+    ("chat.jump_to_bottom", "Nach unten springen"),
+    ("chat.rating_good", "Gute Antwort"),
+    ("chat.rating_bad", "Schlechte Antwort"),
+    ("chat.time_to_first_token", "Zeit bis zum ersten Token: {} s"),
+    ("chat.speed", "Geschwindigkeit: {} Tok./s"),
+    ("chat.total_time", "Gesamtzeit: {} s"),
+    ("chat.click_for_info", "Klicken, um die Chat-Info anzuzeigen"),
+    ("chat.show_info", "Chat-Info anzeigen"),
+    ("chat.context_starts_summarized", "— frühere Nachrichten zusammengefasst, der Kontext beginnt hier —"),
+    ("chat.context_starts", "— der Kontext beginnt hier —"),
+    ("chat.context_summary", "Anstelle der Nachrichten oben gesendet:\n\n{}"),
+    ("chat.context_not_sent", "Die Nachrichten oben wurden mit der letzten Anfrage nicht gesendet"),
+    ("chat.sources", "Wissensauszüge ({})"),
+    ("chat.similarity", "Ähnlichkeit zur Frage"),
+    ("chat.footer", "Prompt: {} Tok., Antwort: {} Tok."),
+    ("chat.footer_speed", ", {} Tok./s"),
+    ("chat.no_matches", "Keine Treffer"),
+    ("chat.previous_match", "Vorheriger Treffer (Umschalt+Eingabe)"),
+    ("chat.next_match", "Nächster Treffer (Eingabe)"),
+    ("chat.close_find", "Schließen (Esc)"),
+    ("chat.valid_json", "✔ Gültiges JSON"),
+    ("chat.invalid_json", "Die Antwort ist kein gültiges JSON"),
+    ("chat.code_block", "Block {}"),
+    ("chat.code_language_help", "Sprache dieses unmarkierten Codeblocks, für die Hervorhebung"),
+    ("chat.sources_help", "Wissensauszüge, die dem Prompt hinzugefügt wurden, Rechtsklick zum Anzeigen"),
+    ("chat.waiting", "Warte auf das Modell… {} s"),
+    ("chat.wait_for_response", "Warte, bis die Antwort fertig ist"),
+    ("chat.prepend_hint", "Text, mit dem die Antwort beginnt…"),
+    ("chat.regenerate_prepended_help", "Die Antwort neu generieren, das Modell macht nach dem vorangestellten Text weiter"),
+    ("chat.edit_message", "✏ Bearbeiten"),
+    ("chat.edit_message_help", "Die Nachricht im Kontext bearbeiten, ohne sie neu zu generieren"),
+    ("chat.preamble", "Einleitung"),
+    ("chat.generating", "Generiere… {} s"),
+    ("chat.note_help", "Deine Notiz, wird nicht an das Modell gesendet. Rechtsklick auf den Namen oben zum Bearbeiten"),
+    ("chat.interrupted", "⚠ Unterbrochen"),
+    ("chat.interrupted_help", "Die App wurde geschlossen, bevor die Antwort fertig war"),
+    ("chat.continue", "⏩ Fortsetzen"),
+    ("chat.continue_help", "Das Modell die Antwort dort fortsetzen lassen, wo sie aufhört"),
+    ("chat.copied", "Kopiert!"),
+    ("chat.copy_code", "Nur den Codeblock kopieren"),
+    ("chat.copy_message", "Nachricht kopieren"),
+    ("chat.copy_code_button", "🗐 Code"),
+    ("chat.show_rendered", "Formatiert anzeigen"),
+    ("chat.view_source", "Quelltext anzeigen"),
+    ("chat.save_golden", "Als Golden Prompt speichern"),
+    ("chat.stop_reading", "Vorlesen beenden. Rechtsklick, um von vorn zu beginnen"),
+    ("chat.read_aloud", "Die Nachricht vorlesen"),
+    ("chat.branch_help", "Hier abzweigen: in einem neuen Chat weitermachen, der mit dieser Nachricht endet"),
+    ("chat.regenerate_help", "Neu generieren"),
+    ("chat.regenerate_with", "Neu generieren mit"),
+    ("chat.regenerate_with_help", "Mit einem anderen Modell neu generieren"),
+    ("chat.strategy_send_all", "Alles senden"),
+    ("chat.strategy_sliding_window", "Nur die neuesten Nachrichten"),
+    ("chat.strategy_summarize", "Überlauf zusammenfassen"),
+    ("chat.strategy_send_all_help", "Die ganze Unterhaltung senden. Sobald sie das Kontextfenster übersteigt, vergisst das Modell ihren Anfang, samt System-Prompt"),
+    ("chat.strategy_sliding_window_help", "Den System-Prompt und die neuesten Nachrichten senden"),
+    ("chat.strategy_summarize_help", "Die ältesten Nachrichten durch eine Zusammenfassung des Modells ersetzen, wenn die Unterhaltung das Kontextfenster fast füllt"),
+    ("chat.remove_attachment", "Anhang entfernen"),
+    ("chat.knowledge_failed", "Die Wissenssuche ist fehlgeschlagen: {}"),
+    ("chat.stalled", "Die Antwort stockte länger als {} s und wurde abgeschnitten"),
+    ("chat.first_token_timeout", "Das Modell hat nicht innerhalb von {} s zu antworten begonnen (Zeitüberschreitung)"),
+    ("chat.lost_while_asleep", "Die Verbindung ging verloren, während das System schlief"),
+    ("chat.format_report", "Bericht (Markdown)"),
+    ("chat.format_html", "Webseite (HTML)"),
+    ("chat.export_cancelled", "Export abgebrochen"),
+    ("chat.exported", "{} Nachrichten nach {} exportiert"),
+    ("chat.stats_messages", "Nachrichten"),
+    ("chat.stats_message_counts", "{} von dir, {} Antworten"),
+    ("chat.stats_errors", ", {} Fehler"),
+    ("chat.stats_text", "Text"),
+    ("chat.stats_text_counts", "{} Wörter, {} Zeichen"),
+    ("chat.stats_tokens", "Tokens"),
+    ("chat.stats_token_counts", "{} Prompt, {} generiert"),
+    ("chat.stats_tokens_help", "Summe über alle Antworten, jeder Prompt enthält den Verlauf"),
+    ("chat.stats_generation_time", "Generierungszeit"),
+    ("chat.seconds", "{} s"),
+    ("chat.stats_avg_speed", "Ø Geschwindigkeit"),
+    ("chat.tokens_per_second", "{} Tok./s"),
+    ("chat.stats_models", "Modelle"),
+    ("chat.stats_model_replies_one", "{}: {} Antwort"),
+    ("chat.stats_model_replies_many", "{}: {} Antworten"),
+    ("chat.info_context_window", "Kontextfenster"),
+    ("chat.token_count", "{} Tokens"),
+    ("chat.info_last_prompt", "Letzter Prompt"),
+    ("chat.info_generated", "Generiert"),
+    ("chat.info_generated_tokens", "{} Tokens in {} Antworten"),
+    ("chat.info_avg_ttft", "Ø Zeit bis zum ersten Token"),
+    ("chat.info_avg_speed", "{} Tok./s über {} Antworten"),
+    ("chat.context_used", "Kontext belegt: {} von {} Tokens ({} %)"),
+    ("chat.save_golden_title", "Golden Prompt speichern"),
+    ("chat.golden_name", "Name"),
+    ("chat.golden_tags", "Tags"),
+    ("chat.golden_tags_hint", "Durch Kommas getrennt, z. B. Schreiben, Review"),
+    ("chat.golden_prompts", "Golden Prompts:"),
+    ("chat.overflow", "⚠ ~{} Tokens, der Kontext fasst {}; ältere Nachrichten fallen weg"),
+    ("chat.context_strategy", "Kontextstrategie…"),
+    ("chat.context_strategy_help", "Wählen, wie die Unterhaltung in den Kontext passt"),
+    ("chat.attach_images", "Bilder anhängen"),
+    ("chat.paste_image", "Ein Bild aus der Zwischenablage einfügen"),
+    ("chat.insert_golden", "Einen Golden Prompt einfügen, oder / im Eingabefeld tippen"),
+    ("chat.send_help", "Senden ({}, {} für eine neue Zeile)"),
+    ("chat.transcribing", "Transkribiere…"),
+    ("chat.voice_needs_model", "Lege in den Einstellungen ein Whisper-Modell fest, um die Spracheingabe zu nutzen"),
+    ("chat.recording", "Aufnahme läuft…"),
+    ("chat.transcription_failed", "Transkription fehlgeschlagen: {}"),
+    ("chat.stop_recording", "Aufnahme beenden und transkribieren"),
+    ("chat.record", "Spracheingabe aufnehmen"),
+    ("chat.mic_unavailable", "Mikrofon nicht verfügbar: {}"),
+    ("chat.compare_model_help", "Modell, das jeden Prompt daneben beantwortet"),
+    ("chat.versus", "vs."),
+    ("chat.compare_with", "Vergleichen mit"),
+    ("chat.stop_comparing", "Vergleich beenden"),
+    ("chat.compare_help", "Vergleichsmodus: ein zweites Modell beantwortet jeden Prompt ebenfalls"),
+    ("chat.completion_mode", "Vervollständigungsmodus"),
+    ("chat.completion_mode_help", "Die Unterhaltung als einen Text senden, den das Modell fortsetzt, ohne Chat-Vorlage oder System-Prompt. Für Basismodelle"),
+    ("chat.copy_code_only", "Bei Antworten aus Einleitung + Code nur den Code kopieren"),
+    ("chat.collapse_preamble", "Einleitungen wie „Klar! Hier ist…“ einklappen"),
+    ("chat.render_math", "LaTeX-Formeln darstellen"),
+    ("chat.render_math_help", "$…$- und $$…$$-Formeln als Text mit mathematischen Symbolen zeigen, oder als Code, wenn sie zu komplex sind"),
+    ("chat.auto_speak", "Antworten automatisch vorlesen"),
+    ("chat.tts_unavailable", "Die Sprachausgabe konnte nicht gestartet werden"),
+    ("chat.copy_conversation", "📋 Unterhaltung kopieren"),
+    ("chat.copy_conversation_help", "Die Unterhaltung als Markdown kopieren, wie beim Berichtsexport"),
+    ("chat.copy_system_prompt", "Beim Kopieren den System-Prompt einschließen"),
+    ("chat.clear", "🗑 Unterhaltung leeren"),
+    ("chat.clear_help", "Alle Nachrichten entfernen, System-Prompt, Modell und Einstellungen bleiben"),
+    ("chat.no_messages", "Es gibt noch keine Nachrichten"),
+    ("chat.system_prompt", "System-Prompt"),
+    ("chat.system_prompt_hint", "Dauerhafte Anweisungen für das Modell, z. B. „Antworte auf Spanisch“…"),
+    ("chat.context", "Kontext"),
+    ("chat.window_prefix", "Letzte "),
+    ("chat.window_suffix", " Nachrichten"),
+    ("chat.threshold", "des Kontextfensters"),
+    ("chat.threshold_help", "Zusammenfassen, wenn die Unterhaltung mehr als diesen Anteil des Kontextfensters von {} Tokens belegt"),
+    ("chat.forget_summary", "Zusammenfassung verwerfen"),
+    ("chat.forget_summary_help", "Die zusammengefassten Nachrichten wieder unverändert senden"),
+    ("chat.knowledge_help", "Die Auszüge dieser Dokumente, die jeder Frage am nächsten sind, werden dem Prompt hinzugefügt."),
+    ("chat.remove_source", "Entfernen"),
+    ("chat.add_files", "➕ Dateien…"),
+    ("chat.add_folder", "➕ Ordner…"),
+    ("chat.add_folder_help", "Die Textdateien im Ordner und seinen Unterordnern werden indiziert"),
+    ("chat.embedding_model", "Embedding-Modell"),
+    ("chat.select", "Auswählen…"),
+    ("chat.no_embedding_model", "Lade ein Embedding-Modell wie nomic-embed-text herunter, um damit zu indizieren"),
+    ("chat.excerpts_per_question", "Auszüge pro Frage"),
+    ("chat.embedding_progress", "Erzeuge Embeddings {}/{}"),
+    ("chat.reading_files", "Lese Dateien…"),
+    ("chat.indexed", "{} Abschnitte mit `{}` indiziert"),
+    ("chat.index_stale", "Die Quellen oder das Modell haben sich geändert, indiziere erneut, um sie zu nutzen"),
+    ("chat.index_again", "⟳ Erneut indizieren"),
+    ("chat.index", "Indizieren"),
+    ("chat.index_disabled", "Füge zuerst Quellen hinzu und wähle ein Embedding-Modell"),
+    ("chat.clear_index", "Index leeren"),
+    ("chat.clear_index_help", "Keine Auszüge mehr zu Prompts hinzufügen"),
+    ("chat.switch_mode_title", "Modus wechseln"),
+    ("chat.switch_to_completion", "Im Vervollständigungsmodus werden die Nachrichten als ein Text gesendet, den das Modell fortsetzt, ohne Chat-Vorlage, System-Prompt, Zusammenfassungen oder Wissen. Antworten auf die bisherigen Nachrichten können anders ausfallen."),
+    ("chat.switch_to_chat", "Im Chatmodus werden die Nachrichten als Unterhaltung über die Chat-Vorlage des Modells gesendet. Ein als ein Dokument geschriebener Text liest sich für das Modell womöglich nicht wie eine Unterhaltung."),
+    ("chat.switch", "Wechseln"),
+    ("chat.clear_title", "Unterhaltung leeren"),
+    ("chat.clear_body", "Alle {} Nachrichten dieses Chats entfernen? System-Prompt, Modell, Einstellungen und Wissen bleiben erhalten."),
+    ("chat.clear_button", "Leeren"),
+    ("chat.branch_of", "Abzweigung von {}"),
+    ("chat.suggest_fact", "Erzähl mir eine spannende Tatsache"),
+    ("chat.suggest_fact_about", "über das Römische Reich"),
+    ("chat.suggest_code", "Zeig mir ein Codebeispiel"),
+    ("chat.suggest_code_about", "für einen Webserver in Rust"),
+    ("chat.suggest_joke", "Erzähl mir einen Witz"),
+    ("chat.suggest_joke_about", "über Krabben"),
+    ("chat.suggest_ideas", "Gib mir Ideen"),
+    ("chat.suggest_ideas_about", "für ein Geburtstagsgeschenk"),
+    ("chat.promote", "⬆ Mit {} weitermachen"),
+    ("chat.promote_help", "Den Vergleich beenden und die Unterhaltung nur mit diesem Modell fortsetzen"),
+    ("chat.new_messages", "⬇ Neue Nachrichten"),
+    (
+        "chat.explain_auth",
+        "Anmeldung fehlgeschlagen. Prüfe das Token bzw. Benutzername und Passwort in den Verbindungseinstellungen",
+    ),
+    (
+        "chat.explain_timeout",
+        "Zeitüberschreitung der Anfrage. Der Server lädt das Modell eventuell noch, versuche es erneut",
+    ),
+    (
+        "chat.explain_refused",
+        "Keine Verbindung zum Ollama-Server. Stelle sicher, dass er unter der eingestellten Adresse läuft",
+    ),
+    (
+        "chat.explain_model_missing",
+        "Das Modell ist auf dem Server nicht verfügbar. Lade es herunter oder wähle ein anderes Modell",
+    ),
+    (
+        "chat.explain_connection_lost",
+        "Die Verbindung zum Server ist während der Antwort abgebrochen",
+    ),
+    ("chat.explain_proxy", "Die Anfrage ist am Proxy gescheitert"),
+    ("chat.explain_failed", "Die Anfrage ist fehlgeschlagen"),
+    ("chat.exported_on", "Aus Ellama exportiert am {}"),
+    ("chat.report_model", "Modell"),
+    ("chat.report_settings", "Einstellungen"),
+    ("chat.report_setting", "Einstellung"),
+    ("chat.report_value", "Wert"),
+    ("chat.report_template", "Vorlage"),
+    ("chat.report_overview", "Übersicht"),
+    ("chat.report_role", "Rolle"),
+    ("chat.report_time", "Zeit"),
+    ("chat.report_characters", "Zeichen"),
+    ("chat.report_images", "Bilder"),
+    ("chat.report_stats", "Statistik"),
+    ("chat.report_prompt_tokens", "Prompt-Tokens"),
+    ("chat.report_reply_tokens", "Antwort-Tokens"),
+    ("chat.report_first_token", "Zeit bis zum ersten Token"),
+    ("chat.report_speed", "Geschwindigkeit"),
+    ("chat.report_rating", "Bewertung"),
+    ("chat.report_conversation", "Unterhaltung"),
+    ("chat.report_reply_settings", "Einstellungen: {}"),
+    ("chat.report_image", "Bild: `{}`"),
+    ("chat.report_note", "Notiz:"),
+    ("chat.report_default", "{} Standard"),
+    ("chat.missing_image", "Fehlendes Bild: {}"),
+    (
+        "chat.not_multimodal",
+        "{} scheint kein multimodales Modell zu sein und kann Bilder eventuell nicht verarbeiten",
+    ),
+    ("chat.indexing_panicked", "Indizierung abgestürzt: {}"),
+    ("chat.knowledge_no_text", "In den Quellen wurde kein Text gefunden"),
+    ("chat.knowledge_read_failed", "Die Quellen konnten nicht gelesen werden: {}"),
+    ("chat.knowledge_empty_embedding", "`{}` hat ein leeres Embedding geliefert"),
+    ("chat.knowledge_embed_failed", "Embedding mit `{}` fehlgeschlagen: {}"),
+    ("chat.voice_no_microphone", "kein Mikrofon gefunden"),
+    ("chat.voice_microphone_config", "die Mikrofonkonfiguration konnte nicht abgefragt werden"),
+    ("chat.voice_whisper_path", "der Pfad zum Whisper-Modell ist kein gültiges UTF-8"),
+    ("chat.voice_whisper_load", "das Whisper-Modell konnte nicht geladen werden"),
+    ("chat.voice_whisper_state", "der Whisper-Zustand konnte nicht erstellt werden"),
+    ("chat.voice_transcribe", "die Aufnahme konnte nicht transkribiert werden"),
+    ("chat.voice_sample_format", "nicht unterstütztes Sampleformat {}"),
+    ("chat.voice_open", "das Mikrofon konnte nicht geöffnet werden"),
+    ("chat.voice_record", "die Aufnahme konnte nicht gestartet werden"),
+    // inference settings
+    ("inference.quick_temperature_help", "Temperatur, höhere Werte lassen das Modell kreativer antworten"),
+    ("inference.seed", "Seed"),
+    ("inference.quick_seed_help", "Derselbe Seed lässt das Modell für denselben Prompt denselben Text generieren"),
+    ("inference.random_seed", "Zufälliger Seed"),
+    ("inference.enable", "Aktivieren"),
+    ("inference.json_mode", "JSON-Modus"),
+    ("inference.json_mode_help", "Das Modell zwingen, mit gültigem JSON zu antworten. Antworten werden formatiert angezeigt. Bitte auch im Prompt um JSON, sonst erzeugt das Modell womöglich endlos Leerraum."),
+    ("inference.suggest_stops", "Aus der Modellvorlage vorschlagen"),
+    ("inference.suggest_stops_help", "{} hinzufügen"),
+    ("inference.template_not_loaded", "Die Vorlage des Modells ist nicht geladen"),
+    ("inference.no_turn_markers", "Keine bekannten Sprecherwechsel-Marker in der Vorlage des Modells"),
+    ("inference.stops_added", "Alle vorgeschlagenen Stoppsequenzen sind hinzugefügt"),
+    ("inference.disabled", "Deaktiviert"),
+    ("inference.keep_alive_default", "5m (Standard)"),
+    ("inference.keep_alive_forever", "Für immer (-1)"),
+    ("inference.keep_alive_unload", "Sofort entladen (0)"),
+    ("inference.keep_alive_30m", "30m"),
+    (
+        "inference.warn_mirostat",
+        "Mirostat eta und tau wirken nicht, solange Mirostat deaktiviert ist",
+    ),
+    ("inference.warn_top_p", "Ein Top-P von 0 lässt keine Tokens zur Auswahl übrig"),
+    ("inference.warn_repeat_last_n", "Repeat Last N ({}) ist größer als das Kontextfenster ({})"),
+    ("inference.warn_empty_stop", "Leere Stoppsequenzen werden ignoriert"),
+    ("inference.warn_keep_alive", "Keep Alive ist keine gültige Dauer und wird ignoriert"),
+    ("inference.header", "Inferenz-Einstellungen"),
+    ("inference.mirostat_eta", "Mirostat eta"),
+    ("inference.mirostat_tau", "Mirostat tau"),
+    ("inference.num_ctx", "Kontextfenster"),
+    ("inference.num_gqa", "Anzahl der GQA-Gruppen"),
+    ("inference.num_gpu", "GPU-Schichten"),
+    ("inference.num_thread", "Anzahl der Threads"),
+    ("inference.repeat_last_n", "Repeat Last N"),
+    ("inference.repeat_penalty", "Wiederholungsstrafe"),
+    ("inference.temperature", "Temperatur"),
+    ("inference.stop", "Stoppsequenz"),
+    ("inference.tfs_z", "Tail-Free Sampling Z"),
+    ("inference.num_predict", "Anzahl vorherzusagender Tokens"),
+    ("inference.top_k", "Top-K"),
+    ("inference.top_p", "Top-P"),
+    ("inference.min_p", "Min-P"),
+    ("inference.typical_p", "Typical-P"),
+    ("inference.presence_penalty", "Präsenzstrafe"),
+    ("inference.frequency_penalty", "Häufigkeitsstrafe"),
+    ("inference.num_batch", "Batchgröße"),
+    ("inference.penalize_newline", "Zeilenumbrüche bestrafen"),
+    ("inference.keep_alive", "Keep Alive"),
+    ("inference.on", "an"),
+    (
+        "inference.keep_alive_help",
+        "Wie lange das Modell nach einer Anfrage im Speicher bleibt. Geladen bleibt die erste Antwort nach einer Pause schneller.",
+    ),
+    ("inference.custom", "Eigene"),
+    ("inference.keep_alive_hint", "z. B. 90s, 2h, -1"),
+    (
+        "inference.keep_alive_invalid",
+        "Erwartet wird eine Anzahl Sekunden oder eine Dauer wie 10m oder 1h",
+    ),
+    ("inference.max", "max"),
+    ("inference.max_help", "Maximalwert setzen"),
+    ("inference.min", "min"),
+    ("inference.min_help", "Minimalwert setzen"),
+    ("inference.rand", "zufällig"),
+    ("inference.rand_help", "Zufallswert setzen"),
+    ("inference.reset", "zurücksetzen"),
+    ("inference.reset_help", "Standardwert setzen"),
+    ("inference.ollama_only", "Von OpenAI-kompatiblen Servern nicht unterstützt"),
+    ("inference.preset", "Voreinstellung"),
+    ("inference.no_presets", "Noch keine Voreinstellungen gespeichert"),
+    ("inference.save_as", "Speichern unter…"),
+    ("inference.preset_name", "Name der Voreinstellung"),
+    ("inference.overwrite", "Überschreiben"),
+    ("inference.manage_presets", "Voreinstellungen verwalten"),
+    ("inference.rename", "Umbenennen"),
+    ("inference.delete_preset", "Voreinstellung löschen"),
+    ("inference.reset_settings", "Einstellungen zurücksetzen"),
+    ("inference.mirostat_help", "Mirostat-Sampling zur Steuerung der Perplexität aktivieren."),
+    (
+        "inference.mirostat_eta_help",
+        "Beeinflusst, wie schnell der Algorithmus auf Rückmeldungen aus dem generierten Text reagiert. Eine niedrigere Lernrate führt zu langsameren Anpassungen, eine höhere macht den Algorithmus reaktionsfreudiger.",
+    ),
+    (
+        "inference.mirostat_tau_help",
+        "Steuert das Gleichgewicht zwischen Kohärenz und Vielfalt der Ausgabe. Ein niedrigerer Wert führt zu fokussierterem und kohärenterem Text.",
+    ),
+    (
+        "inference.num_ctx_help",
+        "Legt die Größe des Kontextfensters fest, das zum Generieren des nächsten Tokens verwendet wird.",
+    ),
+    (
+        "inference.num_gqa_help",
+        "Die Anzahl der GQA-Gruppen in der Transformer-Schicht. Für manche Modelle nötig, bei llama2:70b ist sie zum Beispiel 8.",
+    ),
+    (
+        "inference.num_gpu_help",
+        "Die Anzahl der Schichten, die an die GPU(s) gehen. Unter macOS ist sie standardmäßig 1, um Metal zu nutzen, 0 deaktiviert es.",
+    ),
+    (
+        "inference.num_thread_help",
+        "Legt die Anzahl der Threads für die Berechnung fest. Standardmäßig ermittelt Ollama sie für die beste Leistung. Empfohlen ist die Anzahl der physischen CPU-Kerne des Systems (nicht die der logischen Kerne).",
+    ),
+    (
+        "inference.repeat_last_n_help",
+        "Legt fest, wie weit das Modell zurückblickt, um Wiederholungen zu vermeiden.",
+    ),
+    (
+        "inference.repeat_penalty_help",
+        "Legt fest, wie stark Wiederholungen bestraft werden. Ein höherer Wert (z. B. 1,5) bestraft sie stärker, ein niedrigerer Wert (z. B. 0,9) ist nachsichtiger.",
+    ),
+    (
+        "inference.temperature_help",
+        "Die Temperatur des Modells. Eine höhere Temperatur lässt das Modell kreativer antworten.",
+    ),
+    (
+        "inference.seed_help",
+        "Legt den Startwert des Zufallsgenerators fest. Mit einem festen Wert generiert das Modell für denselben Prompt denselben Text.",
+    ),
+    (
+        "inference.stop_help",
+        "Legt die Stoppsequenzen fest. Trifft das LLM auf eine davon, hört es auf, Text zu generieren.",
+    ),
+    (
+        "inference.tfs_z_help",
+        "Tail-Free Sampling verringert den Einfluss weniger wahrscheinlicher Tokens auf die Ausgabe. Ein höherer Wert (z. B. 2,0) verringert ihn stärker, 1,0 deaktiviert die Einstellung.",
+    ),
+    (
+        "inference.num_predict_help",
+        "Höchstzahl der Tokens, die beim Generieren vorhergesagt werden. (Standard: 128, -1 = unbegrenzt, -2 = Kontext füllen)",
+    ),
+    (
+        "inference.top_k_help",
+        "Verringert die Wahrscheinlichkeit, Unsinn zu generieren. Ein höherer Wert (z. B. 100) ergibt vielfältigere Antworten, ein niedrigerer (z. B. 10) zurückhaltendere.",
+    ),
+    (
+        "inference.top_p_help",
+        "Wirkt zusammen mit Top-K. Ein höherer Wert (z. B. 0,95) führt zu vielfältigerem Text, ein niedrigerer (z. B. 0,5) zu fokussierterem und zurückhaltenderem Text.",
+    ),
+    (
+        "inference.min_p_help",
+        "Alternative zu Top-P, die ein Gleichgewicht aus Qualität und Vielfalt anstrebt. Der Parameter p ist die Mindestwahrscheinlichkeit eines Tokens, relativ zur Wahrscheinlichkeit des wahrscheinlichsten Tokens. Bei p=0,05 und einem wahrscheinlichsten Token mit 0,9 werden zum Beispiel Logits unter 0,045 herausgefiltert.",
+    ),
+    (
+        "inference.typical_p_help",
+        "Aktiviert lokal typisches Sampling mit dem Parameter p, das die Tokens behält, deren Wahrscheinlichkeit nahe am erwarteten Informationsgehalt liegt. 1,0 deaktiviert die Einstellung.",
+    ),
+    (
+        "inference.presence_penalty_help",
+        "Bestraft Tokens, die schon im Text vorkamen, und bringt das Modell so zu neuen Themen.",
+    ),
+    (
+        "inference.frequency_penalty_help",
+        "Bestraft Tokens danach, wie oft sie bisher im Text vorkamen, und verringert so wörtliche Wiederholungen.",
+    ),
+    (
+        "inference.num_batch_help",
+        "Legt die Anzahl der parallel verarbeiteten Prompt-Tokens fest. Größere Werte beschleunigen die Verarbeitung auf Kosten des Speichers.",
+    ),
+    (
+        "inference.penalize_newline_help",
+        "Ob die Wiederholungsstrafen auch für Zeilenumbrüche gelten.",
+    ),
+    ("inference.no_stops", "Keine Stoppsequenzen festgelegt, füge eine hinzu."),
+    ("inference.add", "➕ Hinzufügen"),
+    ("inference.clear", "Leeren"),
+];
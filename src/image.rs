@@ -1,7 +1,7 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use base64_stream::ToBase64Reader;
 use eframe::egui::{self, vec2, Color32, Rect, RichText, Stroke};
-use image::ImageFormat;
+use image::{imageops::FilterType, ImageFormat};
 use ollama_rs::generation::images::Image;
 use std::{
     fs::File,
@@ -35,6 +35,59 @@ pub fn convert_image(path: &Path) -> Result<Image> {
     Ok(Image::from_base64(&base64))
 }
 
+/// `data:` URL of the image at `path`, for embedding it in HTML. Formats browsers can't
+/// show are converted to PNG.
+pub fn data_url(path: &Path) -> Result<String> {
+    let format = ImageFormat::from_path(path)?;
+    let (mime, bytes) = match format {
+        ImageFormat::Png => ("image/png", std::fs::read(path)?),
+        ImageFormat::Jpeg => ("image/jpeg", std::fs::read(path)?),
+        ImageFormat::Gif => ("image/gif", std::fs::read(path)?),
+        ImageFormat::WebP => ("image/webp", std::fs::read(path)?),
+        ImageFormat::Bmp => ("image/bmp", std::fs::read(path)?),
+        _ => {
+            log::debug!("got {format:?} image, converting to png");
+            let img = image::load(BufReader::new(File::open(path)?), format)?;
+            let mut buf = Vec::new();
+            img.write_to(&mut Cursor::new(&mut buf), ImageFormat::Png)?;
+            ("image/png", buf)
+        }
+    };
+    let mut reader = ToBase64Reader::new(bytes.as_slice());
+    let mut base64 = String::new();
+    reader.read_to_string(&mut base64)?;
+    Ok(format!("data:{mime};base64,{base64}"))
+}
+
+/// Save an RGBA image pasted from the clipboard as a PNG in the app's data directory,
+/// downscaled so that neither side exceeds `max_dimension`. Returns the path of the file.
+pub fn save_pasted_image(
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+    max_dimension: u32,
+) -> Result<PathBuf> {
+    let img = image::RgbaImage::from_raw(width, height, rgba)
+        .context("clipboard image has an invalid size")?;
+    let mut img = image::DynamicImage::ImageRgba8(img);
+    if width.max(height) > max_dimension {
+        log::debug!("downscaling pasted {width}x{height} image to fit {max_dimension}px");
+        img = img.resize(max_dimension, max_dimension, FilterType::Triangle);
+    }
+
+    let dir = eframe::storage_dir(crate::TITLE)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("pasted");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!(
+        "pasted-{}.png",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S%.3f")
+    ));
+    img.save_with_format(&path, ImageFormat::Png)?;
+    log::info!("saved pasted image to `{}`", path.display());
+    Ok(path)
+}
+
 pub fn show_images(ui: &mut egui::Ui, images: &mut Vec<PathBuf>, mutate: bool) {
     const MAX_IMAGE_HEIGHT: f32 = 128.0;
     let pointer_pos = ui.input(|i| i.pointer.interact_pos());
@@ -0,0 +1,416 @@
+//! Grounding answers in local documents: the files of a chat are split into chunks that
+//! are embedded with an Ollama embedding model, and the chunks closest to a question are
+//! added to its prompt.
+
+use crate::{
+    backend::{BoxError, OllamaClient},
+    i18n::{tr, tr_args},
+};
+use flowync::{CompactFlower, CompactHandle};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+/// Extensions of files picked up when indexing a directory
+const TEXT_EXTENSIONS: &[&str] = &[
+    "md", "markdown", "txt", "text", "org", "rst", "adoc", "tex", "html", "htm", "csv", "json",
+    "yaml", "yml", "toml",
+];
+
+/// Size chunks are packed up to, in bytes
+const CHUNK_LEN: usize = 1500;
+
+/// Embedding model, sources and chunks of a finished indexing
+pub type Indexed = (String, Vec<PathBuf>, Vec<Chunk>);
+
+// <(embedded chunks, total chunks), index, error>
+pub type IndexFlower = CompactFlower<(usize, usize), Indexed, String>;
+pub type IndexFlowerHandle = CompactHandle<(usize, usize), Indexed, String>;
+
+/// Piece of a source file with its embedding.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct Chunk {
+    /// Path of the file relative to the added source
+    pub source: String,
+    pub text: String,
+    pub embedding: Vec<f32>,
+}
+
+/// Chunk added to a prompt, kept with the reply to show what it was based on.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct Retrieved {
+    pub source: String,
+    pub text: String,
+    /// Cosine similarity to the question
+    pub score: f32,
+}
+
+/// What to ask for in the file dialog when adding sources
+#[derive(Clone, Copy)]
+pub enum SourcePick {
+    Files,
+    Folder,
+}
+
+/// Documents a chat answers from.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Knowledge {
+    /// Files and directories to index
+    pub sources: Vec<PathBuf>,
+    /// Model the next indexing embeds with
+    pub embedding_model: String,
+    /// Number of chunks added to each prompt
+    pub top_k: usize,
+    /// Embedded chunks, empty until indexed
+    pub chunks: Vec<Chunk>,
+    /// Model the chunks were embedded with, questions have to be embedded with it too
+    pub indexed_model: String,
+    /// Sources the chunks were made from
+    pub indexed_sources: Vec<PathBuf>,
+}
+
+impl Default for Knowledge {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            embedding_model: String::new(),
+            top_k: 4,
+            chunks: Vec::new(),
+            indexed_model: String::new(),
+            indexed_sources: Vec::new(),
+        }
+    }
+}
+
+impl Knowledge {
+    #[inline]
+    pub fn is_indexed(&self) -> bool {
+        !self.chunks.is_empty()
+    }
+
+    /// Whether the sources or the model changed since indexing
+    pub fn is_stale(&self) -> bool {
+        self.indexed_sources != self.sources || self.indexed_model != self.embedding_model
+    }
+
+    /// Forget the chunks, keeping the sources.
+    pub fn clear_index(&mut self) {
+        self.chunks.clear();
+        self.indexed_model.clear();
+        self.indexed_sources.clear();
+    }
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with('.'))
+}
+
+fn has_text_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| TEXT_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// Files of `sources` with their labels. Directories are searched recursively for text
+/// files, skipping hidden entries; files added directly are taken whatever their type.
+/// Each directory is read once, so symlinks to an ancestor don't loop.
+fn collect_files(sources: &[PathBuf]) -> Vec<(PathBuf, String)> {
+    let label = |path: &Path| {
+        path.file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string())
+    };
+    let mut pending: Vec<(PathBuf, String, bool)> = sources
+        .iter()
+        .map(|path| (path.clone(), label(path), true))
+        .collect();
+    let mut files = Vec::new();
+    let mut visited = HashSet::new();
+    while let Some((path, name, added)) = pending.pop() {
+        if path.is_dir() {
+            match path.canonicalize() {
+                Ok(real) => {
+                    if !visited.insert(real) {
+                        continue;
+                    }
+                }
+                Err(e) => {
+                    log::warn!("failed to read `{}`: {e}", path.display());
+                    continue;
+                }
+            }
+            match std::fs::read_dir(&path) {
+                Ok(entries) => pending.extend(
+                    entries
+                        .filter_map(Result::ok)
+                        .map(|entry| entry.path())
+                        .filter(|path| !is_hidden(path))
+                        .map(|path| {
+                            let name = format!("{name}/{}", label(&path));
+                            (path, name, false)
+                        }),
+                ),
+                Err(e) => log::warn!("failed to read `{}`: {e}", path.display()),
+            }
+        } else if added || has_text_extension(&path) {
+            files.push((path, name));
+        }
+    }
+    files.sort();
+    files.dedup_by(|a, b| a.0 == b.0);
+    files
+}
+
+/// Split `text` at paragraph boundaries into chunks of up to about [`CHUNK_LEN`] bytes,
+/// splitting longer paragraphs between words.
+fn split_chunks(text: &str) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        if !current.is_empty() && current.len() + paragraph.len() + 2 > CHUNK_LEN {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if paragraph.len() > CHUNK_LEN {
+            for word in paragraph.split_inclusive(char::is_whitespace) {
+                if !current.is_empty() && current.len() + word.len() > CHUNK_LEN {
+                    chunks.push(current.trim_end().to_owned());
+                    current.clear();
+                }
+                current.push_str(word);
+            }
+            current.truncate(current.trim_end().len());
+            continue;
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(paragraph);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Labeled chunks of the text files in `sources`.
+fn read_chunks(sources: &[PathBuf]) -> Vec<(String, String)> {
+    let mut chunks = Vec::new();
+    for (path, label) in collect_files(sources) {
+        match std::fs::read_to_string(&path) {
+            Ok(text) => chunks.extend(
+                split_chunks(&text)
+                    .into_iter()
+                    .map(|chunk| (label.clone(), chunk)),
+            ),
+            // binary files in a directory end up here too
+            Err(e) => log::warn!("skipping `{}`: {e}", path.display()),
+        }
+    }
+    chunks
+}
+
+/// Chunk and embed the files of `sources` with `model`, reporting progress per chunk.
+pub async fn index(
+    ollama: OllamaClient,
+    model: String,
+    sources: Vec<PathBuf>,
+    handle: &IndexFlowerHandle,
+) {
+    log::info!("indexing {} source(s) with `{model}`...", sources.len());
+    let read = {
+        let sources = sources.clone();
+        tokio::task::spawn_blocking(move || read_chunks(&sources)).await
+    };
+    let texts = match read {
+        Ok(texts) if texts.is_empty() => {
+            handle.error(tr("chat.knowledge_no_text").to_owned());
+            return;
+        }
+        Ok(texts) => texts,
+        Err(e) => {
+            handle.error(tr_args("chat.knowledge_read_failed", &[&e]));
+            return;
+        }
+    };
+
+    let total = texts.len();
+    let mut chunks = Vec::with_capacity(total);
+    handle.send((0, total));
+    // the `/api/embeddings` endpoint takes one prompt at a time
+    for (source, text) in texts {
+        let embedding = match embed(&ollama, &model, &text).await {
+            Ok(embedding) if !embedding.is_empty() => embedding,
+            Ok(_) => {
+                handle.error(tr_args("chat.knowledge_empty_embedding", &[&model]));
+                return;
+            }
+            Err(e) => {
+                log::error!("failed to embed chunks: {e}");
+                handle.error(tr_args("chat.knowledge_embed_failed", &[&model, &e]));
+                return;
+            }
+        };
+        chunks.push(Chunk {
+            source,
+            text,
+            embedding,
+        });
+        handle.send((chunks.len(), total));
+    }
+    log::info!("indexed {total} chunk(s)");
+    handle.success((model, sources, chunks));
+}
+
+/// Embedding of `text`, stored as `f32` to halve the size of saved chats.
+async fn embed(ollama: &OllamaClient, model: &str, text: &str) -> Result<Vec<f32>, BoxError> {
+    let embedding = ollama.embeddings(model, text).await?;
+    Ok(embedding.into_iter().map(|x| x as f32).collect())
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(a, b)| a * b).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+/// The `top_k` chunks of `knowledge` closest to `query`.
+pub async fn retrieve(
+    ollama: &OllamaClient,
+    knowledge: &Knowledge,
+    query: &str,
+) -> Result<Vec<Retrieved>, BoxError> {
+    let query = embed(ollama, &knowledge.indexed_model, query).await?;
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut scored: Vec<(f32, &Chunk)> = knowledge
+        .chunks
+        .iter()
+        .map(|chunk| (cosine_similarity(&query, &chunk.embedding), chunk))
+        .collect();
+    scored.sort_unstable_by(|a, b| b.0.total_cmp(&a.0));
+    Ok(scored
+        .into_iter()
+        .take(knowledge.top_k)
+        .map(|(score, chunk)| Retrieved {
+            source: chunk.source.clone(),
+            text: chunk.text.clone(),
+            score,
+        })
+        .collect())
+}
+
+/// `prompt` preceded by the `retrieved` excerpts, each labeled with its source.
+pub fn augment_prompt(prompt: &str, retrieved: &[Retrieved]) -> String {
+    if retrieved.is_empty() {
+        return prompt.to_owned();
+    }
+    let mut text = String::from(
+        "Use the following excerpts from my documents where they are relevant, \
+        and mention the sources you used.\n\n",
+    );
+    for (i, chunk) in retrieved.iter().enumerate() {
+        text.push_str(&format!(
+            "[{}] From `{}`:\n{}\n\n",
+            i + 1,
+            chunk.source,
+            chunk.text
+        ));
+    }
+    text.push_str("Question:\n");
+    text.push_str(prompt);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_keep_paragraphs_together() {
+        assert!(split_chunks("").is_empty());
+        assert!(split_chunks("\n\n  \n\n").is_empty());
+        assert_eq!(
+            split_chunks("  First.\n\n\n\nSecond.  \n\n"),
+            ["First.\n\nSecond."]
+        );
+
+        let paragraph = "word ".repeat(200);
+        let chunks = split_chunks(&format!("{paragraph}\n\n{paragraph}"));
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0], paragraph.trim_end());
+        assert_eq!(chunks[1], paragraph.trim_end());
+    }
+
+    #[test]
+    fn long_paragraphs_split_between_words() {
+        let paragraph = "word ".repeat(1000);
+        let chunks = split_chunks(&paragraph);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.len() <= CHUNK_LEN, "{}", chunk.len());
+            assert!(chunk.starts_with("word") && chunk.ends_with("word"));
+        }
+        assert_eq!(chunks.join(" "), paragraph.trim_end());
+    }
+
+    #[test]
+    fn similarity() {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-6;
+        assert!(close(cosine_similarity(&[1.0, 2.0], &[2.0, 4.0]), 1.0));
+        assert!(close(cosine_similarity(&[1.0, 0.0], &[0.0, 3.0]), 0.0));
+        assert!(close(cosine_similarity(&[1.0, 1.0], &[-1.0, -1.0]), -1.0));
+        // zero vectors are unrelated to everything instead of NaN
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 2.0]), 0.0);
+        assert_eq!(cosine_similarity(&[], &[]), 0.0);
+    }
+
+    #[test]
+    fn augmented_prompt_labels_sources() {
+        assert_eq!(augment_prompt("Why?", &[]), "Why?");
+
+        let retrieved = [
+            Retrieved {
+                source: "notes/a.md".to_owned(),
+                text: "Because.".to_owned(),
+                score: 0.9,
+            },
+            Retrieved {
+                source: "b.txt".to_owned(),
+                text: "Also this.".to_owned(),
+                score: 0.5,
+            },
+        ];
+        let prompt = augment_prompt("Why?", &retrieved);
+        assert!(prompt.contains("[1] From `notes/a.md`:\nBecause.\n\n"));
+        assert!(prompt.contains("[2] From `b.txt`:\nAlso this.\n\n"));
+        assert!(prompt.find("[1]") < prompt.find("[2]"));
+        assert!(prompt.ends_with("Question:\nWhy?"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_ancestors_are_read_once() {
+        let root = std::env::temp_dir().join(format!("ellama-knowledge-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("docs/a.md"), "A").unwrap();
+        std::fs::write(root.join("docs/image.png"), "").unwrap();
+        std::os::unix::fs::symlink(&root, root.join("docs/up")).unwrap();
+
+        let files = collect_files(&[root.clone()]);
+        std::fs::remove_dir_all(&root).unwrap();
+        let labels: Vec<&str> = files.iter().map(|(_, label)| label.as_str()).collect();
+        let name = root.file_name().unwrap().to_str().unwrap();
+        assert_eq!(labels, [format!("{name}/docs/a.md")]);
+    }
+}
@@ -0,0 +1,329 @@
+//! Lightweight heuristics for fenced code blocks in model replies: guessing the language of
+//! untagged blocks and detecting chatty preambles.
+
+/// A fenced code block found in a markdown document.
+pub struct FencedBlock {
+    /// Byte offset of the start of the opening fence line
+    pub start: usize,
+    /// Byte offset right after the closing fence line
+    pub end: usize,
+    /// Byte offset right after the opening fence, where the info string starts
+    pub info_pos: usize,
+    /// The info string (language tag) of the block, may be empty
+    pub info: String,
+    /// Contents of the block
+    pub code: String,
+}
+
+/// Find all fenced (``` or ~~~) code blocks in a markdown document.
+pub fn fenced_blocks(markdown: &str) -> Vec<FencedBlock> {
+    let mut blocks = Vec::new();
+    let mut open: Option<(&str, FencedBlock)> = None;
+    let mut offset = 0;
+
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        if let Some((fence, block)) = &mut open {
+            if trimmed.starts_with(*fence) && trimmed.trim_end() == *fence {
+                block.end = offset + line.len();
+                blocks.push(open.take().unwrap().1);
+            } else {
+                block.code.push_str(line);
+            }
+        } else {
+            for fence in ["```", "~~~"] {
+                if trimmed.starts_with(fence) {
+                    open = Some((
+                        fence,
+                        FencedBlock {
+                            start: offset,
+                            end: markdown.len(),
+                            info_pos: offset + indent + fence.len(),
+                            info: trimmed[fence.len()..].trim().to_owned(),
+                            code: String::new(),
+                        },
+                    ));
+                    break;
+                }
+            }
+        }
+
+        offset += line.len();
+    }
+
+    // unterminated blocks still render as code
+    if let Some((_, block)) = open {
+        blocks.push(block);
+    }
+
+    blocks
+}
+
+/// (language, [(pattern, weight)])
+const KEYWORDS: &[(&str, &[(&str, i32)])] = &[
+    (
+        "rust",
+        &[
+            ("fn ", 3),
+            ("let mut ", 4),
+            ("impl ", 3),
+            ("pub fn ", 4),
+            ("use std::", 5),
+            ("println!", 5),
+            ("-> ", 1),
+            ("::", 1),
+            ("&mut ", 3),
+            ("#[derive", 5),
+            ("match ", 1),
+        ],
+    ),
+    (
+        "python",
+        &[
+            ("def ", 4),
+            ("import ", 2),
+            ("from ", 1),
+            ("self.", 2),
+            ("elif ", 5),
+            ("print(", 3),
+            ("__init__", 5),
+            ("None", 2),
+            ("True", 1),
+        ],
+    ),
+    (
+        "javascript",
+        &[
+            ("const ", 2),
+            ("function ", 3),
+            ("=> ", 2),
+            ("console.log", 5),
+            ("let ", 1),
+            ("require(", 4),
+            ("document.", 4),
+            ("===", 3),
+        ],
+    ),
+    (
+        "typescript",
+        &[
+            ("interface ", 3),
+            (": string", 4),
+            (": number", 4),
+            ("export type ", 5),
+            (": boolean", 4),
+        ],
+    ),
+    (
+        "c",
+        &[
+            ("#include <", 4),
+            ("int main(", 3),
+            ("printf(", 4),
+            ("malloc(", 4),
+        ],
+    ),
+    (
+        "cpp",
+        &[
+            ("std::", 4),
+            ("#include <iostream>", 6),
+            ("cout <<", 5),
+            ("template<", 4),
+            ("nullptr", 4),
+        ],
+    ),
+    (
+        "go",
+        &[("package ", 4), ("func ", 4), (":= ", 3), ("fmt.", 5)],
+    ),
+    (
+        "java",
+        &[
+            ("public class ", 5),
+            ("System.out.", 6),
+            ("public static void ", 5),
+            ("private ", 1),
+        ],
+    ),
+    (
+        "bash",
+        &[
+            ("echo ", 3),
+            ("sudo ", 4),
+            ("fi\n", 4),
+            ("then\n", 3),
+            ("apt ", 3),
+            ("cd ", 2),
+            ("$ ", 2),
+        ],
+    ),
+    (
+        "html",
+        &[("<html", 6), ("<div", 4), ("</", 2), ("<!DOCTYPE", 6)],
+    ),
+    (
+        "css",
+        &[("px;", 4), ("color:", 3), ("margin:", 3), ("display:", 3)],
+    ),
+    (
+        "sql",
+        &[
+            ("SELECT ", 4),
+            ("FROM ", 2),
+            ("WHERE ", 3),
+            ("INSERT INTO ", 5),
+            ("CREATE TABLE ", 5),
+        ],
+    ),
+    ("toml", &[("[package]", 6), ("[dependencies]", 6)]),
+];
+
+/// Guess the language of a code snippet. Returns `None` if there is no convincing guess.
+pub fn detect_language(code: &str) -> Option<&'static str> {
+    const MIN_SCORE: i32 = 4;
+
+    let code = code.trim();
+    if code.is_empty() {
+        return None;
+    }
+
+    // shebangs are the most reliable hint
+    if let Some(first_line) = code.lines().next().filter(|l| l.starts_with("#!")) {
+        for (needle, lang) in [
+            ("python", "python"),
+            ("node", "javascript"),
+            ("bash", "bash"),
+            ("/sh", "bash"),
+            ("zsh", "bash"),
+            ("ruby", "ruby"),
+            ("perl", "perl"),
+        ] {
+            if first_line.contains(needle) {
+                return Some(lang);
+            }
+        }
+    }
+
+    if (code.starts_with('{') || code.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(code).is_ok()
+    {
+        return Some("json");
+    }
+
+    KEYWORDS
+        .iter()
+        .map(|(lang, patterns)| {
+            let score: i32 = patterns
+                .iter()
+                .map(|(pat, weight)| code.matches(pat).count().min(3) as i32 * weight)
+                .sum();
+            (*lang, score)
+        })
+        .filter(|(_, score)| *score >= MIN_SCORE)
+        .max_by_key(|(_, score)| *score)
+        .map(|(lang, _)| lang)
+}
+
+/// If a reply consists of a short preamble ("Sure! Here's…") followed by a single code
+/// block and nothing else, returns the byte offset where the code block starts.
+pub fn preamble_split(markdown: &str) -> Option<usize> {
+    const MAX_PREAMBLE_LEN: usize = 240;
+    const MAX_PREAMBLE_LINES: usize = 3;
+
+    let blocks = fenced_blocks(markdown);
+    let [block] = blocks.as_slice() else {
+        return None;
+    };
+
+    let preamble = markdown[..block.start].trim();
+    if preamble.is_empty()
+        || preamble.len() > MAX_PREAMBLE_LEN
+        || preamble.lines().count() > MAX_PREAMBLE_LINES
+        || !markdown[block.end..].trim().is_empty()
+    {
+        return None;
+    }
+
+    Some(block.start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reply shapes as models produce them
+    #[test]
+    fn preambles() {
+        let reply = "Sure! Here's the function:\n\n```rust\nfn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n```\n";
+        assert_eq!(preamble_split(reply), reply.find("```"));
+
+        let reply = "Here you go:\n~~~python\nprint('hi')\n~~~";
+        assert_eq!(preamble_split(reply), Some(13));
+
+        // indented fences and trailing whitespace still count
+        let reply = "Certainly.\n  ```\n  ls -la\n  ```\n\n";
+        assert_eq!(preamble_split(reply), Some(11));
+
+        // an unterminated block, like a reply cut off while streaming
+        let reply = "Here is the script:\n```bash\necho hi\n";
+        assert_eq!(preamble_split(reply), Some(20));
+    }
+
+    #[test]
+    fn not_preambles() {
+        // no preamble, nothing to collapse
+        assert_eq!(preamble_split("```\ncode\n```"), None);
+        // no code
+        assert_eq!(preamble_split("Sure! Happy to help."), None);
+        assert_eq!(preamble_split(""), None);
+        // an explanation after the code
+        assert_eq!(
+            preamble_split("Here:\n```\ncode\n```\nThis prints the result."),
+            None
+        );
+        // two blocks
+        assert_eq!(
+            preamble_split("First:\n```\na\n```\nThen:\n```\nb\n```"),
+            None
+        );
+        // a long explanation isn't a preamble
+        let reply = format!("{}\n```\ncode\n```", "Let me explain. ".repeat(20));
+        assert_eq!(preamble_split(&reply), None);
+        let reply = "One.\nTwo.\nThree.\nFour.\n```\ncode\n```";
+        assert_eq!(preamble_split(reply), None);
+    }
+
+    #[test]
+    fn blocks() {
+        let markdown = "Text\n```py\nx = 1\n```\nMore\n~~~\n```not a fence```\n~~~\n";
+        let blocks = fenced_blocks(markdown);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].info, "py");
+        assert_eq!(blocks[0].code, "x = 1\n");
+        assert_eq!(
+            &markdown[blocks[0].start..blocks[0].end],
+            "```py\nx = 1\n```\n"
+        );
+        assert_eq!(&markdown[blocks[0].info_pos..].lines().next(), &Some("py"));
+        assert_eq!(blocks[1].info, "");
+        assert_eq!(blocks[1].code, "```not a fence```\n");
+    }
+
+    #[test]
+    fn languages() {
+        assert_eq!(
+            detect_language("#!/usr/bin/env python3\nprint(1)"),
+            Some("python")
+        );
+        assert_eq!(detect_language("{\"a\": [1, 2]}"), Some("json"));
+        assert_eq!(
+            detect_language("fn main() {\n    let x = 1;\n    println!(\"{x}\");\n}"),
+            Some("rust")
+        );
+        assert_eq!(detect_language("hello world"), None);
+        assert_eq!(detect_language("  \n"), None);
+    }
+}
@@ -0,0 +1,439 @@
+//! In-memory log of everything routed through the `log` crate, shown in the "Logs" window
+//! so failures are visible without launching the app from a terminal. Records still go to
+//! stderr as filtered by `RUST_LOG`, and to a log file if enabled in the settings.
+
+use crate::i18n::{tr, tr_args};
+use anyhow::{Context, Result};
+use eframe::egui::{self, Color32, RichText};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, OnceLock,
+    },
+};
+
+/// Number of records kept, the oldest are dropped first
+const CAPACITY: usize = 2000;
+
+/// Size a log file grows to before it's rotated
+const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Number of log files kept, counting the one being written
+const KEPT_FILES: usize = 3;
+
+static RECORDS: Mutex<VecDeque<LogRecord>> = Mutex::new(VecDeque::new());
+
+/// Errors logged since the log window was last looked at
+static UNSEEN_ERRORS: AtomicUsize = AtomicUsize::new(0);
+
+static FILE_LOG: Mutex<FileLog> = Mutex::new(FileLog {
+    requested: None,
+    file: None,
+    error: None,
+});
+
+/// Most verbose level written to the log file, `Off` without one
+static FILE_LEVEL: AtomicUsize = AtomicUsize::new(LevelFilter::Off as usize);
+
+/// Most verbose level `env_logger` writes to stderr
+static STDERR_LEVEL: AtomicUsize = AtomicUsize::new(LevelFilter::Off as usize);
+
+fn load_level(level: &AtomicUsize) -> LevelFilter {
+    match level.load(Ordering::Relaxed) {
+        1 => LevelFilter::Error,
+        2 => LevelFilter::Warn,
+        3 => LevelFilter::Info,
+        4 => LevelFilter::Debug,
+        5 => LevelFilter::Trace,
+        _ => LevelFilter::Off,
+    }
+}
+
+#[inline]
+fn file_level() -> LevelFilter {
+    load_level(&FILE_LEVEL)
+}
+
+/// The log file as configured in the settings.
+struct FileLog {
+    /// Path from the settings, kept so a file that failed to open isn't retried every frame
+    requested: Option<PathBuf>,
+    file: Option<LogFile>,
+    /// Why the file couldn't be opened
+    error: Option<String>,
+}
+
+/// Log file, rotated to `<name>.1`, `<name>.2`, … once it grows past [`MAX_FILE_SIZE`].
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl LogFile {
+    fn open(path: PathBuf) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let file = File::options()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        let size = file.metadata().map_or(0, |m| m.len());
+        Ok(Self { path, file, size })
+    }
+
+    /// `path` with the number of an older file appended.
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{n}"));
+        name.into()
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        // the oldest file is overwritten by the next one
+        for n in (1..KEPT_FILES).rev() {
+            let from = if n == 1 {
+                self.path.clone()
+            } else {
+                self.rotated_path(n - 1)
+            };
+            if from.exists() {
+                std::fs::rename(from, self.rotated_path(n))?;
+            }
+        }
+        self.file = File::create(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+
+    fn write(&mut self, record: &Record) {
+        let line = format!(
+            "{} {:<5} {}: {}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            record.level(),
+            record.module_path().unwrap_or(record.target()),
+            record.args()
+        );
+        if self.size + line.len() as u64 > MAX_FILE_SIZE && self.size > 0 {
+            if let Err(e) = self.rotate() {
+                // logging it would end up here again
+                eprintln!("failed to rotate {}: {e}", self.path.display());
+            }
+        }
+        if self.file.write_all(line.as_bytes()).is_ok() {
+            self.size += line.len() as u64;
+        }
+    }
+}
+
+/// Where the log file goes unless another path is set.
+pub fn default_file_path() -> Option<&'static Path> {
+    static PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
+    PATH.get_or_init(|| eframe::storage_dir(crate::TITLE).map(|dir| dir.join("ellama.log")))
+        .as_deref()
+}
+
+/// Write records up to `level` to the file at `path`, or stop writing the log file if
+/// `path` is `None`. Cheap if that's the current configuration already.
+pub fn configure_file(path: Option<&Path>, level: LevelFilter) {
+    let Ok(mut file_log) = FILE_LOG.lock() else {
+        return;
+    };
+    let reopen = file_log.requested.as_deref() != path;
+    let level = if path.is_some() {
+        level
+    } else {
+        LevelFilter::Off
+    };
+    if !reopen && file_level() == level {
+        return;
+    }
+    if reopen {
+        file_log.requested = path.map(Path::to_path_buf);
+        file_log.file = None;
+        file_log.error = None;
+        if let Some(path) = path {
+            match LogFile::open(path.to_owned()) {
+                Ok(file) => file_log.file = Some(file),
+                Err(e) => file_log.error = Some(format!("{e:#}")),
+            }
+        }
+    }
+    let opened = file_log.file.is_some();
+    FILE_LEVEL.store(level as usize, Ordering::Relaxed);
+    drop(file_log);
+    update_max_level();
+    match path {
+        Some(path) if opened => log::info!("logging to {} at level {level}", path.display()),
+        Some(path) if reopen => log::error!("failed to open the log file {}", path.display()),
+        _ => {}
+    }
+}
+
+/// Why the log file couldn't be opened, if it couldn't.
+pub fn file_error() -> Option<String> {
+    FILE_LOG.lock().ok()?.error.clone()
+}
+
+/// Let through what any of the outputs wants.
+fn update_max_level() {
+    log::set_max_level(
+        load_level(&STDERR_LEVEL)
+            .max(LevelFilter::Debug)
+            .max(file_level()),
+    );
+}
+
+/// Show `dir` in the system file manager.
+pub fn reveal(dir: &Path) -> Result<()> {
+    #[cfg(target_os = "windows")]
+    let program = "explorer";
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let program = "xdg-open";
+    std::process::Command::new(program)
+        .arg(dir)
+        .spawn()
+        .with_context(|| format!("failed to run {program}"))?;
+    Ok(())
+}
+
+struct LogRecord {
+    time: chrono::DateTime<chrono::Local>,
+    level: Level,
+    target: String,
+    message: String,
+}
+
+impl LogRecord {
+    fn to_line(&self) -> String {
+        format!(
+            "{} {:<5} {}: {}",
+            self.time.format("%H:%M:%S%.3f"),
+            self.level,
+            self.target,
+            self.message
+        )
+    }
+}
+
+/// Passes records on to `env_logger` and keeps a copy of them in memory.
+struct Logger {
+    stderr: env_logger::Logger,
+}
+
+impl Logger {
+    /// Whether a record is kept in memory. Debug output of the app itself is kept, other
+    /// crates are too chatty below info.
+    fn captures(metadata: &Metadata) -> bool {
+        let ours = metadata.target().starts_with(env!("CARGO_CRATE_NAME"));
+        metadata.level() <= if ours { Level::Debug } else { Level::Info }
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        Self::captures(metadata)
+            || metadata.level() <= file_level()
+            || self.stderr.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.stderr.matches(record) {
+            self.stderr.log(record);
+        }
+        if record.level() <= file_level() {
+            if let Ok(mut file_log) = FILE_LOG.lock() {
+                if let Some(file) = &mut file_log.file {
+                    file.write(record);
+                }
+            }
+        }
+        if !Self::captures(record.metadata()) {
+            return;
+        }
+        if record.level() == Level::Error {
+            UNSEEN_ERRORS.fetch_add(1, Ordering::Relaxed);
+        }
+        let Ok(mut records) = RECORDS.lock() else {
+            return;
+        };
+        if records.len() >= CAPACITY {
+            records.pop_front();
+        }
+        records.push_back(LogRecord {
+            time: chrono::Local::now(),
+            level: record.level(),
+            target: record.target().to_owned(),
+            message: record.args().to_string(),
+        });
+    }
+
+    fn flush(&self) {
+        self.stderr.flush();
+        if let Ok(mut file_log) = FILE_LOG.lock() {
+            if let Some(file) = &mut file_log.file {
+                let _ = file.file.flush();
+            }
+        }
+    }
+}
+
+/// Install the logger, replacing `env_logger::init`.
+pub fn init() {
+    let stderr = env_logger::Builder::from_default_env().build();
+    STDERR_LEVEL.store(stderr.filter() as usize, Ordering::Relaxed);
+    match log::set_boxed_logger(Box::new(Logger { stderr })) {
+        Ok(()) => update_max_level(),
+        Err(e) => eprintln!("failed to install the logger: {e}"),
+    }
+}
+
+/// Number of errors logged since the log window was last open.
+pub fn unseen_errors() -> usize {
+    UNSEEN_ERRORS.load(Ordering::Relaxed)
+}
+
+fn level_color(ui: &egui::Ui, level: Level) -> Color32 {
+    match level {
+        Level::Error => ui.visuals().error_fg_color,
+        Level::Warn => ui.visuals().warn_fg_color,
+        Level::Info => ui.visuals().text_color(),
+        Level::Debug | Level::Trace => ui.visuals().weak_text_color(),
+    }
+}
+
+/// The "Logs" window with its filters.
+pub struct LogViewer {
+    pub open: bool,
+    /// Levels shown, indexed by `Level as usize - 1`
+    levels: [bool; 5],
+    filter: String,
+}
+
+impl Default for LogViewer {
+    fn default() -> Self {
+        Self {
+            open: false,
+            levels: [true, true, true, false, false],
+            filter: String::new(),
+        }
+    }
+}
+
+impl LogViewer {
+    const LEVELS: [Level; 4] = [Level::Error, Level::Warn, Level::Info, Level::Debug];
+
+    fn shows(&self, record: &LogRecord, filter: &str) -> bool {
+        self.levels[record.level as usize - 1]
+            && (filter.is_empty()
+                || record.message.to_lowercase().contains(filter)
+                || record.target.to_lowercase().contains(filter))
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if !self.open {
+            return;
+        }
+        // everything is in view now
+        UNSEEN_ERRORS.store(0, Ordering::Relaxed);
+
+        let mut open = self.open;
+        egui::Window::new(tr("logs.title"))
+            .id(egui::Id::new("log_viewer"))
+            .open(&mut open)
+            .default_size([640.0, 360.0])
+            .show(ctx, |ui| self.show_inner(ui));
+        self.open = open;
+    }
+
+    fn show_inner(&mut self, ui: &mut egui::Ui) {
+        let filter = self.filter.to_lowercase();
+        ui.horizontal(|ui| {
+            for level in Self::LEVELS {
+                let shown = &mut self.levels[level as usize - 1];
+                ui.toggle_value(
+                    shown,
+                    RichText::new(level.as_str()).color(level_color(ui, level)),
+                );
+            }
+            ui.separator();
+            ui.add(
+                egui::TextEdit::singleline(&mut self.filter)
+                    .hint_text(tr("logs.filter"))
+                    .desired_width(160.0),
+            );
+            if ui.button(tr("logs.copy_all")).clicked() {
+                let (shown, _) = self.snapshot(&filter);
+                let text = shown
+                    .into_iter()
+                    .map(|(_, line)| line)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                ui.ctx().copy_text(text);
+            }
+            if ui.button(tr("logs.clear")).clicked() {
+                if let Ok(mut records) = RECORDS.lock() {
+                    records.clear();
+                }
+            }
+        });
+        ui.separator();
+
+        let (shown, total) = self.snapshot(&filter);
+        if shown.is_empty() {
+            ui.weak(if total == 0 {
+                tr("logs.empty")
+            } else {
+                tr("logs.no_matches")
+            });
+            return;
+        }
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        egui::ScrollArea::both()
+            .auto_shrink([false, false])
+            .max_height(ui.available_height() - row_height - ui.spacing().item_spacing.y)
+            .stick_to_bottom(true)
+            .show_rows(ui, row_height, shown.len(), |ui, range| {
+                for (level, line) in &shown[range] {
+                    ui.add(
+                        egui::Label::new(
+                            RichText::new(line)
+                                .monospace()
+                                .color(level_color(ui, *level)),
+                        )
+                        .wrap(false),
+                    );
+                }
+            });
+        ui.weak(tr_args("logs.count", &[&shown.len(), &total]));
+    }
+
+    /// Lines of the records passing the filters and the number of records. Copied out so
+    /// the lock isn't held while drawing, which may log itself.
+    fn snapshot(&self, filter: &str) -> (Vec<(Level, String)>, usize) {
+        let Ok(records) = RECORDS.lock() else {
+            return (Vec::new(), 0);
+        };
+        let shown = records
+            .iter()
+            .filter(|r| self.shows(r, filter))
+            .map(|r| (r.level, r.to_line()))
+            .collect();
+        (shown, records.len())
+    }
+}
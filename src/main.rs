@@ -2,14 +2,31 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
 use eframe::egui;
-use ollama_rs::Ollama;
 use sessions::Sessions;
+use widgets::Settings;
 
+mod backend;
+mod backup;
 mod chat;
+mod cli;
 mod easymark;
+mod golden;
+mod html;
+mod i18n;
 mod image;
+mod knowledge;
+mod langdetect;
+mod logs;
+mod math;
+mod memory;
+mod model_table;
+mod secrets;
 mod sessions;
 mod style;
+mod template;
+#[cfg(feature = "voice")]
+mod voice;
+mod wake;
 mod widgets;
 
 const TITLE: &str = "Ellama";
@@ -38,7 +55,8 @@ fn load_icon() -> egui::IconData {
 
 #[tokio::main]
 async fn main() {
-    env_logger::init();
+    logs::init();
+    let args = cli::Args::parse();
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_icon(load_icon()),
         ..Default::default()
@@ -46,30 +64,18 @@ async fn main() {
     eframe::run_native(
         TITLE,
         native_options,
-        Box::new(|cc| Box::new(Ellama::new(cc))),
+        Box::new(|cc| Box::new(Ellama::new(cc, args))),
     )
     .expect("failed to run app");
 }
 
-#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Default, serde::Deserialize, serde::Serialize)]
 struct Ellama {
     sessions: Sessions,
-    #[serde(skip)]
-    ollama: Ollama,
-}
-
-impl Default for Ellama {
-    fn default() -> Self {
-        let ollama = Ollama::default();
-        Self {
-            sessions: Sessions::new(ollama.clone()),
-            ollama,
-        }
-    }
 }
 
 impl Ellama {
-    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    fn new(cc: &eframe::CreationContext<'_>, args: cli::Args) -> Self {
         // change visuals
         style::set_style(&cc.egui_ctx);
         egui_extras::install_image_loaders(&cc.egui_ctx);
@@ -80,30 +86,77 @@ impl Ellama {
             eframe::storage_dir(TITLE)
         );
 
-        if let Some(storage) = cc.storage {
-            if let Some(mut app_state) = eframe::get_value::<Self>(storage, eframe::APP_KEY) {
-                log::debug!("app state successfully restored from storage");
-                app_state.sessions.list_models(app_state.ollama.clone());
-                app_state.ollama = app_state.sessions.settings.make_ollama();
-                return app_state;
-            }
-        }
-
-        log::debug!("app state is not saved in storage, using default app state");
+        let restored = cc
+            .storage
+            .and_then(|storage| eframe::get_value::<Self>(storage, eframe::APP_KEY));
+        let mut app = if let Some(mut app_state) = restored {
+            log::debug!("app state successfully restored from storage");
+            app_state.sessions.purge_deleted();
+            app_state.sessions.migrate_chat_settings();
+            app_state.sessions.clamp_settings();
+            app_state.sessions.settings.auth.load_secret();
+            app_state.sessions.settings.proxy.load_password();
+            app_state
+        } else {
+            log::debug!("app state is not saved in storage, using default app state");
+            Self::default()
+        };
 
-        // default app
-        let app = Self::default();
+        // precedence: --host > OLLAMA_HOST > saved setting > default
+        let host = args.host.or_else(|| {
+            let host = std::env::var("OLLAMA_HOST").ok()?;
+            if host.trim().is_empty() {
+                return None;
+            }
+            Settings::parse_ollama_host(&host)
+                .map_err(|e| log::warn!("ignoring invalid OLLAMA_HOST `{host}`: {e}"))
+                .ok()
+        });
+        app.sessions.settings.endpoint_override = host;
+        app.sessions.connect();
+        app.sessions.apply_startup_args(args.model, args.new_chat);
+        app.sessions.settings.language.apply();
+        app.sessions.settings.file_log.apply();
+        style::apply_theme(
+            &cc.egui_ctx,
+            app.sessions
+                .settings
+                .theme
+                .is_dark(cc.integration_info.system_theme),
+        );
         app
     }
 }
 
 impl eframe::App for Ellama {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        self.sessions.show(ctx, &self.ollama);
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        // eframe switches to the system theme when it changes, so this also undoes that
+        // when a theme is chosen explicitly
+        let dark = self
+            .sessions
+            .settings
+            .theme
+            .is_dark(frame.info().system_theme);
+        style::apply_theme(ctx, dark);
+        self.sessions.settings.language.apply();
+        self.sessions.settings.file_log.apply();
+        self.sessions.show(ctx);
     }
 
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        if !self.sessions.is_dirty() {
+            return;
+        }
         log::debug!("saving app state");
+        self.sessions.purge_deleted();
+        // serialized here on the UI thread, eframe only writes the file in the background.
+        // Streaming replies keep the state dirty, so this runs once per autosave interval
+        // while a reply streams
         eframe::set_value(storage, eframe::APP_KEY, self);
+        self.sessions.mark_saved();
+    }
+
+    fn auto_save_interval(&self) -> std::time::Duration {
+        self.sessions.autosave_interval()
     }
 }
@@ -0,0 +1,738 @@
+//! LaTeX math in replies: `$…$`, `$$…$$`, `\(…\)` and `\[…\]` spans are turned into plain
+//! Unicode text before the markdown is rendered. Formulas that can't be written that way are
+//! shown as inline code instead. Nothing is rasterized, there is no TeX layout: fractions
+//! are written on one line like "(a + b)/2" and matrices fall back to code.
+
+use crate::langdetect;
+use std::{iter::Peekable, ops::Range, str::Chars};
+
+/// A math span found in a markdown document.
+struct MathSpan {
+    /// Byte range of the span including its delimiters
+    range: Range<usize>,
+    /// Byte range of the formula
+    tex: Range<usize>,
+    /// Whether it's display math (`$$…$$` or `\[…\]`)
+    display: bool,
+}
+
+/// Replace the math spans outside of code in `markdown` with their Unicode rendering.
+pub fn render_math(markdown: &str) -> String {
+    let code = code_ranges(markdown);
+    let mut out = String::with_capacity(markdown.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i < markdown.len() {
+        if let Some(range) = code.iter().find(|r| r.contains(&i)) {
+            i = range.end;
+            continue;
+        }
+        if markdown[i..].starts_with("\\$") {
+            i += 2;
+            continue;
+        }
+        if let Some(span) = math_span(markdown, i).filter(|s| !overlaps(&code, &s.range)) {
+            out.push_str(&markdown[copied..i]);
+            out.push_str(&render_span(markdown, &span));
+            copied = span.range.end;
+            i = span.range.end;
+            continue;
+        }
+        i += markdown[i..].chars().next().map_or(1, char::len_utf8);
+    }
+    out.push_str(&markdown[copied..]);
+    out
+}
+
+/// Fenced code blocks and inline code spans, where dollars are not math.
+fn code_ranges(markdown: &str) -> Vec<Range<usize>> {
+    let fenced: Vec<_> = langdetect::fenced_blocks(markdown)
+        .into_iter()
+        .map(|b| b.start..b.end)
+        .collect();
+    let bytes = markdown.as_bytes();
+    let backticks = |from: usize| bytes[from..].iter().take_while(|&&b| b == b'`').count();
+    let mut inline = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if let Some(range) = fenced.iter().find(|r| r.contains(&i)) {
+            i = range.end;
+            continue;
+        }
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+        // an inline code span ends at the next run of exactly as many backticks
+        let run = backticks(i);
+        let mut end = None;
+        let mut j = i + run;
+        while let Some(pos) = markdown[j..].find('`') {
+            let len = backticks(j + pos);
+            if len == run {
+                end = Some(j + pos + len);
+                break;
+            }
+            j += pos + len;
+        }
+        match end {
+            Some(end) => {
+                inline.push(i..end);
+                i = end;
+            }
+            None => i += run,
+        }
+    }
+    fenced.into_iter().chain(inline).collect()
+}
+
+fn overlaps(ranges: &[Range<usize>], range: &Range<usize>) -> bool {
+    ranges
+        .iter()
+        .any(|r| r.start < range.end && range.start < r.end)
+}
+
+/// The math span starting at byte `i`, if any.
+fn math_span(text: &str, i: usize) -> Option<MathSpan> {
+    let rest = &text[i..];
+    let (open, close, display) = if rest.starts_with("$$") {
+        ("$$", "$$", true)
+    } else if rest.starts_with("\\[") {
+        ("\\[", "\\]", true)
+    } else if rest.starts_with("\\(") {
+        ("\\(", "\\)", false)
+    } else if rest.starts_with('$') {
+        ("$", "$", false)
+    } else {
+        return None;
+    };
+    let start = i + open.len();
+    let end = start + text[start..].find(close)?;
+    let tex = &text[start..end];
+    if tex.trim().is_empty() {
+        return None;
+    }
+    if open == "$" {
+        // like pandoc, so that prices such as "$5 to $10" stay text: no whitespace inside the
+        // dollars, no digit right after the closing one, and no line breaks
+        let after = text[end + 1..].chars().next();
+        if tex.starts_with(char::is_whitespace)
+            || tex.ends_with(char::is_whitespace)
+            || tex.contains('\n')
+            || after.is_some_and(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+    }
+    Some(MathSpan {
+        range: i..end + close.len(),
+        tex: start..end,
+        display,
+    })
+}
+
+fn render_span(text: &str, span: &MathSpan) -> String {
+    let tex = text[span.tex.clone()].trim();
+    let Some(math) = tex_to_unicode(tex) else {
+        return code_span(&tex.split_whitespace().collect::<Vec<_>>().join(" "));
+    };
+    let math = escape_markdown(&math);
+    if !span.display {
+        return math.replace('\n', " ");
+    }
+    // keep the lines of display math inside the list item or quote it's written in
+    let line_start = text[..span.range.start].rfind('\n').map_or(0, |p| p + 1);
+    let prefix: String = text[line_start..span.range.start]
+        .chars()
+        .take_while(|c| matches!(c, ' ' | '\t' | '>'))
+        .collect();
+    math.replace('\n', &format!("  \n{prefix}"))
+}
+
+/// `text` as an inline code span, with enough backticks around it.
+fn code_span(text: &str) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in text.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    let fence = "`".repeat(longest + 1);
+    format!("{fence} {text} {fence}")
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#' | '~' | '$'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Unicode rendering of a TeX formula, `None` if it uses something that can't be shown as
+/// plain text.
+fn tex_to_unicode(tex: &str) -> Option<String> {
+    let mut parser = TexParser {
+        chars: tex.chars().peekable(),
+    };
+    let math = parser.expr(false)?;
+    Some(
+        math.lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+/// Operators written with spaces around them, unless they are unary.
+const SPACED_OPERATORS: &str = "=<>+−×÷±∓≤≥≠≈≡∼≃≅∝∈∉⊂⊆⊃⊇→←⇒⇐↔⇔↦≪≫";
+
+struct TexParser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl TexParser<'_> {
+    /// Parse up to the closing brace if `in_group`, otherwise to the end of the input.
+    fn expr(&mut self, in_group: bool) -> Option<String> {
+        let mut out = String::new();
+        // function names like `\sin` need a space before a following variable
+        let mut after_name = false;
+        loop {
+            let Some(c) = self.chars.next() else {
+                return (!in_group).then_some(out);
+            };
+            let atom = match c {
+                '}' => return in_group.then_some(out),
+                '^' => {
+                    out += &script(&self.argument()?, superscript, '^');
+                    continue;
+                }
+                '_' => {
+                    out += &script(&self.argument()?, subscript, '_');
+                    continue;
+                }
+                c if c.is_whitespace() => continue,
+                '{' => self.expr(true)?,
+                '\\' => self.command()?,
+                '&' => " ".to_owned(),
+                '~' => "\u{a0}".to_owned(),
+                '-' => "−".to_owned(),
+                '*' => "∗".to_owned(),
+                c => c.to_string(),
+            };
+            if after_name && atom.starts_with(char::is_alphanumeric) {
+                out.push(' ');
+            }
+            after_name = atom.len() > 1 && atom.chars().all(|c| c.is_ascii_alphabetic());
+            push_atom(&mut out, &atom);
+        }
+    }
+
+    /// A single token or a braced group, the argument of a command or a script.
+    fn argument(&mut self) -> Option<String> {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match self.chars.next()? {
+            '{' => self.expr(true),
+            '\\' => self.command(),
+            '-' => Some("−".to_owned()),
+            c => Some(c.to_string()),
+        }
+    }
+
+    /// The contents of a braced group as written, for `\text{…}` and the like.
+    fn raw_group(&mut self) -> Option<String> {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if self.chars.next()? != '{' {
+            return None;
+        }
+        let mut text = String::new();
+        let mut depth = 0;
+        loop {
+            match self.chars.next()? {
+                '{' => depth += 1,
+                '}' if depth == 0 => return Some(text),
+                '}' => depth -= 1,
+                c => text.push(c),
+            }
+        }
+    }
+
+    /// A command, after its backslash.
+    fn command(&mut self) -> Option<String> {
+        let mut name = String::new();
+        while let Some(c) = self.chars.next_if(char::is_ascii_alphabetic) {
+            name.push(c);
+        }
+        if name.is_empty() {
+            let c = self.chars.next()?;
+            return match c {
+                ',' | ':' | ';' | '>' | ' ' => Some(" ".to_owned()),
+                '!' => Some(String::new()),
+                '\\' => Some("\n".to_owned()),
+                '{' | '}' | '$' | '%' | '#' | '&' | '_' => Some(c.to_string()),
+                '|' => Some("‖".to_owned()),
+                _ => None,
+            };
+        }
+        if let Some(symbol) = symbol(&name) {
+            return Some(symbol.to_owned());
+        }
+        match name.as_str() {
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.argument()?;
+                let denominator = self.argument()?;
+                Some(format!("{}/{}", group(&numerator), group(&denominator)))
+            }
+            "sqrt" => {
+                let root = match self.chars.next_if_eq(&'[') {
+                    Some(_) => {
+                        let index: String = self.chars.by_ref().take_while(|&c| c != ']').collect();
+                        match index.trim() {
+                            "3" => "∛",
+                            "4" => "∜",
+                            _ => return None,
+                        }
+                    }
+                    None => "√",
+                };
+                Some(format!("{root}{}", group(&self.argument()?)))
+            }
+            "text" | "textrm" | "textit" | "textbf" | "mbox" | "operatorname" => self.raw_group(),
+            "mathrm" | "mathit" | "mathbf" | "mathsf" | "mathtt" | "boldsymbol" | "bm" => {
+                self.argument()
+            }
+            "mathbb" => Some(self.argument()?.chars().map(double_struck).collect()),
+            "hat" | "widehat" | "bar" | "overline" | "vec" | "dot" | "ddot" | "tilde"
+            | "widetilde" => {
+                let accent = match name.as_str() {
+                    "hat" | "widehat" => '\u{302}',
+                    "bar" | "overline" => '\u{305}',
+                    "vec" => '\u{20d7}',
+                    "dot" => '\u{307}',
+                    "ddot" => '\u{308}',
+                    _ => '\u{303}',
+                };
+                let base = self.argument()?;
+                // combining characters only look right on a single letter, except for bars
+                if base.chars().count() != 1 && accent != '\u{305}' {
+                    return None;
+                }
+                Some(base.chars().flat_map(|c| [c, accent]).collect())
+            }
+            "left" | "right" | "bigl" | "bigr" | "Bigl" | "Bigr" | "big" | "Big" | "bigg"
+            | "Bigg" => {
+                // `\left.` is an invisible delimiter
+                self.chars.next_if_eq(&'.');
+                Some(String::new())
+            }
+            "displaystyle" | "textstyle" | "limits" | "nolimits" => Some(String::new()),
+            "quad" => Some("  ".to_owned()),
+            "qquad" => Some("    ".to_owned()),
+            "begin" | "end" => {
+                // environments that only align lines, matrices and the like aren't supported
+                let env = self.raw_group()?;
+                matches!(
+                    env.trim_end_matches('*'),
+                    "aligned" | "align" | "gathered" | "gather" | "split" | "equation"
+                )
+                .then(String::new)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Append a symbol to `out`, with spaces around binary operators.
+fn push_atom(out: &mut String, atom: &str) {
+    let mut chars = atom.chars();
+    let operator = match (chars.next(), chars.next()) {
+        (Some(c), None) => SPACED_OPERATORS.contains(c),
+        _ => false,
+    };
+    if !operator {
+        out.push_str(atom);
+        return;
+    }
+    let before = out.trim_end();
+    let line_start = before.is_empty() || before.ends_with('\n');
+    let unary = line_start
+        || before.ends_with(['(', '[', '{', ','])
+        || before.ends_with(|c: char| SPACED_OPERATORS.contains(c));
+    if unary && matches!(atom, "−" | "+" | "±" | "∓") {
+        out.push_str(atom);
+        return;
+    }
+    out.truncate(before.len());
+    if !line_start {
+        out.push(' ');
+    }
+    out.push_str(atom);
+    out.push(' ');
+}
+
+/// Wrap `text` in parentheses unless it's a single number, name or symbol.
+fn group(text: &str) -> String {
+    let text = text.trim();
+    if text.chars().count() == 1 || text.chars().all(char::is_alphanumeric) {
+        text.to_owned()
+    } else {
+        format!("({text})")
+    }
+}
+
+/// A script in Unicode super- or subscript characters, or like `^(…)` if some are missing.
+fn script(text: &str, map: fn(char) -> Option<char>, marker: char) -> String {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if marker == '^' && matches!(text.as_str(), "′" | "″" | "‴") {
+        return text;
+    }
+    if marker == '^' && text == "∘" {
+        return "°".to_owned();
+    }
+    match text.chars().map(map).collect::<Option<String>>() {
+        Some(script) => script,
+        None => format!("{marker}{}", group(&text)),
+    }
+}
+
+fn superscript(c: char) -> Option<char> {
+    const DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+    Some(match c {
+        '0'..='9' => DIGITS[c as usize - '0' as usize],
+        '+' => '⁺',
+        '−' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'a' => 'ᵃ',
+        'b' => 'ᵇ',
+        'c' => 'ᶜ',
+        'd' => 'ᵈ',
+        'e' => 'ᵉ',
+        'f' => 'ᶠ',
+        'g' => 'ᵍ',
+        'h' => 'ʰ',
+        'i' => 'ⁱ',
+        'j' => 'ʲ',
+        'k' => 'ᵏ',
+        'l' => 'ˡ',
+        'm' => 'ᵐ',
+        'n' => 'ⁿ',
+        'o' => 'ᵒ',
+        'p' => 'ᵖ',
+        'r' => 'ʳ',
+        's' => 'ˢ',
+        't' => 'ᵗ',
+        'u' => 'ᵘ',
+        'v' => 'ᵛ',
+        'w' => 'ʷ',
+        'x' => 'ˣ',
+        'y' => 'ʸ',
+        'z' => 'ᶻ',
+        'A' => 'ᴬ',
+        'B' => 'ᴮ',
+        'D' => 'ᴰ',
+        'E' => 'ᴱ',
+        'G' => 'ᴳ',
+        'H' => 'ᴴ',
+        'I' => 'ᴵ',
+        'J' => 'ᴶ',
+        'K' => 'ᴷ',
+        'L' => 'ᴸ',
+        'M' => 'ᴹ',
+        'N' => 'ᴺ',
+        'O' => 'ᴼ',
+        'P' => 'ᴾ',
+        'R' => 'ᴿ',
+        'T' => 'ᵀ',
+        'U' => 'ᵁ',
+        'V' => 'ⱽ',
+        'W' => 'ᵂ',
+        '∗' => '*',
+        _ => return None,
+    })
+}
+
+fn subscript(c: char) -> Option<char> {
+    const DIGITS: [char; 10] = ['₀', '₁', '₂', '₃', '₄', '₅', '₆', '₇', '₈', '₉'];
+    Some(match c {
+        '0'..='9' => DIGITS[c as usize - '0' as usize],
+        '+' => '₊',
+        '−' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'h' => 'ₕ',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'k' => 'ₖ',
+        'l' => 'ₗ',
+        'm' => 'ₘ',
+        'n' => 'ₙ',
+        'o' => 'ₒ',
+        'p' => 'ₚ',
+        'r' => 'ᵣ',
+        's' => 'ₛ',
+        't' => 'ₜ',
+        'u' => 'ᵤ',
+        'v' => 'ᵥ',
+        'x' => 'ₓ',
+        'β' => 'ᵦ',
+        'γ' => 'ᵧ',
+        'ρ' => 'ᵨ',
+        'φ' => 'ᵩ',
+        'χ' => 'ᵪ',
+        _ => return None,
+    })
+}
+
+fn double_struck(c: char) -> char {
+    match c {
+        'C' => 'ℂ',
+        'H' => 'ℍ',
+        'N' => 'ℕ',
+        'P' => 'ℙ',
+        'Q' => 'ℚ',
+        'R' => 'ℝ',
+        'Z' => 'ℤ',
+        '1' => '𝟙',
+        c => c,
+    }
+}
+
+/// Commands that stand for a single symbol or a function name.
+fn symbol(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" | "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" => "θ",
+        "vartheta" => "ϑ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "pi" => "π",
+        "varpi" => "ϖ",
+        "rho" => "ρ",
+        "varrho" => "ϱ",
+        "sigma" => "σ",
+        "varsigma" => "ς",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" => "ϕ",
+        "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Upsilon" => "Υ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        "times" => "×",
+        "cdot" | "cdotp" => "⋅",
+        "div" => "÷",
+        "pm" => "±",
+        "mp" => "∓",
+        "ast" => "∗",
+        "star" => "⋆",
+        "circ" => "∘",
+        "bullet" => "•",
+        "oplus" => "⊕",
+        "otimes" => "⊗",
+        "setminus" => "∖",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "simeq" => "≃",
+        "cong" => "≅",
+        "propto" => "∝",
+        "ll" => "≪",
+        "gg" => "≫",
+        "in" => "∈",
+        "notin" => "∉",
+        "ni" => "∋",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "supset" => "⊃",
+        "supseteq" => "⊇",
+        "cup" => "∪",
+        "cap" => "∩",
+        "emptyset" | "varnothing" => "∅",
+        "forall" => "∀",
+        "exists" => "∃",
+        "neg" | "lnot" => "¬",
+        "land" | "wedge" => "∧",
+        "lor" | "vee" => "∨",
+        "to" | "rightarrow" => "→",
+        "gets" | "leftarrow" => "←",
+        "Rightarrow" | "implies" => "⇒",
+        "Leftarrow" => "⇐",
+        "leftrightarrow" => "↔",
+        "Leftrightarrow" | "iff" => "⇔",
+        "mapsto" => "↦",
+        "uparrow" => "↑",
+        "downarrow" => "↓",
+        "infty" => "∞",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "sum" => "∑",
+        "prod" => "∏",
+        "int" => "∫",
+        "iint" => "∬",
+        "oint" => "∮",
+        "ldots" | "dots" => "…",
+        "cdots" => "⋯",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        "angle" => "∠",
+        "perp" => "⊥",
+        "parallel" => "∥",
+        "mid" => "∣",
+        "prime" => "′",
+        "hbar" => "ℏ",
+        "ell" => "ℓ",
+        "Re" => "ℜ",
+        "Im" => "ℑ",
+        "aleph" => "ℵ",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "lbrace" => "{",
+        "rbrace" => "}",
+        "vert" => "|",
+        "Vert" => "‖",
+        "sin" => "sin",
+        "cos" => "cos",
+        "tan" => "tan",
+        "cot" => "cot",
+        "sec" => "sec",
+        "csc" => "csc",
+        "arcsin" => "arcsin",
+        "arccos" => "arccos",
+        "arctan" => "arctan",
+        "sinh" => "sinh",
+        "cosh" => "cosh",
+        "tanh" => "tanh",
+        "log" => "log",
+        "ln" => "ln",
+        "exp" => "exp",
+        "lim" => "lim",
+        "max" => "max",
+        "min" => "min",
+        "sup" => "sup",
+        "inf" => "inf",
+        "det" => "det",
+        "gcd" => "gcd",
+        "deg" => "deg",
+        "mod" | "bmod" => " mod ",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline_and_display_spans() {
+        assert_eq!(render_math("Area: $\\pi r^2$."), "Area: πr².");
+        assert_eq!(render_math("\\(a \\le b\\)"), "a ≤ b");
+        assert_eq!(render_math("$$\\frac{a+b}{2}$$"), "(a + b)/2");
+        assert_eq!(render_math("\\[x_1 + x_2\\]"), "x₁ + x₂");
+    }
+
+    #[test]
+    fn prices_stay_text() {
+        for text in [
+            "It costs $5 to $10.",
+            "between $ 3 and 4 $",
+            "\\$x\\$ is escaped",
+            "a lone $ sign",
+            "$$ $$",
+        ] {
+            assert_eq!(render_math(text), text);
+        }
+    }
+
+    #[test]
+    fn code_is_left_alone() {
+        let markdown = "Use `$HOME` and\n```bash\necho $PATH $x$\n```\nthen $x^2$";
+        assert_eq!(
+            render_math(markdown),
+            "Use `$HOME` and\n```bash\necho $PATH $x$\n```\nthen x²"
+        );
+        assert_eq!(render_math("``a ` $x$``"), "``a ` $x$``");
+    }
+
+    #[test]
+    fn display_math_in_lists() {
+        assert_eq!(
+            render_math("- item\n  $$a = 1 \\\\ b = 2$$"),
+            "- item\n  a = 1  \n  b = 2"
+        );
+        assert_eq!(render_math("> $$a \\\\ b$$"), "> a  \n> b");
+    }
+
+    #[test]
+    fn unsupported_falls_back_to_code() {
+        assert_eq!(
+            render_math("$\\begin{pmatrix} 1 \\end{pmatrix}$"),
+            "` \\begin{pmatrix} 1 \\end{pmatrix} `"
+        );
+        assert_eq!(render_math("$\\unknown{x}$"), "` \\unknown{x} `");
+        // unbalanced braces
+        assert_eq!(render_math("${x$"), "` {x `");
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(tex_to_unicode("\\sqrt{2}").as_deref(), Some("√2"));
+        assert_eq!(
+            tex_to_unicode("\\sqrt[3]{x+1}").as_deref(),
+            Some("∛(x + 1)")
+        );
+        assert_eq!(tex_to_unicode("\\mathbb{R}^n").as_deref(), Some("ℝⁿ"));
+        assert_eq!(tex_to_unicode("\\text{if } x").as_deref(), Some("if x"));
+        assert_eq!(tex_to_unicode("\\sin x").as_deref(), Some("sin x"));
+        assert_eq!(tex_to_unicode("-x = y").as_deref(), Some("−x = y"));
+        assert_eq!(tex_to_unicode("90^\\circ").as_deref(), Some("90°"));
+        assert_eq!(tex_to_unicode("x^{q}").as_deref(), Some("x^q"));
+        assert_eq!(tex_to_unicode("\\hat{x}").as_deref(), Some("x\u{302}"));
+        assert_eq!(tex_to_unicode("\\hat{xy}"), None);
+    }
+
+    #[test]
+    fn escapes_markdown() {
+        assert_eq!(render_math("$a*b*c$"), "a∗b∗c");
+        assert_eq!(render_math("$\\text{a_b}$"), "a\\_b");
+    }
+}
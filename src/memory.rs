@@ -0,0 +1,219 @@
+//! Rough estimate of whether a model fits into the memory of this machine. It only looks
+//! at the model size, the context window and the total memory, so it's a hint and not a
+//! promise: other apps, the exact architecture and Ollama's own overhead all shift it.
+
+use std::sync::OnceLock;
+
+use bytesize::ByteSize;
+use eframe::egui::{self, Color32, RichText};
+
+use crate::i18n::{tr, tr_args};
+
+/// Memory a model can be loaded into
+#[derive(Debug, Clone, Copy)]
+pub struct SystemMemory {
+    /// Total system RAM in bytes
+    pub ram: u64,
+    /// Total GPU memory in bytes, `None` if no GPU was detected
+    pub vram: Option<u64>,
+    /// The GPU memory is part of the RAM, like on Apple silicon
+    pub unified: bool,
+}
+
+static MEMORY: OnceLock<SystemMemory> = OnceLock::new();
+
+/// The detected memory, `None` until detection finished. The first call starts it on a
+/// background thread since asking the GPU driver can take a moment.
+pub fn system_memory() -> Option<SystemMemory> {
+    static STARTED: OnceLock<()> = OnceLock::new();
+    STARTED.get_or_init(|| {
+        std::thread::spawn(|| {
+            let memory = detect();
+            log::info!("detected memory: {memory:?}");
+            let _ = MEMORY.set(memory);
+        });
+    });
+    MEMORY.get().copied()
+}
+
+fn detect() -> SystemMemory {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_memory();
+    let ram = sys.total_memory();
+    SystemMemory {
+        ram,
+        vram: gpu_memory(ram),
+        unified: cfg!(all(target_os = "macos", target_arch = "aarch64")),
+    }
+}
+
+/// Apple silicon shares RAM with the GPU, Metal lets it use about two thirds of it.
+#[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+fn gpu_memory(ram: u64) -> Option<u64> {
+    Some(ram / 3 * 2)
+}
+
+/// Total memory of all NVIDIA GPUs, Ollama splits a model across them.
+#[cfg(not(all(target_os = "macos", target_arch = "aarch64")))]
+fn gpu_memory(_ram: u64) -> Option<u64> {
+    let mut cmd = std::process::Command::new("nvidia-smi");
+    cmd.args(["--query-gpu=memory.total", "--format=csv,noheader,nounits"]);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = cmd.output().ok().filter(|output| output.status.success())?;
+    let mib: u64 = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().parse::<u64>().ok())
+        .sum();
+    (mib > 0).then_some(mib * 1024 * 1024)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fit {
+    Comfortable,
+    Tight,
+    TooLarge,
+}
+
+impl Fit {
+    pub fn color(self) -> Color32 {
+        match self {
+            Self::Comfortable => Color32::from_rgb(0x4c, 0xaf, 0x50),
+            Self::Tight => Color32::from_rgb(0xff, 0xc1, 0x07),
+            Self::TooLarge => Color32::from_rgb(0xe5, 0x39, 0x35),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Comfortable => tr("memory.fits"),
+            Self::Tight => tr("memory.tight"),
+            Self::TooLarge => tr("memory.too_large"),
+        }
+    }
+}
+
+/// Parameter count from Ollama's `parameter_size`, e.g. "8.0B", "270M" or "8x7B".
+pub fn parse_parameters(size: &str) -> Option<f64> {
+    let size = size.trim().to_uppercase();
+    let (last, _) = size.char_indices().last()?;
+    let (number, unit) = size.split_at(last);
+    let scale = match unit {
+        "B" => 1e9,
+        "M" => 1e6,
+        "K" => 1e3,
+        _ => return None,
+    };
+    let count = match number.split_once('X') {
+        Some((experts, each)) => experts.parse::<f64>().ok()? * each.parse::<f64>().ok()?,
+        None => number.parse().ok()?,
+    };
+    Some(count * scale)
+}
+
+/// Bytes the KV cache needs for `num_ctx` tokens. Without the architecture this scales with
+/// the square root of the parameter count, which lands close to common f16 caches, e.g.
+/// ~128 KiB per token for an 8B model with grouped-query attention.
+fn kv_cache(parameters: f64, num_ctx: u64) -> u64 {
+    let per_token = 45.0 * 1024.0 * (parameters / 1e9).max(0.1).sqrt();
+    (per_token * num_ctx as f64) as u64
+}
+
+/// Buffers Ollama needs besides the weights and the cache
+const OVERHEAD: u64 = 512 * 1024 * 1024;
+
+pub struct Estimate {
+    pub fit: Fit,
+    /// Why, e.g. "model 41 GB, system RAM 32 GB"
+    pub reason: String,
+}
+
+/// Estimate whether a model of `size` bytes fits with a context of `num_ctx` tokens.
+/// `parameter_size` is Ollama's detail string, the size is used to guess it if it's unknown.
+pub fn estimate(memory: SystemMemory, size: u64, parameter_size: &str, num_ctx: u64) -> Estimate {
+    // most local models are ~4.5 bit quantizations
+    let parameters = parse_parameters(parameter_size).unwrap_or(size as f64 * 8.0 / 4.5);
+    let cache = kv_cache(parameters, num_ctx);
+    let needed = size + cache + OVERHEAD;
+
+    let vram = memory.vram.unwrap_or(0);
+    // unified memory is the RAM itself, so the GPU's share of it isn't extra memory
+    let total = if memory.unified {
+        memory.ram
+    } else {
+        memory.ram + vram
+    };
+    let fit = if needed as f64 <= vram as f64 * 0.9 {
+        Fit::Comfortable
+    } else if vram == 0 && needed as f64 <= memory.ram as f64 * 0.6 {
+        // without a GPU the OS and other apps share the same RAM
+        Fit::Comfortable
+    } else if needed as f64 <= total as f64 * 0.85 {
+        Fit::Tight
+    } else {
+        Fit::TooLarge
+    };
+
+    let mut reason = tr_args(
+        "memory.reason",
+        &[
+            &ByteSize(size),
+            &ByteSize(cache),
+            &num_ctx,
+            &ByteSize(needed),
+            &ByteSize(memory.ram),
+        ],
+    );
+    if let Some(vram) = memory.vram {
+        reason.push_str(&tr_args("memory.reason_gpu", &[&ByteSize(vram)]));
+    }
+    let reason = format!(
+        "{}\n{}\n{}",
+        fit.label(),
+        reason,
+        tr("memory.estimate_note")
+    );
+    Estimate { fit, reason }
+}
+
+/// Colored dot for `estimate` with the reasoning on hover.
+pub fn fit_indicator(ui: &mut egui::Ui, estimate: &Estimate) -> egui::Response {
+    ui.add(egui::Label::new(RichText::new("●").color(estimate.fit.color())).selectable(false))
+        .on_hover_text(&estimate.reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parameters() {
+        assert_eq!(parse_parameters("8.0B"), Some(8e9));
+        assert_eq!(parse_parameters("270M"), Some(270e6));
+        assert_eq!(parse_parameters(" 8x7b "), Some(56e9));
+        assert_eq!(parse_parameters(""), None);
+        assert_eq!(parse_parameters("7 млрд"), None);
+        assert_eq!(parse_parameters("8B½"), None);
+    }
+
+    #[test]
+    fn unified_memory_counted_once() {
+        let gib = 1024 * 1024 * 1024;
+        let memory = SystemMemory {
+            ram: 16 * gib,
+            vram: Some(16 * gib / 3 * 2),
+            unified: true,
+        };
+        // fits into RAM plus the GPU share, but not into the RAM alone
+        assert_eq!(estimate(memory, 20 * gib, "30B", 2048).fit, Fit::TooLarge);
+        let discrete = SystemMemory {
+            unified: false,
+            ..memory
+        };
+        assert_eq!(estimate(discrete, 20 * gib, "30B", 2048).fit, Fit::Tight);
+    }
+}
@@ -0,0 +1,274 @@
+//! Table of all local models in the Model tab, for comparing them side by side and
+//! deleting several at once.
+
+use crate::{
+    i18n::{tr, tr_args},
+    memory,
+    widgets::{parse_modified_at, ModelDetails, ModelTasks, RequestInfoType},
+};
+use eframe::egui::{self, RichText};
+use ollama_rs::models::LocalModel;
+use std::collections::BTreeSet;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    #[default]
+    Name,
+    Size,
+    Parameters,
+    Quantization,
+    Family,
+    Modified,
+}
+
+impl Column {
+    const ALL: [Self; 6] = [
+        Self::Name,
+        Self::Size,
+        Self::Parameters,
+        Self::Quantization,
+        Self::Family,
+        Self::Modified,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Name => tr("model_table.name"),
+            Self::Size => tr("models.size"),
+            Self::Parameters => tr("models.parameters"),
+            Self::Quantization => tr("models.quantization"),
+            Self::Family => tr("model_table.family"),
+            Self::Modified => tr("models.modified"),
+        }
+    }
+
+    fn sort(self, models: &mut [&LocalModel], tasks: &ModelTasks, descending: bool) {
+        let details = |m: &LocalModel| tasks.details.get(&m.name);
+        let text = |m: &LocalModel, field: fn(&ModelDetails) -> &str| {
+            details(m)
+                .map(field)
+                .filter(|s| !s.is_empty())
+                .map(str::to_lowercase)
+        };
+        models.sort_by(|a, b| {
+            let order = match self {
+                Self::Name => a.name.cmp(&b.name),
+                Self::Size => a.size.cmp(&b.size),
+                Self::Parameters => {
+                    let count = |m: &LocalModel| {
+                        details(m).and_then(|d| memory::parse_parameters(&d.parameter_size))
+                    };
+                    count(a)
+                        .partial_cmp(&count(b))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                }
+                Self::Quantization => {
+                    text(a, |d| &d.quantization_level).cmp(&text(b, |d| &d.quantization_level))
+                }
+                Self::Family => text(a, |d| &d.family).cmp(&text(b, |d| &d.family)),
+                Self::Modified => parse_modified_at(&a.modified_at)
+                    .ok()
+                    .cmp(&parse_modified_at(&b.modified_at).ok()),
+            };
+            order.then_with(|| a.name.cmp(&b.name))
+        });
+        if descending {
+            models.reverse();
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ModelTable {
+    sort: Column,
+    descending: bool,
+    filter: String,
+    /// Models ticked for bulk deletion
+    checked: BTreeSet<String>,
+    /// The deletion prompt is open
+    confirm_delete: bool,
+}
+
+impl ModelTable {
+    /// Show the table, returns the model whose row was clicked. `deleting` disables the
+    /// deletion while one is running.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        models: &[LocalModel],
+        tasks: &ModelTasks,
+        selected: &str,
+        deleting: bool,
+        request_info: &mut dyn FnMut(RequestInfoType),
+    ) -> Option<LocalModel> {
+        // forget models that were deleted or renamed in the meantime
+        self.checked
+            .retain(|name| models.iter().any(|m| &m.name == name));
+        if self.checked.is_empty() {
+            self.confirm_delete = false;
+        }
+
+        ui.add(
+            egui::TextEdit::singleline(&mut self.filter)
+                .hint_text(tr("model_table.filter"))
+                .desired_width(f32::INFINITY),
+        );
+        let filter = self.filter.trim().to_lowercase();
+        let mut shown: Vec<&LocalModel> = models
+            .iter()
+            .filter(|m| {
+                filter.is_empty()
+                    || m.name.to_lowercase().contains(&filter)
+                    || tasks
+                        .details
+                        .get(&m.name)
+                        .is_some_and(|d| d.family.to_lowercase().contains(&filter))
+            })
+            .collect();
+        self.sort.sort(&mut shown, tasks, self.descending);
+
+        self.show_delete_bar(ui, &shown, deleting, request_info);
+
+        let mut clicked = None;
+        egui::ScrollArea::horizontal()
+            .id_source("model_table_scroll")
+            .show(ui, |ui| {
+                egui::Grid::new("model_table_grid")
+                    .num_columns(Column::ALL.len() + 1)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        let all_checked = !shown.is_empty()
+                            && shown.iter().all(|m| self.checked.contains(&m.name));
+                        let mut check_all = all_checked;
+                        if ui
+                            .checkbox(&mut check_all, "")
+                            .on_hover_text(tr("model_table.select_all"))
+                            .changed()
+                        {
+                            for model in &shown {
+                                if check_all {
+                                    self.checked.insert(model.name.clone());
+                                } else {
+                                    self.checked.remove(&model.name);
+                                }
+                            }
+                        }
+                        for column in Column::ALL {
+                            let arrow = match (column == self.sort, self.descending) {
+                                (false, _) => "",
+                                (true, false) => " ⏶",
+                                (true, true) => " ⏷",
+                            };
+                            if ui
+                                .selectable_label(
+                                    column == self.sort,
+                                    RichText::new(format!("{}{arrow}", column.title())).strong(),
+                                )
+                                .clicked()
+                            {
+                                if column == self.sort {
+                                    self.descending = !self.descending;
+                                } else {
+                                    self.sort = column;
+                                    self.descending = false;
+                                }
+                            }
+                        }
+                        ui.end_row();
+
+                        for model in &shown {
+                            let mut checked = self.checked.contains(&model.name);
+                            if ui.checkbox(&mut checked, "").changed() {
+                                if checked {
+                                    self.checked.insert(model.name.clone());
+                                } else {
+                                    self.checked.remove(&model.name);
+                                }
+                            }
+                            if ui
+                                .selectable_label(selected == model.name, &model.name)
+                                .on_hover_text(tr("model_table.select_help"))
+                                .clicked()
+                            {
+                                clicked = Some((*model).clone());
+                            }
+                            ui.label(format!("{}", bytesize::ByteSize(model.size)));
+                            let details = tasks.details.get(&model.name);
+                            if details.is_none() {
+                                request_info(RequestInfoType::ModelDetails(&model.name));
+                            }
+                            let field =
+                                |f: fn(&ModelDetails) -> &str| details.map_or("…", f).to_owned();
+                            ui.label(field(|d| &d.parameter_size));
+                            ui.label(field(|d| &d.quantization_level));
+                            ui.label(field(|d| &d.family));
+                            let ago = parse_modified_at(&model.modified_at).map_or_else(
+                                |_| tr("common.unknown").to_owned(),
+                                |time| {
+                                    timeago::Formatter::new()
+                                        .convert_chrono(time, chrono::Utc::now())
+                                },
+                            );
+                            ui.label(ago).on_hover_text(&model.modified_at);
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if models.is_empty() {
+            ui.label(tr("models.none_found"));
+        } else if shown.is_empty() {
+            ui.label(tr("models.none_match"));
+        }
+        clicked
+    }
+
+    /// Row with the number of ticked models and the button to delete them, turning into a
+    /// confirmation prompt when clicked.
+    fn show_delete_bar(
+        &mut self,
+        ui: &mut egui::Ui,
+        shown: &[&LocalModel],
+        deleting: bool,
+        request_info: &mut dyn FnMut(RequestInfoType),
+    ) {
+        ui.horizontal(|ui| {
+            if deleting {
+                ui.add(egui::Spinner::new());
+                ui.label(tr("model_table.deleting"));
+                return;
+            }
+            if self.checked.is_empty() {
+                ui.weak(tr_args("model_table.count", &[&shown.len()]));
+                return;
+            }
+            let count = self.checked.len();
+            if self.confirm_delete {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    tr_args("model_table.confirm_delete", &[&count]),
+                );
+                if ui.button(tr("common.delete")).clicked() {
+                    let names: Vec<String> =
+                        std::mem::take(&mut self.checked).into_iter().collect();
+                    request_info(RequestInfoType::DeleteModels(&names));
+                    self.confirm_delete = false;
+                }
+                if ui.button(tr("common.cancel")).clicked() {
+                    self.confirm_delete = false;
+                }
+            } else {
+                if ui
+                    .button(tr_args("model_table.delete", &[&count]))
+                    .on_hover_text(self.checked.iter().cloned().collect::<Vec<_>>().join("\n"))
+                    .clicked()
+                {
+                    self.confirm_delete = true;
+                }
+                if ui.small_button(tr("model_table.clear")).clicked() {
+                    self.checked.clear();
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,44 @@
+//! Secrets kept in the OS keyring instead of the saved settings. Without the `keyring`
+//! feature, or when no keyring service is running, every call fails and callers keep the
+//! secret with the settings instead.
+
+use anyhow::Result;
+
+/// Service the entries are filed under in the keyring
+#[cfg(feature = "keyring")]
+const SERVICE: &str = "ellama";
+
+/// Store `secret` under `name`, removing the entry if it's empty.
+#[cfg(feature = "keyring")]
+pub fn store(name: &str, secret: &str) -> Result<()> {
+    let entry = keyring::Entry::new(SERVICE, name)?;
+    if secret.is_empty() {
+        match entry.delete_password() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    } else {
+        entry.set_password(secret)?;
+        Ok(())
+    }
+}
+
+/// The secret stored under `name`, `None` if there is none.
+#[cfg(feature = "keyring")]
+pub fn load(name: &str) -> Result<Option<String>> {
+    match keyring::Entry::new(SERVICE, name)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn store(_name: &str, _secret: &str) -> Result<()> {
+    anyhow::bail!("built without keyring support")
+}
+
+#[cfg(not(feature = "keyring"))]
+pub fn load(_name: &str) -> Result<Option<String>> {
+    anyhow::bail!("built without keyring support")
+}
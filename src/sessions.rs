@@ -1,6 +1,18 @@
+#[cfg(feature = "tts")]
+use crate::widgets::SpeechSettings;
 use crate::{
-    chat::{Chat, ChatAction, ChatExportFormat},
-    widgets::{ModelPicker, RequestInfoType, Settings},
+    backend::{Backend, OllamaClient, OpenAiClient, RunningModel},
+    backup::Backup,
+    chat::{self, Chat, ChatAction, ChatExportFormat, FileAttachment},
+    i18n::{tr, tr_args},
+    knowledge::SourcePick,
+    logs::{self, LogViewer},
+    model_table::ModelTable,
+    wake::WakeDetector,
+    widgets::{
+        self, describe_request_error, CreateState, KeyAction, ModelDetails, ModelPicker,
+        ModelTasks, PullState, RequestInfoType, Settings,
+    },
 };
 use eframe::egui::{self, vec2, Color32, Frame, Layout, Rounding, Stroke};
 use egui_commonmark::CommonMarkCache;
@@ -9,15 +21,20 @@ use egui_notify::{Toast, Toasts};
 use egui_twemoji::EmojiLabel;
 use egui_virtual_list::VirtualList;
 use flowync::{CompactFlower, CompactHandle};
-use ollama_rs::{
-    models::{LocalModel, ModelInfo},
-    Ollama,
-};
+use ollama_rs::models::{LocalModel, ModelInfo};
 #[cfg(feature = "tts")]
 use parking_lot::RwLock;
-#[cfg(feature = "tts")]
-use std::sync::Arc;
-use std::{cell::RefCell, collections::HashMap, path::PathBuf, rc::Rc, time::Instant};
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap, HashSet},
+    path::PathBuf,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 #[cfg(feature = "tts")]
 use tts::Tts;
 
@@ -25,18 +42,81 @@ use tts::Tts;
 enum SessionTab {
     #[default]
     Chats,
+    Model,
+}
+
+/// Order of the chats in the sidebar
+#[derive(Default, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+enum ChatOrder {
+    /// The order of `Sessions::chats`, rearranged by dragging
+    #[default]
+    Manual,
+    /// Most recently active first
+    Recent,
+}
+
+impl ChatOrder {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Manual => tr("sidebar.order_manual"),
+            Self::Recent => tr("sidebar.order_recent"),
+        }
+    }
+}
+
+/// Keys that jump to the first nine listed chats, held with Ctrl (Cmd on macOS)
+const JUMP_KEYS: [egui::Key; 9] = [
+    egui::Key::Num1,
+    egui::Key::Num2,
+    egui::Key::Num3,
+    egui::Key::Num4,
+    egui::Key::Num5,
+    egui::Key::Num6,
+    egui::Key::Num7,
+    egui::Key::Num8,
+    egui::Key::Num9,
+];
+
+/// Shortcut jumping to the listed chat at `n`, which must be below 9
+fn jump_shortcut(n: usize) -> egui::KeyboardShortcut {
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, JUMP_KEYS[n])
 }
 
+/// Width of the sidebar while it's collapsed to a strip of icons
+const COLLAPSED_SIDEBAR_WIDTH: f32 = 40.0;
+
+/// Number of deletions that can be undone
+const UNDO_LIMIT: usize = 5;
+
+/// How long the undo notice stays after a deletion
+const UNDO_NOTICE_DURATION: Duration = Duration::from_secs(10);
+
 #[cfg(feature = "tts")]
 pub type SharedTts = Option<Arc<RwLock<Tts>>>;
 
 enum OllamaResponse {
     Ignore,
     Models(Vec<LocalModel>),
-    ModelInfo { name: String, info: ModelInfo },
+    ModelInfo {
+        name: String,
+        info: ModelInfo,
+    },
     Toast(Toast),
-    Images { id: usize, files: Vec<PathBuf> },
+    Images {
+        id: usize,
+        files: Vec<PathBuf>,
+    },
+    KnowledgeSources {
+        uid: u64,
+        paths: Vec<PathBuf>,
+    },
     Settings(Box<Settings>),
+    Backup(Box<Backup>),
+    /// Models removed from the server, and why the others couldn't be
+    ModelsDeleted {
+        deleted: Vec<String>,
+        failed: Vec<String>,
+    },
 }
 
 #[derive(Default, PartialEq, Eq)]
@@ -48,32 +128,298 @@ enum OllamaFlowerActivity {
     ListModels,
     /// Get model info
     ModelInfo,
+    /// Copy a model
+    CopyModel,
+    /// Delete models
+    DeleteModels,
 }
 
 // <progress, response, error>
 type OllamaFlower = CompactFlower<(), OllamaResponse, String>;
 type OllamaFlowerHandle = CompactHandle<(), OllamaResponse, String>;
 
-#[derive(Default, serde::Serialize, serde::Deserialize)]
-struct SelectedModel {
-    name: String,
-    #[serde(default)]
-    modified_ago: String,
-    modified_at: String,
-    size: u64,
+// <(status, completed, total), pulled model (None if cancelled), error>
+type PullFlower = CompactFlower<(String, u64, u64), Option<String>, String>;
+type PullFlowerHandle = CompactHandle<(String, u64, u64), Option<String>, String>;
+
+// <status line, created model, error>
+type CreateFlower = CompactFlower<String, String, String>;
+type CreateFlowerHandle = CompactHandle<String, String, String>;
+
+// <(model name, details), (), error>
+type DetailsFlower = CompactFlower<(String, ModelDetails), (), String>;
+type DetailsFlowerHandle = CompactHandle<(String, ModelDetails), (), String>;
+
+// <(), running models (None if the server doesn't support listing them), error>
+type RunningFlower = CompactFlower<(), Option<Vec<RunningModel>>, String>;
+type RunningFlowerHandle = CompactHandle<(), Option<Vec<RunningModel>>, String>;
+
+#[derive(Default)]
+enum RunningModels {
+    #[default]
+    NotLoaded,
+    Loaded(Vec<RunningModel>),
+    Unsupported,
+    Failed(String),
 }
 
-impl From<LocalModel> for SelectedModel {
-    fn from(model: LocalModel) -> Self {
-        let ago = chrono::DateTime::parse_from_rfc3339(&model.modified_at)
-            .map(|time| timeago::Formatter::new().convert_chrono(time, chrono::Utc::now()))
-            .unwrap_or_else(|e| e.to_string());
-        Self {
-            name: model.name,
-            modified_ago: ago,
-            modified_at: model.modified_at,
-            size: model.size,
+/// Owned version of a [`RequestInfoType`], handled after the widget requesting it is shown
+enum ModelRequest {
+    Models,
+    ModelInfo(String),
+    RefreshModelInfo(String),
+    LoadSettings,
+    PullModel(String),
+    CancelPull,
+    CopyModel { source: String, destination: String },
+    CreateModel { name: String, modelfile: String },
+    ModelDetails(String),
+    SetDefaultModel(Option<String>),
+    Reconnect,
+    ExportBackup,
+    RestoreBackup,
+    DeleteModels(Vec<String>),
+}
+
+impl From<RequestInfoType<'_>> for ModelRequest {
+    fn from(typ: RequestInfoType<'_>) -> Self {
+        match typ {
+            RequestInfoType::Models => Self::Models,
+            RequestInfoType::ModelInfo(name) => Self::ModelInfo(name.to_owned()),
+            RequestInfoType::RefreshModelInfo(name) => Self::RefreshModelInfo(name.to_owned()),
+            RequestInfoType::LoadSettings => Self::LoadSettings,
+            RequestInfoType::ExportBackup => Self::ExportBackup,
+            RequestInfoType::RestoreBackup => Self::RestoreBackup,
+            RequestInfoType::PullModel(name) => Self::PullModel(name.to_owned()),
+            RequestInfoType::CancelPull => Self::CancelPull,
+            RequestInfoType::CopyModel {
+                source,
+                destination,
+            } => Self::CopyModel {
+                source: source.to_owned(),
+                destination: destination.to_owned(),
+            },
+            RequestInfoType::CreateModel { name, modelfile } => Self::CreateModel {
+                name: name.to_owned(),
+                modelfile: modelfile.to_owned(),
+            },
+            RequestInfoType::ModelDetails(name) => Self::ModelDetails(name.to_owned()),
+            RequestInfoType::SetDefaultModel(name) => {
+                Self::SetDefaultModel(name.map(str::to_owned))
+            }
+            RequestInfoType::Reconnect => Self::Reconnect,
+            RequestInfoType::DeleteModels(names) => Self::DeleteModels(names.to_vec()),
+        }
+    }
+}
+
+/// A chat in the "Recently deleted" bin
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DeletedChat {
+    chat: Chat,
+    deleted_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Deletion that can be undone during the session.
+enum Deletion {
+    /// Chat moved to the bin from `index` of the list
+    Chat {
+        uid: u64,
+        index: usize,
+        selected: bool,
+    },
+    Message {
+        chat_uid: u64,
+        index: usize,
+        message: Box<chat::Message>,
+    },
+}
+
+/// Small label with a background color derived from the tag, the same tag always gets the
+/// same color.
+fn tag_chip(tag: &str) -> egui::Label {
+    egui::Label::new(
+        egui::RichText::new(format!(" {tag} "))
+            .small()
+            .color(Color32::WHITE)
+            .background_color(widgets::name_color(tag)),
+    )
+    .selectable(false)
+}
+
+/// Drag-and-drop payload of a chat being reordered in the sidebar, holds its index
+struct DraggedChat(usize);
+
+/// Like [`egui::Ui::dnd_drag_source`], but senses the drag below the contents instead of
+/// on top of them so the buttons in the row stay clickable. Also senses clicks for the
+/// context menu, even if the row isn't `draggable`. The row size is remembered from the
+/// last frame, so it can't be interacted with on the first one.
+fn chat_row_drag_source(
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    payload: DraggedChat,
+    draggable: bool,
+    add_contents: impl FnOnce(&mut egui::Ui),
+) -> egui::Response {
+    let size_id = id.with("row_size");
+    let sense = if draggable {
+        egui::Sense::click_and_drag()
+    } else {
+        egui::Sense::click()
+    };
+    let background = ui
+        .data(|d| d.get_temp::<egui::Vec2>(size_id))
+        .map(|size| ui.interact(egui::Rect::from_min_size(ui.cursor().min, size), id, sense));
+    let content = if draggable && ui.ctx().is_being_dragged(id) {
+        egui::DragAndDrop::set_payload(ui.ctx(), payload);
+        ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+
+        // paint the row in a layer above everything else and move it under the pointer
+        let layer_id = egui::LayerId::new(egui::Order::Tooltip, id);
+        let response = ui.with_layer_id(layer_id, add_contents).response;
+        if let Some(pos) = ui.ctx().pointer_interact_pos() {
+            ui.ctx().transform_layer_shapes(
+                layer_id,
+                egui::emath::TSTransform::from_translation(pos - response.rect.center()),
+            );
+        }
+        response
+    } else {
+        ui.scope(add_contents).response
+    };
+    ui.data_mut(|d| d.insert_temp(size_id, content.rect.size()));
+    background.unwrap_or(content)
+}
+
+/// What to permanently delete from the bin once confirmed
+#[derive(Default, Clone, Copy)]
+enum BinDeletion {
+    #[default]
+    All,
+    One(usize),
+}
+
+/// State of the connection to the Ollama server, driven by the model list requests
+#[derive(Default)]
+enum ConnectionState {
+    #[default]
+    Connecting,
+    Connected,
+    Failed {
+        error: String,
+        /// Number of failed attempts in a row
+        attempts: u32,
+        retry_at: Instant,
+    },
+    /// The server refused the credentials, not retried until they change
+    Unauthorized {
+        error: String,
+    },
+}
+
+impl ConnectionState {
+    const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+    fn failed(&mut self, error: String) {
+        let attempts = match self {
+            Self::Failed { attempts, .. } => *attempts + 1,
+            _ => 1,
+        };
+        // 1s, 2s, 4s, ... up to a minute
+        let delay = Duration::from_secs(1 << (attempts - 1).min(6)).min(Self::MAX_RETRY_DELAY);
+        *self = Self::Failed {
+            error,
+            attempts,
+            retry_at: Instant::now() + delay,
+        };
+    }
+}
+
+/// Search across all chats in the sidebar
+#[derive(Default)]
+struct ChatSearch {
+    query: String,
+    /// Lowercase query the results are for
+    applied: String,
+    /// When the query was last edited, the search runs once typing pauses
+    edited_at: Option<Instant>,
+    /// Order and content of the chats the results were computed for, `None` to rerun
+    fingerprint: Option<u64>,
+    /// When the chats were last checked for changes
+    checked_at: Option<Instant>,
+    results: Vec<SearchResult>,
+    /// Focus the search field on the next frame
+    focus: bool,
+}
+
+struct SearchResult {
+    /// [`Chat::uid`], indices change when chats are moved
+    chat: u64,
+    /// First matching message and a snippet around the match, `None` if only the title
+    /// matched
+    message: Option<(usize, String)>,
+}
+
+impl ChatSearch {
+    const DEBOUNCE: Duration = Duration::from_millis(250);
+
+    fn is_active(&self) -> bool {
+        !self.query.trim().is_empty()
+    }
+
+    /// Rerun the search if the query settled or the chats changed.
+    fn update(&mut self, ctx: &egui::Context, chats: &[Chat]) {
+        if let Some(edited_at) = self.edited_at {
+            let elapsed = edited_at.elapsed();
+            if elapsed < Self::DEBOUNCE {
+                ctx.request_repaint_after(Self::DEBOUNCE - elapsed);
+                return;
+            }
+            self.edited_at = None;
+            self.applied = self.query.trim().to_lowercase();
+            self.fingerprint = None;
+        }
+        if !self.is_active() {
+            return;
         }
+        // hashing every message each frame would be wasteful, changes can wait a moment
+        if self.fingerprint.is_some()
+            && self
+                .checked_at
+                .is_some_and(|checked_at| checked_at.elapsed() < Self::DEBOUNCE)
+        {
+            return;
+        }
+        self.checked_at = Some(Instant::now());
+        let fingerprint = Self::fingerprint(chats);
+        if self.fingerprint == Some(fingerprint) {
+            return;
+        }
+        self.fingerprint = Some(fingerprint);
+        self.results = chats
+            .iter()
+            .filter_map(|chat| {
+                let message = chat.find_message(&self.applied);
+                (message.is_some()
+                    || !chat::find_ignore_case(&chat.summary, &self.applied).is_empty())
+                .then_some(SearchResult {
+                    chat: chat.uid,
+                    message,
+                })
+            })
+            .collect();
+    }
+
+    /// Changes when chats are added, removed, moved, renamed or their messages change.
+    fn fingerprint(chats: &[Chat]) -> u64 {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        for chat in chats {
+            chat.uid.hash(&mut hasher);
+            chat.hash_searchable(&mut hasher);
+        }
+        hasher.finish()
     }
 }
 
@@ -91,6 +437,14 @@ pub struct Sessions {
     #[cfg(feature = "tts")]
     #[serde(skip)]
     tts: SharedTts,
+    /// Speech settings last applied to `tts`
+    #[cfg(feature = "tts")]
+    #[serde(skip)]
+    applied_speech: Option<SpeechSettings>,
+    /// Voice the TTS started with, restored when "Default" is selected
+    #[cfg(feature = "tts")]
+    #[serde(skip)]
+    default_voice: Option<tts::Voice>,
     #[serde(skip)]
     commonmark_cache: CommonMarkCache,
     #[serde(skip)]
@@ -103,14 +457,96 @@ pub struct Sessions {
     last_request_time: Instant,
     #[serde(skip)]
     pending_model_infos: HashMap<String, ()>,
+    /// Rows of the chat list in the sidebar. They all have about the same height, which
+    /// this handles well, unlike the messages of a chat (see `chat::RowHeights`)
     #[serde(skip)]
     virtual_list: Rc<RefCell<VirtualList>>,
     edited_chat: Option<usize>,
     chat_export_format: ChatExportFormat,
+    /// Include the notes on messages in exports
+    export_notes: bool,
     #[serde(skip)]
     toasts: Toasts,
     settings_open: bool,
     pub settings: Settings,
+    #[serde(skip)]
+    pull_flower: PullFlower,
+    #[serde(skip)]
+    create_flower: CreateFlower,
+    #[serde(skip)]
+    model_tasks: ModelTasks,
+    #[serde(skip)]
+    details_flower: DetailsFlower,
+    #[serde(skip)]
+    details_queue: Vec<String>,
+    #[serde(skip)]
+    pending_details: HashSet<String>,
+    #[serde(skip)]
+    cancel_pull: Arc<AtomicBool>,
+    recently_deleted: Vec<DeletedChat>,
+    #[serde(skip)]
+    show_bin: bool,
+    #[serde(skip)]
+    bin_marked_for_deletion: BinDeletion,
+    #[serde(skip)]
+    wake: WakeDetector,
+    #[serde(skip)]
+    log_viewer: LogViewer,
+    #[serde(skip)]
+    model_table: ModelTable,
+    /// Latest deletions, most recent last
+    #[serde(skip)]
+    undo_stack: Vec<Deletion>,
+    /// Until when to offer undoing the latest deletion
+    #[serde(skip)]
+    undo_notice_until: Option<Instant>,
+    /// Rebuild the Ollama client from the connection settings on the next frame
+    #[serde(skip)]
+    reconnect: bool,
+    /// Client of the OpenAI-compatible server chats and model lists go to instead of
+    /// Ollama, if that backend is selected
+    #[serde(skip)]
+    openai: Option<OpenAiClient>,
+    /// Client chats with Ollama are streamed from
+    #[serde(skip)]
+    ollama_client: OllamaClient,
+    /// Model passed with `--model`, selected when the model list arrives
+    #[serde(skip)]
+    preferred_model: Option<String>,
+    #[serde(skip)]
+    connection: ConnectionState,
+    #[serde(skip)]
+    running_flower: RunningFlower,
+    #[serde(skip)]
+    running_models: RunningModels,
+    #[serde(skip)]
+    last_running_poll: Option<Instant>,
+    #[serde(skip)]
+    search: ChatSearch,
+    chat_order: ChatOrder,
+    /// Only list chats with all of these tags
+    tag_filter: BTreeSet<String>,
+    /// Whether anything may have changed since the last save
+    #[serde(skip)]
+    dirty: bool,
+    #[serde(skip)]
+    last_save: Instant,
+    /// Tag being typed in a chat's context menu
+    #[serde(skip)]
+    new_tag: String,
+    /// Backup waiting for the user to choose between merging and replacing
+    #[serde(skip)]
+    pending_restore: Option<Box<Backup>>,
+    /// Whether the sidebar is shrunk to a strip of icons
+    sidebar_collapsed: bool,
+    /// Width of the expanded sidebar, as last chosen by the user
+    sidebar_width: f32,
+    /// Title last set on the window
+    #[serde(skip)]
+    window_title: String,
+    /// Chat shown last frame
+    #[serde(skip)]
+    shown_chat: Option<u64>,
 }
 
 impl Default for Sessions {
@@ -128,6 +564,10 @@ impl Default for Sessions {
                 .map_err(|e| log::error!("failed to initialize TTS: {e}"))
                 .map(|tts| Arc::new(RwLock::new(tts)))
                 .ok(),
+            #[cfg(feature = "tts")]
+            applied_speech: None,
+            #[cfg(feature = "tts")]
+            default_voice: None,
             commonmark_cache: CommonMarkCache::default(),
             flower: OllamaFlower::new(1),
             models: Vec::new(),
@@ -137,29 +577,80 @@ impl Default for Sessions {
             virtual_list: Rc::new(RefCell::new(VirtualList::default())),
             edited_chat: None,
             chat_export_format: ChatExportFormat::default(),
+            export_notes: false,
             toasts: Toasts::default(),
             settings_open: false,
             settings: Settings::default(),
+            pull_flower: PullFlower::new(1),
+            create_flower: CreateFlower::new(1),
+            model_tasks: ModelTasks::default(),
+            details_flower: DetailsFlower::new(1),
+            details_queue: Vec::new(),
+            pending_details: HashSet::new(),
+            cancel_pull: Arc::new(AtomicBool::new(false)),
+            recently_deleted: Vec::new(),
+            show_bin: false,
+            bin_marked_for_deletion: BinDeletion::default(),
+            wake: WakeDetector::default(),
+            log_viewer: LogViewer::default(),
+            model_table: ModelTable::default(),
+            undo_stack: Vec::new(),
+            undo_notice_until: None,
+            reconnect: false,
+            openai: None,
+            ollama_client: OllamaClient::default(),
+            preferred_model: None,
+            connection: ConnectionState::default(),
+            running_flower: RunningFlower::new(1),
+            running_models: RunningModels::default(),
+            last_running_poll: None,
+            search: ChatSearch::default(),
+            chat_order: ChatOrder::default(),
+            tag_filter: BTreeSet::new(),
+            dirty: false,
+            last_save: now,
+            new_tag: String::new(),
+            pending_restore: None,
+            sidebar_collapsed: false,
+            sidebar_width: 200.0,
+            window_title: String::new(),
+            shown_chat: None,
         }
     }
 }
 
-async fn list_local_models(ollama: Ollama, handle: &OllamaFlowerHandle) {
+/// Run `request`, failing with a timeout error if it takes longer than `timeout`.
+async fn with_timeout<T, E: std::fmt::Display>(
+    timeout: Duration,
+    request: impl std::future::Future<Output = Result<T, E>>,
+) -> Result<T, String> {
+    match tokio::time::timeout(timeout, request).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err(tr_args("sidebar.request_timeout", &[&timeout.as_secs()])),
+    }
+}
+
+async fn list_local_models(backend: Backend, timeout: Duration, handle: &OllamaFlowerHandle) {
     log::debug!("requesting local models...");
-    match ollama.list_local_models().await {
+    match with_timeout(timeout, backend.list_models()).await {
         Ok(models) => {
             log::debug!("{} local models: {models:?}", models.len());
             handle.success(OllamaResponse::Models(models));
         }
         Err(e) => {
             log::error!("failed to list local models: {e}");
-            handle.error(e.to_string());
+            handle.error(e);
         }
     }
 }
 
-async fn request_model_info(ollama: Ollama, model_name: String, handle: &OllamaFlowerHandle) {
-    match ollama.show_model_info(model_name.clone()).await {
+async fn request_model_info(
+    client: OllamaClient,
+    model_name: String,
+    timeout: Duration,
+    handle: &OllamaFlowerHandle,
+) {
+    match with_timeout(timeout, client.model_info(&model_name)).await {
         Ok(info) => {
             log::debug!("model `{model_name}` info: {info:?}");
             handle.success(OllamaResponse::ModelInfo {
@@ -169,14 +660,53 @@ async fn request_model_info(ollama: Ollama, model_name: String, handle: &OllamaF
         }
         Err(e) => {
             log::error!("failed to request model `{model_name}` info: {e}");
-            handle.error(e.to_string());
+            handle.error(e);
+        }
+    }
+}
+
+async fn copy_model(
+    client: OllamaClient,
+    source: String,
+    destination: String,
+    timeout: Duration,
+    handle: &OllamaFlowerHandle,
+) {
+    log::info!("copying model `{source}` to `{destination}`");
+    if let Err(e) = client.copy_model(&source, &destination).await {
+        log::error!("failed to copy model `{source}` to `{destination}`: {e}");
+        handle.error(e.to_string());
+        return;
+    }
+
+    // refresh the model list so the copy shows up
+    list_local_models(Backend::Ollama(client), timeout, handle).await;
+}
+
+async fn delete_models(
+    client: OllamaClient,
+    names: Vec<String>,
+    timeout: Duration,
+    handle: &OllamaFlowerHandle,
+) {
+    let mut deleted = Vec::new();
+    let mut failed = Vec::new();
+    for name in names {
+        log::info!("deleting model `{name}`");
+        match with_timeout(timeout, client.delete_model(&name)).await {
+            Ok(()) => deleted.push(name),
+            Err(e) => {
+                log::error!("failed to delete model `{name}`: {e}");
+                failed.push(format!("{name}: {e}"));
+            }
         }
     }
+    handle.success(OllamaResponse::ModelsDeleted { deleted, failed });
 }
 
 async fn pick_images(id: usize, handle: &OllamaFlowerHandle) {
     let Some(files) = rfd::AsyncFileDialog::new()
-        .add_filter("Image", crate::IMAGE_FORMATS)
+        .add_filter(tr("sidebar.image_filter"), crate::IMAGE_FORMATS)
         .pick_files()
         .await
     else {
@@ -192,13 +722,32 @@ async fn pick_images(id: usize, handle: &OllamaFlowerHandle) {
     });
 }
 
+async fn pick_knowledge_sources(uid: u64, pick: SourcePick, handle: &OllamaFlowerHandle) {
+    let dialog = rfd::AsyncFileDialog::new();
+    let picked = match pick {
+        SourcePick::Files => dialog.pick_files().await,
+        SourcePick::Folder => dialog.pick_folder().await.map(|folder| vec![folder]),
+    };
+    let Some(picked) = picked else {
+        handle.success(OllamaResponse::Ignore);
+        return;
+    };
+
+    handle.success(OllamaResponse::KnowledgeSources {
+        uid,
+        paths: picked.iter().map(|f| f.path().to_path_buf()).collect(),
+    });
+}
+
 async fn load_settings(handle: &OllamaFlowerHandle) {
     let Some(file) = rfd::AsyncFileDialog::new()
-        .add_filter("JSON file", &["json"])
+        .add_filter(tr("sidebar.json_filter"), &["json"])
         .pick_file()
         .await
     else {
-        handle.success(OllamaResponse::Toast(Toast::info("No file selected")));
+        handle.success(OllamaResponse::Toast(Toast::info(tr(
+            "sidebar.no_file_selected",
+        ))));
         return;
     };
 
@@ -219,13 +768,211 @@ async fn load_settings(handle: &OllamaFlowerHandle) {
     }
 }
 
+async fn export_backup(json: String, handle: &OllamaFlowerHandle) {
+    let Some(file) = rfd::AsyncFileDialog::new()
+        .add_filter(tr("sidebar.json_filter"), &["json"])
+        .set_file_name(format!(
+            "ellama-backup-{}.json",
+            chrono::Local::now().format("%Y-%m-%d")
+        ))
+        .save_file()
+        .await
+    else {
+        handle.success(OllamaResponse::Ignore);
+        return;
+    };
+
+    log::info!("writing backup to `{}`", file.path().display());
+    if let Err(e) = std::fs::write(file.path(), json) {
+        log::error!("failed to write backup `{}`: {e}", file.path().display());
+        handle.success(OllamaResponse::Toast(Toast::error(e.to_string())));
+        return;
+    }
+    handle.success(OllamaResponse::Toast(Toast::success(tr(
+        "sidebar.backup_saved",
+    ))));
+}
+
+async fn pick_backup(handle: &OllamaFlowerHandle) {
+    let Some(file) = rfd::AsyncFileDialog::new()
+        .add_filter(tr("sidebar.json_filter"), &["json"])
+        .pick_file()
+        .await
+    else {
+        handle.success(OllamaResponse::Ignore);
+        return;
+    };
+
+    log::info!("reading backup from `{}`", file.path().display());
+    match Backup::read(file.path()) {
+        Ok(backup) => handle.success(OllamaResponse::Backup(Box::new(backup))),
+        Err(e) => {
+            log::error!("failed to read backup: {e:#}");
+            handle.success(OllamaResponse::Toast(Toast::error(format!("{e:#}"))));
+        }
+    }
+}
+
+async fn pull_model(
+    client: OllamaClient,
+    model: String,
+    cancel: Arc<AtomicBool>,
+    handle: &PullFlowerHandle,
+) {
+    log::info!("pulling model `{model}`");
+    let mut stream = match client.pull_model(&model).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            log::error!("failed to pull model `{model}`: {e}");
+            handle.error(e.to_string());
+            return;
+        }
+    };
+
+    while let Some(status) = stream.next().await {
+        if cancel.load(Ordering::Relaxed) {
+            log::info!("pull of model `{model}` cancelled");
+            handle.success(None);
+            return;
+        }
+        match status {
+            Ok(status) => {
+                handle.send((status.message, status.completed, status.total));
+            }
+            Err(e) => {
+                log::error!("failed to pull model `{model}`: {e}");
+                handle.error(e.to_string());
+                return;
+            }
+        }
+    }
+
+    log::info!("pulled model `{model}`");
+    handle.success(Some(model));
+}
+
+async fn create_model(
+    client: OllamaClient,
+    model: String,
+    modelfile: String,
+    handle: &CreateFlowerHandle,
+) {
+    log::info!("creating model `{model}`");
+    let mut stream = match client.create_model(&model, &modelfile).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            log::error!("failed to create model `{model}`: {e}");
+            handle.error(e.to_string());
+            return;
+        }
+    };
+
+    while let Some(status) = stream.next().await {
+        match status {
+            Ok(status) => {
+                log::debug!("create `{model}`: {}", status.message);
+                handle.send(status.message);
+            }
+            Err(e) => {
+                log::error!("failed to create model `{model}`: {e}");
+                handle.error(e.to_string());
+                return;
+            }
+        }
+    }
+
+    log::info!("created model `{model}`");
+    handle.success(model);
+}
+
+/// List the models loaded into memory, optionally unloading one of them first.
+async fn request_running_models(
+    client: OllamaClient,
+    unload: Option<String>,
+    timeout: Duration,
+    handle: &RunningFlowerHandle,
+) {
+    if let Some(model) = unload {
+        log::info!("unloading model `{model}`");
+        if let Err(e) = with_timeout(timeout, client.unload_model(&model)).await {
+            log::error!("failed to unload model `{model}`: {e}");
+            handle.error(tr_args("sidebar.unload_failed", &[&model, &e]));
+            return;
+        }
+    }
+
+    match with_timeout(timeout, client.running_models()).await {
+        Ok(models) => handle.success(models),
+        Err(e) => {
+            log::warn!("failed to list running models: {e}");
+            handle.error(e);
+        }
+    }
+}
+
+async fn fetch_model_details(
+    client: OllamaClient,
+    names: Vec<String>,
+    timeout: Duration,
+    handle: &DetailsFlowerHandle,
+) {
+    for name in names {
+        let mut details = match with_timeout(timeout, client.model_details(&name)).await {
+            Ok(details) => details,
+            Err(e) => {
+                log::warn!("failed to request details of model `{name}`: {e}");
+                ModelDetails::default()
+            }
+        };
+
+        // older servers don't report details, fall back to what the tag says
+        let guess = ModelDetails::from_name(&name);
+        if details.parameter_size.is_empty() {
+            details.parameter_size = guess.parameter_size;
+        }
+        if details.quantization_level.is_empty() {
+            details.quantization_level = guess.quantization_level;
+        }
+        handle.send((name, details));
+    }
+    handle.success(());
+}
+
+/// Read a dropped file to attach it to the prompt as text. Refuses files over `max_kb` and
+/// binary files.
+fn read_text_attachment(path: &std::path::Path, max_kb: u32) -> anyhow::Result<FileAttachment> {
+    let name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    let size = std::fs::metadata(path)?.len();
+    if size > u64::from(max_kb) * 1024 {
+        anyhow::bail!(tr_args(
+            "sidebar.attach_too_large",
+            &[
+                &name,
+                &bytesize::ByteSize(size),
+                &bytesize::ByteSize(u64::from(max_kb) * 1024),
+            ],
+        ));
+    }
+    let bytes = std::fs::read(path)?;
+    if bytes.contains(&0) {
+        anyhow::bail!(tr_args("sidebar.attach_binary", &[&name]));
+    }
+    let content = String::from_utf8(bytes)
+        .map_err(|_| anyhow::anyhow!(tr_args("sidebar.attach_not_utf8", &[&name])))?;
+    Ok(FileAttachment { name, content })
+}
+
 fn preview_files_being_dropped(ctx: &egui::Context) {
     use egui::*;
     use std::fmt::Write as _;
 
     if !ctx.input(|i| i.raw.hovered_files.is_empty()) {
         let text = ctx.input(|i| {
-            let mut text = "Dropping files:".to_owned();
+            let mut text = tr("sidebar.dropping_files").to_owned();
             for file in &i.raw.hovered_files {
                 if let Some(path) = &file.path {
                     write!(text, "\n{}", path.display()).ok();
@@ -254,71 +1001,668 @@ fn preview_files_being_dropped(ctx: &egui::Context) {
 }
 
 impl Sessions {
-    pub fn new(ollama: Ollama) -> Self {
-        let mut sessions = Self::default();
-        sessions.list_models(ollama);
-        sessions
+    /// Apply the `--model` and `--new-chat` startup arguments. The model is selected once the
+    /// model list arrives.
+    pub fn apply_startup_args(&mut self, model: Option<String>, new_chat: bool) {
+        if new_chat {
+            if let Some(idx) = self.chats.iter().position(|c| c.messages.is_empty()) {
+                self.selected_chat = idx;
+            } else {
+                self.add_default_chat();
+                self.selected_chat = self.chats.len() - 1;
+            }
+        }
+        self.preferred_model = model;
     }
 
-    pub fn list_models(&mut self, ollama: Ollama) {
+    pub fn list_models(&mut self) {
         let handle = self.flower.handle();
         self.flower_activity = OllamaFlowerActivity::ListModels;
         self.last_request_time = Instant::now();
+        let backend = Backend::pick(&self.ollama_client, self.openai.as_ref());
+        let timeout = self.settings.timeouts.first_token();
         tokio::spawn(async move {
             handle.activate();
-            list_local_models(ollama, &handle).await;
+            list_local_models(backend, timeout, &handle).await;
         });
     }
 
-    fn request_model_info(&mut self, model_name: String, ollama: Ollama) {
-        // check if any chats have the info of this model
-        let handle = self.flower.handle();
-        for chat in &self.chats {
-            if chat.model_picker.selected_model() == model_name {
-                if let Some(info) = chat.model_picker.info.clone() {
-                    handle.activate();
-                    handle.success(OllamaResponse::ModelInfo {
-                        name: model_name.clone(),
-                        info,
-                    });
-                    return;
-                }
-            }
-        }
+    /// Build the clients from the connection settings and request the model list.
+    pub fn connect(&mut self) {
+        log::info!("connecting to {}", self.settings.server_url());
+        self.ollama_client = self.settings.make_ollama_client();
+        self.openai = self.settings.make_openai();
+        self.list_models();
+    }
 
+    fn request_model_info(&mut self, model_name: String) {
+        let handle = self.flower.handle();
         self.flower_activity = OllamaFlowerActivity::ModelInfo;
         self.last_request_time = Instant::now();
         self.pending_model_infos.insert(model_name.clone(), ());
+        let timeout = self.settings.timeouts.first_token();
+        let client = self.ollama_client.clone();
         tokio::spawn(async move {
             handle.activate();
-            request_model_info(ollama, model_name, &handle).await;
+            request_model_info(client, model_name, timeout, &handle).await;
         });
     }
 
-    pub fn show(&mut self, ctx: &egui::Context, ollama: &Ollama) {
-        // check if tts stopped speaking
-        #[cfg(feature = "tts")]
-        let prev_is_speaking = self.is_speaking;
-        #[cfg(feature = "tts")]
-        {
-            self.is_speaking = if let Some(tts) = &self.tts {
-                tts.read().is_speaking().unwrap_or(false)
-            } else {
-                false
-            };
-        }
-
-        // if speaking, continuously check if stopped
-        #[cfg(feature = "tts")]
-        let mut request_repaint = self.is_speaking;
-
-        #[cfg(not(feature = "tts"))]
-        let mut request_repaint = false;
+    fn copy_model(&mut self, source: String, destination: String) {
+        let handle = self.flower.handle();
+        self.flower_activity = OllamaFlowerActivity::CopyModel;
+        self.last_request_time = Instant::now();
+        let timeout = self.settings.timeouts.first_token();
+        let client = self.ollama_client.clone();
+        tokio::spawn(async move {
+            handle.activate();
+            copy_model(client, source, destination, timeout, &handle).await;
+        });
+    }
 
-        let mut modal = Modal::new(ctx, "sessions_main_modal");
-        let mut chat_modal = Modal::new(ctx, "chat_main_modal").with_close_on_outside_click(true);
-        let settings_modal =
-            Modal::new(ctx, "global_settings_modal").with_close_on_outside_click(true);
+    fn delete_models(&mut self, names: Vec<String>) {
+        let handle = self.flower.handle();
+        self.flower_activity = OllamaFlowerActivity::DeleteModels;
+        self.last_request_time = Instant::now();
+        let timeout = self.settings.timeouts.first_token();
+        let client = self.ollama_client.clone();
+        tokio::spawn(async move {
+            handle.activate();
+            delete_models(client, names, timeout, &handle).await;
+        });
+    }
+
+    fn pull_model(&mut self, model: String) {
+        if self.pull_flower.is_active() {
+            return;
+        }
+        let handle = self.pull_flower.handle();
+        let cancel = self.cancel_pull.clone();
+        cancel.store(false, Ordering::Relaxed);
+        self.model_tasks.pull = PullState::Pulling {
+            model: model.clone(),
+            status: tr("models.starting").to_owned(),
+            completed: 0,
+            total: 0,
+        };
+        let client = self.ollama_client.clone();
+        tokio::spawn(async move {
+            handle.activate();
+            pull_model(client, model, cancel, &handle).await;
+        });
+    }
+
+    fn create_model(&mut self, model: String, modelfile: String) {
+        if self.create_flower.is_active() {
+            return;
+        }
+        let handle = self.create_flower.handle();
+        self.model_tasks.create = CreateState::Creating {
+            model: model.clone(),
+            log: Vec::new(),
+        };
+        let client = self.ollama_client.clone();
+        tokio::spawn(async move {
+            handle.activate();
+            create_model(client, model, modelfile, &handle).await;
+        });
+    }
+
+    fn poll_create_flower(&mut self) {
+        let mut refresh_models = false;
+        self.create_flower
+            .extract(|line| {
+                if let CreateState::Creating { log, .. } = &mut self.model_tasks.create {
+                    log.push(line);
+                }
+            })
+            .finalize(|resp| {
+                let (model, log) = match std::mem::take(&mut self.model_tasks.create) {
+                    CreateState::Creating { model, log } => (model, log),
+                    _ => Default::default(),
+                };
+                let error = match resp {
+                    Ok(model) => {
+                        self.toasts
+                            .success(tr_args("sidebar.model_created", &[&model]));
+                        refresh_models = true;
+                        None
+                    }
+                    Err(flowync::error::Compact::Suppose(e)) => Some(describe_request_error(
+                        e,
+                        self.settings.proxy.description().as_ref(),
+                    )),
+                    Err(flowync::error::Compact::Panicked(e)) => {
+                        log::error!("task panicked: {e}");
+                        Some(tr_args("common.task_panicked", &[&e]))
+                    }
+                };
+                self.model_tasks.create = CreateState::Finished { model, log, error };
+            });
+        if refresh_models {
+            self.list_models();
+        }
+    }
+
+    fn handle_model_request(&mut self, request: ModelRequest) {
+        match request {
+            ModelRequest::Models => self.list_models(),
+            ModelRequest::ModelInfo(name) => {
+                if !self.pending_model_infos.contains_key(&name) {
+                    if let Some(info) = self.model_tasks.infos.get(&name) {
+                        // a picker that didn't see the cache yet, e.g. of a hidden chat
+                        let info = info.clone();
+                        self.set_model_info(&name, &info);
+                    } else {
+                        self.request_model_info(name);
+                    }
+                }
+            }
+            ModelRequest::RefreshModelInfo(name) => {
+                self.model_tasks.infos.remove(&name);
+                if !self.pending_model_infos.contains_key(&name) {
+                    self.request_model_info(name);
+                }
+            }
+            ModelRequest::LoadSettings => {
+                let handle = self.flower.handle();
+                tokio::spawn(async move {
+                    handle.activate();
+                    load_settings(&handle).await;
+                });
+            }
+            ModelRequest::PullModel(name) => self.pull_model(name),
+            ModelRequest::CancelPull => self.cancel_pull.store(true, Ordering::Relaxed),
+            ModelRequest::CopyModel {
+                source,
+                destination,
+            } => self.copy_model(source, destination),
+            ModelRequest::CreateModel { name, modelfile } => self.create_model(name, modelfile),
+            ModelRequest::DeleteModels(names) => self.delete_models(names),
+            ModelRequest::ModelDetails(name) => {
+                if !self.model_tasks.details.contains_key(&name)
+                    && self.pending_details.insert(name.clone())
+                {
+                    self.details_queue.push(name);
+                }
+            }
+            ModelRequest::SetDefaultModel(name) => {
+                self.settings.model_choice.default_model = name.unwrap_or_default();
+            }
+            ModelRequest::Reconnect => self.forget_models(),
+            ModelRequest::ExportBackup => {
+                let json = match Backup::to_json(&self.chats, &self.settings) {
+                    Ok(json) => json,
+                    Err(e) => {
+                        log::error!("{e:#}");
+                        self.toasts.error(format!("{e:#}"));
+                        return;
+                    }
+                };
+                let handle = self.flower.handle();
+                tokio::spawn(async move {
+                    handle.activate();
+                    export_backup(json, &handle).await;
+                });
+            }
+            ModelRequest::RestoreBackup => {
+                let handle = self.flower.handle();
+                tokio::spawn(async move {
+                    handle.activate();
+                    pick_backup(&handle).await;
+                });
+            }
+        }
+    }
+
+    /// Add the chats and presets of `backup` that aren't here yet, or replace all chats
+    /// and settings with it.
+    fn restore_backup(&mut self, backup: Backup, replace: bool) {
+        if replace {
+            let chats = backup.chats.len();
+            self.chats = backup.chats;
+            if self.chats.is_empty() {
+                self.add_default_chat();
+            }
+            // the server given on the command line still takes precedence
+            let endpoint_override = self.settings.endpoint_override.take();
+            let mut settings = backup.settings;
+            settings.keep_secrets(&self.settings);
+            self.settings = settings;
+            self.settings.endpoint_override = endpoint_override;
+            self.settings.auth.load_secret();
+            self.settings.proxy.load_password();
+            self.selected_chat = 0;
+            self.edited_chat = None;
+            self.tag_filter.clear();
+            self.forget_models();
+            let key = if chats == 1 {
+                "sidebar.restored_one"
+            } else {
+                "sidebar.restored_many"
+            };
+            self.toasts.success(tr_args(key, &[&chats]));
+        } else {
+            // chats are matched by their stable id, so restoring twice adds nothing
+            let known: HashSet<u64> = self.chats.iter().map(|chat| chat.uid).collect();
+            let new_chats: Vec<Chat> = backup
+                .chats
+                .into_iter()
+                .filter(|chat| !known.contains(&chat.uid))
+                .collect();
+            let presets = &mut self.settings.model_picker.presets;
+            let new_presets: Vec<_> = backup
+                .settings
+                .model_picker
+                .presets
+                .into_iter()
+                .filter(|preset| !presets.iter().any(|p| p.name == preset.name))
+                .collect();
+            let plural = |n: usize, one, many| tr_args(if n == 1 { one } else { many }, &[&n]);
+            self.toasts.success(tr_args(
+                "sidebar.merged",
+                &[
+                    &plural(new_chats.len(), "sidebar.chats_one", "sidebar.chats_many"),
+                    &plural(
+                        new_presets.len(),
+                        "sidebar.presets_one",
+                        "sidebar.presets_many",
+                    ),
+                ],
+            ));
+            presets.extend(new_presets);
+            self.chats.extend(new_chats);
+        }
+        self.search.fingerprint = None;
+        self.migrate_chat_settings();
+        self.clamp_settings();
+        self.dirty = true;
+    }
+
+    /// Ask whether to merge or replace with the backup picked by the user.
+    fn show_restore_modal(&mut self, modal: &Modal) {
+        let Some(backup) = &self.pending_restore else {
+            return;
+        };
+        if !modal.is_open() {
+            modal.open();
+        }
+        let summary = backup.summary();
+        let mut choice = None;
+        modal.show(|ui| {
+            modal.title(ui, tr("sidebar.restore_title"));
+            modal.frame(ui, |ui| {
+                modal.body_and_icon(ui, tr_args("sidebar.restore_body", &[&summary]), Icon::Info);
+            });
+            modal.buttons(ui, |ui| {
+                if modal.button(ui, tr("common.cancel")).clicked() {
+                    choice = Some(None);
+                }
+                if modal.caution_button(ui, tr("sidebar.replace")).clicked() {
+                    choice = Some(Some(true));
+                }
+                if modal.suggested_button(ui, tr("sidebar.merge")).clicked() {
+                    choice = Some(Some(false));
+                }
+            });
+        });
+        if let Some(replace) = choice {
+            // cancelling drops the backup as well
+            let backup = self.pending_restore.take();
+            if let (Some(backup), Some(replace)) = (backup, replace) {
+                self.restore_backup(*backup, replace);
+            }
+        }
+    }
+
+    /// Drop everything known about the models of the current server, they are
+    /// requested again once the client is rebuilt.
+    fn forget_models(&mut self) {
+        self.models.clear();
+        self.pending_model_infos.clear();
+        self.model_tasks.details.clear();
+        self.model_tasks.infos.clear();
+        self.pending_details.clear();
+        self.details_queue.clear();
+        self.connection = ConnectionState::Connecting;
+        self.running_models = RunningModels::NotLoaded;
+        self.last_running_poll = None;
+        self.settings.model_picker.info = None;
+        for chat in &mut self.chats {
+            chat.model_picker.info = None;
+        }
+        self.reconnect = true;
+    }
+
+    /// Give the info of model `name` to every picker that has it selected.
+    fn set_model_info(&mut self, name: &str, info: &ModelInfo) {
+        self.settings.model_picker.on_new_model_info(name, info);
+        for chat in self.chats.iter_mut() {
+            chat.model_picker.on_new_model_info(name, info);
+        }
+    }
+
+    /// Drop the cached info of models that were re-pulled or removed since the last listing,
+    /// `models` being the new list.
+    fn invalidate_model_infos(&mut self, models: &[LocalModel]) {
+        let stale: Vec<String> = self
+            .models
+            .iter()
+            .filter(|old| {
+                !models
+                    .iter()
+                    .any(|m| m.name == old.name && m.modified_at == old.modified_at)
+            })
+            .map(|m| m.name.clone())
+            .collect();
+        if stale.is_empty() {
+            return;
+        }
+        log::debug!("dropping stale info of {stale:?}");
+        for name in &stale {
+            self.model_tasks.infos.remove(name);
+        }
+        let pickers = std::iter::once(&mut self.settings.model_picker)
+            .chain(self.chats.iter_mut().map(|c| &mut c.model_picker));
+        for picker in pickers {
+            if stale.iter().any(|name| name == picker.selected_model()) {
+                picker.info = None;
+            }
+        }
+    }
+
+    /// Fetch the details of all queued models, one request at a time.
+    fn fetch_model_details(&mut self) {
+        let handle = self.details_flower.handle();
+        let client = self.ollama_client.clone();
+        let names = std::mem::take(&mut self.details_queue);
+        let timeout = self.settings.timeouts.first_token();
+        tokio::spawn(async move {
+            handle.activate();
+            fetch_model_details(client, names, timeout, &handle).await;
+        });
+    }
+
+    /// Refresh the running models, unloading `unload` first.
+    fn request_running_models(&mut self, unload: Option<String>) {
+        let handle = self.running_flower.handle();
+        let client = self.ollama_client.clone();
+        let timeout = self.settings.timeouts.first_token();
+        self.last_running_poll = Some(Instant::now());
+        tokio::spawn(async move {
+            handle.activate();
+            request_running_models(client, unload, timeout, &handle).await;
+        });
+    }
+
+    fn poll_running_flower(&mut self) {
+        self.running_flower.extract(|()| ()).finalize(|result| {
+            self.running_models = match result {
+                Ok(Some(models)) => RunningModels::Loaded(models),
+                Ok(None) => RunningModels::Unsupported,
+                Err(flowync::error::Compact::Suppose(e)) => RunningModels::Failed(e),
+                Err(flowync::error::Compact::Panicked(e)) => {
+                    RunningModels::Failed(tr_args("common.task_panicked", &[&e]))
+                }
+            };
+        });
+    }
+
+    /// "Installed" section of the Model tab, clicking a row selects the model for the chat.
+    fn show_model_table(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(egui::RichText::new(tr("models.installed")).heading())
+            .default_open(true)
+            .show(ui, |ui| {
+                if self.is_loading_models() {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::Spinner::new());
+                        ui.label(tr("models.loading"));
+                    });
+                    return;
+                }
+                let deleting = self.flower.is_active()
+                    && self.flower_activity == OllamaFlowerActivity::DeleteModels;
+                let selected = self
+                    .chats
+                    .get(self.selected_chat)
+                    .map_or("", |chat| chat.model_picker.selected_model());
+                let mut requests = Vec::new();
+                let clicked = self.model_table.show(
+                    ui,
+                    &self.models,
+                    &self.model_tasks,
+                    selected,
+                    deleting,
+                    &mut |typ| requests.push(ModelRequest::from(typ)),
+                );
+                if let Some(model) = clicked {
+                    if let Some(chat) = self.chats.get_mut(self.selected_chat) {
+                        chat.model_picker.selected = model.into();
+                        chat.model_picker.info = None;
+                    }
+                    if self.settings.inherit_chat_picker {
+                        self.settings.model_picker.selected =
+                            self.chats[self.selected_chat].model_picker.selected.clone();
+                    }
+                }
+                for request in requests {
+                    self.handle_model_request(request);
+                }
+            });
+    }
+
+    /// Forget the deleted models and move the pickers that had one of them selected to
+    /// another model.
+    fn remove_deleted_models(&mut self, deleted: &[String]) {
+        self.models.retain(|m| !deleted.contains(&m.name));
+        for name in deleted {
+            self.model_tasks.details.remove(name);
+            self.model_tasks.infos.remove(name);
+        }
+        let picker = &mut self.settings.model_picker;
+        if deleted.iter().any(|name| name == picker.selected_model()) {
+            picker.selected = Default::default();
+            picker.info = None;
+            picker.select_best_model(&self.models, &self.settings.model_choice);
+        }
+        for chat in &mut self.chats {
+            if deleted
+                .iter()
+                .any(|name| name == chat.model_picker.selected_model())
+            {
+                chat.model_picker.selected = self.settings.model_picker.selected.clone();
+                chat.model_picker.info = None;
+            }
+        }
+    }
+
+    /// "Running" section of the Model tab, polls the server while it's visible.
+    fn show_running_models(&mut self, ui: &mut egui::Ui) {
+        const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+        if !self.running_flower.is_active()
+            && !matches!(self.running_models, RunningModels::Unsupported)
+            && self
+                .last_running_poll
+                .map_or(true, |t| t.elapsed() >= POLL_INTERVAL)
+        {
+            self.request_running_models(None);
+        }
+        ui.ctx().request_repaint_after(POLL_INTERVAL);
+
+        let mut unload = None;
+        ui.horizontal(|ui| {
+            ui.heading(tr("models.running"));
+            if self.running_flower.is_active() {
+                ui.add(egui::Spinner::new());
+            }
+        });
+        match &self.running_models {
+            RunningModels::NotLoaded => {
+                ui.label(tr("common.loading"));
+            }
+            RunningModels::Unsupported => {
+                ui.label(tr("models.running_unsupported"));
+            }
+            RunningModels::Failed(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+            RunningModels::Loaded(models) if models.is_empty() => {
+                ui.label(tr("models.none_loaded"));
+            }
+            RunningModels::Loaded(models) => {
+                egui::Grid::new("running_models_grid")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for model in models {
+                            ui.label(&model.name);
+                            ui.label(format!(
+                                "{} / {}",
+                                bytesize::ByteSize(model.size_vram),
+                                bytesize::ByteSize(model.size)
+                            ))
+                            .on_hover_text(tr("models.vram_help"));
+                            ui.label(tr_args("models.unloads", &[&model.expires_in()]));
+                            if ui
+                                .small_button(tr("models.unload"))
+                                .on_hover_text(tr("models.unload_help"))
+                                .clicked()
+                            {
+                                unload = Some(model.name.clone());
+                            }
+                            ui.end_row();
+                        }
+                    });
+            }
+        }
+        if let Some(model) = unload {
+            if !self.running_flower.is_active() {
+                self.request_running_models(Some(model));
+            }
+        }
+    }
+
+    fn poll_details_flower(&mut self) {
+        self.details_flower
+            .extract(|(name, details)| {
+                self.pending_details.remove(&name);
+                self.model_tasks.details.insert(name, details);
+            })
+            .finalize(|_| ());
+    }
+
+    fn poll_pull_flower(&mut self) {
+        let mut refresh_models = false;
+        self.pull_flower
+            .extract(|(new_status, new_completed, new_total)| {
+                if let PullState::Pulling {
+                    status,
+                    completed,
+                    total,
+                    ..
+                } = &mut self.model_tasks.pull
+                {
+                    *status = new_status;
+                    *completed = new_completed;
+                    *total = new_total;
+                }
+            })
+            .finalize(|resp| {
+                let model = match std::mem::take(&mut self.model_tasks.pull) {
+                    PullState::Pulling { model, .. } => model,
+                    _ => String::new(),
+                };
+                match resp {
+                    Ok(Some(model)) => {
+                        self.toasts
+                            .success(tr_args("sidebar.model_pulled", &[&model]));
+                        refresh_models = true;
+                    }
+                    Ok(None) => {
+                        self.toasts
+                            .info(tr_args("sidebar.pull_cancelled", &[&model]));
+                    }
+                    Err(flowync::error::Compact::Suppose(error)) => {
+                        let error = describe_request_error(
+                            error,
+                            self.settings.proxy.description().as_ref(),
+                        );
+                        self.model_tasks.pull = PullState::Failed { model, error };
+                    }
+                    Err(flowync::error::Compact::Panicked(e)) => {
+                        log::error!("task panicked: {e}");
+                        self.model_tasks.pull = PullState::Failed {
+                            model,
+                            error: tr_args("common.task_panicked", &[&e]),
+                        };
+                    }
+                }
+            });
+        if refresh_models {
+            self.list_models();
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        self.model_tasks
+            .default_model
+            .clone_from(&self.settings.model_choice.default_model);
+        self.model_tasks.backend = self.settings.backend;
+        if std::mem::take(&mut self.reconnect) {
+            self.connect();
+        }
+
+        self.track_changes(ctx);
+
+        // check if tts stopped speaking
+        #[cfg(feature = "tts")]
+        let prev_is_speaking = self.is_speaking;
+        #[cfg(feature = "tts")]
+        {
+            self.is_speaking = if let Some(tts) = &self.tts {
+                tts.read().is_speaking().unwrap_or(false)
+            } else {
+                false
+            };
+        }
+
+        // the selected chat resets its icons itself, the others aren't shown this frame
+        #[cfg(feature = "tts")]
+        if prev_is_speaking && !self.is_speaking {
+            for (i, chat) in self.chats.iter_mut().enumerate() {
+                if i != self.selected_chat {
+                    chat.clear_speaking();
+                }
+            }
+        }
+
+        #[cfg(feature = "tts")]
+        if self.applied_speech.as_ref() != Some(&self.settings.speech) {
+            self.apply_speech_settings();
+        }
+
+        // if speaking, continuously check if stopped
+        #[cfg(feature = "tts")]
+        let mut request_repaint = self.is_speaking;
+
+        #[cfg(not(feature = "tts"))]
+        let mut request_repaint = false;
+
+        // the server may have gone away while we were asleep, re-check it right away
+        if let Some(slept) = self.wake.check() {
+            log::info!("system woke up after ~{}s", slept.as_secs());
+            if !self.flower.is_active() {
+                self.list_models();
+            }
+        }
+
+        self.retry_connection(ctx);
+
+        let mut modal = Modal::new(ctx, "sessions_main_modal");
+        let settings_modal =
+            Modal::new(ctx, "global_settings_modal").with_close_on_outside_click(true);
+        let restore_modal = Modal::new(ctx, "restore_backup_modal");
 
         // if self.edit_modal_open {
         //     let mut open = self.edit_modal_open;
@@ -334,30 +1678,83 @@ impl Sessions {
 
         // show dialogs created on the previous frame, if we move this into the end of the function
         // it won't be located in the center of the window but in the center of the centralpanel instead
-        chat_modal.show_dialog();
         modal.show_dialog();
         self.settings.show_modal(&settings_modal);
-
-        let avail_width = ctx.available_rect().width();
-        egui::SidePanel::left("sessions_panel")
-            .resizable(true)
-            .max_width(avail_width * 0.5)
-            .show(ctx, |ui| {
-                self.show_left_panel(ui);
-                ui.allocate_space(ui.available_size());
-            });
+        self.show_restore_modal(&restore_modal);
+
+        self.handle_chat_shortcuts(ctx, &modal);
+        self.show_undo_notice(ctx);
+        self.log_viewer.show(ctx);
+
+        // the collapsed strip is a separate panel, so it has its own fixed width and the
+        // expanded one keeps its size
+        if self.sidebar_collapsed {
+            egui::SidePanel::left("sessions_panel_collapsed")
+                .resizable(false)
+                .exact_width(COLLAPSED_SIDEBAR_WIDTH)
+                .show(ctx, |ui| {
+                    self.show_collapsed_panel(ui);
+                    ui.allocate_space(ui.available_size());
+                });
+        } else {
+            let max_width = ctx.available_rect().width() * 0.5;
+            let panel = egui::SidePanel::left("sessions_panel")
+                .resizable(true)
+                .default_width(self.sidebar_width)
+                .max_width(max_width)
+                .show(ctx, |ui| {
+                    self.show_left_panel(ui);
+                    ui.allocate_space(ui.available_size());
+                });
+            // don't remember the width a narrow window squeezed the panel to
+            let width = panel.response.rect.width();
+            if width < max_width - 1.0 {
+                self.sidebar_width = width;
+            }
+        }
 
         // poll all flowers
+        let mut completed = false;
         for chat in self.chats.iter_mut() {
             if chat.flower_active() {
                 request_repaint = true;
-                chat.poll_flower(&mut chat_modal);
+                completed |= chat.poll_flower(
+                    #[cfg(feature = "tts")]
+                    &self.tts,
+                );
+            }
+            if chat.is_indexing() {
+                request_repaint = true;
+                chat.poll_index();
             }
         }
+        if completed && !ctx.input(|i| i.focused) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::RequestUserAttention(
+                egui::UserAttentionType::Informational,
+            ));
+        }
         if self.flower.is_active() {
             request_repaint = true;
             self.poll_ollama_flower(&modal);
         }
+        if self.pull_flower.is_active() {
+            request_repaint = true;
+            self.poll_pull_flower();
+        }
+        if self.create_flower.is_active() {
+            request_repaint = true;
+            self.poll_create_flower();
+        }
+        if self.running_flower.is_active() {
+            request_repaint = true;
+            self.poll_running_flower();
+        }
+        if self.details_flower.is_active() {
+            request_repaint = true;
+            self.poll_details_flower();
+        } else if !self.details_queue.is_empty() {
+            self.fetch_model_details();
+        }
 
         if request_repaint {
             ctx.request_repaint();
@@ -367,9 +1764,7 @@ impl Sessions {
             self.edited_chat = None;
             egui::CentralPanel::default().show(ctx, |ui| {
                 egui::ScrollArea::both().auto_shrink(false).show(ui, |ui| {
-                    let mut request_info_for: Option<String> = None;
-                    let mut list_models = false;
-
+                    let mut requests = Vec::new();
                     self.settings.show(
                         ui,
                         if self.is_loading_models() {
@@ -377,44 +1772,26 @@ impl Sessions {
                         } else {
                             Some(&self.models)
                         },
-                        &mut |typ| match typ {
-                            RequestInfoType::ModelInfo(name) => {
-                                if !self.pending_model_infos.contains_key(name) {
-                                    request_info_for = Some(name.to_string());
-                                }
-                            }
-                            RequestInfoType::Models => {
-                                list_models = true;
-                            }
-                            RequestInfoType::LoadSettings => {
-                                let handle = self.flower.handle();
-                                tokio::spawn(async move {
-                                    handle.activate();
-                                    load_settings(&handle).await;
-                                });
-                            }
-                        },
+                        &self.model_tasks,
+                        &mut |typ| requests.push(ModelRequest::from(typ)),
                         &settings_modal,
+                        #[cfg(feature = "tts")]
+                        &self.tts,
                     );
-
-                    if let Some(name) = request_info_for {
-                        self.request_model_info(name, ollama.clone());
-                    }
-                    if list_models {
-                        self.list_models(ollama.clone());
+                    for request in requests {
+                        self.handle_model_request(request);
                     }
                 });
             });
         } else if let Some(edited_chat) = self.edited_chat {
             egui::CentralPanel::default().show(ctx, |ui| {
                 egui::ScrollArea::both().auto_shrink(false).show(ui, |ui| {
-                    self.show_chat_edit_panel(ui, edited_chat, ollama);
+                    self.show_chat_edit_panel(ui, edited_chat);
                 })
             });
         } else {
             self.show_selected_chat(
                 ctx,
-                ollama,
                 #[cfg(feature = "tts")]
                 (prev_is_speaking && !self.is_speaking),
             );
@@ -423,12 +1800,41 @@ impl Sessions {
 
         // display toast queue
         self.toasts.show(ctx);
+
+        self.update_window_title(ctx);
+    }
+
+    /// Show the selected chat and whether it's generating in the window title.
+    fn update_window_title(&mut self, ctx: &egui::Context) {
+        let title = match self.chats.get(self.selected_chat) {
+            Some(chat) => {
+                let summary = chat.summary.trim();
+                let summary = if summary.is_empty() {
+                    tr("chat.new_chat")
+                } else {
+                    summary
+                };
+                if chat.flower_active() {
+                    format!(
+                        "{summary} — {} {}",
+                        crate::TITLE,
+                        tr("sidebar.title_generating")
+                    )
+                } else {
+                    format!("{summary} — {}", crate::TITLE)
+                }
+            }
+            None => crate::TITLE.to_owned(),
+        };
+        if title != self.window_title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.window_title = title;
+        }
     }
 
     fn show_selected_chat(
         &mut self,
         ctx: &egui::Context,
-        ollama: &Ollama,
         #[cfg(feature = "tts")] stopped_talking: bool,
     ) {
         let Some(chat) = self.chats.get_mut(self.selected_chat) else {
@@ -436,38 +1842,79 @@ impl Sessions {
             return;
         };
 
+        // all chats share the scroll area, start at the bottom of a newly shown chat unless
+        // a message is to be revealed
+        if self.shown_chat != Some(chat.uid) {
+            self.shown_chat = Some(chat.uid);
+            if !chat.is_revealing() {
+                chat.scroll_to_bottom();
+            }
+        }
+
         ctx.input(|i| {
             for file in &i.raw.dropped_files {
                 if let Some(path) = &file.path {
-                    let filename = path.file_name().unwrap_or_default().to_string_lossy();
-                    let Some(ext) = path.extension().and_then(|s| s.to_str()) else {
-                        log::warn!("dropped file `{}` has no extension", path.display());
-                        self.toasts
-                            .add(Toast::info(format!("Skipping non-image `{filename}`")));
-                        continue;
-                    };
-                    if !crate::IMAGE_FORMATS.contains(&ext) {
-                        log::warn!(
-                            "dropped file `{}` has unsupported extension `{ext}`",
-                            path.display()
-                        );
-                        self.toasts
-                            .add(Toast::info(format!("Skipping non-image `{filename}`")));
+                    let is_image = path
+                        .extension()
+                        .and_then(|s| s.to_str())
+                        .is_some_and(|ext| {
+                            crate::IMAGE_FORMATS.contains(&ext.to_lowercase().as_str())
+                        });
+                    if is_image {
+                        chat.images.push(path.clone());
                         continue;
                     }
-                    chat.images.push(path.clone());
+                    match read_text_attachment(path, self.settings.max_attachment_kb) {
+                        Ok(file) => chat.files.push(file),
+                        Err(e) => {
+                            log::warn!("not attaching dropped file `{}`: {e}", path.display());
+                            self.toasts.add(Toast::info(e.to_string()));
+                        }
+                    }
                 }
             }
         });
 
+        // egui only reports pastes of text and swallows the key press of the paste shortcut,
+        // so an image on the clipboard is pasted when the shortcut's key is released
+        let paste_released = ctx.input(|i| {
+            i.events.iter().any(|e| {
+                matches!(e, egui::Event::Key { key: egui::Key::V, pressed: false, modifiers, .. }
+                    if modifiers.command)
+            })
+        });
+        if paste_released
+            && self
+                .chats
+                .get(self.selected_chat)
+                .is_some_and(|chat| chat.chatbox_has_focus(ctx))
+        {
+            self.paste_image();
+        }
+        let Some(chat) = self.chats.get_mut(self.selected_chat) else {
+            return;
+        };
+
+        chat.inherited_settings
+            .clone_from(self.settings.model_picker.settings());
+        chat.proxy = self.settings.proxy.description();
+        chat.openai.clone_from(&self.openai);
+        chat.ollama_client.clone_from(&self.ollama_client);
+        chat.timeouts = self.settings.timeouts;
+        chat.send_shortcut = self.settings.send_shortcut;
+        chat.user_name.clone_from(&self.settings.user_name);
+        chat.quick_settings = self.settings.quick_settings;
+        #[cfg(feature = "voice")]
+        chat.whisper_model.clone_from(&self.settings.whisper_model);
         let action = chat.show(
             ctx,
-            ollama,
             #[cfg(feature = "tts")]
             self.tts.clone(),
             #[cfg(feature = "tts")]
             stopped_talking,
             &mut self.commonmark_cache,
+            &self.models,
+            &mut self.settings.golden_prompts,
         );
 
         match action {
@@ -479,21 +1926,85 @@ impl Sessions {
                     pick_images(id, &handle).await;
                 });
             }
+            ChatAction::PasteImage => {
+                if !self.paste_image() {
+                    self.toasts
+                        .add(Toast::info(tr("sidebar.no_clipboard_image")));
+                }
+            }
+            ChatAction::Branch { message } => {
+                let idx = self.selected_chat;
+                let branch = self.chats[idx].branch(message, self.next_chat_id());
+                self.chats.insert(idx + 1, branch);
+                self.selected_chat = idx + 1;
+                self.edited_chat = None;
+            }
+            ChatAction::ConversationCopied { messages } => {
+                let key = if messages == 1 {
+                    "sidebar.copied_one"
+                } else {
+                    "sidebar.copied_many"
+                };
+                self.toasts.add(Toast::success(tr_args(key, &[&messages])));
+            }
+            ChatAction::MessageDeleted { index, message } => {
+                let chat_uid = self.chats[self.selected_chat].uid;
+                self.push_undo(Deletion::Message {
+                    chat_uid,
+                    index,
+                    message,
+                });
+            }
         }
     }
 
+    /// Attach the image on the clipboard to the selected chat. Returns `false` if the
+    /// clipboard doesn't hold an image.
+    fn paste_image(&mut self) -> bool {
+        let image = match arboard::Clipboard::new().and_then(|mut c| c.get_image()) {
+            Ok(image) => image,
+            Err(e) => {
+                log::debug!("no image on the clipboard: {e}");
+                return false;
+            }
+        };
+        let Some(chat) = self.chats.get_mut(self.selected_chat) else {
+            return false;
+        };
+        match crate::image::save_pasted_image(
+            image.width as u32,
+            image.height as u32,
+            image.bytes.into_owned(),
+            self.settings.max_image_dimension,
+        ) {
+            Ok(path) => {
+                if !chat.model_picker.likely_multimodal() {
+                    self.toasts.add(Toast::info(tr_args(
+                        "sidebar.image_unsupported",
+                        &[&chat.model_picker.selected_model()],
+                    )));
+                }
+                chat.images.push(path);
+            }
+            Err(e) => {
+                log::error!("failed to save pasted image: {e:#}");
+                self.toasts
+                    .add(Toast::error(tr_args("sidebar.paste_failed", &[&e])));
+            }
+        }
+        true
+    }
+
     fn show_remove_chat_modal_inner(&mut self, ui: &mut egui::Ui, modal: &Modal) {
-        modal.title(ui, "Remove Chat");
+        modal.title(ui, tr("sidebar.remove_title"));
         modal.frame(ui, |ui| {
             modal.body_and_icon(
                 ui,
-                "Do you really want to remove this chat? \
-                You cannot undo this action later.\n\
-                Hold Shift to surpass this warning.",
+                tr_args("sidebar.remove_body", &[&self.settings.retention_days]),
                 Icon::Warning,
             );
             modal.buttons(ui, |ui| {
-                if modal.button(ui, "No").clicked() {
+                if modal.button(ui, tr("common.no")).clicked() {
                     modal.close();
                 }
                 let summary = self
@@ -501,15 +2012,15 @@ impl Sessions {
                     .get(self.chat_marked_for_deletion)
                     .map(|c| {
                         if c.summary.is_empty() {
-                            "New Chat"
+                            tr("chat.new_chat")
                         } else {
                             c.summary.as_str()
                         }
                     })
-                    .unwrap_or("New Chat");
+                    .unwrap_or(tr("chat.new_chat"));
                 if modal
-                    .caution_button(ui, "Yes")
-                    .on_hover_text(format!("Remove chat \"{summary}\"",))
+                    .caution_button(ui, tr("common.yes"))
+                    .on_hover_text(tr_args("sidebar.remove_chat_help", &[&summary]))
                     .clicked()
                 {
                     modal.close();
@@ -519,16 +2030,94 @@ impl Sessions {
         });
     }
 
-    fn show_chat_edit_panel(&mut self, ui: &mut egui::Ui, chat_idx: usize, ollama: &Ollama) {
+    fn show_chat_model_picker(&mut self, ui: &mut egui::Ui, chat_idx: usize) {
+        let is_loading_models = self.is_loading_models();
+        let Some(chat) = self.chats.get_mut(chat_idx) else {
+            return;
+        };
+        let mut requests = Vec::new();
+        let picker_id = ui.id().with(("chat_model_picker", chat_idx));
+        chat.model_picker.show(
+            ui,
+            picker_id,
+            if is_loading_models {
+                None
+            } else {
+                Some(&self.models)
+            },
+            &self.model_tasks,
+            &mut |typ| requests.push(ModelRequest::from(typ)),
+            false,
+        );
+
+        chat.settings().collapsing_header().show(ui, |ui| {
+            let (shared, presets) = self.settings.model_picker.settings_with_presets();
+            let mut reset = false;
+            match &mut chat.settings_override {
+                None => {
+                    ui.label(tr("sidebar.uses_shared_settings"));
+                    if ui
+                        .button(tr("sidebar.override_settings"))
+                        .on_hover_text(tr("sidebar.override_settings_help"))
+                        .clicked()
+                    {
+                        chat.settings_override = Some(shared.clone());
+                    }
+                }
+                Some(settings) => {
+                    let overridden = settings.overridden_fields(shared);
+                    if overridden.is_empty() {
+                        ui.label(tr("sidebar.all_inherited"));
+                    } else {
+                        ui.label(tr_args("sidebar.overridden", &[&overridden.join(", ")]));
+                    }
+                    reset = ui
+                        .button(tr("sidebar.use_shared_settings"))
+                        .on_hover_text(tr("sidebar.use_shared_settings_help"))
+                        .clicked();
+                    let settings_id = ui.id().with(("chat_settings_override", chat_idx));
+                    settings.show(
+                        ui,
+                        settings_id,
+                        &mut chat.model_picker.template,
+                        chat.model_picker
+                            .info
+                            .as_ref()
+                            .map(|info| info.template.as_str()),
+                        presets,
+                        self.model_tasks.backend,
+                    );
+                }
+            }
+            if reset {
+                chat.settings_override = None;
+            }
+        });
+
+        for request in requests {
+            if let ModelRequest::ModelInfo(name) = &request {
+                if self.settings.inherit_chat_picker
+                    && (name != self.settings.model_picker.selected_model())
+                {
+                    self.settings.model_picker.selected =
+                        self.chats[chat_idx].model_picker.selected.clone();
+                }
+            }
+            self.handle_model_request(request);
+        }
+    }
+
+    fn show_chat_edit_panel(&mut self, ui: &mut egui::Ui, chat_idx: usize) {
         ui.horizontal(|ui| {
             let Some(chat) = self.chats.get(chat_idx) else {
                 return;
             };
-            if chat.summary.is_empty() {
-                ui.heading("Editing Chat \"New Chat\"");
+            let summary = if chat.summary.is_empty() {
+                tr("chat.new_chat")
             } else {
-                ui.heading(format!("Editing Chat \"{}\"", chat.summary));
-            }
+                chat.summary.as_str()
+            };
+            ui.heading(tr_args("sidebar.editing_chat", &[&summary]));
 
             ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui| {
                 if ui
@@ -537,7 +2126,7 @@ impl Sessions {
                             .fill(Color32::TRANSPARENT)
                             .frame(false),
                     )
-                    .on_hover_text("Close")
+                    .on_hover_text(tr("common.close"))
                     .clicked()
                 {
                     self.edited_chat = None;
@@ -545,85 +2134,169 @@ impl Sessions {
             });
         });
 
-        egui::CollapsingHeader::new("Model")
-            .default_open(true)
-            .show(ui, |ui| {
-                let mut request_info_for: Option<String> = None;
-                let is_loading_models = self.is_loading_models();
-                let Some(chat) = self.chats.get_mut(chat_idx) else {
-                    return;
-                };
-                let mut list_models = false;
-                chat.model_picker.show(
-                    ui,
-                    if is_loading_models {
-                        None
-                    } else {
-                        Some(&self.models)
-                    },
-                    &mut |typ| match typ {
-                        RequestInfoType::ModelInfo(name) => {
-                            if !self.pending_model_infos.contains_key(name) {
-                                request_info_for = Some(name.to_string());
+        egui::CollapsingHeader::new(tr("settings.model"))
+            .id_source("edited_chat_model")
+            .default_open(true)
+            .show(ui, |ui| {
+                self.show_chat_model_picker(ui, chat_idx);
+            });
+        ui.collapsing(tr("sidebar.knowledge"), |ui| {
+            let Some(chat) = self.chats.get_mut(chat_idx) else {
+                return;
+            };
+            if let Some(pick) = chat.show_knowledge(ui, &self.ollama_client, &self.models) {
+                let uid = chat.uid;
+                let handle = self.flower.handle();
+                tokio::spawn(async move {
+                    handle.activate();
+                    pick_knowledge_sources(uid, pick, &handle).await;
+                });
+            }
+        });
+        egui::CollapsingHeader::new(tr("sidebar.export"))
+            .id_source("edited_chat_export")
+            .show(ui, |ui| {
+                ui.label(tr("sidebar.export_label"));
+                let format = self.chat_export_format;
+                egui::ComboBox::from_label(tr("sidebar.export_format"))
+                    .selected_text(format.to_string())
+                    .show_ui(ui, |ui| {
+                        for format in ChatExportFormat::ALL {
+                            ui.selectable_value(
+                                &mut self.chat_export_format,
+                                format,
+                                format.to_string(),
+                            );
+                        }
+                    });
+                ui.checkbox(&mut self.export_notes, tr("sidebar.include_notes"));
+                if ui.button(tr("sidebar.save_as")).clicked() {
+                    self.export_chat(chat_idx, format);
+                }
+            });
+    }
+
+    /// Ask where to save the messages of the chat at `idx` and write them there.
+    fn export_chat(&self, idx: usize, format: ChatExportFormat) {
+        let Some(chat) = self.chats.get(idx) else {
+            return;
+        };
+        let task = rfd::AsyncFileDialog::new()
+            .add_filter(
+                tr_args("sidebar.export_filter", &[&format.to_string()]),
+                format.extensions(),
+            )
+            .save_file();
+        let messages = chat.messages.clone();
+        let mut report = chat.report_info();
+        report.notes = self.export_notes;
+        let handle = self.flower.handle();
+        tokio::spawn(async move {
+            let toast = crate::chat::export_messages(messages, report, format, task)
+                .await
+                .map_err(|e| {
+                    log::error!("failed to export messages: {e}");
+                    e
+                });
+
+            handle.activate();
+            if let Ok(toast) = toast {
+                handle.success(OllamaResponse::Toast(toast))
+            } else if let Err(e) = toast {
+                handle.success(OllamaResponse::Toast(Toast::error(e.to_string())))
+            };
+        });
+    }
+
+    /// Remove the chat at `idx`, asking for confirmation first unless it's empty or Shift
+    /// is held.
+    fn request_remove_chat(&mut self, ctx: &egui::Context, idx: usize, modal: &Modal) {
+        if self.chats[idx].messages.is_empty() || ctx.input(|i| i.modifiers.shift) {
+            self.remove_chat(idx);
+        } else {
+            self.chat_marked_for_deletion = idx;
+            self.edited_chat = None;
+            modal.open();
+        }
+    }
+
+    /// Strip of icons shown instead of the sidebar while it's collapsed.
+    fn show_collapsed_panel(&mut self, ui: &mut egui::Ui) {
+        ui.add_space(ui.style().spacing.window_margin.top);
+        ui.vertical_centered(|ui| {
+            if ui
+                .button("⏵")
+                .on_hover_text(tr_args(
+                    "sidebar.expand",
+                    &[&self
+                        .settings
+                        .keybindings
+                        .format(ui.ctx(), KeyAction::ToggleSidebar)],
+                ))
+                .clicked()
+            {
+                self.sidebar_collapsed = false;
+            }
+            ui.toggle_value(&mut self.settings_open, "⚙")
+                .on_hover_text(tr("sidebar.settings"));
+            ui.separator();
+            if ui
+                .button("➕")
+                .on_hover_text(tr_args(
+                    "sidebar.new_chat",
+                    &[&self
+                        .settings
+                        .keybindings
+                        .format(ui.ctx(), KeyAction::NewChat)],
+                ))
+                .clicked()
+            {
+                self.new_chat();
+            }
+            ui.add_space(4.0);
+
+            let (pinned, unpinned, _) = self.chat_list();
+            let mut clicked = None;
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (n, idx) in pinned.into_iter().chain(unpinned).enumerate() {
+                    let summary = self.chats[idx].summary.trim();
+                    // the first letter of the title, or the position for untitled chats
+                    let label = summary
+                        .chars()
+                        .next()
+                        .filter(|c| c.is_alphanumeric())
+                        .map(|c| c.to_uppercase().to_string())
+                        .unwrap_or_else(|| (n + 1).to_string());
+                    let selected = idx == self.selected_chat && !self.settings_open;
+                    if ui
+                        .add(
+                            egui::Button::new(label)
+                                .selected(selected)
+                                .min_size(vec2(28.0, 28.0)),
+                        )
+                        .on_hover_text(format!(
+                            "{}{}",
+                            if summary.is_empty() {
+                                tr("chat.new_chat")
+                            } else {
+                                summary
+                            },
+                            if n < JUMP_KEYS.len() {
+                                format!(" ({})", ui.ctx().format_shortcut(&jump_shortcut(n)))
+                            } else {
+                                String::new()
                             }
-                        }
-                        RequestInfoType::Models => {
-                            list_models = true;
-                        }
-                        RequestInfoType::LoadSettings => (), // can't be called from here
-                    },
-                );
-                if let Some(name) = request_info_for {
-                    if self.settings.inherit_chat_picker
-                        && (name != self.settings.model_picker.selected_model())
+                        ))
+                        .clicked()
                     {
-                        self.settings.model_picker.selected = chat.model_picker.selected.clone();
+                        clicked = Some(idx);
                     }
-
-                    self.request_model_info(name, ollama.clone());
-                }
-                if list_models {
-                    self.list_models(ollama.clone());
                 }
             });
-        ui.collapsing("Export", |ui| {
-            ui.label("Export chat history to a file");
-            let format = self.chat_export_format;
-            egui::ComboBox::from_label("Export Format")
-                .selected_text(format.to_string())
-                .show_ui(ui, |ui| {
-                    for format in ChatExportFormat::ALL {
-                        ui.selectable_value(
-                            &mut self.chat_export_format,
-                            format,
-                            format.to_string(),
-                        );
-                    }
-                });
-            if ui.button("Save As…").clicked() {
-                let task = rfd::AsyncFileDialog::new()
-                    .add_filter(format!("{format:?} file"), format.extensions())
-                    .save_file();
-                let Some(chat) = self.chats.get_mut(chat_idx) else {
-                    return;
-                };
-                let messages = chat.messages.clone();
-                let handle = self.flower.handle();
-                tokio::spawn(async move {
-                    let toast = crate::chat::export_messages(messages, format, task)
-                        .await
-                        .map_err(|e| {
-                            log::error!("failed to export messages: {e}");
-                            e
-                        });
-
-                    handle.activate();
-                    if let Ok(toast) = toast {
-                        handle.success(OllamaResponse::Toast(toast))
-                    } else if let Err(e) = toast {
-                        handle.success(OllamaResponse::Toast(Toast::error(e.to_string())))
-                    };
-                });
+            if let Some(idx) = clicked {
+                self.selected_chat = idx;
+                self.edited_chat = None;
+                self.settings_open = false;
             }
         });
     }
@@ -631,16 +2304,59 @@ impl Sessions {
     fn show_left_panel(&mut self, ui: &mut egui::Ui) {
         ui.add_space(ui.style().spacing.window_margin.top);
         ui.horizontal(|ui| {
-            ui.selectable_value(&mut self.tab, SessionTab::Chats, "Chats");
+            ui.selectable_value(&mut self.tab, SessionTab::Chats, tr("sidebar.chats"));
+            ui.selectable_value(&mut self.tab, SessionTab::Model, tr("sidebar.model"));
             ui.with_layout(Layout::right_to_left(egui::Align::Max), |ui| {
+                if ui
+                    .button("⏴")
+                    .on_hover_text(tr_args(
+                        "sidebar.collapse",
+                        &[&self
+                            .settings
+                            .keybindings
+                            .format(ui.ctx(), KeyAction::ToggleSidebar)],
+                    ))
+                    .clicked()
+                {
+                    self.sidebar_collapsed = true;
+                }
                 ui.toggle_value(&mut self.settings_open, "⚙")
-                    .on_hover_text("Settings");
+                    .on_hover_text(tr("sidebar.settings"));
+                // errors nobody saw yet are counted on the button
+                let errors = logs::unseen_errors();
+                let label = if errors > 0 && !self.log_viewer.open {
+                    egui::RichText::new(format!("📜 {errors}")).color(ui.visuals().error_fg_color)
+                } else {
+                    egui::RichText::new("📜")
+                };
+                ui.toggle_value(&mut self.log_viewer.open, label)
+                    .on_hover_text(tr_args(
+                        "sidebar.logs",
+                        &[&self
+                            .settings
+                            .keybindings
+                            .format(ui.ctx(), KeyAction::ToggleLogs)],
+                    ));
+                self.show_connection_status(ui);
             });
         });
 
         ui.add_space(8.0);
 
+        #[cfg(feature = "tts")]
+        if self.is_speaking {
+            self.show_speaking_indicator(ui);
+            ui.add_space(4.0);
+        }
+
         match self.tab {
+            SessionTab::Chats if self.show_bin => {
+                let modal = Modal::new(ui.ctx(), "delete_permanently_modal");
+                self.show_bin(ui, &modal);
+                modal.show(|ui| {
+                    self.show_delete_permanently_modal_inner(ui, &modal);
+                });
+            }
             SessionTab::Chats => {
                 let modal = Modal::new(ui.ctx(), "remove_chat_modal");
                 self.show_chats(ui, &modal);
@@ -648,6 +2364,145 @@ impl Sessions {
                     self.show_remove_chat_modal_inner(ui, &modal);
                 });
             }
+            SessionTab::Model => {
+                let summary = self
+                    .chats
+                    .get(self.selected_chat)
+                    .map(|c| c.summary.as_str())
+                    .filter(|s| !s.is_empty())
+                    .unwrap_or(tr("chat.new_chat"));
+                ui.label(tr_args("sidebar.model_for", &[&summary]));
+                ui.add_space(2.0);
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    self.show_chat_model_picker(ui, self.selected_chat);
+                    ui.separator();
+                    self.show_model_table(ui);
+                    ui.separator();
+                    self.show_running_models(ui);
+                });
+            }
+        }
+    }
+
+    /// Strip showing what is being read aloud, with a button to stop it.
+    #[cfg(feature = "tts")]
+    fn show_speaking_indicator(&mut self, ui: &mut egui::Ui) {
+        let speaking = self
+            .chats
+            .iter()
+            .enumerate()
+            .find_map(|(i, chat)| Some((i, chat.speaking_message()?)));
+        Frame::group(ui.style()).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                if ui
+                    .button("⏹")
+                    .on_hover_text(tr("sidebar.stop_speaking"))
+                    .clicked()
+                {
+                    if let Some(tts) = &self.tts {
+                        let _ = tts
+                            .write()
+                            .stop()
+                            .map_err(|e| log::error!("failed to stop tts: {e}"));
+                    }
+                }
+                ui.label("🔊");
+                if let Some((chat_idx, msg_idx)) = speaking {
+                    let summary = self.chats[chat_idx].summary.as_str();
+                    let summary = if summary.is_empty() {
+                        tr("chat.new_chat")
+                    } else {
+                        summary
+                    };
+                    if ui
+                        .add(
+                            egui::Label::new(tr_args(
+                                "sidebar.reading",
+                                &[&summary, &(msg_idx + 1)],
+                            ))
+                            .truncate(true)
+                            .sense(egui::Sense::click()),
+                        )
+                        .on_hover_text(tr("sidebar.go_to_chat"))
+                        .clicked()
+                    {
+                        self.selected_chat = chat_idx;
+                        self.tab = SessionTab::Chats;
+                        self.edited_chat = None;
+                    }
+                } else {
+                    ui.label(tr("sidebar.speaking"));
+                }
+            });
+        });
+    }
+
+    /// Colored dot showing the state of the connection, with a retry button on failure.
+    fn show_connection_status(&mut self, ui: &mut egui::Ui) {
+        let endpoint = self.settings.server_url();
+        let (color, status) = match &self.connection {
+            _ if self.is_loading_models() => (
+                ui.visuals().warn_fg_color,
+                tr_args("sidebar.connecting", &[&endpoint]),
+            ),
+            ConnectionState::Connecting => (
+                ui.visuals().warn_fg_color,
+                tr_args("sidebar.connecting", &[&endpoint]),
+            ),
+            ConnectionState::Connected => {
+                (Color32::GREEN, tr_args("sidebar.connected", &[&endpoint]))
+            }
+            ConnectionState::Failed {
+                error, retry_at, ..
+            } => (
+                ui.visuals().error_fg_color,
+                tr_args(
+                    "sidebar.connection_failed",
+                    &[
+                        &endpoint,
+                        error,
+                        &retry_at
+                            .saturating_duration_since(Instant::now())
+                            .as_secs_f32()
+                            .ceil(),
+                    ],
+                ),
+            ),
+            ConnectionState::Unauthorized { error } => (
+                ui.visuals().error_fg_color,
+                tr_args("sidebar.unauthorized", &[&endpoint, error]),
+            ),
+        };
+        if matches!(
+            self.connection,
+            ConnectionState::Failed { .. } | ConnectionState::Unauthorized { .. }
+        ) && !self.is_loading_models()
+            && ui
+                .small_button(tr("sidebar.retry_now"))
+                .on_hover_text(&status)
+                .clicked()
+        {
+            self.list_models();
+        }
+        ui.label(egui::RichText::new("●").color(color))
+            .on_hover_text(status);
+    }
+
+    /// Retry listing the models once the backoff delay of a failed connection has passed.
+    fn retry_connection(&mut self, ctx: &egui::Context) {
+        let ConnectionState::Failed { retry_at, .. } = self.connection else {
+            return;
+        };
+        if self.flower.is_active() {
+            return;
+        }
+        let now = Instant::now();
+        if now >= retry_at {
+            log::info!("retrying to connect to {}", self.settings.server_url());
+            self.list_models();
+        } else {
+            // keep the countdown fresh and wake up in time for the retry
+            ctx.request_repaint_after((retry_at - now).min(Duration::from_secs(1)));
         }
     }
 
@@ -658,14 +2513,22 @@ impl Sessions {
 
     fn poll_ollama_flower(&mut self, modal: &Modal) {
         self.flower.extract(|()| ()).finalize(|resp| {
-            self.flower_activity = OllamaFlowerActivity::Idle;
+            let activity = std::mem::take(&mut self.flower_activity);
             match resp {
                 Ok(OllamaResponse::Ignore) => (),
                 Ok(OllamaResponse::Models(models)) => {
+                    self.connection = ConnectionState::Connected;
+                    self.invalidate_model_infos(&models);
+                    let first_listing = self.models.is_empty();
                     self.models = models;
-                    if !self.settings.model_picker.has_selection() {
-                        self.settings.model_picker.select_best_model(&self.models);
-
+                    let had_selection = self.settings.model_picker.has_selection();
+                    // the default model replaces the last used one on startup
+                    if !had_selection || first_listing {
+                        self.settings
+                            .model_picker
+                            .select_best_model(&self.models, &self.settings.model_choice);
+                    }
+                    if !had_selection {
                         // for each chat with unselected models, select the best model
                         for chat in self.chats.iter_mut() {
                             if !chat.model_picker.has_selection() {
@@ -674,13 +2537,25 @@ impl Sessions {
                             }
                         }
                     }
+                    if let Some(name) = self.preferred_model.take() {
+                        let model = self.models.iter().find(|m| {
+                            m.name == name || m.name.strip_suffix(":latest") == Some(name.as_str())
+                        });
+                        if let Some(model) = model {
+                            let picker = &mut self.chats[self.selected_chat].model_picker;
+                            picker.selected = model.clone().into();
+                            picker.info = None;
+                        } else {
+                            log::warn!("model `{name}` from the command line is not installed");
+                            self.toasts
+                                .warning(tr_args("sidebar.model_not_installed", &[&name]));
+                        }
+                    }
                 }
                 Ok(OllamaResponse::ModelInfo { name, info }) => {
                     self.pending_model_infos.remove(&name);
-                    self.settings.model_picker.on_new_model_info(&name, &info);
-                    for chat in self.chats.iter_mut() {
-                        chat.model_picker.on_new_model_info(&name, &info);
-                    }
+                    self.set_model_info(&name, &info);
+                    self.model_tasks.infos.insert(name, info);
                 }
                 Ok(OllamaResponse::Toast(toast)) => {
                     self.toasts.add(toast);
@@ -691,15 +2566,60 @@ impl Sessions {
                         chat.images.extend(files);
                     }
                 }
+                Ok(OllamaResponse::KnowledgeSources { uid, paths }) => {
+                    if let Some(chat) = self.chats.iter_mut().find(|c| c.uid == uid) {
+                        chat.add_knowledge_sources(paths);
+                    }
+                }
                 Ok(OllamaResponse::Settings(settings)) => {
-                    self.settings = *settings;
+                    let mut settings = *settings;
+                    settings.keep_secrets(&self.settings);
+                    self.settings = settings;
+                    self.settings.model_picker.clamp_settings();
+                    self.settings.auth.load_secret();
+                    self.settings.proxy.load_password();
+                }
+                Ok(OllamaResponse::Backup(backup)) => self.pending_restore = Some(backup),
+                Ok(OllamaResponse::ModelsDeleted { deleted, failed }) => {
+                    self.remove_deleted_models(&deleted);
+                    if !deleted.is_empty() {
+                        self.toasts.success(if deleted.len() == 1 {
+                            tr_args("sidebar.model_deleted", &[&deleted[0]])
+                        } else {
+                            tr_args("sidebar.models_deleted", &[&deleted.len()])
+                        });
+                    }
+                    if !failed.is_empty() {
+                        modal
+                            .dialog()
+                            .with_icon(Icon::Error)
+                            .with_title(tr("sidebar.delete_models_failed"))
+                            .with_body(failed.join("\n"))
+                            .open();
+                    }
+                }
+                Err(flowync::error::Compact::Suppose(e))
+                    if activity == OllamaFlowerActivity::ListModels =>
+                {
+                    // shown by the connection indicator, retried automatically unless the
+                    // credentials were refused
+                    if widgets::is_auth_error(&e) {
+                        self.connection = ConnectionState::Unauthorized { error: e };
+                    } else {
+                        let error =
+                            describe_request_error(e, self.settings.proxy.description().as_ref());
+                        self.connection.failed(error);
+                    }
                 }
                 Err(flowync::error::Compact::Suppose(e)) => {
                     modal
                         .dialog()
                         .with_icon(Icon::Error)
-                        .with_title("Ollama request failed")
-                        .with_body(e)
+                        .with_title(tr("sidebar.request_failed"))
+                        .with_body(describe_request_error(
+                            e,
+                            self.settings.proxy.description().as_ref(),
+                        ))
                         .open();
                 }
                 Err(flowync::error::Compact::Panicked(e)) => {
@@ -707,8 +2627,8 @@ impl Sessions {
                     modal
                         .dialog()
                         .with_icon(Icon::Error)
-                        .with_title("Ollama request task panicked")
-                        .with_body(format!("Task panicked: {e}"))
+                        .with_title(tr("sidebar.request_panicked"))
+                        .with_body(tr_args("common.task_panicked", &[&e]))
                         .open();
                 }
             };
@@ -720,20 +2640,454 @@ impl Sessions {
         self.flower.is_active() && self.flower_activity == OllamaFlowerActivity::ListModels
     }
 
-    #[inline]
-    fn add_default_chat(&mut self) {
-        // id 1 is already used, and we (probably) don't want to reuse ids for flowers
-        self.chats
-            .push(Chat::new(self.chats.len() + 2, self.model_picker().clone()));
-    }
+    #[inline]
+    /// Flower id not used by any chat
+    fn next_chat_id(&self) -> usize {
+        // id 1 is used by the other flowers
+        self.chats.iter().map(Chat::id).max().unwrap_or(1).max(1) + 1
+    }
+
+    /// Insert a copy of the chat at `idx` right after it.
+    fn duplicate_chat(&mut self, idx: usize) {
+        let copy = self.chats[idx].duplicate(self.next_chat_id());
+        self.chats.insert(idx + 1, copy);
+        if self.selected_chat > idx {
+            self.selected_chat += 1;
+        }
+        self.edited_chat = None;
+    }
+
+    fn add_default_chat(&mut self) {
+        // id 1 is already used, and we (probably) don't want to reuse ids for flowers
+        self.chats
+            .push(Chat::new(self.chats.len() + 2, self.model_picker().clone()));
+    }
+
+    fn remove_chat(&mut self, idx: usize) {
+        let chat = self.chats.remove(idx);
+        // empty chats aren't worth keeping
+        if !chat.messages.is_empty() {
+            self.push_undo(Deletion::Chat {
+                uid: chat.uid,
+                index: idx,
+                selected: idx == self.selected_chat,
+            });
+            self.recently_deleted.push(DeletedChat {
+                chat,
+                deleted_at: chrono::Utc::now(),
+            });
+        }
+        // keep the open chat open when one above it goes away
+        if idx < self.selected_chat {
+            self.selected_chat -= 1;
+        }
+        if self.chats.is_empty() {
+            self.add_default_chat();
+            self.selected_chat = 0;
+        } else if self.selected_chat >= self.chats.len() {
+            self.selected_chat = self.chats.len() - 1;
+        }
+    }
+
+    /// Apply the speech settings to the shared TTS instance.
+    #[cfg(feature = "tts")]
+    fn apply_speech_settings(&mut self) {
+        let speech = self.settings.speech.clone();
+        if let Some(tts) = &self.tts {
+            let mut tts = tts.write();
+            if self.default_voice.is_none() {
+                self.default_voice = tts.voice().ok().flatten();
+            }
+            let voice = match &speech.voice {
+                Some(id) => {
+                    let voice = tts
+                        .voices()
+                        .ok()
+                        .and_then(|voices| voices.into_iter().find(|v| v.id() == *id));
+                    if voice.is_none() {
+                        log::warn!("TTS voice `{id}` is not available, using the default voice");
+                    }
+                    voice.or_else(|| self.default_voice.clone())
+                }
+                None => self.default_voice.clone(),
+            };
+            if let Some(voice) = voice {
+                let _ = tts
+                    .set_voice(&voice)
+                    .map_err(|e| log::error!("failed to set TTS voice: {e}"));
+            }
+
+            // unsupported properties are skipped, the setters would only return an error
+            let features = tts.supported_features();
+            if features.rate {
+                let rate = SpeechSettings::denormalize(
+                    speech.rate,
+                    tts.min_rate(),
+                    tts.max_rate(),
+                    tts.normal_rate(),
+                );
+                let _ = tts
+                    .set_rate(rate)
+                    .map_err(|e| log::error!("failed to set TTS rate: {e}"));
+            }
+            if features.pitch {
+                let pitch = SpeechSettings::denormalize(
+                    speech.pitch,
+                    tts.min_pitch(),
+                    tts.max_pitch(),
+                    tts.normal_pitch(),
+                );
+                let _ = tts
+                    .set_pitch(pitch)
+                    .map_err(|e| log::error!("failed to set TTS pitch: {e}"));
+            }
+            if features.volume {
+                let volume = SpeechSettings::denormalize(
+                    speech.volume,
+                    tts.min_volume(),
+                    tts.max_volume(),
+                    tts.normal_volume(),
+                );
+                let _ = tts
+                    .set_volume(volume)
+                    .map_err(|e| log::error!("failed to set TTS volume: {e}"));
+            }
+        }
+        self.applied_speech = Some(speech);
+    }
+
+    /// Mark the state dirty if it may have changed this frame. Anything the user does may
+    /// change it, saving once too often is cheaper than tracking every change.
+    fn track_changes(&mut self, ctx: &egui::Context) {
+        let interacted = ctx.input(|i| {
+            i.viewport().close_requested()
+                || !i.raw.dropped_files.is_empty()
+                || i.events.iter().any(|e| {
+                    matches!(
+                        e,
+                        egui::Event::Text(_)
+                            | egui::Event::Paste(_)
+                            | egui::Event::Key { pressed: true, .. }
+                            | egui::Event::PointerButton { pressed: false, .. }
+                    )
+                })
+        });
+        for chat in &mut self.chats {
+            self.dirty |= chat.take_modified();
+        }
+        self.dirty |= interacted;
+
+        // make sure there's a frame to save on once the interval passed
+        if self.dirty {
+            ctx.request_repaint_after(
+                self.autosave_interval()
+                    .saturating_sub(self.last_save.elapsed()),
+            );
+        }
+    }
+
+    /// How often changes are saved, eframe calls [`eframe::App::save`] this often.
+    pub fn autosave_interval(&self) -> Duration {
+        Duration::from_secs(self.settings.autosave_secs.into())
+    }
+
+    /// Whether anything may have changed since the last save.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.dirty = false;
+        self.last_save = Instant::now();
+    }
+
+    /// See [`Chat::migrate_picker_settings`].
+    pub fn migrate_chat_settings(&mut self) {
+        for chat in &mut self.chats {
+            chat.migrate_picker_settings(self.settings.model_picker.settings());
+        }
+    }
+
+    /// Clamp all saved inference settings into their valid ranges.
+    pub fn clamp_settings(&mut self) {
+        self.settings.model_picker.clamp_settings();
+        for chat in &mut self.chats {
+            chat.model_picker.clamp_settings();
+            if let Some(settings) = &mut chat.settings_override {
+                settings.clamp_to_ranges();
+            }
+        }
+    }
+
+    fn push_undo(&mut self, deletion: Deletion) {
+        if self.undo_stack.len() == UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(deletion);
+        self.undo_notice_until = Some(Instant::now() + UNDO_NOTICE_DURATION);
+    }
+
+    /// Undo the most recent deletion: a chat goes back to where it was in the list, a
+    /// message to where it was in its chat.
+    fn undo_deletion(&mut self) {
+        self.undo_notice_until = None;
+        let Some(deletion) = self.undo_stack.pop() else {
+            return;
+        };
+        match deletion {
+            Deletion::Chat {
+                uid,
+                index,
+                selected,
+            } => {
+                let Some(pos) = self.recently_deleted.iter().position(|d| d.chat.uid == uid) else {
+                    self.toasts.add(Toast::info(tr("sidebar.undo_chat_gone")));
+                    return;
+                };
+                let deleted = self.recently_deleted.remove(pos);
+                let index = index.min(self.chats.len());
+                self.chats.insert(index, deleted.chat);
+                if selected {
+                    self.selected_chat = index;
+                } else if self.selected_chat >= index {
+                    self.selected_chat += 1;
+                }
+                self.edited_chat = None;
+                if self.recently_deleted.is_empty() {
+                    self.show_bin = false;
+                }
+            }
+            Deletion::Message {
+                chat_uid,
+                index,
+                message,
+            } => {
+                let Some(chat) = self.chats.iter_mut().find(|c| c.uid == chat_uid) else {
+                    self.toasts
+                        .add(Toast::info(tr("sidebar.undo_message_chat_gone")));
+                    return;
+                };
+                if chat.flower_active() {
+                    // the streamed reply is written by index
+                    self.toasts
+                        .add(Toast::info(tr("sidebar.undo_wait_for_response")));
+                    self.undo_stack.push(Deletion::Message {
+                        chat_uid,
+                        index,
+                        message,
+                    });
+                    return;
+                }
+                chat.restore_message(index, *message);
+            }
+        }
+    }
+
+    /// Notice offering to undo the latest deletion, shown for a few seconds after it.
+    fn show_undo_notice(&mut self, ctx: &egui::Context) {
+        let Some(until) = self.undo_notice_until else {
+            return;
+        };
+        let now = Instant::now();
+        let Some(latest) = self.undo_stack.last().filter(|_| now < until) else {
+            self.undo_notice_until = None;
+            return;
+        };
+        let text = match latest {
+            Deletion::Chat { .. } => tr("sidebar.chat_deleted"),
+            Deletion::Message { .. } => tr("sidebar.message_deleted"),
+        };
+        let mut undo = false;
+        egui::Area::new(egui::Id::new("undo_notice"))
+            .anchor(egui::Align2::CENTER_BOTTOM, vec2(0.0, -96.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(text);
+                        undo = ui
+                            .button(tr("sidebar.undo"))
+                            .on_hover_text(tr_args(
+                                "sidebar.undo_help",
+                                &[&self
+                                    .settings
+                                    .keybindings
+                                    .format(ctx, KeyAction::UndoDeletion)],
+                            ))
+                            .clicked();
+                    });
+                });
+            });
+        if undo {
+            self.undo_deletion();
+        } else {
+            ctx.request_repaint_after(until - now);
+        }
+    }
+
+    /// Permanently remove chats that have been in the bin for longer than the retention period.
+    pub fn purge_deleted(&mut self) {
+        let retention = chrono::Duration::days(self.settings.retention_days.into());
+        let now = chrono::Utc::now();
+        let len = self.recently_deleted.len();
+        self.recently_deleted
+            .retain(|deleted| now - deleted.deleted_at < retention);
+        if self.recently_deleted.len() != len {
+            log::info!(
+                "purged {} chat(s) from recently deleted",
+                len - self.recently_deleted.len()
+            );
+        }
+    }
+
+    fn restore_chat(&mut self, idx: usize) {
+        let deleted = self.recently_deleted.remove(idx);
+        self.chats.push(deleted.chat);
+        self.selected_chat = self.chats.len() - 1;
+        self.edited_chat = None;
+        if self.recently_deleted.is_empty() {
+            self.show_bin = false;
+        }
+    }
+
+    fn show_delete_permanently_modal_inner(&mut self, ui: &mut egui::Ui, modal: &Modal) {
+        let deletion = self.bin_marked_for_deletion;
+        modal.title(ui, tr("sidebar.delete_permanently_title"));
+        modal.frame(ui, |ui| {
+            modal.body_and_icon(
+                ui,
+                match deletion {
+                    BinDeletion::All => tr("sidebar.delete_all_permanently_body"),
+                    BinDeletion::One(_) => tr("sidebar.delete_permanently_body"),
+                },
+                Icon::Warning,
+            );
+            modal.buttons(ui, |ui| {
+                if modal.button(ui, tr("common.no")).clicked() {
+                    modal.close();
+                }
+                if modal.caution_button(ui, tr("common.yes")).clicked() {
+                    modal.close();
+                    match deletion {
+                        BinDeletion::All => self.recently_deleted.clear(),
+                        BinDeletion::One(idx) => {
+                            if idx < self.recently_deleted.len() {
+                                self.recently_deleted.remove(idx);
+                            }
+                        }
+                    }
+                    if self.recently_deleted.is_empty() {
+                        self.show_bin = false;
+                    }
+                }
+            });
+        });
+    }
+
+    fn show_bin(&mut self, ui: &mut egui::Ui, modal: &Modal) {
+        ui.horizontal(|ui| {
+            if ui.button(tr("sidebar.back")).clicked() {
+                self.show_bin = false;
+            }
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .add_enabled(
+                        !self.recently_deleted.is_empty(),
+                        egui::Button::new(tr("sidebar.empty_bin")),
+                    )
+                    .clicked()
+                {
+                    self.bin_marked_for_deletion = BinDeletion::All;
+                    modal.open();
+                }
+            });
+        });
+        ui.add_enabled(
+            false,
+            egui::Label::new(tr_args(
+                "sidebar.kept_for",
+                &[&self.settings.retention_days],
+            )),
+        );
+        ui.add_space(2.0);
+
+        let mut restore = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if self.recently_deleted.is_empty() {
+                ui.label(tr("sidebar.bin_empty"));
+            }
+            // newest first
+            for idx in (0..self.recently_deleted.len()).rev() {
+                let deleted = &self.recently_deleted[idx];
+                Frame::group(ui.style())
+                    .rounding(Rounding::same(6.0))
+                    .stroke(Stroke::new(2.0, ui.style().visuals.window_stroke.color))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            let summary = if deleted.chat.summary.is_empty() {
+                                tr("chat.new_chat")
+                            } else {
+                                deleted.chat.summary.as_str()
+                            };
+                            ui.add(egui::Label::new(summary).selectable(false).truncate(true));
+                            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                                ui.spacing_mut().item_spacing.x = 0.0;
+                                if ui
+                                    .add(
+                                        egui::Button::new("❌")
+                                            .small()
+                                            .fill(Color32::TRANSPARENT)
+                                            .stroke(Stroke::NONE),
+                                    )
+                                    .on_hover_text(tr("sidebar.delete_permanently"))
+                                    .clicked()
+                                {
+                                    self.bin_marked_for_deletion = BinDeletion::One(idx);
+                                    modal.open();
+                                }
+                                if ui
+                                    .add(
+                                        egui::Button::new("⟲")
+                                            .small()
+                                            .fill(Color32::TRANSPARENT)
+                                            .stroke(Stroke::NONE),
+                                    )
+                                    .on_hover_text(tr("sidebar.restore"))
+                                    .clicked()
+                                {
+                                    restore = Some(idx);
+                                }
+                            });
+                        });
+                        let ago = timeago::Formatter::new()
+                            .convert_chrono(deleted.deleted_at, chrono::Utc::now());
+                        ui.add_enabled(
+                            false,
+                            egui::Label::new(tr_args(
+                                "sidebar.deleted_info",
+                                &[
+                                    &ago,
+                                    &bytesize::ByteSize(deleted.chat.content_size() as u64),
+                                    &deleted.chat.messages.len(),
+                                ],
+                            ))
+                            .selectable(false)
+                            .truncate(true),
+                        )
+                        .on_disabled_hover_text(
+                            deleted
+                                .deleted_at
+                                .with_timezone(&chrono::Local)
+                                .format("%Y-%m-%d %H:%M")
+                                .to_string(),
+                        );
+                    });
+                ui.add_space(2.0);
+            }
+        });
 
-    fn remove_chat(&mut self, idx: usize) {
-        self.chats.remove(idx);
-        if self.chats.is_empty() {
-            self.add_default_chat();
-            self.selected_chat = 0;
-        } else if self.selected_chat >= self.chats.len() {
-            self.selected_chat = self.chats.len() - 1;
+        if let Some(idx) = restore {
+            self.restore_chat(idx);
         }
     }
 
@@ -746,14 +3100,26 @@ impl Sessions {
 
         let last_message = chat
             .last_message_contents()
-            .unwrap_or_else(|| "No recent messages".to_string());
+            .unwrap_or_else(|| tr("sidebar.no_recent_messages").to_string());
 
         let summary = chat.summary.clone();
+        let tags = chat.tags.clone();
+        let pinned = chat.pinned;
+        let archived = chat.archived;
+        let unread = chat.unread;
+        let generating = chat.flower_active();
 
         ui.horizontal(|ui| {
+            if generating {
+                ui.add(egui::Spinner::new().size(12.0))
+                    .on_hover_text(tr("sidebar.generating"));
+            } else if unread {
+                ui.label(egui::RichText::new("●").color(ui.visuals().selection.bg_fill))
+                    .on_hover_text(tr("sidebar.new_response"));
+            }
             if summary.is_empty() {
                 ui.add(
-                    egui::Label::new("New Chat")
+                    egui::Label::new(tr("chat.new_chat"))
                         .selectable(false)
                         .truncate(true),
                 );
@@ -773,16 +3139,10 @@ impl Sessions {
                             .fill(Color32::TRANSPARENT)
                             .stroke(Stroke::NONE),
                     )
-                    .on_hover_text("Remove chat")
+                    .on_hover_text(tr("sidebar.remove_chat"))
                     .clicked()
                 {
-                    if self.chats[idx].messages.is_empty() || ui.input(|i| i.modifiers.shift) {
-                        self.remove_chat(idx);
-                    } else {
-                        self.chat_marked_for_deletion = idx;
-                        self.edited_chat = None;
-                        modal.open();
-                    }
+                    self.request_remove_chat(ui.ctx(), idx, modal);
                     ignore_click = true;
                 }
                 if ui
@@ -792,7 +3152,7 @@ impl Sessions {
                             .fill(Color32::TRANSPARENT)
                             .stroke(Stroke::NONE),
                     )
-                    .on_hover_text("Edit")
+                    .on_hover_text(tr("common.edit"))
                     .clicked()
                 {
                     ignore_click = true;
@@ -804,9 +3164,74 @@ impl Sessions {
                         Some(idx)
                     };
                 }
+                if ui
+                    .add(
+                        egui::Button::new(if archived { "📤" } else { "🗄" })
+                            .small()
+                            .fill(Color32::TRANSPARENT)
+                            .stroke(Stroke::NONE),
+                    )
+                    .on_hover_text(if archived {
+                        tr("sidebar.unarchive_help")
+                    } else {
+                        tr("sidebar.archive_help")
+                    })
+                    .clicked()
+                {
+                    ignore_click = true;
+                    if archived {
+                        self.chats[idx].archived = false;
+                    } else {
+                        self.archive_chat(idx);
+                    }
+                }
+                if ui
+                    .add(
+                        egui::Button::new("🗐")
+                            .small()
+                            .fill(Color32::TRANSPARENT)
+                            .stroke(Stroke::NONE),
+                    )
+                    .on_hover_text(tr("sidebar.duplicate"))
+                    .clicked()
+                {
+                    ignore_click = true;
+                    self.duplicate_chat(idx);
+                }
+                let pin = if pinned {
+                    egui::RichText::new("📌")
+                } else {
+                    egui::RichText::new("📌").weak()
+                };
+                if ui
+                    .add(
+                        egui::Button::new(pin)
+                            .small()
+                            .fill(Color32::TRANSPARENT)
+                            .stroke(Stroke::NONE),
+                    )
+                    .on_hover_text(if pinned {
+                        tr("sidebar.unpin_help")
+                    } else {
+                        tr("sidebar.pin_help")
+                    })
+                    .clicked()
+                {
+                    ignore_click = true;
+                    self.chats[idx].pinned = !pinned;
+                }
             });
         });
 
+        if !tags.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                ui.spacing_mut().item_spacing.x = 4.0;
+                for tag in &tags {
+                    ui.add(tag_chip(tag));
+                }
+            });
+        }
+
         ui.add_enabled(
             false,
             egui::Label::new(last_message)
@@ -855,35 +3280,632 @@ impl Sessions {
         !ignore_click && primary_clicked && hovered
     }
 
+    fn show_search_field(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let clear = self.search.is_active()
+                && ui
+                    .add(egui::Button::new("✖").small().fill(Color32::TRANSPARENT))
+                    .on_hover_text(tr("sidebar.clear_search"))
+                    .clicked();
+            let resp = ui.add(
+                egui::TextEdit::singleline(&mut self.search.query)
+                    .hint_text(tr("sidebar.search"))
+                    .desired_width(f32::INFINITY),
+            );
+            if std::mem::take(&mut self.search.focus) {
+                resp.request_focus();
+            }
+            if resp.changed() {
+                self.search.edited_at = Some(Instant::now());
+            }
+            if clear || (resp.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Escape))) {
+                self.search = ChatSearch::default();
+            }
+        });
+        self.search.update(ui.ctx(), &self.chats);
+    }
+
+    fn show_search_results(&mut self, ui: &mut egui::Ui, modal: &Modal) {
+        if self.search.edited_at.is_none() && self.search.results.is_empty() {
+            ui.vertical_centered(|ui| {
+                ui.weak(tr("sidebar.no_chats_found"));
+            });
+            return;
+        }
+        let mut selected = None;
+        let results = std::mem::take(&mut self.search.results);
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for result in &results {
+                let Some(idx) = self.chats.iter().position(|c| c.uid == result.chat) else {
+                    continue;
+                };
+                if self.show_chat_in_sidepanel(ui, idx, modal) {
+                    selected = Some((idx, result.message.as_ref().map(|m| m.0)));
+                }
+                if let Some((_, snippet)) = &result.message {
+                    ui.add(
+                        egui::Label::new(egui::RichText::new(snippet).small().weak())
+                            .truncate(true),
+                    );
+                }
+                ui.add_space(2.0);
+            }
+        });
+        self.search.results = results;
+        if let Some((chat, message)) = selected {
+            self.selected_chat = chat;
+            self.settings_open = false;
+            self.edited_chat = None;
+            if let Some(message) = message {
+                self.chats[chat].reveal_message(message);
+            }
+        }
+    }
+
     fn show_chats(&mut self, ui: &mut egui::Ui, modal: &Modal) {
         ui.vertical_centered_justified(|ui| {
             if ui
-                .add(egui::Button::new("➕ New Chat").min_size(vec2(0.0, 24.0)))
-                .on_hover_text("Create a new chat")
+                .add(egui::Button::new(tr("sidebar.new_chat_button")).min_size(vec2(0.0, 24.0)))
+                .on_hover_text(tr_args(
+                    "sidebar.create_chat",
+                    &[&self
+                        .settings
+                        .keybindings
+                        .format(ui.ctx(), KeyAction::NewChat)],
+                ))
                 .clicked()
             {
-                self.add_default_chat();
-                self.selected_chat = self.chats.len() - 1;
-                self.edited_chat = None;
-                self.settings_open = false;
+                self.new_chat();
+            }
+            if !self.recently_deleted.is_empty()
+                && ui
+                    .add(
+                        egui::Button::new(tr_args(
+                            "sidebar.recently_deleted",
+                            &[&self.recently_deleted.len()],
+                        ))
+                        .small()
+                        .fill(Color32::TRANSPARENT),
+                    )
+                    .on_hover_text(tr("sidebar.bin_help"))
+                    .clicked()
+            {
+                self.show_bin = true;
             }
         });
 
         ui.add_space(2.0);
+        self.show_search_field(ui);
+        ui.add_space(2.0);
+
+        if self.search.is_active() {
+            self.show_search_results(ui, modal);
+            return;
+        }
+
+        let all_tags = self.all_tags();
+        // tags that are no longer used can't be filtered by
+        self.tag_filter.retain(|tag| all_tags.contains(tag));
+        ui.horizontal(|ui| {
+            ui.weak(tr("sidebar.sort"));
+            egui::ComboBox::from_id_source("chat_order_combo")
+                .selected_text(self.chat_order.label())
+                .show_ui(ui, |ui| {
+                    for order in [ChatOrder::Manual, ChatOrder::Recent] {
+                        ui.selectable_value(&mut self.chat_order, order, order.label());
+                    }
+                });
+            if !all_tags.is_empty() {
+                egui::ComboBox::from_id_source("chat_tag_filter_combo")
+                    .selected_text(if self.tag_filter.is_empty() {
+                        tr("sidebar.all_tags").to_owned()
+                    } else {
+                        self.tag_filter
+                            .iter()
+                            .cloned()
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                    .show_ui(ui, |ui| {
+                        for tag in &all_tags {
+                            let mut selected = self.tag_filter.contains(tag);
+                            if ui.checkbox(&mut selected, tag).changed() {
+                                if selected {
+                                    self.tag_filter.insert(tag.clone());
+                                } else {
+                                    self.tag_filter.remove(tag);
+                                }
+                            }
+                        }
+                    })
+                    .response
+                    .on_hover_text(tr("sidebar.tag_filter_help"));
+            }
+        });
 
+        let draggable = self.chat_order == ChatOrder::Manual;
+        let (pinned, unpinned, archived) = self.chat_list();
         let vlist = self.virtual_list.clone();
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            vlist
-                .borrow_mut()
-                .ui_custom_layout(ui, self.chats.len(), |ui, i| {
-                    if self.show_chat_in_sidepanel(ui, i, modal) {
-                        self.selected_chat = i;
-                        self.settings_open = false;
-                        self.edited_chat = None;
+        let mut dropped = None;
+        let layout = *ui.layout();
+        ui.with_layout(Layout::bottom_up(egui::Align::Min), |ui| {
+            self.show_chats_footer(ui);
+            ui.separator();
+            ui.with_layout(layout, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    if !pinned.is_empty() {
+                        egui::CollapsingHeader::new(tr_args("sidebar.pinned", &[&pinned.len()]))
+                            .id_source("pinned_chats")
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for &i in &pinned {
+                                    if let Some(drop) = self.show_chat_row(ui, i, modal, draggable)
+                                    {
+                                        dropped = Some(drop);
+                                    }
+                                }
+                            });
+                        ui.separator();
+                    }
+                    vlist
+                        .borrow_mut()
+                        .ui_custom_layout(ui, unpinned.len(), |ui, i| {
+                            if let Some(drop) =
+                                self.show_chat_row(ui, unpinned[i], modal, draggable)
+                            {
+                                dropped = Some(drop);
+                            }
+                            1
+                        });
+                    if !archived.is_empty() {
+                        ui.separator();
+                        egui::CollapsingHeader::new(tr_args(
+                            "sidebar.archived",
+                            &[&archived.len()],
+                        ))
+                        .id_source("archived_chats")
+                        .show(ui, |ui| {
+                            for &i in &archived {
+                                self.show_chat_row(ui, i, modal, false);
+                            }
+                        });
                     }
-                    ui.add_space(2.0);
-                    1
                 });
+            });
+        });
+        if let Some((from, to, pinned)) = dropped {
+            self.move_chat(from, to, pinned);
+        }
+    }
+
+    /// Totals over all chats, below the chat list.
+    fn show_chats_footer(&self, ui: &mut egui::Ui) {
+        let messages: usize = self.chats.iter().map(|chat| chat.messages.len()).sum();
+        let size: usize = self.chats.iter().map(Chat::content_size).sum();
+        let chats_key = if self.chats.len() == 1 {
+            "sidebar.chats_one"
+        } else {
+            "sidebar.chats_many"
+        };
+        let messages_key = if messages == 1 {
+            "sidebar.messages_one"
+        } else {
+            "sidebar.messages_many"
+        };
+        ui.weak(format!(
+            "{}, {}",
+            tr_args(chats_key, &[&self.chats.len()]),
+            tr_args(messages_key, &[&chat::compact_count(messages as u64)]),
+        ))
+        .on_hover_text(tr_args(
+            "sidebar.text_size",
+            &[&bytesize::ByteSize(size as u64)],
+        ));
+    }
+
+    /// Tags used by any chat, sorted.
+    fn all_tags(&self) -> BTreeSet<String> {
+        self.chats
+            .iter()
+            .flat_map(|chat| chat.tags.iter().cloned())
+            .collect()
+    }
+
+    /// Context menu of a chat in the sidebar, acting on the chat at `idx` which may not be
+    /// the selected one.
+    fn show_chat_menu(&mut self, ui: &mut egui::Ui, idx: usize, modal: &Modal) {
+        let all_tags = self.all_tags();
+        let Some(chat) = self.chats.get_mut(idx) else {
+            ui.close_menu();
+            return;
+        };
+        let (pinned, archived) = (chat.pinned, chat.archived);
+
+        ui.menu_button(tr("sidebar.rename"), |ui| {
+            let resp = ui.add(
+                egui::TextEdit::singleline(&mut chat.summary)
+                    .hint_text(tr("chat.new_chat"))
+                    .desired_width(200.0),
+            );
+            if ui.memory(|m| m.focused().is_none()) {
+                resp.request_focus();
+            }
+            if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                ui.close_menu();
+            }
+        });
+        if ui.button(tr("sidebar.menu_edit")).clicked() {
+            self.edited_chat = Some(idx);
+            ui.close_menu();
+        }
+        if ui.button(tr("sidebar.menu_duplicate")).clicked() {
+            self.duplicate_chat(idx);
+            ui.close_menu();
+            return;
+        }
+        ui.menu_button(tr("sidebar.menu_export"), |ui| {
+            for format in ChatExportFormat::ALL {
+                if ui.button(format.to_string()).clicked() {
+                    self.export_chat(idx, format);
+                    ui.close_menu();
+                }
+            }
+            ui.separator();
+            ui.checkbox(&mut self.export_notes, tr("sidebar.include_notes"));
+        });
+        if ui
+            .button(if pinned {
+                tr("sidebar.unpin")
+            } else {
+                tr("sidebar.pin")
+            })
+            .clicked()
+        {
+            self.chats[idx].pinned = !pinned;
+            ui.close_menu();
+        }
+        if ui
+            .button(if archived {
+                tr("sidebar.unarchive")
+            } else {
+                tr("sidebar.archive")
+            })
+            .clicked()
+        {
+            if archived {
+                self.chats[idx].archived = false;
+            } else {
+                self.archive_chat(idx);
+            }
+            ui.close_menu();
+            return;
+        }
+        self.show_chat_tags_menu(ui, idx, &all_tags);
+
+        ui.separator();
+        let mut delete = egui::Button::new(tr("sidebar.delete_chat"));
+        if idx == self.selected_chat {
+            delete = delete.shortcut_text(
+                self.settings
+                    .keybindings
+                    .format(ui.ctx(), KeyAction::CloseChat),
+            );
+        }
+        if ui.add(delete).clicked() {
+            ui.close_menu();
+            self.request_remove_chat(ui.ctx(), idx, modal);
+        }
+    }
+
+    /// Menu items to add and remove tags of the chat at `idx`.
+    fn show_chat_tags_menu(&mut self, ui: &mut egui::Ui, idx: usize, all_tags: &BTreeSet<String>) {
+        let Some(chat) = self.chats.get_mut(idx) else {
+            return;
+        };
+        ui.separator();
+        ui.menu_button(tr("sidebar.add_tag"), |ui| {
+            let resp = ui.add(
+                egui::TextEdit::singleline(&mut self.new_tag)
+                    .hint_text(tr("sidebar.new_tag"))
+                    .desired_width(160.0),
+            );
+            let submitted = resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let typed = self.new_tag.trim().to_lowercase();
+            let mut add = None;
+            for tag in all_tags
+                .iter()
+                .filter(|tag| !chat.tags.contains(tag) && tag.to_lowercase().contains(&typed))
+                .take(8)
+            {
+                if ui.add(tag_chip(tag).sense(egui::Sense::click())).clicked() {
+                    add = Some(tag.clone());
+                }
+            }
+            if submitted || ui.button(tr("sidebar.add")).clicked() {
+                add = Some(self.new_tag.trim().to_owned()).filter(|tag| !tag.is_empty());
+            }
+            if let Some(tag) = add {
+                if !chat.tags.contains(&tag) {
+                    chat.tags.push(tag);
+                }
+                self.new_tag.clear();
+                ui.close_menu();
+            }
+        });
+        if !chat.tags.is_empty() {
+            ui.separator();
+            let mut remove = None;
+            for (i, tag) in chat.tags.iter().enumerate() {
+                if ui.button(tr_args("sidebar.remove_tag", &[tag])).clicked() {
+                    remove = Some(i);
+                }
+            }
+            if let Some(i) = remove {
+                chat.tags.remove(i);
+                ui.close_menu();
+            }
+        }
+    }
+
+    fn new_chat(&mut self) {
+        self.add_default_chat();
+        self.selected_chat = self.chats.len() - 1;
+        self.edited_chat = None;
+        self.settings_open = false;
+    }
+
+    /// Shortcuts of the [`KeyAction`]s, remappable in the settings, and Ctrl+1..9 to jump
+    /// to one of the listed chats. Archived chats are skipped when cycling.
+    fn handle_chat_shortcuts(&mut self, ctx: &egui::Context, modal: &Modal) {
+        if modal.is_open() || self.pending_restore.is_some() {
+            return;
+        }
+        let keys = self.settings.keybindings.clone();
+        let pressed = |action: KeyAction| keys.pressed(ctx, action);
+
+        if pressed(KeyAction::ToggleSidebar) {
+            self.sidebar_collapsed = !self.sidebar_collapsed;
+        }
+        if pressed(KeyAction::ToggleLogs) {
+            self.log_viewer.toggle();
+        }
+        if pressed(KeyAction::NewChat) {
+            self.new_chat();
+            return;
+        }
+        // text fields have their own undo
+        if !self.undo_stack.is_empty()
+            && ctx.memory(|m| m.focused().is_none())
+            && pressed(KeyAction::UndoDeletion)
+        {
+            self.undo_deletion();
+            return;
+        }
+        if pressed(KeyAction::CloseChat) {
+            if self.selected_chat < self.chats.len() {
+                self.request_remove_chat(ctx, self.selected_chat, modal);
+            }
+            return;
+        }
+        if pressed(KeyAction::SearchChats) {
+            self.sidebar_collapsed = false;
+            self.show_bin = false;
+            self.tab = SessionTab::Chats;
+            self.search.focus = true;
+        }
+        if let Some(chat) = self.chats.get_mut(self.selected_chat) {
+            if pressed(KeyAction::FindInChat) {
+                chat.open_find();
+            }
+            if pressed(KeyAction::StopGeneration) {
+                chat.stop_generating();
+            }
+        }
+
+        let (pinned, unpinned, _) = self.chat_list();
+        let listed: Vec<usize> = pinned.into_iter().chain(unpinned).collect();
+        if listed.is_empty() {
+            return;
+        }
+        let current = listed.iter().position(|&i| i == self.selected_chat);
+        // the more specific shortcut first, Ctrl+Tab also matches with Shift held
+        let target = if pressed(KeyAction::PreviousChat) {
+            let pos = current.unwrap_or(0);
+            Some(listed[(pos + listed.len() - 1) % listed.len()])
+        } else if pressed(KeyAction::NextChat) {
+            Some(current.map_or(listed[0], |pos| listed[(pos + 1) % listed.len()]))
+        } else {
+            (0..JUMP_KEYS.len())
+                .position(|n| ctx.input_mut(|i| i.consume_shortcut(&jump_shortcut(n))))
+                .and_then(|n| listed.get(n).copied())
+        };
+        if let Some(idx) = target {
+            self.selected_chat = idx;
+            self.edited_chat = None;
+            self.settings_open = false;
+        }
+    }
+
+    /// Indices of the pinned, other and archived chats in the order they're listed in.
+    fn chat_list(&self) -> (Vec<usize>, Vec<usize>, Vec<usize>) {
+        // sorting only changes what's shown, `self.chats` keeps the manual order
+        let (mut listed, archived): (Vec<usize>, Vec<usize>) = (0..self.chats.len())
+            .filter(|&i| {
+                let tags = &self.chats[i].tags;
+                self.tag_filter.iter().all(|tag| tags.contains(tag))
+            })
+            .partition(|&i| !self.chats[i].archived);
+        if self.chat_order == ChatOrder::Recent {
+            listed.sort_by_key(|&i| std::cmp::Reverse(self.chats[i].last_activity()));
+        }
+        let (pinned, unpinned) = listed.into_iter().partition(|&i| self.chats[i].pinned);
+        (pinned, unpinned, archived)
+    }
+
+    /// Move the chat at `idx` to the archive, selecting the next listed chat if it was
+    /// selected.
+    fn archive_chat(&mut self, idx: usize) {
+        if self.selected_chat == idx {
+            let (pinned, unpinned, _) = self.chat_list();
+            let listed: Vec<usize> = pinned.into_iter().chain(unpinned).collect();
+            let pos = listed.iter().position(|&i| i == idx).unwrap_or_default();
+            match listed
+                .get(pos + 1)
+                .or(pos.checked_sub(1).and_then(|p| listed.get(p)))
+            {
+                Some(&next) => self.selected_chat = next,
+                None => {
+                    self.add_default_chat();
+                    self.selected_chat = self.chats.len() - 1;
+                }
+            }
+        }
+        self.chats[idx].archived = true;
+        self.edited_chat = None;
+    }
+
+    /// Shows a chat row, draggable in the manual order. Returns the index of the chat
+    /// dropped onto it, where it should be inserted and whether it should be pinned, in the
+    /// same section as this row.
+    fn show_chat_row(
+        &mut self,
+        ui: &mut egui::Ui,
+        idx: usize,
+        modal: &Modal,
+        draggable: bool,
+    ) -> Option<(usize, usize, bool)> {
+        let show = |ui: &mut egui::Ui| {
+            if self.show_chat_in_sidepanel(ui, idx, modal) {
+                self.selected_chat = idx;
+                self.settings_open = false;
+                self.edited_chat = None;
+            }
+        };
+        let resp = chat_row_drag_source(
+            ui,
+            egui::Id::new(("chat_drag", idx)),
+            DraggedChat(idx),
+            draggable,
+            show,
+        );
+        ui.add_space(2.0);
+        resp.context_menu(|ui| self.show_chat_menu(ui, idx, modal));
+        if !draggable {
+            return None;
+        }
+
+        // insert before this row if the pointer is over its upper half, after it otherwise
+        let after = ui
+            .input(|i| i.pointer.interact_pos())
+            .is_some_and(|pos| pos.y > resp.rect.center().y);
+        if resp.dnd_hover_payload::<DraggedChat>().is_some() {
+            let y = if after {
+                resp.rect.bottom() + 1.0
+            } else {
+                resp.rect.top() - 1.0
+            };
+            ui.painter().hline(
+                resp.rect.x_range(),
+                y,
+                Stroke::new(2.0, ui.visuals().selection.bg_fill),
+            );
+        }
+        let pinned = self.chats.get(idx).is_some_and(|chat| chat.pinned);
+        resp.dnd_release_payload::<DraggedChat>()
+            .map(|dragged| (dragged.0, idx + after as usize, pinned))
+    }
+
+    /// Move the chat at `from` so it's inserted at `to`, an index from before the move.
+    fn move_chat(&mut self, from: usize, to: usize, pinned: bool) {
+        if from >= self.chats.len() || to > self.chats.len() {
+            return;
+        }
+        let mut chat = self.chats.remove(from);
+        chat.pinned = pinned;
+        let to = if to > from { to - 1 } else { to };
+        self.chats.insert(to, chat);
+
+        // keep the selection on the same chat
+        self.selected_chat = if self.selected_chat == from {
+            to
+        } else {
+            let shifted = self.selected_chat - (self.selected_chat > from) as usize;
+            shifted + (shifted >= to) as usize
+        };
+        self.edited_chat = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Chat with one message, empty chats aren't kept in the bin
+    fn chat(summary: &str) -> Chat {
+        ron::from_str(&format!(
+            r#"(summary: "{summary}", messages: [(content: "Hi")])"#
+        ))
+        .unwrap()
+    }
+
+    fn summaries(sessions: &Sessions) -> Vec<&str> {
+        sessions.chats.iter().map(|c| c.summary.as_str()).collect()
+    }
+
+    #[test]
+    fn delete_and_undo_keep_the_selection() {
+        let mut sessions = Sessions {
+            chats: ["A", "B", "C", "D"].map(chat).into(),
+            selected_chat: 2,
+            ..Default::default()
+        };
+
+        // deleting a chat above the open one keeps it open
+        sessions.remove_chat(0);
+        assert_eq!(summaries(&sessions), ["B", "C", "D"]);
+        assert_eq!(sessions.chats[sessions.selected_chat].summary, "C");
+        sessions.undo_deletion();
+        assert_eq!(summaries(&sessions), ["A", "B", "C", "D"]);
+        assert_eq!(sessions.selected_chat, 2);
+
+        // deleting the open chat opens the next one, undo opens it again
+        sessions.remove_chat(2);
+        assert_eq!(sessions.chats[sessions.selected_chat].summary, "D");
+        sessions.undo_deletion();
+        assert_eq!(summaries(&sessions), ["A", "B", "C", "D"]);
+        assert_eq!(sessions.selected_chat, 2);
+
+        // deleting below the open chat doesn't move it
+        sessions.remove_chat(3);
+        sessions.remove_chat(1);
+        assert_eq!(summaries(&sessions), ["A", "C"]);
+        assert_eq!(sessions.chats[sessions.selected_chat].summary, "C");
+        sessions.undo_deletion();
+        sessions.undo_deletion();
+        assert_eq!(summaries(&sessions), ["A", "B", "C", "D"]);
+        assert_eq!(sessions.selected_chat, 2);
+    }
+
+    #[test]
+    fn merging_a_backup_twice_adds_nothing_new() {
+        let mut settings = Settings::default();
+        settings.model_picker.presets.push(widgets::SettingsPreset {
+            name: "Creative".to_owned(),
+            settings: Default::default(),
         });
+        let json = Backup::to_json(&["A", "B"].map(chat), &settings).unwrap();
+
+        let mut sessions = Sessions {
+            chats: vec![chat("Local")],
+            ..Default::default()
+        };
+        for _ in 0..2 {
+            let backup: Backup = serde_json::from_str(&json).unwrap();
+            sessions.restore_backup(backup, false);
+            assert_eq!(summaries(&sessions), ["Local", "A", "B"]);
+            let presets = &sessions.settings.model_picker.presets;
+            assert_eq!(presets.len(), 1);
+            assert_eq!(presets[0].name, "Creative");
+        }
     }
 }
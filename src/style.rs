@@ -60,3 +60,17 @@ pub fn set_style(ctx: &egui::Context) {
     ctx.set_zoom_factor(1.09);
     ctx.set_fonts(fonts);
 }
+
+/// Switch to dark or light visuals, if not already using them.
+pub fn apply_theme(ctx: &egui::Context, dark: bool) {
+    if ctx.style().visuals.dark_mode == dark {
+        return;
+    }
+    let mut visuals = if dark {
+        egui::Visuals::dark()
+    } else {
+        egui::Visuals::light()
+    };
+    visuals.interact_cursor = Some(egui::CursorIcon::PointingHand);
+    ctx.set_visuals(visuals);
+}
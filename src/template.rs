@@ -0,0 +1,804 @@
+//! Preview of a model's chat template filled with a sample conversation. Implements the
+//! part of Go's `text/template` that Ollama templates use: fields, variables, `if`,
+//! `range`, `with` and the comparison functions. Anything else is left in the output as
+//! written instead of failing the whole preview.
+
+const SAMPLE_SYSTEM: &str = "You are a helpful assistant.";
+const SAMPLE_PROMPT: &str = "Why is the sky blue?";
+const SAMPLE_RESPONSE: &str =
+    "Sunlight is scattered by the air, and blue light is scattered the most.";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Nil,
+    Bool(bool),
+    Int(i64),
+    Str(String),
+    List(Vec<Value>),
+    Map(Vec<(&'static str, Value)>),
+}
+
+impl Value {
+    fn truthy(&self) -> bool {
+        match self {
+            Self::Nil => false,
+            Self::Bool(b) => *b,
+            Self::Int(i) => *i != 0,
+            Self::Str(s) => !s.is_empty(),
+            Self::List(list) => !list.is_empty(),
+            Self::Map(map) => !map.is_empty(),
+        }
+    }
+
+    /// The text `{{ value }}` prints, `None` for values we don't know how Go formats.
+    fn text(&self) -> Option<String> {
+        match self {
+            Self::Nil => Some(String::new()),
+            Self::Bool(b) => Some(b.to_string()),
+            Self::Int(i) => Some(i.to_string()),
+            Self::Str(s) => Some(s.clone()),
+            Self::List(_) | Self::Map(_) => None,
+        }
+    }
+
+    /// Missing fields are empty like unset fields of Ollama's structs.
+    fn field(&self, name: &str) -> Result<Self, ()> {
+        match self {
+            Self::Map(map) => Ok(map
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map_or(Self::Nil, |(_, value)| value.clone())),
+            Self::Nil => Ok(Self::Nil),
+            _ => Err(()),
+        }
+    }
+}
+
+fn message(role: &str, content: &str) -> Value {
+    Value::Map(vec![
+        ("Role", Value::Str(role.to_owned())),
+        ("Content", Value::Str(content.to_owned())),
+    ])
+}
+
+/// What Ollama passes to the template: the whole conversation as `.Messages`, or for older
+/// templates that don't use them, the turn as `.Prompt` and `.Response`.
+fn sample_data(with_messages: bool) -> Value {
+    let turn = |text: &str| Value::Str(if with_messages { "" } else { text }.to_owned());
+    Value::Map(vec![
+        ("System", Value::Str(SAMPLE_SYSTEM.to_owned())),
+        ("Prompt", turn(SAMPLE_PROMPT)),
+        ("Response", turn(SAMPLE_RESPONSE)),
+        (
+            "Messages",
+            Value::List(vec![
+                message("system", SAMPLE_SYSTEM),
+                message("user", SAMPLE_PROMPT),
+                message("assistant", SAMPLE_RESPONSE),
+            ]),
+        ),
+    ])
+}
+
+/// `template` filled with a sample system prompt, user message and reply.
+pub fn preview(template: &str) -> String {
+    let tokens = tokenize(template);
+    let mut parser = Parser {
+        src: template,
+        tokens: &tokens,
+        pos: 0,
+    };
+    let nodes = parser.parse_all();
+    let root = sample_data(template.contains(".Messages"));
+    let mut scope = Scope {
+        root: &root,
+        dot: root.clone(),
+        vars: Vec::new(),
+    };
+    let mut out = String::new();
+    scope.render(&nodes, &mut out);
+    out
+}
+
+enum Token {
+    Text(String),
+    /// `{{ body }}`, `start..end` is the whole action in the source
+    Action {
+        body: String,
+        start: usize,
+        end: usize,
+    },
+}
+
+/// Split `src` into text and actions, applying the `{{-` and `-}}` trim markers.
+fn tokenize(src: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = 0;
+    let mut trim_next = false;
+    while let Some(open) = src[rest..].find("{{").map(|i| rest + i) {
+        let Some(close) = src[open + 2..].find("}}").map(|i| open + 2 + i) else {
+            break;
+        };
+        let inner = &src[open + 2..close];
+        // a dash only trims when followed by a space, `{{-3}}` is a negative number
+        let trim_left = inner.starts_with('-') && inner[1..].starts_with(char::is_whitespace);
+        let trim_right =
+            inner.ends_with('-') && inner[..inner.len() - 1].ends_with(char::is_whitespace);
+        let mut body = inner;
+        if trim_left {
+            body = &body[1..];
+        }
+        if trim_right {
+            body = &body[..body.len() - 1];
+        }
+
+        let mut text = &src[rest..open];
+        if trim_next {
+            text = text.trim_start();
+        }
+        if trim_left {
+            text = text.trim_end();
+        }
+        if !text.is_empty() {
+            tokens.push(Token::Text(text.to_owned()));
+        }
+        tokens.push(Token::Action {
+            body: body.trim().to_owned(),
+            start: open,
+            end: close + 2,
+        });
+        trim_next = trim_right;
+        rest = close + 2;
+    }
+    let mut text = &src[rest..];
+    if trim_next {
+        text = text.trim_start();
+    }
+    if !text.is_empty() {
+        tokens.push(Token::Text(text.to_owned()));
+    }
+    tokens
+}
+
+enum Node {
+    Text(String),
+    Action {
+        body: String,
+        raw: String,
+    },
+    /// `if`, `range` and `with`, `branches` holds the conditions of `if` and `else if`
+    Block {
+        keyword: &'static str,
+        branches: Vec<(String, Vec<Node>)>,
+        otherwise: Vec<Node>,
+        raw: String,
+    },
+    /// Shown as written
+    Raw(String),
+}
+
+/// What ended a list of nodes
+enum Stop {
+    End(usize),
+    /// `else`, with the condition of an `else if`, `Err` for other `else` forms
+    Else(Result<Option<String>, ()>),
+    Eof,
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn parse_all(&mut self) -> Vec<Node> {
+        let mut nodes = Vec::new();
+        loop {
+            let (mut list, stop) = self.parse_list();
+            nodes.append(&mut list);
+            // a stray `end` or `else` is kept as text
+            match stop {
+                Stop::Eof => return nodes,
+                Stop::End(_) | Stop::Else(_) => {
+                    let Token::Action { start, end, .. } = &self.tokens[self.pos - 1] else {
+                        unreachable!()
+                    };
+                    nodes.push(Node::Raw(self.src[*start..*end].to_owned()));
+                }
+            }
+        }
+    }
+
+    fn parse_list(&mut self) -> (Vec<Node>, Stop) {
+        let mut nodes = Vec::new();
+        while let Some(token) = self.tokens.get(self.pos) {
+            self.pos += 1;
+            let (body, start, end) = match token {
+                Token::Text(text) => {
+                    nodes.push(Node::Text(text.clone()));
+                    continue;
+                }
+                Token::Action { body, start, end } => (body.as_str(), *start, *end),
+            };
+            let (word, rest) = body.split_once(char::is_whitespace).unwrap_or((body, ""));
+            let rest = rest.trim();
+            match word {
+                "end" => return (nodes, Stop::End(end)),
+                "else" if rest.is_empty() => return (nodes, Stop::Else(Ok(None))),
+                "else" => {
+                    let cond = rest.strip_prefix("if ").map(|cond| cond.trim().to_owned());
+                    return (nodes, Stop::Else(cond.map(Some).ok_or(())));
+                }
+                "if" | "range" | "with" | "define" | "block" => {
+                    nodes.push(self.parse_block(word, rest, start));
+                }
+                _ => nodes.push(Node::Action {
+                    body: body.to_owned(),
+                    raw: self.src[start..end].to_owned(),
+                }),
+            }
+        }
+        (nodes, Stop::Eof)
+    }
+
+    /// Parse up to the `end` of a block opened at `start`, unknown blocks become [`Node::Raw`].
+    fn parse_block(&mut self, word: &str, cond: &str, start: usize) -> Node {
+        let keyword = match word {
+            "if" => "if",
+            "range" => "range",
+            "with" => "with",
+            _ => "",
+        };
+        let mut known = !keyword.is_empty();
+        let mut branches = Vec::new();
+        let mut otherwise = Vec::new();
+        let mut cond = cond.to_owned();
+        let mut in_else = false;
+        let end = loop {
+            let (nodes, stop) = self.parse_list();
+            if in_else {
+                otherwise = nodes;
+            } else {
+                branches.push((std::mem::take(&mut cond), nodes));
+            }
+            match stop {
+                Stop::End(end) => break end,
+                // unclosed, show the rest as written
+                Stop::Eof => return Node::Raw(self.src[start..].to_owned()),
+                Stop::Else(Ok(Some(next))) if keyword == "if" && !in_else => cond = next,
+                Stop::Else(Ok(None)) if !in_else => in_else = true,
+                // `else` after `else`, `else with` and the like, keep going to find the `end`
+                Stop::Else(_) => {
+                    known = false;
+                    in_else = true;
+                }
+            }
+        };
+        let raw = self.src[start..end].to_owned();
+        if known {
+            Node::Block {
+                keyword,
+                branches,
+                otherwise,
+                raw,
+            }
+        } else {
+            Node::Raw(raw)
+        }
+    }
+}
+
+struct Scope<'a> {
+    root: &'a Value,
+    dot: Value,
+    vars: Vec<(String, Value)>,
+}
+
+impl Scope<'_> {
+    fn render(&mut self, nodes: &[Node], out: &mut String) {
+        // variables live until the end of the block they're declared in
+        let vars = self.vars.len();
+        for node in nodes {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Raw(raw) => out.push_str(raw),
+                Node::Action { body, raw } => {
+                    if !self.action(body, out) {
+                        out.push_str(raw);
+                    }
+                }
+                Node::Block {
+                    keyword,
+                    branches,
+                    otherwise,
+                    raw,
+                } => {
+                    let mut block = String::new();
+                    if self.block(keyword, branches, otherwise, &mut block) {
+                        out.push_str(&block);
+                    } else {
+                        out.push_str(raw);
+                    }
+                }
+            }
+        }
+        self.vars.truncate(vars);
+    }
+
+    /// Render an action, `false` if it isn't supported.
+    fn action(&mut self, body: &str, out: &mut String) -> bool {
+        if body.starts_with("/*") {
+            return true;
+        }
+        if let Some((name, expr, declare)) = split_declaration(body) {
+            let Ok(value) = self.eval(expr) else {
+                return false;
+            };
+            let existing = self.vars.iter_mut().rev().find(|(var, _)| var == name);
+            match existing {
+                Some((_, var)) if !declare => *var = value,
+                None if !declare => return false,
+                _ => self.vars.push((name.to_owned(), value)),
+            }
+            return true;
+        }
+        match self.eval(body).map(|value| value.text()) {
+            Ok(Some(text)) => {
+                out.push_str(&text);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Render a block into `out`, `false` if a condition couldn't be evaluated.
+    fn block(
+        &mut self,
+        keyword: &str,
+        branches: &[(String, Vec<Node>)],
+        otherwise: &[Node],
+        out: &mut String,
+    ) -> bool {
+        match keyword {
+            "if" => {
+                for (cond, nodes) in branches {
+                    let Ok(value) = self.eval(cond) else {
+                        return false;
+                    };
+                    if value.truthy() {
+                        self.render(nodes, out);
+                        return true;
+                    }
+                }
+                self.render(otherwise, out);
+                true
+            }
+            "with" => {
+                let Some((expr, nodes)) = branches.first() else {
+                    return false;
+                };
+                let Ok(value) = self.eval(expr) else {
+                    return false;
+                };
+                if value.truthy() {
+                    let dot = std::mem::replace(&mut self.dot, value);
+                    self.render(nodes, out);
+                    self.dot = dot;
+                } else {
+                    self.render(otherwise, out);
+                }
+                true
+            }
+            "range" => {
+                let Some((header, nodes)) = branches.first() else {
+                    return false;
+                };
+                let (names, expr) = match split_range(header) {
+                    Some((names, expr)) => (names, expr),
+                    None => (Vec::new(), header.as_str()),
+                };
+                let items = match self.eval(expr) {
+                    Ok(Value::List(items)) => items,
+                    Ok(Value::Nil) => Vec::new(),
+                    _ => return false,
+                };
+                if items.is_empty() {
+                    self.render(otherwise, out);
+                    return true;
+                }
+                let vars = self.vars.len();
+                for (i, item) in items.into_iter().enumerate() {
+                    match names.as_slice() {
+                        [] => (),
+                        [value] => self.vars.push((value.to_string(), item.clone())),
+                        [index, value, ..] => {
+                            self.vars.push((index.to_string(), Value::Int(i as i64)));
+                            self.vars.push((value.to_string(), item.clone()));
+                        }
+                    }
+                    let dot = std::mem::replace(&mut self.dot, item);
+                    self.render(nodes, out);
+                    self.dot = dot;
+                    self.vars.truncate(vars);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn eval(&self, expr: &str) -> Result<Value, ()> {
+        let tokens = lex(expr)?;
+        let mut pos = 0;
+        let value = self.command(&tokens, &mut pos)?;
+        if pos == tokens.len() {
+            Ok(value)
+        } else {
+            // pipes and anything else we don't understand
+            Err(())
+        }
+    }
+
+    /// A function call or a single operand, up to a closing parenthesis.
+    fn command(&self, tokens: &[Lexeme], pos: &mut usize) -> Result<Value, ()> {
+        let Some(Lexeme::Ident(name)) = tokens.get(*pos) else {
+            return self.operand(tokens, pos);
+        };
+        if matches!(name.as_str(), "true" | "false" | "nil") {
+            return self.operand(tokens, pos);
+        }
+        *pos += 1;
+        let mut args = Vec::new();
+        while !matches!(tokens.get(*pos), None | Some(Lexeme::Close | Lexeme::Pipe)) {
+            args.push(self.operand(tokens, pos)?);
+        }
+        call(name, args)
+    }
+
+    fn operand(&self, tokens: &[Lexeme], pos: &mut usize) -> Result<Value, ()> {
+        let token = tokens.get(*pos).ok_or(())?;
+        *pos += 1;
+        let value = match token {
+            Lexeme::Str(s) => Value::Str(s.clone()),
+            Lexeme::Int(i) => Value::Int(*i),
+            Lexeme::Ident(word) => match word.as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "nil" => Value::Nil,
+                _ => return Err(()),
+            },
+            Lexeme::Field(path) => path_value(self.dot.clone(), path)?,
+            Lexeme::Var(name, path) => {
+                let value = if name == "$" {
+                    self.root.clone()
+                } else {
+                    self.vars
+                        .iter()
+                        .rev()
+                        .find(|(var, _)| var == name)
+                        .map(|(_, value)| value.clone())
+                        .ok_or(())?
+                };
+                path_value(value, path)?
+            }
+            Lexeme::Open => {
+                let value = self.command(tokens, pos)?;
+                if tokens.get(*pos) != Some(&Lexeme::Close) {
+                    return Err(());
+                }
+                *pos += 1;
+                value
+            }
+            Lexeme::Close | Lexeme::Pipe => return Err(()),
+        };
+        Ok(value)
+    }
+}
+
+fn path_value(mut value: Value, path: &[String]) -> Result<Value, ()> {
+    for field in path {
+        value = value.field(field)?;
+    }
+    Ok(value)
+}
+
+/// `$name := expr` or `$name = expr`, with whether it declares a new variable
+fn split_declaration(body: &str) -> Option<(&str, &str, bool)> {
+    if !body.starts_with('$') {
+        return None;
+    }
+    let (name, expr, declare) = match body.split_once(":=") {
+        Some((name, expr)) => (name, expr, true),
+        None => body
+            .split_once(" = ")
+            .map(|(name, expr)| (name, expr, false))?,
+    };
+    let name = name.trim();
+    name.chars()
+        .skip(1)
+        .all(|c| c.is_alphanumeric() || c == '_')
+        .then_some((name, expr.trim(), declare))
+}
+
+/// `$i, $m := expr` or `$m := expr`
+fn split_range(header: &str) -> Option<(Vec<&str>, &str)> {
+    let (names, expr) = header.split_once(":=")?;
+    let names = names.split(',').map(str::trim).collect::<Vec<_>>();
+    names
+        .iter()
+        .all(|name| name.starts_with('$'))
+        .then_some((names, expr.trim()))
+}
+
+#[derive(Debug, PartialEq)]
+enum Lexeme {
+    Open,
+    Close,
+    Pipe,
+    Str(String),
+    Int(i64),
+    Ident(String),
+    /// `.A.B`, empty for `.`
+    Field(Vec<String>),
+    /// `$name.A.B`, the name is `$` for the root
+    Var(String, Vec<String>),
+}
+
+fn lex(expr: &str) -> Result<Vec<Lexeme>, ()> {
+    let mut tokens = Vec::new();
+    let mut chars = expr.char_indices().peekable();
+    let word_end = |from: usize| {
+        expr[from..]
+            .find(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == '|')
+            .map_or(expr.len(), |i| from + i)
+    };
+    let path = |word: &str| -> Vec<String> {
+        word.split('.')
+            .filter(|part| !part.is_empty())
+            .map(str::to_owned)
+            .collect()
+    };
+    while let Some(&(i, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Lexeme::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Lexeme::Close);
+            }
+            '|' => {
+                chars.next();
+                tokens.push(Lexeme::Pipe);
+            }
+            '"' | '`' => {
+                chars.next();
+                let mut text = String::new();
+                let mut closed = false;
+                while let Some((_, ch)) = chars.next() {
+                    if ch == c {
+                        closed = true;
+                        break;
+                    }
+                    if ch == '\\' && c == '"' {
+                        match chars.next().map(|(_, ch)| ch) {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some(ch) => text.push(ch),
+                            None => return Err(()),
+                        }
+                    } else {
+                        text.push(ch);
+                    }
+                }
+                if !closed {
+                    return Err(());
+                }
+                tokens.push(Lexeme::Str(text));
+            }
+            _ => {
+                let end = word_end(i);
+                let word = &expr[i..end];
+                // fields of a parenthesized result, e.g. `(index .Messages 0).Content`
+                if word.starts_with('.') && expr[..i].ends_with(')') {
+                    return Err(());
+                }
+                while chars.peek().is_some_and(|&(j, _)| j < end) {
+                    chars.next();
+                }
+                let token = if let Some(field) = word.strip_prefix('.') {
+                    Lexeme::Field(path(field))
+                } else if let Some(var) = word.strip_prefix('$') {
+                    let (name, fields) = var.split_once('.').unwrap_or((var, ""));
+                    Lexeme::Var(format!("${name}"), path(fields))
+                } else if let Ok(int) = word.parse() {
+                    Lexeme::Int(int)
+                } else if word.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                    Lexeme::Ident(word.to_owned())
+                } else {
+                    return Err(());
+                };
+                tokens.push(token);
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// The template functions Ollama templates use, `Err` for the others.
+fn call(name: &str, args: Vec<Value>) -> Result<Value, ()> {
+    let compare = |args: &[Value]| -> Result<std::cmp::Ordering, ()> {
+        match args {
+            [Value::Int(a), Value::Int(b)] => Ok(a.cmp(b)),
+            [Value::Str(a), Value::Str(b)] => Ok(a.cmp(b)),
+            _ => Err(()),
+        }
+    };
+    let value = match (name, args.as_slice()) {
+        ("eq", [first, rest @ ..]) if !rest.is_empty() => {
+            Value::Bool(rest.iter().any(|arg| arg == first))
+        }
+        ("ne", [a, b]) => Value::Bool(a != b),
+        ("lt", _) => Value::Bool(compare(&args)?.is_lt()),
+        ("le", _) => Value::Bool(compare(&args)?.is_le()),
+        ("gt", _) => Value::Bool(compare(&args)?.is_gt()),
+        ("ge", _) => Value::Bool(compare(&args)?.is_ge()),
+        ("not", [arg]) => Value::Bool(!arg.truthy()),
+        ("and", [.., last]) => args
+            .iter()
+            .find(|arg| !arg.truthy())
+            .unwrap_or(last)
+            .clone(),
+        ("or", [.., last]) => args.iter().find(|arg| arg.truthy()).unwrap_or(last).clone(),
+        ("len", [arg]) => {
+            let len = match arg {
+                Value::Str(s) => s.len(),
+                Value::List(list) => list.len(),
+                Value::Map(map) => map.len(),
+                _ => return Err(()),
+            };
+            Value::Int(len as i64)
+        }
+        ("index", [Value::List(list), Value::Int(i)]) => usize::try_from(*i)
+            .ok()
+            .and_then(|i| list.get(i))
+            .ok_or(())?
+            .clone(),
+        ("slice", [Value::List(list), bounds @ ..]) if bounds.len() <= 2 => {
+            let bound = |arg: Option<&Value>, default: usize| match arg {
+                None => Ok(default),
+                Some(Value::Int(i)) => usize::try_from(*i).map_err(|_| ()),
+                Some(_) => Err(()),
+            };
+            let start = bound(bounds.first(), 0)?;
+            let end = bound(bounds.get(1), list.len())?;
+            Value::List(list.get(start..end).ok_or(())?.to_vec())
+        }
+        _ => return Err(()),
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chatml() {
+        let template = "{{ if .System }}<|im_start|>system
+{{ .System }}<|im_end|>
+{{ end }}{{ if .Prompt }}<|im_start|>user
+{{ .Prompt }}<|im_end|>
+{{ end }}<|im_start|>assistant
+{{ .Response }}<|im_end|>
+";
+        assert_eq!(
+            preview(template),
+            "<|im_start|>system\nYou are a helpful assistant.<|im_end|>\n\
+             <|im_start|>user\nWhy is the sky blue?<|im_end|>\n\
+             <|im_start|>assistant\n\
+             Sunlight is scattered by the air, and blue light is scattered the most.<|im_end|>\n"
+        );
+
+        let template = "{{- range $i, $_ := .Messages }}
+{{- $last := eq (len (slice $.Messages $i)) 1 -}}
+<|im_start|>{{ .Role }}
+{{ .Content }}{{ if not $last }}<|im_end|>
+{{ end }}
+{{- end }}";
+        assert_eq!(
+            preview(template),
+            "<|im_start|>system\nYou are a helpful assistant.<|im_end|>\n\
+             <|im_start|>user\nWhy is the sky blue?<|im_end|>\n\
+             <|im_start|>assistant\n\
+             Sunlight is scattered by the air, and blue light is scattered the most."
+        );
+    }
+
+    #[test]
+    fn llama3() {
+        let template = "{{- if .System }}<|start_header_id|>system<|end_header_id|>
+
+{{ .System }}<|eot_id|>
+{{- end }}
+{{- range $i, $_ := .Messages }}
+{{- $last := eq (len (slice $.Messages $i)) 1 }}
+{{- if eq .Role \"user\" }}<|start_header_id|>user<|end_header_id|>
+
+{{ .Content }}<|eot_id|>{{ if $last }}<|start_header_id|>assistant<|end_header_id|>
+
+{{ end }}
+{{- else if eq .Role \"assistant\" }}<|start_header_id|>assistant<|end_header_id|>
+
+{{ .Content }}{{ if not $last }}<|eot_id|>{{ end }}
+{{- end }}
+{{- end }}";
+        assert_eq!(
+            preview(template),
+            "<|start_header_id|>system<|end_header_id|>\n\n\
+             You are a helpful assistant.<|eot_id|>\
+             <|start_header_id|>user<|end_header_id|>\n\n\
+             Why is the sky blue?<|eot_id|>\
+             <|start_header_id|>assistant<|end_header_id|>\n\n\
+             Sunlight is scattered by the air, and blue light is scattered the most."
+        );
+    }
+
+    #[test]
+    fn gemma() {
+        // no system role, the system prompt goes into the first user turn
+        let template = "<start_of_turn>user
+{{ if .System }}{{ .System }} {{ end }}{{ .Prompt }}<end_of_turn>
+<start_of_turn>model
+{{ .Response }}<end_of_turn>
+";
+        assert_eq!(
+            preview(template),
+            "<start_of_turn>user\n\
+             You are a helpful assistant. Why is the sky blue?<end_of_turn>\n\
+             <start_of_turn>model\n\
+             Sunlight is scattered by the air, and blue light is scattered the most.<end_of_turn>\n"
+        );
+
+        let template = "{{- range $i, $_ := .Messages }}
+{{- $last := eq (len (slice $.Messages $i)) 1 }}
+{{- if or (eq .Role \"user\") (eq .Role \"system\") }}<start_of_turn>user
+{{ .Content }}<end_of_turn>
+{{ if $last }}<start_of_turn>model
+{{ end }}
+{{- else if eq .Role \"assistant\" }}<start_of_turn>model
+{{ .Content }}{{ if not $last }}<end_of_turn>
+{{ end }}
+{{- end }}
+{{- end }}";
+        assert_eq!(
+            preview(template),
+            "<start_of_turn>user\nYou are a helpful assistant.<end_of_turn>\n\
+             <start_of_turn>user\nWhy is the sky blue?<end_of_turn>\n\
+             <start_of_turn>model\n\
+             Sunlight is scattered by the air, and blue light is scattered the most."
+        );
+    }
+
+    #[test]
+    fn unsupported_constructs_pass_through() {
+        let template =
+            "{{ template \"tools\" . }}[INST] {{ .Prompt | json }} {{ toJson .Prompt }} \
+                        {{ .Prompt }} [/INST]{{ block \"x\" . }}x{{ end }}";
+        assert_eq!(
+            preview(template),
+            "{{ template \"tools\" . }}[INST] {{ .Prompt | json }} {{ toJson .Prompt }} \
+             Why is the sky blue? [/INST]{{ block \"x\" . }}x{{ end }}"
+        );
+        // an unknown function in a condition keeps the whole block as written
+        let template = "{{ if hasPrefix .Prompt \"Why\" }}yes{{ else }}no{{ end }}";
+        assert_eq!(preview(template), template);
+        // an unclosed block isn't dropped either
+        assert_eq!(preview("{{ if .System }}open"), "{{ if .System }}open");
+    }
+}
@@ -0,0 +1,144 @@
+//! Voice input: recording from the default microphone and transcribing the recording
+//! locally with whisper.
+
+use crate::i18n::{tr, tr_args};
+use anyhow::{anyhow, Context, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+/// Sample rate whisper expects its input in
+const WHISPER_SAMPLE_RATE: u32 = 16_000;
+
+/// Recording from the default input device, stops when dropped.
+pub struct Recorder {
+    _stream: cpal::Stream,
+    samples: Arc<Mutex<Vec<f32>>>,
+    sample_rate: u32,
+}
+
+impl Recorder {
+    /// Start recording from the default input device.
+    pub fn start() -> Result<Self> {
+        let device = cpal::default_host()
+            .default_input_device()
+            .context(tr("chat.voice_no_microphone"))?;
+        let config = device
+            .default_input_config()
+            .context(tr("chat.voice_microphone_config"))?;
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels() as usize;
+        let samples = Arc::new(Mutex::new(Vec::new()));
+
+        let err_fn = |e| log::error!("audio input error: {e}");
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => {
+                let samples = samples.clone();
+                device.build_input_stream(
+                    &config.into(),
+                    move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                        push_mono(&samples, data, channels, |s| s);
+                    },
+                    err_fn,
+                    None,
+                )
+            }
+            cpal::SampleFormat::I16 => {
+                let samples = samples.clone();
+                device.build_input_stream(
+                    &config.into(),
+                    move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                        push_mono(&samples, data, channels, |s| s as f32 / i16::MAX as f32);
+                    },
+                    err_fn,
+                    None,
+                )
+            }
+            cpal::SampleFormat::U16 => {
+                let samples = samples.clone();
+                device.build_input_stream(
+                    &config.into(),
+                    move |data: &[u16], _: &cpal::InputCallbackInfo| {
+                        push_mono(&samples, data, channels, |s| (s as f32 - 32768.0) / 32768.0);
+                    },
+                    err_fn,
+                    None,
+                )
+            }
+            format => return Err(anyhow!(tr_args("chat.voice_sample_format", &[&format]))),
+        }
+        .context(tr("chat.voice_open"))?;
+        stream.play().context(tr("chat.voice_record"))?;
+
+        Ok(Self {
+            _stream: stream,
+            samples,
+            sample_rate,
+        })
+    }
+
+    /// Stop recording and return the audio as mono samples at whisper's sample rate.
+    pub fn finish(self) -> Vec<f32> {
+        let samples = std::mem::take(&mut *self.samples.lock().unwrap());
+        resample(&samples, self.sample_rate, WHISPER_SAMPLE_RATE)
+    }
+}
+
+/// Downmix interleaved `data` to mono and append it to `samples`.
+fn push_mono<T: Copy>(
+    samples: &Mutex<Vec<f32>>,
+    data: &[T],
+    channels: usize,
+    to_f32: impl Fn(T) -> f32,
+) {
+    let mut samples = samples.lock().unwrap();
+    samples.extend(
+        data.chunks(channels.max(1))
+            .map(|frame| frame.iter().map(|&s| to_f32(s)).sum::<f32>() / frame.len() as f32),
+    );
+}
+
+/// Linear resampling, good enough for speech.
+fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let ratio = from as f64 / to as f64;
+    let len = (samples.len() as f64 / ratio) as usize;
+    (0..len)
+        .map(|i| {
+            let pos = i as f64 * ratio;
+            let idx = pos as usize;
+            let frac = (pos - idx as f64) as f32;
+            let a = samples[idx];
+            let b = samples.get(idx + 1).copied().unwrap_or(a);
+            a + (b - a) * frac
+        })
+        .collect()
+}
+
+/// Transcribe 16 kHz mono `samples` with the whisper model at `model_path`.
+pub fn transcribe(model_path: &Path, samples: &[f32]) -> Result<String> {
+    let model_path = model_path.to_str().context(tr("chat.voice_whisper_path"))?;
+    let ctx = WhisperContext::new_with_params(model_path, WhisperContextParameters::default())
+        .context(tr("chat.voice_whisper_load"))?;
+    let mut state = ctx.create_state().context(tr("chat.voice_whisper_state"))?;
+
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    state
+        .full(params, samples)
+        .context(tr("chat.voice_transcribe"))?;
+
+    let mut text = String::new();
+    for i in 0..state.full_n_segments()? {
+        text.push_str(&state.full_get_segment_text(i)?);
+    }
+    Ok(text.trim().to_owned())
+}
@@ -0,0 +1,34 @@
+//! Detecting resume from system sleep. The monotonic clock stops while the system is
+//! suspended but the wall clock keeps going, so a gap between the two means we just woke up.
+
+use std::time::{Duration, Instant, SystemTime};
+
+/// Minimum drift between the clocks treated as a sleep, small wall clock adjustments
+/// (e.g. NTP) stay below it.
+const SLEEP_THRESHOLD: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy)]
+pub struct WakeDetector {
+    instant: Instant,
+    system: SystemTime,
+}
+
+impl Default for WakeDetector {
+    fn default() -> Self {
+        Self {
+            instant: Instant::now(),
+            system: SystemTime::now(),
+        }
+    }
+}
+
+impl WakeDetector {
+    /// Returns roughly how long the system slept since the last check, if it did.
+    pub fn check(&mut self) -> Option<Duration> {
+        let monotonic = self.instant.elapsed();
+        let wall = self.system.elapsed().unwrap_or_default();
+        *self = Self::default();
+        wall.checked_sub(monotonic)
+            .filter(|slept| *slept >= SLEEP_THRESHOLD)
+    }
+}
@@ -1,3 +1,11 @@
+#[cfg(feature = "tts")]
+use crate::sessions::SharedTts;
+use crate::{
+    backend::{BackendKind, OpenAiClient, OpenAiSettings},
+    golden::{self, GoldenPrompt},
+    i18n::{tr, tr_args, Language},
+    logs,
+};
 use anyhow::Result;
 use eframe::{
     egui::{
@@ -8,31 +16,91 @@ use eframe::{
 };
 use egui_modal::{Icon, Modal};
 use ollama_rs::{
-    generation::options::GenerationOptions,
+    generation::parameters::{KeepAlive, TimeUnit},
     models::{LocalModel, ModelInfo},
-    Ollama,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    time::Duration,
 };
 use url::Url;
 
 #[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(from = "PersistedSelectedModel")]
 pub struct SelectedModel {
     pub name: String,
-    #[serde(default)]
-    modified_ago: String,
+    /// As reported by the server
+    modified_at: String,
+    /// Parsed `modified_at`, `None` if it isn't a valid timestamp
+    #[serde(skip)]
+    modified: Option<chrono::DateTime<chrono::FixedOffset>>,
+    size: u64,
+}
+
+/// [`SelectedModel`] as saved. Older versions also saved a preformatted `modified_ago`,
+/// which is ignored.
+#[derive(serde::Deserialize)]
+struct PersistedSelectedModel {
+    name: String,
     modified_at: String,
     size: u64,
 }
 
+impl From<PersistedSelectedModel> for SelectedModel {
+    fn from(model: PersistedSelectedModel) -> Self {
+        Self::new(model.name, model.modified_at, model.size)
+    }
+}
+
+impl SelectedModel {
+    fn new(name: String, modified_at: String, size: u64) -> Self {
+        Self {
+            name,
+            modified: parse_modified_at(&modified_at).ok(),
+            modified_at,
+            size,
+        }
+    }
+}
+
+pub fn parse_modified_at(
+    modified_at: &str,
+) -> chrono::ParseResult<chrono::DateTime<chrono::FixedOffset>> {
+    chrono::DateTime::parse_from_rfc3339(modified_at)
+}
+
 impl From<LocalModel> for SelectedModel {
     fn from(model: LocalModel) -> Self {
-        let ago = chrono::DateTime::parse_from_rfc3339(&model.modified_at)
-            .map(|time| timeago::Formatter::new().convert_chrono(time, chrono::Utc::now()))
-            .unwrap_or_else(|e| e.to_string());
-        Self {
-            name: model.name,
-            modified_ago: ago,
-            modified_at: model.modified_at,
-            size: model.size,
+        Self::new(model.name, model.modified_at, model.size)
+    }
+}
+
+/// Order of the models in the model list.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum ModelSort {
+    /// As returned by the server
+    #[default]
+    Server,
+    Name,
+    Size,
+    Modified,
+}
+
+impl ModelSort {
+    fn sort(self, models: &mut [&LocalModel], descending: bool) {
+        let key = |m: &LocalModel| parse_modified_at(&m.modified_at).ok();
+        match self {
+            Self::Server => return,
+            Self::Name => models.sort_by(|a, b| a.name.cmp(&b.name)),
+            Self::Size => models.sort_by(|a, b| a.size.cmp(&b.size).then(a.name.cmp(&b.name))),
+            Self::Modified => {
+                models.sort_by(|a, b| key(a).cmp(&key(b)).then(a.name.cmp(&b.name)));
+            }
+        }
+        if descending {
+            models.reverse();
         }
     }
 }
@@ -43,12 +111,262 @@ pub struct ModelPicker {
     pub info: Option<ModelInfo>,
     settings: ModelSettings,
     pub template: Option<String>,
+    #[serde(skip)]
+    pull_name: String,
+    /// Destination name of the model copy, `Some` while the duplicate prompt is open
+    #[serde(skip)]
+    duplicate_name: Option<String>,
+    /// Edited Modelfile, `None` if it wasn't modified
+    #[serde(skip)]
+    modelfile: Option<String>,
+    #[serde(skip)]
+    create_name: String,
+    /// Filter of the model list, kept while the popup is closed
+    #[serde(skip)]
+    model_filter: String,
+    /// Model picked while a settings widget was being edited, applied once the edit ends
+    #[serde(skip)]
+    pending_selection: Option<SelectedModel>,
+    #[serde(default)]
+    sort: ModelSort,
+    #[serde(default)]
+    sort_descending: bool,
+    /// Named snapshots of inference settings
+    #[serde(default)]
+    pub presets: Vec<SettingsPreset>,
+}
+
+/// Named snapshot of [`ModelSettings`]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub struct SettingsPreset {
+    pub name: String,
+    pub settings: ModelSettings,
+}
+
+/// How the model is chosen on startup.
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ModelChoice {
+    /// Model selected on startup whenever it's installed, empty to pick one automatically
+    pub default_model: String,
+    /// Prefer models tagged as instruct or chat models when picking automatically
+    pub prefer_chat: bool,
+    /// Skip larger models when picking automatically, e.g. ones that don't fit in memory
+    pub max_size_gb: Option<f32>,
+}
+
+impl Default for ModelChoice {
+    fn default() -> Self {
+        Self {
+            default_model: String::new(),
+            prefer_chat: true,
+            max_size_gb: None,
+        }
+    }
+}
+
+impl ModelChoice {
+    /// The default model, if one is set and installed.
+    fn default_in<'a>(&self, models: &'a [LocalModel]) -> Option<&'a LocalModel> {
+        if self.default_model.is_empty() {
+            return None;
+        }
+        let default = models.iter().find(|m| {
+            m.name == self.default_model
+                || m.name.strip_suffix(":latest") == Some(self.default_model.as_str())
+        });
+        if default.is_none() {
+            log::warn!("default model `{}` is not installed", self.default_model);
+        }
+        default
+    }
+
+    /// The best fitting model by the heuristic.
+    fn best_in<'a>(&self, models: &'a [LocalModel]) -> Option<&'a LocalModel> {
+        let limit = self.max_size_gb.map(|gb| (gb as f64 * 1e9) as u64);
+        let fits = |m: &&LocalModel| limit.map_or(true, |limit| m.size <= limit);
+        // "-it" is how Gemma tags its instruction tuned models
+        let is_chat = |m: &LocalModel| {
+            m.name
+                .to_lowercase()
+                .split([':', '-', '_', '/'])
+                .any(|part| matches!(part, "instruct" | "chat" | "it"))
+        };
+        let rank = |m: &&LocalModel| {
+            (
+                !m.name.contains("embed"),
+                self.prefer_chat && is_chat(m),
+                m.size,
+            )
+        };
+        models
+            .iter()
+            .filter(fits)
+            .max_by_key(rank)
+            // nothing fits, the smallest one has the best chance
+            .or_else(|| models.iter().min_by_key(|m| m.size))
+    }
 }
 
 pub enum RequestInfoType<'a> {
     Models,
     ModelInfo(&'a str),
+    /// Fetch the info of a model again, bypassing the cache
+    RefreshModelInfo(&'a str),
     LoadSettings,
+    PullModel(&'a str),
+    CancelPull,
+    CopyModel {
+        source: &'a str,
+        destination: &'a str,
+    },
+    CreateModel {
+        name: &'a str,
+        modelfile: &'a str,
+    },
+    ModelDetails(&'a str),
+    /// Select this model on startup, `None` to pick one automatically
+    SetDefaultModel(Option<&'a str>),
+    /// The connection settings were applied, reconnect to the server
+    Reconnect,
+    /// Save all chats and settings to a single file
+    ExportBackup,
+    /// Pick a backup file and ask whether to merge or replace
+    RestoreBackup,
+    /// Remove these models from the server
+    DeleteModels(&'a [String]),
+}
+
+/// Check that `name` is a valid model name with an optional tag, like `mymodel:v2`.
+fn validate_model_name(name: &str) -> Result<(), &'static str> {
+    if name.is_empty() {
+        return Err(tr("models.name_empty"));
+    }
+    if name.chars().any(char::is_whitespace) {
+        return Err(tr("models.name_spaces"));
+    }
+    let (model, tag) = name.split_once(':').unwrap_or((name, "latest"));
+    if model.is_empty() || tag.is_empty() || tag.contains(':') {
+        return Err(tr("models.name_format"));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/' | ':'))
+    {
+        return Err(tr("models.name_chars"));
+    }
+    Ok(())
+}
+
+/// State of a model being pulled from the Ollama library.
+#[derive(Default)]
+pub enum PullState {
+    #[default]
+    Idle,
+    Pulling {
+        model: String,
+        status: String,
+        completed: u64,
+        total: u64,
+    },
+    Failed {
+        model: String,
+        error: String,
+    },
+}
+
+/// State of a model being created from a Modelfile.
+#[derive(Default)]
+pub enum CreateState {
+    #[default]
+    Idle,
+    Creating {
+        model: String,
+        log: Vec<String>,
+    },
+    Finished {
+        model: String,
+        log: Vec<String>,
+        error: Option<String>,
+    },
+}
+
+/// Model management tasks running on the server and model details, shared by all model
+/// pickers.
+#[derive(Default)]
+pub struct ModelTasks {
+    pub pull: PullState,
+    pub create: CreateState,
+    /// Details of local models by name, filled lazily via [`RequestInfoType::ModelDetails`]
+    pub details: HashMap<String, ModelDetails>,
+    /// [`ModelChoice::default_model`], to mark it in the pickers
+    pub default_model: String,
+    /// Info of local models by name, filled by [`RequestInfoType::ModelInfo`]
+    pub infos: HashMap<String, ModelInfo>,
+    /// [`Settings::backend`], to grey out the settings it doesn't support
+    pub backend: BackendKind,
+}
+
+/// Parameter size, quantization level and family of a model.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct ModelDetails {
+    pub parameter_size: String,
+    pub quantization_level: String,
+    /// Architecture, e.g. "llama" or "gemma"
+    pub family: String,
+}
+
+impl ModelDetails {
+    /// Guess the details from a model tag like `llama3:8b-instruct-q4_K_M`, used if the
+    /// server doesn't report them.
+    pub fn from_name(name: &str) -> Self {
+        let tag = name.split_once(':').map(|(_, tag)| tag).unwrap_or_default();
+        let mut details = Self::default();
+        for part in tag.split('-') {
+            let lower = part.to_ascii_lowercase();
+            if details.parameter_size.is_empty()
+                && lower.len() > 1
+                && lower.ends_with(&['b', 'm'][..])
+                && lower[..lower.len() - 1]
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || c == '.' || c == 'x')
+            {
+                details.parameter_size = part.to_ascii_uppercase();
+            } else if details.quantization_level.is_empty()
+                && (lower.starts_with('q') || lower.starts_with("fp") || lower.starts_with("iq"))
+                && lower[1..].starts_with(|c: char| c.is_ascii_digit() || c == 'p' || c == 'q')
+            {
+                details.quantization_level = part.to_ascii_uppercase();
+            }
+        }
+        details
+    }
+}
+
+/// Small rounded label, used for model details.
+fn badge(ui: &mut egui::Ui, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    Frame::none()
+        .fill(ui.visuals().faint_bg_color)
+        .stroke(ui.visuals().widgets.noninteractive.bg_stroke)
+        .rounding(Rounding::same(4.0))
+        .inner_margin(egui::Margin::symmetric(3.0, 0.0))
+        .show(ui, |ui| {
+            ui.add(egui::Label::new(RichText::new(text).small()).selectable(false));
+        });
+}
+
+/// Color derived from `name`, the same name always gets the same color.
+pub fn name_color(name: &str) -> Color32 {
+    // FNV-1a, stable across runs and platforms unlike the std hasher
+    let hash = name.bytes().fold(0x811c9dc5_u32, |h, b| {
+        (h ^ b as u32).wrapping_mul(0x01000193)
+    });
+    let hue = (hash % 360) as f32 / 360.0;
+    egui::ecolor::Hsva::new(hue, 0.45, 0.55, 1.0).into()
 }
 
 fn collapsing_frame<R>(
@@ -100,22 +418,201 @@ const TEMPLATE_HINT_TEXT: &str = r#"{{ if .System }}<|im_start|>system
 {{ .Prompt }}<|im_end|>
 {{ end }}<|im_start|>assistant"#;
 
+/// Panics in debug builds if a widget with the id `id` is shown more than once per frame,
+/// which would make the instances share their egui state (open popups, collapsing headers).
+fn debug_assert_unique_id(ui: &egui::Ui, id: egui::Id) {
+    if cfg!(debug_assertions) {
+        let frame_nr = ui.ctx().frame_nr();
+        let key = id.with("__last_shown_frame");
+        let last_frame_nr = ui.data_mut(|d| {
+            let last = d.get_temp::<u64>(key);
+            d.insert_temp(key, frame_nr);
+            last
+        });
+        debug_assert_ne!(
+            last_frame_nr,
+            Some(frame_nr),
+            "widget id {id:?} is shown more than once in a frame"
+        );
+    }
+}
+
+const SETTINGS_EDIT_FRAME_KEY: &str = "__settings_edit_frame";
+
+/// Remember that a widget of the settings shown with `settings_id` is being dragged or
+/// typed into this frame.
+fn mark_settings_edit(resp: &egui::Response, settings_id: egui::Id) {
+    if resp.dragged() || resp.has_focus() {
+        note_settings_edit(&resp.ctx, settings_id);
+    }
+}
+
+fn note_settings_edit(ctx: &egui::Context, settings_id: egui::Id) {
+    let frame_nr = ctx.frame_nr();
+    ctx.data_mut(|d| d.insert_temp(settings_id.with(SETTINGS_EDIT_FRAME_KEY), frame_nr));
+}
+
+/// Whether a widget of the settings shown with `settings_id` was being dragged or typed
+/// into during the last frame, i.e. a value is mid-edit.
+fn is_editing_settings(ctx: &egui::Context, settings_id: egui::Id) -> bool {
+    let last_edit = ctx.data(|d| d.get_temp::<u64>(settings_id.with(SETTINGS_EDIT_FRAME_KEY)));
+    last_edit.is_some_and(|frame_nr| frame_nr + 1 >= ctx.frame_nr())
+}
+
 impl ModelPicker {
+    /// Show the model picker. `id` must be unique for every picker shown in a frame.
+    /// Inference settings are only shown if `with_settings` is set, chats show their own
+    /// settings overrides instead.
     pub fn show<R>(
         &mut self,
         ui: &mut egui::Ui,
+        id: egui::Id,
+        models: Option<&[LocalModel]>,
+        tasks: &ModelTasks,
+        request_info: &mut R,
+        with_settings: bool,
+    ) where
+        R: FnMut(RequestInfoType<'_>),
+    {
+        debug_assert_unique_id(ui, id);
+        ui.push_id(id, |ui| {
+            self.show_inner(ui, id, models, tasks, request_info, with_settings);
+        });
+    }
+
+    fn show_pull<R>(&mut self, ui: &mut egui::Ui, pull: &PullState, request_info: &mut R)
+    where
+        R: FnMut(RequestInfoType<'_>),
+    {
+        if let PullState::Pulling {
+            model,
+            status,
+            completed,
+            total,
+        } = pull
+        {
+            ui.label(tr_args("models.pulling", &[model, status]));
+            ui.horizontal(|ui| {
+                let progress = if *total > 0 {
+                    *completed as f32 / *total as f32
+                } else {
+                    0.0
+                };
+                let text = if *total > 0 {
+                    format!(
+                        "{} / {}",
+                        bytesize::ByteSize(*completed),
+                        bytesize::ByteSize(*total)
+                    )
+                } else {
+                    String::new()
+                };
+                ui.add(
+                    egui::ProgressBar::new(progress)
+                        .desired_width(200.0)
+                        .text(text)
+                        .animate(*total == 0),
+                );
+                if ui.button(tr("common.cancel")).clicked() {
+                    request_info(RequestInfoType::CancelPull);
+                }
+            });
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.pull_name)
+                    .hint_text("llama3:8b")
+                    .desired_width(150.0),
+            );
+            let name = self.pull_name.trim();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new(tr("models.pull")))
+                .on_hover_text(tr("models.pull_help"))
+                .clicked()
+            {
+                request_info(RequestInfoType::PullModel(name));
+            }
+        });
+
+        if let PullState::Failed { model, error } = pull {
+            ui.label(
+                RichText::new(tr_args("models.pull_failed", &[model, error]))
+                    .color(ui.visuals().error_fg_color),
+            );
+        }
+    }
+
+    fn show_inner<R>(
+        &mut self,
+        ui: &mut egui::Ui,
+        id: egui::Id,
         models: Option<&[LocalModel]>,
+        tasks: &ModelTasks,
         request_info: &mut R,
+        with_settings: bool,
     ) where
         R: FnMut(RequestInfoType<'_>),
     {
+        self.apply_pending_selection(ui.ctx(), id.with("settings"));
+
         if let Some(models) = models {
             ui.horizontal(|ui| {
-                egui::ComboBox::from_id_source("model_selector_combobox")
+                egui::ComboBox::from_id_source(id.with("model_selector_combobox"))
                     .selected_text(self.selected_model())
                     .show_ui(ui, |ui| {
-                        for model in models {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.model_filter)
+                                .hint_text(tr("models.filter")),
+                        );
+                        ui.horizontal(|ui| {
+                            ui.label(tr("models.sort"));
+                            for (sort, name) in [
+                                (ModelSort::Name, tr("models.sort_name")),
+                                (ModelSort::Size, tr("models.sort_size")),
+                                (ModelSort::Modified, tr("models.sort_modified")),
+                            ] {
+                                let selected = self.sort == sort;
+                                let text = match (selected, self.sort_descending) {
+                                    (false, _) => format!("{name} ⬍"),
+                                    (true, false) => format!("{name} ⬆"),
+                                    (true, true) => format!("{name} ⬇"),
+                                };
+                                if ui.selectable_label(selected, text).clicked() {
+                                    if selected {
+                                        self.sort_descending = !self.sort_descending;
+                                    } else {
+                                        self.sort = sort;
+                                        self.sort_descending = false;
+                                    }
+                                }
+                            }
+                            if self.sort != ModelSort::Server
+                                && ui
+                                    .small_button("↺")
+                                    .on_hover_text(tr("models.server_order"))
+                                    .clicked()
+                            {
+                                self.sort = ModelSort::Server;
+                            }
+                        });
+
+                        let mut models: Vec<&LocalModel> = models.iter().collect();
+                        self.sort.sort(&mut models, self.sort_descending);
+
+                        // matches the tag too, e.g. "q5" finds all q5 quantizations
+                        let filter = self.model_filter.trim().to_lowercase();
+                        let memory = crate::memory::system_memory();
+                        let num_ctx = self.num_ctx();
+                        let mut any_shown = false;
+                        for model in models.iter().copied() {
+                            if !filter.is_empty() && !model.name.to_lowercase().contains(&filter) {
+                                continue;
+                            }
+                            any_shown = true;
                             ui.horizontal(|ui| {
+                                crate::chat::model_avatar(ui, &model.name);
                                 if ui
                                     .selectable_label(
                                         self.selected_model() == model.name,
@@ -123,8 +620,25 @@ impl ModelPicker {
                                     )
                                     .clicked()
                                 {
-                                    self.selected = model.clone().into();
-                                    self.info = None;
+                                    self.pending_selection = Some(model.clone().into());
+                                    ui.ctx().request_repaint();
+                                }
+                                let details = tasks.details.get(&model.name);
+                                if let Some(details) = details {
+                                    badge(ui, &details.parameter_size);
+                                    badge(ui, &details.quantization_level);
+                                } else {
+                                    request_info(RequestInfoType::ModelDetails(&model.name));
+                                }
+                                if let Some(memory) = memory {
+                                    let parameters =
+                                        details.map_or("", |d| d.parameter_size.as_str());
+                                    crate::memory::fit_indicator(
+                                        ui,
+                                        &crate::memory::estimate(
+                                            memory, model.size, parameters, num_ctx,
+                                        ),
+                                    );
                                 }
                                 // TODO: make this stick to the right
                                 ui.add_enabled(
@@ -134,12 +648,14 @@ impl ModelPicker {
                             });
                         }
                         if models.is_empty() {
-                            ui.label("No models found, is the server running?");
+                            ui.label(tr("models.none_found"));
+                        } else if !any_shown {
+                            ui.label(tr("models.none_match"));
                         }
                     });
                 if ui
                     .add(egui::Button::new("⟳").small().fill(Color32::TRANSPARENT))
-                    .on_hover_text("Refresh model list")
+                    .on_hover_text(tr("models.refresh"))
                     .clicked()
                 {
                     request_info(RequestInfoType::Models);
@@ -148,81 +664,172 @@ impl ModelPicker {
         } else {
             ui.horizontal(|ui| {
                 ui.add(egui::Spinner::new());
-                ui.label("Loading model list…");
+                ui.label(tr("models.loading"));
             });
         }
 
+        self.show_pull(ui, &tasks.pull, request_info);
+
         if !self.has_selection() {
             return;
         }
 
-        ui.collapsing("Inference Settings", |ui| {
-            self.settings.show(ui, &mut self.template);
-        });
+        if with_settings {
+            self.settings.collapsing_header().show(ui, |ui| {
+                self.settings.show(
+                    ui,
+                    id.with("settings"),
+                    &mut self.template,
+                    self.info.as_ref().map(|info| info.template.as_str()),
+                    &mut self.presets,
+                    tasks.backend,
+                );
+            });
+        }
 
         egui::Grid::new("selected_model_info_grid")
             .num_columns(2)
             .show(ui, |ui| {
-                ui.label("Size");
+                ui.label(tr("models.size"));
                 ui.label(format!("{}", bytesize::ByteSize(self.selected.size)))
                     .on_hover_text(format!("{} bytes", self.selected.size));
                 ui.end_row();
 
-                ui.label("Modified");
-                ui.add(egui::Label::new(&self.selected.modified_ago).truncate(true))
+                // formatted every frame so it doesn't go stale while the app stays open
+                ui.label(tr("models.modified"));
+                let ago = self.selected.modified.map_or_else(
+                    || tr("common.unknown").to_owned(),
+                    |time| timeago::Formatter::new().convert_chrono(time, chrono::Utc::now()),
+                );
+                ui.add(egui::Label::new(ago).truncate(true))
                     .on_hover_text(&self.selected.modified_at);
                 ui.end_row();
+
+                if let Some(details) = tasks.details.get(&self.selected.name) {
+                    if !details.parameter_size.is_empty() {
+                        ui.label(tr("models.parameters"));
+                        ui.horizontal(|ui| badge(ui, &details.parameter_size));
+                        ui.end_row();
+                    }
+                    if !details.quantization_level.is_empty() {
+                        ui.label(tr("models.quantization"));
+                        ui.horizontal(|ui| badge(ui, &details.quantization_level));
+                        ui.end_row();
+                    }
+                    if let Some(memory) = crate::memory::system_memory() {
+                        let estimate = crate::memory::estimate(
+                            memory,
+                            self.selected.size,
+                            &details.parameter_size,
+                            self.num_ctx(),
+                        );
+                        ui.label(tr("models.memory"));
+                        ui.horizontal(|ui| {
+                            crate::memory::fit_indicator(ui, &estimate);
+                            ui.label(estimate.fit.label());
+                        });
+                        ui.end_row();
+                    }
+                } else {
+                    request_info(RequestInfoType::ModelDetails(&self.selected.name));
+                }
             });
 
+        ui.horizontal(|ui| {
+            if tasks.default_model == self.selected.name {
+                ui.label(tr("models.default"));
+                if ui
+                    .small_button(tr("models.unset"))
+                    .on_hover_text(tr("models.unset_help"))
+                    .clicked()
+                {
+                    request_info(RequestInfoType::SetDefaultModel(None));
+                }
+            } else if ui
+                .small_button(tr("models.use_default"))
+                .on_hover_text(tr("models.use_default_help"))
+                .clicked()
+            {
+                request_info(RequestInfoType::SetDefaultModel(Some(&self.selected.name)));
+            }
+        });
+
+        self.show_duplicate(ui, models, request_info);
+
+        if self.info.is_none() {
+            if let Some(info) = tasks.infos.get(&self.selected.name) {
+                self.info = Some(info.clone());
+                self.modelfile = None;
+            }
+        }
         if let Some(info) = &self.info {
-            for (heading, mut text) in [
-                ("License", info.license.as_str()),
-                ("Modelfile", info.modelfile.as_str()),
-                ("Parameters", info.parameters.as_str()),
-            ] {
+            let readonly_frame = |ui: &mut egui::Ui, heading: &str, mut text: &str| {
                 if !text.is_empty() {
                     collapsing_frame(ui, heading, |ui| {
                         ui.code_editor(&mut text);
                     });
                 }
-            }
+            };
+
+            readonly_frame(ui, tr("models.license"), &info.license);
+            collapsing_frame(ui, tr("models.modelfile"), |ui| {
+                Self::show_modelfile_editor(
+                    ui,
+                    &info.modelfile,
+                    &mut self.modelfile,
+                    &mut self.create_name,
+                    &tasks.create,
+                    models,
+                    request_info,
+                );
+            });
+            readonly_frame(ui, tr("models.parameters"), &info.parameters);
 
-            collapsing_frame(ui, "Template", |ui| {
+            collapsing_frame(ui, tr("models.template"), |ui| {
                 ui.horizontal_wrapped(|ui| {
                     ui.spacing_mut().item_spacing.x = 0.0;
-                    ui.label("Prompt template to be passed into the model. It may include (optionally) a system message, a user's message and the response from the model. Note: syntax may be model specific. Templates use Go ");
+                    ui.label(tr("models.template_help"));
                     ui.spacing_mut().item_spacing.x = 0.0;
                     const TEMPLATE_LINK: &str = "https://pkg.go.dev/text/template";
-                    ui.hyperlink_to("template syntax", TEMPLATE_LINK).on_hover_text(TEMPLATE_LINK);
-                    ui.label(". This overrides what is defined in the Modelfile. The default template is shown in the Template header.");
+                    ui.hyperlink_to(tr("models.template_syntax"), TEMPLATE_LINK)
+                        .on_hover_text(TEMPLATE_LINK);
+                    ui.label(tr("models.template_help_end"));
                 });
-                egui::Grid::new("set_template_variable_grid").num_columns(2).show(ui, |ui| {
-                    ui.add(egui::Label::new(RichText::new("Variable").strong()).wrap(true));
-                    ui.add(egui::Label::new(RichText::new("Description").strong()).wrap(true));
-                    ui.end_row();
+                egui::Grid::new("set_template_variable_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::Label::new(RichText::new(tr("models.variable")).strong())
+                                .wrap(true),
+                        );
+                        ui.add(
+                            egui::Label::new(RichText::new(tr("models.description")).strong())
+                                .wrap(true),
+                        );
+                        ui.end_row();
 
-                    ui.code("{{ .System }}");
-                    ui.add(egui::Label::new("The system message used to specify custom behavior.").wrap(true));
-                    ui.end_row();
+                        ui.code("{{ .System }}");
+                        ui.add(egui::Label::new(tr("models.var_system")).wrap(true));
+                        ui.end_row();
 
-                    ui.code("{{ .Prompt }}");
-                    ui.add(egui::Label::new("The user prompt message.").wrap(true));
-                    ui.end_row();
+                        ui.code("{{ .Prompt }}");
+                        ui.add(egui::Label::new(tr("models.var_prompt")).wrap(true));
+                        ui.end_row();
 
-                    ui.code("{{ .Response }}");
-                    ui.add(egui::Label::new("The response from the model. When generating a response, text after this variable is omitted.").wrap(true));
-                    ui.end_row();
-                });
+                        ui.code("{{ .Response }}");
+                        ui.add(egui::Label::new(tr("models.var_response")).wrap(true));
+                        ui.end_row();
+                    });
 
                 const DOCS_LINK: &str =
                     "https://github.com/ollama/ollama/blob/main/docs/modelfile.md#template";
-                ui.hyperlink_to("Ollama Docmentation", DOCS_LINK)
+                ui.hyperlink_to(tr("models.docs"), DOCS_LINK)
                     .on_hover_text(DOCS_LINK);
 
                 let mut enabled = self.template.is_some();
                 ui.horizontal(|ui| {
                     ui.add(toggle(&mut enabled));
-                    ui.label("Override (overrides the template set in the Modelfile)");
+                    ui.label(tr("models.template_override"));
                 });
                 if !enabled {
                     self.template = None;
@@ -241,14 +848,210 @@ impl ModelPicker {
                 });
 
                 ui.separator();
-                ui.label("Modelfile template:");
+                ui.label(tr("models.modelfile_template"));
                 ui.code_editor(&mut info.template.as_str());
             });
+
+            collapsing_frame(ui, tr("models.template_preview"), |ui| {
+                // the override wins, like when the chat is sent
+                let template = self
+                    .template
+                    .as_deref()
+                    .filter(|t| !t.trim().is_empty())
+                    .unwrap_or(&info.template);
+                if template.trim().is_empty() {
+                    ui.label(tr("models.template_preview_empty"));
+                    return;
+                }
+                ui.label(tr("models.template_preview_help"));
+                ui.code_editor(&mut crate::template::preview(template).as_str());
+            });
+
+            if ui
+                .small_button(tr("models.refresh_info"))
+                .on_hover_text(tr("models.refresh_info_help"))
+                .clicked()
+            {
+                request_info(RequestInfoType::RefreshModelInfo(&self.selected.name));
+                self.info = None;
+            }
         } else {
             request_info(RequestInfoType::ModelInfo(self.selected_model()));
             ui.horizontal(|ui| {
                 ui.add(egui::Spinner::new());
-                ui.label("Loading model info…");
+                ui.label(tr("models.loading_info"));
+            });
+        }
+    }
+
+    fn show_duplicate<R>(
+        &mut self,
+        ui: &mut egui::Ui,
+        models: Option<&[LocalModel]>,
+        request_info: &mut R,
+    ) where
+        R: FnMut(RequestInfoType<'_>),
+    {
+        let Some(destination) = &mut self.duplicate_name else {
+            if ui
+                .button(tr("models.duplicate"))
+                .on_hover_text(tr("models.duplicate_help"))
+                .clicked()
+            {
+                self.duplicate_name = Some(format!("{}-copy", self.selected.name));
+            }
+            return;
+        };
+
+        let mut close = false;
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(destination)
+                    .hint_text("mymodel:v2")
+                    .desired_width(150.0),
+            );
+            let name = destination.trim();
+            let valid = validate_model_name(name);
+            if ui
+                .add_enabled(valid.is_ok(), egui::Button::new(tr("models.copy")))
+                .clicked()
+            {
+                request_info(RequestInfoType::CopyModel {
+                    source: &self.selected.name,
+                    destination: name,
+                });
+                close = true;
+            }
+            if ui.button(tr("common.cancel")).clicked() {
+                close = true;
+            }
+        });
+
+        let name = destination.trim();
+        if let Err(e) = validate_model_name(name) {
+            ui.label(RichText::new(e).color(ui.visuals().error_fg_color));
+        } else if models.is_some_and(|models| {
+            models
+                .iter()
+                .any(|m| m.name == name || m.name == format!("{name}:latest"))
+        }) {
+            ui.label(
+                RichText::new(tr_args("models.overwritten", &[&name]))
+                    .color(ui.visuals().warn_fg_color),
+            );
+        }
+
+        if close {
+            self.duplicate_name = None;
+        }
+    }
+
+    fn show_modelfile_editor<R>(
+        ui: &mut egui::Ui,
+        original: &str,
+        modelfile: &mut Option<String>,
+        name: &mut String,
+        create: &CreateState,
+        models: Option<&[LocalModel]>,
+        request_info: &mut R,
+    ) where
+        R: FnMut(RequestInfoType<'_>),
+    {
+        let draft = modelfile.get_or_insert_with(|| original.to_owned());
+        ui.code_editor(draft);
+
+        let mut reset = false;
+        let creating = matches!(create, CreateState::Creating { .. });
+        let trimmed_name = name.trim().to_owned();
+        let valid = validate_model_name(&trimmed_name);
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(name)
+                    .hint_text("mymodel:custom")
+                    .desired_width(150.0),
+            );
+            if ui
+                .add_enabled(
+                    valid.is_ok() && !creating && !draft.trim().is_empty(),
+                    egui::Button::new(tr("models.create")),
+                )
+                .on_hover_text(tr("models.create_help"))
+                .clicked()
+            {
+                request_info(RequestInfoType::CreateModel {
+                    name: &trimmed_name,
+                    modelfile: draft,
+                });
+            }
+            if draft != original
+                && ui
+                    .button("↺")
+                    .on_hover_text(tr("models.reset_modelfile"))
+                    .clicked()
+            {
+                reset = true;
+            }
+        });
+        if reset {
+            *modelfile = None;
+        }
+
+        if !trimmed_name.is_empty() {
+            if let Err(e) = valid {
+                ui.label(RichText::new(e).color(ui.visuals().error_fg_color));
+            } else if models.is_some_and(|models| {
+                models
+                    .iter()
+                    .any(|m| m.name == trimmed_name || m.name == format!("{trimmed_name}:latest"))
+            }) {
+                ui.label(
+                    RichText::new(tr_args("models.overwritten", &[&trimmed_name]))
+                        .color(ui.visuals().warn_fg_color),
+                );
+            }
+        }
+
+        let (model, log) = match create {
+            CreateState::Idle => return,
+            CreateState::Creating { model, log } => {
+                ui.horizontal(|ui| {
+                    ui.add(egui::Spinner::new());
+                    ui.add(
+                        egui::Label::new(tr_args(
+                            "models.creating",
+                            &[
+                                model,
+                                &log.last().map_or(tr("models.starting"), String::as_str),
+                            ],
+                        ))
+                        .truncate(true),
+                    );
+                });
+                (model, log)
+            }
+            CreateState::Finished { model, log, error } => {
+                if let Some(error) = error {
+                    ui.label(
+                        RichText::new(tr_args("models.create_failed", &[model, error]))
+                            .color(ui.visuals().error_fg_color),
+                    );
+                } else {
+                    ui.label(tr_args("models.created", &[model]));
+                }
+                (model, log)
+            }
+        };
+
+        if !log.is_empty() {
+            ui.collapsing(tr_args("models.build_log", &[model]), |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(150.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for line in log {
+                            ui.monospace(line);
+                        }
+                    });
             });
         }
     }
@@ -256,19 +1059,40 @@ impl ModelPicker {
     pub fn on_new_model_info(&mut self, name: &str, info: &ModelInfo) {
         if self.selected_model() == name {
             self.info = Some(info.clone());
+            self.modelfile = None;
         }
     }
 
-    pub fn select_best_model(&mut self, models: &[LocalModel]) {
-        if let Some(m) = models.iter().max_by_key(|m| m.size) {
-            self.selected = m.clone().into();
+    /// Select the default model if it's installed, otherwise the best fitting one if nothing
+    /// is selected yet.
+    pub fn select_best_model(&mut self, models: &[LocalModel], choice: &ModelChoice) {
+        let model = match choice.default_in(models) {
+            Some(model) => model,
+            None if !self.has_selection() => match choice.best_in(models) {
+                Some(model) => model,
+                None => return,
+            },
+            None => return,
+        };
+        if model.name != self.selected.name {
+            self.selected = model.clone().into();
+            self.info = None;
         }
+        log::info!(
+            "subjectively selected best model: {}",
+            self.selected_model()
+        );
+    }
 
-        if self.has_selection() {
-            log::info!(
-                "subjectively selected best model: {}",
-                self.selected_model()
-            );
+    /// Switch to the model picked in the list, unless a widget of the settings shown with
+    /// `settings_id` is still being dragged or typed into, so the edit lands on the model
+    /// it was started for.
+    fn apply_pending_selection(&mut self, ctx: &egui::Context, settings_id: egui::Id) {
+        if self.pending_selection.is_some() && !is_editing_settings(ctx, settings_id) {
+            if let Some(selected) = self.pending_selection.take() {
+                self.selected = selected;
+                self.info = None;
+            }
         }
     }
 
@@ -278,14 +1102,59 @@ impl ModelPicker {
     }
 
     #[inline]
-    pub fn get_generation_options(&self) -> GenerationOptions {
-        self.settings.clone().into()
+    pub fn settings(&self) -> &ModelSettings {
+        &self.settings
+    }
+
+    /// The settings along with the presets, to edit other settings with the same presets.
+    #[inline]
+    pub fn settings_with_presets(&mut self) -> (&ModelSettings, &mut Vec<SettingsPreset>) {
+        (&self.settings, &mut self.presets)
+    }
+
+    /// Clamp the settings and presets into the valid ranges, see [`ModelSettings::clamp_to_ranges`].
+    pub fn clamp_settings(&mut self) {
+        self.settings.clamp_to_ranges();
+        for preset in &mut self.presets {
+            preset.settings.clamp_to_ranges();
+        }
     }
 
     #[inline]
     pub fn selected_model(&self) -> &str {
         &self.selected.name
     }
+
+    /// Context window the memory estimate assumes
+    fn num_ctx(&self) -> u64 {
+        self.settings
+            .num_ctx
+            .map_or(crate::chat::DEFAULT_NUM_CTX, u64::from)
+    }
+
+    /// Guess whether the selected model accepts images. Vision models ship a projector as a
+    /// second `FROM` layer in their Modelfile, the name is used until the info is loaded.
+    pub fn likely_multimodal(&self) -> bool {
+        const VISION_NAMES: &[&str] = &[
+            "llava",
+            "bakllava",
+            "moondream",
+            "vision",
+            "minicpm-v",
+            "-vl",
+            "gemma3",
+            "pixtral",
+        ];
+        let name = self.selected.name.to_lowercase();
+        VISION_NAMES.iter().any(|n| name.contains(n))
+            || self.info.as_ref().is_some_and(|info| {
+                info.modelfile
+                    .lines()
+                    .filter(|l| l.trim_start().starts_with("FROM "))
+                    .count()
+                    > 1
+            })
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
@@ -302,17 +1171,18 @@ impl MirostatKind {
     }
 
     #[inline]
-    const fn name(self) -> &'static str {
+    fn name(self) -> &'static str {
         match self {
-            Self::Disabled => "Disabled",
+            Self::Disabled => tr("inference.disabled"),
             Self::Mirostat => "Mirostat",
             Self::Mirostat2 => "Mirostat 2.0",
         }
     }
 }
 
-#[derive(Default, Clone, serde::Deserialize, serde::Serialize)]
-struct ModelSettings {
+#[derive(Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ModelSettings {
     /// Enable Mirostat sampling for controlling perplexity. (default: 0, 0 = disabled, 1 = Mirostat, 2 = Mirostat 2.0)
     pub mirostat: Option<MirostatKind>,
     /// Influences how quickly the algorithm responds to feedback from the generated text. A lower learning rate will result in slower adjustments, while a higher learning rate will make the algorithm more responsive. (Default: 0.1)
@@ -345,73 +1215,468 @@ struct ModelSettings {
     pub top_k: Option<u32>,
     /// Works together with top-k. A higher value (e.g., 0.95) will lead to more diverse text, while a lower value (e.g., 0.5) will generate more focused and conservative text. (Default: 0.9)
     pub top_p: Option<f32>,
+    /// Alternative to the top_p, and aims to ensure a balance of quality and variety. The parameter p represents the minimum probability for a token to be considered, relative to the probability of the most likely token. For example, with p=0.05 and the most likely token having a probability of 0.9, logits with a value less than 0.045 are filtered out. (Default: 0.0)
+    pub min_p: Option<f32>,
+    /// Enables locally typical sampling with parameter p, which keeps the tokens whose probability is close to the expected information content. A value of 1.0 disables this setting. (Default: 1.0)
+    pub typical_p: Option<f32>,
+    /// Penalizes tokens that have already appeared in the text, encouraging the model to talk about new topics. (Default: 0.0)
+    pub presence_penalty: Option<f32>,
+    /// Penalizes tokens proportionally to how often they have appeared in the text so far, reducing verbatim repetition. (Default: 0.0)
+    pub frequency_penalty: Option<f32>,
+    /// Sets the number of prompt tokens processed in parallel. Larger values speed up prompt processing at the cost of memory. (Default: 512)
+    pub num_batch: Option<u32>,
+    /// Whether the repetition penalties also apply to newline tokens. (Default: true)
+    pub penalize_newline: Option<bool>,
+    /// How long the model stays loaded after a request, e.g. "30m". (Default: 5m, -1 = forever, 0 = unload immediately)
+    pub keep_alive: Option<String>,
+    /// Force the model to reply with valid JSON
+    pub json_mode: bool,
 }
 
-impl From<ModelSettings> for GenerationOptions {
-    fn from(value: ModelSettings) -> Self {
-        let mut s = Self::default();
-        if let Some(mirostat) = value.mirostat {
-            s = s.mirostat(mirostat.to_u8());
-        }
-        if let Some(mirostat_eta) = value.mirostat_eta {
-            s = s.mirostat_eta(mirostat_eta);
-        }
-        if let Some(mirostat_tau) = value.mirostat_tau {
-            s = s.mirostat_tau(mirostat_tau);
-        }
-        if let Some(num_ctx) = value.num_ctx {
-            s = s.num_ctx(num_ctx);
-        }
-        if let Some(num_gqa) = value.num_gqa {
-            s = s.num_gqa(num_gqa);
-        }
-        if let Some(num_gpu) = value.num_gpu {
-            s = s.num_gpu(num_gpu);
+/// (label key, value) presets offered for `keep_alive`
+const KEEP_ALIVE_PRESETS: &[(&str, &str)] = &[
+    ("inference.keep_alive_default", "5m"),
+    ("inference.keep_alive_30m", "30m"),
+    ("inference.keep_alive_forever", "-1"),
+    ("inference.keep_alive_unload", "0"),
+];
+
+/// Parse a keep_alive duration the way Ollama does: a negative number keeps the model loaded
+/// forever, 0 unloads it right away, otherwise a number of seconds or a number with an
+/// `s`, `m` or `h` suffix.
+pub fn parse_keep_alive(value: &str) -> Option<KeepAlive> {
+    let value = value.trim();
+    if let Ok(n) = value.parse::<i64>() {
+        return Some(match n {
+            ..=-1 => KeepAlive::Indefinitely,
+            0 => KeepAlive::UnloadOnCompletion,
+            n => KeepAlive::Until {
+                time: n as u64,
+                unit: TimeUnit::Seconds,
+            },
+        });
+    }
+    let (time, unit) = if let Some(time) = value.strip_suffix('s') {
+        (time, TimeUnit::Seconds)
+    } else if let Some(time) = value.strip_suffix('m') {
+        (time, TimeUnit::Minutes)
+    } else if let Some(time) = value.strip_suffix('h') {
+        (time, TimeUnit::Hours)
+    } else {
+        return None;
+    };
+    let time: u64 = time.trim().parse().ok()?;
+    Some(if time == 0 {
+        KeepAlive::UnloadOnCompletion
+    } else {
+        KeepAlive::Until { time, unit }
+    })
+}
+
+/// Turn markers of common chat templates that end a reply when the model writes them:
+/// ChatML, Llama 3, Gemma, Llama 2 and Mistral, Phi-3, Command R and DeepSeek.
+const TEMPLATE_STOP_MARKERS: &[&str] = &[
+    "<|im_start|>",
+    "<|im_end|>",
+    "<|start_header_id|>",
+    "<|eot_id|>",
+    "<start_of_turn>",
+    "<end_of_turn>",
+    "[INST]",
+    "</s>",
+    "<|user|>",
+    "<|end|>",
+    "<|START_OF_TURN_TOKEN|>",
+    "<|END_OF_TURN_TOKEN|>",
+    "<｜User｜>",
+    "<｜end▁of▁sentence｜>",
+];
+
+/// Stop sequences for the turn markers used in a model's `template`, in the order they
+/// first appear in it.
+pub fn stop_sequences_from_template(template: &str) -> Vec<String> {
+    let mut found: Vec<(usize, &str)> = TEMPLATE_STOP_MARKERS
+        .iter()
+        .filter_map(|marker| template.find(marker).map(|pos| (pos, *marker)))
+        .collect();
+    found.sort_unstable();
+    found
+        .into_iter()
+        .map(|(_, marker)| marker.to_owned())
+        .collect()
+}
+
+impl ModelSettings {
+    /// `options` of an Ollama request, with the settings that are set.
+    pub fn ollama_options(&self) -> serde_json::Value {
+        use serde_json::{json, Value};
+
+        let mut options = serde_json::Map::new();
+        let mut set = |key: &str, value: Option<Value>| {
+            if let Some(value) = value {
+                options.insert(key.to_owned(), value);
+            }
+        };
+        set("mirostat", self.mirostat.map(|m| json!(m.to_u8())));
+        set("mirostat_eta", self.mirostat_eta.map(|v| json!(v)));
+        set("mirostat_tau", self.mirostat_tau.map(|v| json!(v)));
+        set("num_ctx", self.num_ctx.map(|v| json!(v)));
+        set("num_gqa", self.num_gqa.map(|v| json!(v)));
+        set("num_gpu", self.num_gpu.map(|v| json!(v)));
+        set("num_thread", self.num_thread.map(|v| json!(v)));
+        set("num_batch", self.num_batch.map(|v| json!(v)));
+        set("repeat_last_n", self.repeat_last_n.map(|v| json!(v)));
+        set("repeat_penalty", self.repeat_penalty.map(|v| json!(v)));
+        set("presence_penalty", self.presence_penalty.map(|v| json!(v)));
+        set(
+            "frequency_penalty",
+            self.frequency_penalty.map(|v| json!(v)),
+        );
+        set("penalize_newline", self.penalize_newline.map(|v| json!(v)));
+        set("temperature", self.temperature.map(|v| json!(v)));
+        set("seed", self.seed.map(|v| json!(v)));
+        set("tfs_z", self.tfs_z.map(|v| json!(v)));
+        set("num_predict", self.num_predict.map(|v| json!(v)));
+        set("top_k", self.top_k.map(|v| json!(v)));
+        set("top_p", self.top_p.map(|v| json!(v)));
+        set("min_p", self.min_p.map(|v| json!(v)));
+        set("typical_p", self.typical_p.map(|v| json!(v)));
+        // an empty stop sequence would stop generation right away
+        let stop: Vec<&String> = self
+            .stop
+            .iter()
+            .flatten()
+            .filter(|pat| !pat.is_empty())
+            .collect();
+        set("stop", (!stop.is_empty()).then(|| json!(stop)));
+        Value::Object(options)
+    }
+}
+
+#[inline]
+pub fn f64_range(range: std::ops::RangeInclusive<f64>) -> f64 {
+    fastrand::f64() * (range.end() - range.start()) + range.start()
+}
+
+impl ModelSettings {
+    // valid ranges of the numeric settings
+    const MIROSTAT_ETA: RangeInclusive<f32> = 0.0..=1.0;
+    const MIROSTAT_TAU: RangeInclusive<f32> = 0.0..=10.0;
+    const NUM_CTX: RangeInclusive<u32> = 512..=131072;
+    const NUM_GQA: RangeInclusive<u32> = 1..=64;
+    const NUM_GPU: RangeInclusive<u32> = 0..=256;
+    const NUM_THREAD: RangeInclusive<u32> = 0..=256;
+    const REPEAT_LAST_N: RangeInclusive<i32> = -1..=4096;
+    const REPEAT_PENALTY: RangeInclusive<f32> = 0.0..=2.0;
+    const TEMPERATURE: RangeInclusive<f32> = 0.0..=2.0;
+    const SEED: RangeInclusive<i32> = 0..=i32::MAX;
+    const TFS_Z: RangeInclusive<f32> = 0.0..=5.0;
+    const NUM_PREDICT: RangeInclusive<i32> = -2..=131072;
+    const TOP_K: RangeInclusive<u32> = 0..=500;
+    const TOP_P: RangeInclusive<f32> = 0.0..=1.0;
+    const MIN_P: RangeInclusive<f32> = 0.0..=1.0;
+    const TYPICAL_P: RangeInclusive<f32> = 0.0..=1.0;
+    const PRESENCE_PENALTY: RangeInclusive<f32> = -2.0..=2.0;
+    const FREQUENCY_PENALTY: RangeInclusive<f32> = -2.0..=2.0;
+    const NUM_BATCH: RangeInclusive<u32> = 1..=8192;
+
+    /// Clamp values outside of the valid ranges, older versions allowed any value.
+    pub fn clamp_to_ranges(&mut self) {
+        fn clamp<N: Numeric + std::fmt::Display>(
+            name: &str,
+            val: &mut Option<N>,
+            range: RangeInclusive<N>,
+        ) {
+            let Some(val) = val else {
+                return;
+            };
+            if range.contains(val) {
+                return;
+            }
+            let clamped = if *val < *range.start() {
+                *range.start()
+            } else {
+                *range.end()
+            };
+            log::warn!("{name} = {val} is out of range, clamping to {clamped}");
+            *val = clamped;
         }
-        if let Some(num_thread) = value.num_thread {
-            s = s.num_thread(num_thread);
+
+        clamp("mirostat_eta", &mut self.mirostat_eta, Self::MIROSTAT_ETA);
+        clamp("mirostat_tau", &mut self.mirostat_tau, Self::MIROSTAT_TAU);
+        clamp("num_ctx", &mut self.num_ctx, Self::NUM_CTX);
+        clamp("num_gqa", &mut self.num_gqa, Self::NUM_GQA);
+        clamp("num_gpu", &mut self.num_gpu, Self::NUM_GPU);
+        clamp("num_thread", &mut self.num_thread, Self::NUM_THREAD);
+        clamp(
+            "repeat_last_n",
+            &mut self.repeat_last_n,
+            Self::REPEAT_LAST_N,
+        );
+        clamp(
+            "repeat_penalty",
+            &mut self.repeat_penalty,
+            Self::REPEAT_PENALTY,
+        );
+        clamp("temperature", &mut self.temperature, Self::TEMPERATURE);
+        clamp("seed", &mut self.seed, Self::SEED);
+        clamp("tfs_z", &mut self.tfs_z, Self::TFS_Z);
+        clamp("num_predict", &mut self.num_predict, Self::NUM_PREDICT);
+        clamp("top_k", &mut self.top_k, Self::TOP_K);
+        clamp("top_p", &mut self.top_p, Self::TOP_P);
+        clamp("min_p", &mut self.min_p, Self::MIN_P);
+        clamp("typical_p", &mut self.typical_p, Self::TYPICAL_P);
+        clamp(
+            "presence_penalty",
+            &mut self.presence_penalty,
+            Self::PRESENCE_PENALTY,
+        );
+        clamp(
+            "frequency_penalty",
+            &mut self.frequency_penalty,
+            Self::FREQUENCY_PENALTY,
+        );
+        clamp("num_batch", &mut self.num_batch, Self::NUM_BATCH);
+    }
+
+    /// Warnings about settings that have no effect or don't make sense together.
+    pub fn validate(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let mirostat_enabled = self.mirostat.is_some_and(|m| m != MirostatKind::Disabled);
+        if !mirostat_enabled && (self.mirostat_eta.is_some() || self.mirostat_tau.is_some()) {
+            warnings.push(tr("inference.warn_mirostat").to_owned());
         }
-        if let Some(repeat_last_n) = value.repeat_last_n {
-            s = s.repeat_last_n(repeat_last_n);
+        if self.top_p == Some(0.0) {
+            warnings.push(tr("inference.warn_top_p").to_owned());
         }
-        if let Some(repeat_penalty) = value.repeat_penalty {
-            s = s.repeat_penalty(repeat_penalty);
+        let num_ctx = self.num_ctx.map_or(crate::chat::DEFAULT_NUM_CTX, u64::from);
+        if let Some(repeat_last_n) = self.repeat_last_n {
+            if repeat_last_n > 0 && repeat_last_n as u64 > num_ctx {
+                warnings.push(tr_args(
+                    "inference.warn_repeat_last_n",
+                    &[&repeat_last_n, &num_ctx],
+                ));
+            }
         }
-        if let Some(temperature) = value.temperature {
-            s = s.temperature(temperature);
+        if self
+            .stop
+            .as_ref()
+            .is_some_and(|stop| stop.iter().any(|pat| pat.is_empty()))
+        {
+            warnings.push(tr("inference.warn_empty_stop").to_owned());
         }
-        if let Some(seed) = value.seed {
-            s = s.seed(seed);
+        if self.keep_alive().is_none() && self.keep_alive.is_some() {
+            warnings.push(tr("inference.warn_keep_alive").to_owned());
         }
-        if let Some(stop) = value.stop {
-            s = s.stop(stop);
+        warnings
+    }
+
+    /// The "Inference Settings" header, marked with an icon when there are warnings.
+    pub fn collapsing_header(&self) -> egui::CollapsingHeader {
+        let text = if self.validate().is_empty() {
+            tr("inference.header").to_owned()
+        } else {
+            format!("⚠ {}", tr("inference.header"))
+        };
+        egui::CollapsingHeader::new(text).id_source("Inference Settings")
+    }
+
+    fn show_warnings(&self, ui: &mut egui::Ui) {
+        let warnings = self.validate();
+        if warnings.is_empty() {
+            return;
         }
-        if let Some(tfs_z) = value.tfs_z {
-            s = s.tfs_z(tfs_z);
+        let visuals = ui.visuals();
+        egui::Frame::none()
+            .fill(visuals.warn_fg_color.gamma_multiply(0.15))
+            .stroke(Stroke::new(1.0, visuals.warn_fg_color))
+            .rounding(visuals.menu_rounding)
+            .inner_margin(egui::Margin::same(4.0))
+            .show(ui, |ui| {
+                for warning in warnings {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {warning}"));
+                }
+            });
+    }
+
+    /// Human-readable list of all settings that are explicitly set.
+    pub fn overview(&self) -> Vec<(&'static str, String)> {
+        let mut list = Vec::new();
+        macro_rules! push {
+            ($name:expr, $field:ident) => {
+                if let Some(value) = &self.$field {
+                    list.push(($name, value.to_string()));
+                }
+            };
         }
-        if let Some(num_predict) = value.num_predict {
-            s = s.num_predict(num_predict);
+        if let Some(mirostat) = self.mirostat {
+            list.push(("Mirostat", mirostat.name().to_owned()));
         }
-        if let Some(top_k) = value.top_k {
-            s = s.top_k(top_k);
+        push!(tr("inference.mirostat_eta"), mirostat_eta);
+        push!(tr("inference.mirostat_tau"), mirostat_tau);
+        push!(tr("inference.num_ctx"), num_ctx);
+        push!(tr("inference.num_gqa"), num_gqa);
+        push!(tr("inference.num_gpu"), num_gpu);
+        push!(tr("inference.num_thread"), num_thread);
+        push!(tr("inference.repeat_last_n"), repeat_last_n);
+        push!(tr("inference.repeat_penalty"), repeat_penalty);
+        push!(tr("inference.temperature"), temperature);
+        push!(tr("inference.seed"), seed);
+        if let Some(stop) = &self.stop {
+            list.push((tr("inference.stop"), format!("{stop:?}")));
         }
-        if let Some(top_p) = value.top_p {
-            s = s.top_p(top_p);
+        push!(tr("inference.tfs_z"), tfs_z);
+        push!(tr("inference.num_predict"), num_predict);
+        push!(tr("inference.top_k"), top_k);
+        push!(tr("inference.top_p"), top_p);
+        push!(tr("inference.min_p"), min_p);
+        push!(tr("inference.typical_p"), typical_p);
+        push!(tr("inference.presence_penalty"), presence_penalty);
+        push!(tr("inference.frequency_penalty"), frequency_penalty);
+        push!(tr("inference.num_batch"), num_batch);
+        push!(tr("inference.penalize_newline"), penalize_newline);
+        push!(tr("inference.keep_alive"), keep_alive);
+        if self.json_mode {
+            list.push((tr("inference.json_mode"), tr("inference.on").to_owned()));
         }
-        s
+        list
     }
-}
 
-#[inline]
-pub fn f64_range(range: std::ops::RangeInclusive<f64>) -> f64 {
-    fastrand::f64() * (range.end() - range.start()) + range.start()
-}
+    /// Names of the settings that differ from `base`.
+    pub fn overridden_fields(&self, base: &Self) -> Vec<&'static str> {
+        let ours = self.overview();
+        let theirs = base.overview();
+        let mut fields: Vec<&'static str> = ours
+            .iter()
+            .filter(|entry| !theirs.contains(entry))
+            .chain(theirs.iter().filter(|entry| !ours.contains(entry)))
+            .map(|(name, _)| *name)
+            .collect();
+        fields.sort_unstable();
+        fields.dedup();
+        fields
+    }
+
+    /// Compact temperature and seed controls, returns whether either changed.
+    pub fn show_quick(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.spacing_mut().slider_width = 100.0;
+            let mut temperature = self.temperature.unwrap_or(0.8);
+            ui.label("🌡")
+                .on_hover_text(tr("inference.quick_temperature_help"));
+            if ui
+                .add(egui::Slider::new(&mut temperature, Self::TEMPERATURE).step_by(0.05))
+                .changed()
+            {
+                self.temperature = Some(temperature);
+                changed = true;
+            }
+            ui.separator();
+            let mut seed = self.seed.unwrap_or(0);
+            ui.label(tr("inference.seed"))
+                .on_hover_text(tr("inference.quick_seed_help"));
+            if ui
+                .add(egui::DragValue::new(&mut seed).clamp_range(Self::SEED))
+                .changed()
+            {
+                self.seed = Some(seed);
+                changed = true;
+            }
+            if ui
+                .small_button("🎲")
+                .on_hover_text(tr("inference.random_seed"))
+                .clicked()
+            {
+                self.seed = Some(fastrand::i32(Self::SEED));
+                changed = true;
+            }
+        });
+        changed
+    }
+
+    /// The parsed `keep_alive` setting, `None` if unset or invalid.
+    pub fn keep_alive(&self) -> Option<KeepAlive> {
+        self.keep_alive.as_deref().and_then(parse_keep_alive)
+    }
+
+    fn edit_keep_alive(&mut self, ui: &mut egui::Ui) {
+        collapsing_frame(ui, tr("inference.keep_alive"), |ui| {
+            ui.label(tr("inference.keep_alive_help"));
+            let mut enabled = self.keep_alive.is_some();
+            ui.horizontal(|ui| {
+                ui.add(toggle(&mut enabled));
+                ui.label(tr("inference.enable"));
+            });
+
+            if !enabled {
+                self.keep_alive = None;
+            } else if self.keep_alive.is_none() {
+                self.keep_alive = Some("5m".to_owned());
+            }
+
+            ui.add_enabled_ui(self.keep_alive.is_some(), |ui| {
+                let Some(keep_alive) = &mut self.keep_alive else {
+                    return;
+                };
+                ui.horizontal_wrapped(|ui| {
+                    for (label, value) in KEEP_ALIVE_PRESETS {
+                        if ui
+                            .selectable_label(keep_alive.trim() == *value, tr(*label))
+                            .clicked()
+                        {
+                            *keep_alive = (*value).to_owned();
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr("inference.custom"));
+                    ui.add(
+                        egui::TextEdit::singleline(keep_alive)
+                            .hint_text(tr("inference.keep_alive_hint"))
+                            .desired_width(96.0),
+                    );
+                    if parse_keep_alive(keep_alive).is_none() {
+                        ui.colored_label(
+                            ui.visuals().error_fg_color,
+                            tr("inference.keep_alive_invalid"),
+                        );
+                    }
+                });
+            });
+        });
+    }
+
+    fn edit_bool(ui: &mut egui::Ui, val: &mut Option<bool>, default: bool, name: &str, doc: &str) {
+        collapsing_frame(ui, name, |ui: &mut egui::Ui| {
+            ui.label(doc);
+            let mut enabled = val.is_some();
+            ui.horizontal(|ui| {
+                ui.add(toggle(&mut enabled));
+                ui.label(tr("inference.enable"));
+            });
+
+            if !enabled {
+                *val = None;
+            } else if val.is_none() {
+                *val = Some(default);
+            }
+
+            ui.add_enabled_ui(val.is_some(), |ui| {
+                let mut value = val.unwrap_or(default);
+                if ui.checkbox(&mut value, name).changed() && val.is_some() {
+                    *val = Some(value);
+                }
+            });
+        });
+    }
 
-impl ModelSettings {
     fn edit_numeric<N: Numeric>(
         ui: &mut egui::Ui,
+        id: egui::Id,
         val: &mut Option<N>,
         mut default: N,
+        range: RangeInclusive<N>,
         speed: f64,
         name: &str,
         doc: &str,
@@ -421,7 +1686,7 @@ impl ModelSettings {
             let mut enabled = val.is_some();
             ui.horizontal(|ui| {
                 ui.add(toggle(&mut enabled));
-                ui.label("Enable");
+                ui.label(tr("inference.enable"));
             });
 
             if !enabled {
@@ -432,35 +1697,48 @@ impl ModelSettings {
 
             ui.add_enabled_ui(val.is_some(), |ui| {
                 ui.horizontal(|ui| {
+                    // wide ranges are easier to pick from on a logarithmic scale
+                    let logarithmic = range.end().to_f64() - range.start().to_f64() > 1000.0;
                     if let Some(val) = val {
-                        ui.add(egui::DragValue::new(val).speed(speed));
+                        mark_settings_edit(
+                            &ui.add(
+                                egui::Slider::new(val, range.clone())
+                                    .logarithmic(logarithmic)
+                                    .drag_value_speed(speed),
+                            ),
+                            id,
+                        );
                     } else {
-                        ui.add(egui::DragValue::new(&mut default).speed(speed));
+                        ui.add(
+                            egui::Slider::new(&mut default, range.clone()).logarithmic(logarithmic),
+                        );
                     }
                     if ui
-                        .button("max")
-                        .on_hover_text("Set maximum value")
+                        .button(tr("inference.max"))
+                        .on_hover_text(tr("inference.max_help"))
                         .clicked()
                     {
-                        *val = Some(N::MAX);
+                        *val = Some(*range.end());
                     }
                     if ui
-                        .button("min")
-                        .on_hover_text("Set minimum value")
+                        .button(tr("inference.min"))
+                        .on_hover_text(tr("inference.min_help"))
                         .clicked()
                     {
-                        *val = Some(N::MIN);
+                        *val = Some(*range.start());
                     }
                     if ui
-                        .button("rand")
-                        .on_hover_text("Set random value")
+                        .button(tr("inference.rand"))
+                        .on_hover_text(tr("inference.rand_help"))
                         .clicked()
                     {
-                        *val = Some(N::from_f64(f64_range(N::MIN.to_f64()..=N::MAX.to_f64())));
+                        *val = Some(N::from_f64(f64_range(
+                            range.start().to_f64()..=range.end().to_f64(),
+                        )));
                     }
                     if ui
-                        .button("reset")
-                        .on_hover_text("Set default value")
+                        .button(tr("inference.reset"))
+                        .on_hover_text(tr("inference.reset_help"))
                         .clicked()
                     {
                         *val = None;
@@ -470,95 +1748,311 @@ impl ModelSettings {
         });
     }
 
-    fn show(&mut self, ui: &mut egui::Ui, template: &mut Option<String>) {
-        if ui.button("Reset Settings").clicked() {
-            *self = Self::default();
-            *template = None;
+    /// Show the settings editor. `id` must be unique for every editor shown in a frame.
+    /// Settings `backend` doesn't support are greyed out. Stop sequences are suggested from
+    /// `template`, or the `model_template` if it isn't overridden.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        id: egui::Id,
+        template: &mut Option<String>,
+        model_template: Option<&str>,
+        presets: &mut Vec<SettingsPreset>,
+        backend: BackendKind,
+    ) {
+        debug_assert_unique_id(ui, id);
+        ui.push_id(id, |ui| {
+            self.show_inner(ui, id, template, model_template, presets, backend)
+        });
+    }
+
+    /// Show settings the OpenAI API has no equivalent for, disabled unless using Ollama.
+    fn ollama_only(
+        ui: &mut egui::Ui,
+        backend: BackendKind,
+        add_contents: impl FnOnce(&mut egui::Ui),
+    ) {
+        ui.add_enabled_ui(backend == BackendKind::Ollama, add_contents)
+            .response
+            .on_disabled_hover_text(tr("inference.ollama_only"));
+    }
+
+    fn show_presets(&mut self, ui: &mut egui::Ui, id: egui::Id, presets: &mut Vec<SettingsPreset>) {
+        let name_id = id.with("preset_save_name");
+        let mut save_name: Option<String> = ui.data_mut(|d| d.get_temp(name_id));
+
+        ui.horizontal(|ui| {
+            let current = presets
+                .iter()
+                .find(|p| p.settings == *self)
+                .map_or(tr("inference.custom"), |p| p.name.as_str());
+            let mut apply = None;
+            egui::ComboBox::new(id.with("preset_combobox"), tr("inference.preset"))
+                .selected_text(current)
+                .show_ui(ui, |ui| {
+                    if presets.is_empty() {
+                        ui.label(tr("inference.no_presets"));
+                    }
+                    for (i, preset) in presets.iter().enumerate() {
+                        if ui
+                            .selectable_label(preset.name == current, &preset.name)
+                            .clicked()
+                        {
+                            apply = Some(i);
+                        }
+                    }
+                });
+            if let Some(i) = apply {
+                // unset fields of the preset are cleared too
+                self.clone_from(&presets[i].settings);
+            }
+            if save_name.is_none() && ui.button(tr("inference.save_as")).clicked() {
+                save_name = Some(String::new());
+            }
+        });
+
+        if let Some(name) = &mut save_name {
+            let mut done = false;
+            ui.horizontal(|ui| {
+                let resp = ui.add(
+                    egui::TextEdit::singleline(name)
+                        .hint_text(tr("inference.preset_name"))
+                        .desired_width(128.0),
+                );
+                let name = name.trim();
+                let exists = presets.iter().any(|p| p.name == name);
+                let save = ui
+                    .add_enabled(
+                        !name.is_empty(),
+                        egui::Button::new(if exists {
+                            tr("inference.overwrite")
+                        } else {
+                            tr("common.save")
+                        }),
+                    )
+                    .clicked()
+                    || (!name.is_empty()
+                        && resp.lost_focus()
+                        && ui.input(|i| i.key_pressed(egui::Key::Enter)));
+                if save {
+                    if let Some(preset) = presets.iter_mut().find(|p| p.name == name) {
+                        preset.settings.clone_from(self);
+                    } else {
+                        presets.push(SettingsPreset {
+                            name: name.to_owned(),
+                            settings: self.clone(),
+                        });
+                    }
+                    done = true;
+                }
+                if ui.button(tr("common.cancel")).clicked() {
+                    done = true;
+                }
+            });
+            if done {
+                save_name = None;
+            }
         }
+        ui.data_mut(|d| match save_name {
+            Some(name) => d.insert_temp(name_id, name),
+            None => d.remove::<String>(name_id),
+        });
 
-        collapsing_frame(ui, "Mirostat", |ui| {
-            ui.label("Enable Mirostat sampling for controlling perplexity.");
+        if !presets.is_empty() {
+            collapsing_frame(ui, tr("inference.manage_presets"), |ui| {
+                presets.retain_mut(|preset| {
+                    ui.horizontal(|ui| {
+                        ui.text_edit_singleline(&mut preset.name)
+                            .on_hover_text(tr("inference.rename"));
+                        !ui.button("🗑")
+                            .on_hover_text(tr("inference.delete_preset"))
+                            .clicked()
+                    })
+                    .inner
+                });
+            });
+        }
+    }
 
-            let mut enabled = self.mirostat.is_some();
+    fn show_inner(
+        &mut self,
+        ui: &mut egui::Ui,
+        id: egui::Id,
+        template: &mut Option<String>,
+        model_template: Option<&str>,
+        presets: &mut Vec<SettingsPreset>,
+        backend: BackendKind,
+    ) {
+        if ui.button(tr("inference.reset_settings")).clicked() {
+            *self = Self::default();
+            *template = None;
+        }
+        let suggested_stops = template
+            .as_deref()
+            .or(model_template)
+            .map(stop_sequences_from_template);
+        self.show_warnings(ui);
+        self.show_presets(ui, id, presets);
 
+        collapsing_frame(ui, tr("inference.json_mode"), |ui| {
+            ui.label(tr("inference.json_mode_help"));
             ui.horizontal(|ui| {
-                ui.add(toggle(&mut enabled));
-                ui.label("Enable");
+                ui.add(toggle(&mut self.json_mode));
+                ui.label(tr("inference.enable"));
             });
+        });
 
-            if !enabled {
-                self.mirostat = None;
-            } else if self.mirostat.is_none() {
-                self.mirostat = Some(MirostatKind::Disabled);
-            }
+        Self::ollama_only(ui, backend, |ui| {
+            collapsing_frame(ui, "Mirostat", |ui| {
+                ui.label(tr("inference.mirostat_help"));
 
-            ui.add_enabled_ui(self.mirostat.is_some(), |ui| {
-                if let Some(mirostat) = self.mirostat {
-                    egui::ComboBox::new("mirostat_combobox", "Mirostat")
-                        .selected_text(mirostat.name())
-                        .show_ui(ui, |ui| {
-                            ui.selectable_value(
-                                &mut self.mirostat,
-                                Some(MirostatKind::Disabled),
-                                "Disabled",
-                            );
-                            ui.selectable_value(
-                                &mut self.mirostat,
-                                Some(MirostatKind::Mirostat),
-                                "Mirostat",
-                            );
-                            ui.selectable_value(
-                                &mut self.mirostat,
-                                Some(MirostatKind::Mirostat2),
-                                "Mirostat 2.0",
-                            );
-                        });
+                let mut enabled = self.mirostat.is_some();
+
+                ui.horizontal(|ui| {
+                    ui.add(toggle(&mut enabled));
+                    ui.label(tr("inference.enable"));
+                });
+
+                if !enabled {
+                    self.mirostat = None;
+                } else if self.mirostat.is_none() {
+                    self.mirostat = Some(MirostatKind::Disabled);
                 }
+
+                ui.add_enabled_ui(self.mirostat.is_some(), |ui| {
+                    if let Some(mirostat) = self.mirostat {
+                        egui::ComboBox::new(id.with("mirostat_combobox"), "Mirostat")
+                            .selected_text(mirostat.name())
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(
+                                    &mut self.mirostat,
+                                    Some(MirostatKind::Disabled),
+                                    MirostatKind::Disabled.name(),
+                                );
+                                ui.selectable_value(
+                                    &mut self.mirostat,
+                                    Some(MirostatKind::Mirostat),
+                                    MirostatKind::Mirostat.name(),
+                                );
+                                ui.selectable_value(
+                                    &mut self.mirostat,
+                                    Some(MirostatKind::Mirostat2),
+                                    MirostatKind::Mirostat2.name(),
+                                );
+                            });
+                    }
+                });
             });
-        });
 
-        Self::edit_numeric(ui, &mut self.mirostat_eta, 0.1, 0.01, "Mirostat eta", "Influences how quickly the algorithm responds to feedback from the generated text. A lower learning rate will result in slower adjustments, while a higher learning rate will make the algorithm more responsive.");
-        Self::edit_numeric(ui, &mut self.mirostat_tau, 5.0, 0.01, "Mirostat tau", "Controls the balance between coherence and diversity of the output. A lower value will result in more focused and coherent text.");
+            Self::edit_numeric(
+                ui,
+                id,
+                &mut self.mirostat_eta,
+                0.1,
+                Self::MIROSTAT_ETA,
+                0.01,
+                tr("inference.mirostat_eta"),
+                tr("inference.mirostat_eta_help"),
+            );
+            Self::edit_numeric(
+                ui,
+                id,
+                &mut self.mirostat_tau,
+                5.0,
+                Self::MIROSTAT_TAU,
+                0.01,
+                tr("inference.mirostat_tau"),
+                tr("inference.mirostat_tau_help"),
+            );
+            Self::edit_numeric(
+                ui,
+                id,
+                &mut self.num_ctx,
+                2048,
+                Self::NUM_CTX,
+                1.0,
+                tr("inference.num_ctx"),
+                tr("inference.num_ctx_help"),
+            );
+            Self::edit_numeric(
+                ui,
+                id,
+                &mut self.num_gqa,
+                8,
+                Self::NUM_GQA,
+                1.0,
+                tr("inference.num_gqa"),
+                tr("inference.num_gqa_help"),
+            );
+            Self::edit_numeric(
+                ui,
+                id,
+                &mut self.num_gpu,
+                1,
+                Self::NUM_GPU,
+                1.0,
+                tr("inference.num_gpu"),
+                tr("inference.num_gpu_help"),
+            );
+            Self::edit_numeric(
+                ui,
+                id,
+                &mut self.num_thread,
+                0,
+                Self::NUM_THREAD,
+                1.0,
+                tr("inference.num_thread"),
+                tr("inference.num_thread_help"),
+            );
+            Self::edit_numeric(
+                ui,
+                id,
+                &mut self.repeat_last_n,
+                64,
+                Self::REPEAT_LAST_N,
+                1.0,
+                tr("inference.repeat_last_n"),
+                tr("inference.repeat_last_n_help"),
+            );
+            Self::edit_numeric(
+                ui,
+                id,
+                &mut self.repeat_penalty,
+                1.1,
+                Self::REPEAT_PENALTY,
+                0.01,
+                tr("inference.repeat_penalty"),
+                tr("inference.repeat_penalty_help"),
+            );
+        });
         Self::edit_numeric(
             ui,
-            &mut self.num_ctx,
-            2048,
-            1.0,
-            "Context Window",
-            "Sets the size of the context window used to generate the next token.",
+            id,
+            &mut self.temperature,
+            0.8,
+            Self::TEMPERATURE,
+            0.1,
+            tr("inference.temperature"),
+            tr("inference.temperature_help"),
         );
-        Self::edit_numeric(ui, &mut self.num_gqa, 8, 1.0, "Number of GQA Groups", "The number of GQA groups in the transformer layer. Required for some models, for example it is 8 for llama2:70b.");
-        Self::edit_numeric(ui, &mut self.num_gpu, 1, 1.0, "GPU Layers", "The number of layers to send to the GPU(s). On macOS it defaults to 1 to enable metal support, 0 to disable.");
-        Self::edit_numeric(ui, &mut self.num_thread, 0, 1.0, "Number of Threads", "Sets the number of threads to use during computation. By default, Ollama will detect this for optimal performance. It is recommended to set this value to the number of physical CPU cores your system has (as opposed to the logical number of cores).");
         Self::edit_numeric(
             ui,
-            &mut self.repeat_last_n,
-            64,
+            id,
+            &mut self.seed,
+            0,
+            Self::SEED,
             1.0,
-            "Repeat Last N",
-            "Sets how far back for the model to look back to prevent repetition.",
-        );
-        Self::edit_numeric(
-            ui,
-            &mut self.repeat_penalty,
-            1.1,
-            0.01,
-            "Repeat Penalty",
-            "Sets how strongly to penalize repetitions. A higher value (e.g., 1.5) will penalize repetitions more strongly, while a lower value (e.g., 0.9) will be more lenient.",
+            tr("inference.seed"),
+            tr("inference.seed_help"),
         );
-        Self::edit_numeric(ui, &mut self.temperature, 0.8, 0.1, "Temperature", "The temperature of the model. Increasing the temperature will make the model answer more creatively.");
-        Self::edit_numeric(ui, &mut self.seed, 0, 1.0, "Seed", "Sets the random number seed to use for generation. Setting this to a specific number will make the model generate the same text for the same prompt.");
 
-        collapsing_frame(ui, "Stop Sequence", |ui| {
-            ui.label(
-                "Sets the stop sequences to use. \
-                When this pattern is encountered the LLM will stop generating text and return.",
-            );
+        collapsing_frame(ui, tr("inference.stop"), |ui| {
+            ui.label(tr("inference.stop_help"));
             let mut enabled = self.stop.is_some();
 
             ui.horizontal(|ui| {
                 ui.add(toggle(&mut enabled));
-                ui.label("Enable");
+                ui.label(tr("inference.enable"));
             });
 
             if !enabled {
@@ -567,6 +2061,35 @@ impl ModelSettings {
                 self.stop = Some(Vec::new());
             }
 
+            let missing: Vec<&String> = suggested_stops
+                .iter()
+                .flatten()
+                .filter(|pat| !self.stop.as_ref().is_some_and(|stop| stop.contains(*pat)))
+                .collect();
+            let suggest = ui
+                .add_enabled(
+                    !missing.is_empty(),
+                    egui::Button::new(tr("inference.suggest_stops")),
+                )
+                .on_hover_text(tr_args(
+                    "inference.suggest_stops_help",
+                    &[&missing
+                        .iter()
+                        .map(|pat| format!("`{pat}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")],
+                ))
+                .on_disabled_hover_text(match &suggested_stops {
+                    None => tr("inference.template_not_loaded"),
+                    Some(stops) if stops.is_empty() => tr("inference.no_turn_markers"),
+                    Some(_) => tr("inference.stops_added"),
+                });
+            if suggest.clicked() {
+                self.stop
+                    .get_or_insert_with(Vec::new)
+                    .extend(missing.into_iter().cloned());
+            }
+
             ui.add_enabled_ui(self.stop.is_some(), |ui| {
                 if let Some(ref mut stop) = self.stop {
                     stop.retain_mut(|pat| {
@@ -577,35 +2100,128 @@ impl ModelSettings {
                         .inner
                     });
                     if stop.is_empty() {
-                        ui.label("No stop sequences set, add one.");
+                        ui.label(tr("inference.no_stops"));
                     }
                     ui.horizontal(|ui| {
-                        if ui.button("➕ Add").clicked() {
+                        if ui.button(tr("inference.add")).clicked() {
                             stop.push(String::new());
                         }
-                        if ui.button("Clear").clicked() {
+                        if ui.button(tr("inference.clear")).clicked() {
                             stop.clear();
                         }
                     });
                 } else {
-                    let _ = ui.button("➕ Add");
+                    let _ = ui.button(tr("inference.add"));
                 }
             });
         });
 
+        Self::ollama_only(ui, backend, |ui| {
+            Self::edit_numeric(
+                ui,
+                id,
+                &mut self.tfs_z,
+                1.0,
+                Self::TFS_Z,
+                0.01,
+                tr("inference.tfs_z"),
+                tr("inference.tfs_z_help"),
+            );
+        });
         Self::edit_numeric(
             ui,
-            &mut self.tfs_z,
+            id,
+            &mut self.num_predict,
+            128,
+            Self::NUM_PREDICT,
             1.0,
+            tr("inference.num_predict"),
+            tr("inference.num_predict_help"),
+        );
+        Self::ollama_only(ui, backend, |ui| {
+            Self::edit_numeric(
+                ui,
+                id,
+                &mut self.top_k,
+                40,
+                Self::TOP_K,
+                1.0,
+                tr("inference.top_k"),
+                tr("inference.top_k_help"),
+            );
+        });
+        Self::edit_numeric(
+            ui,
+            id,
+            &mut self.top_p,
+            0.9,
+            Self::TOP_P,
+            0.01,
+            tr("inference.top_p"),
+            tr("inference.top_p_help"),
+        );
+        Self::ollama_only(ui, backend, |ui| {
+            Self::edit_numeric(
+                ui,
+                id,
+                &mut self.min_p,
+                0.0,
+                Self::MIN_P,
+                0.01,
+                tr("inference.min_p"),
+                tr("inference.min_p_help"),
+            );
+            Self::edit_numeric(
+                ui,
+                id,
+                &mut self.typical_p,
+                1.0,
+                Self::TYPICAL_P,
+                0.01,
+                tr("inference.typical_p"),
+                tr("inference.typical_p_help"),
+            );
+        });
+        Self::edit_numeric(
+            ui,
+            id,
+            &mut self.presence_penalty,
+            0.0,
+            Self::PRESENCE_PENALTY,
+            0.01,
+            tr("inference.presence_penalty"),
+            tr("inference.presence_penalty_help"),
+        );
+        Self::edit_numeric(
+            ui,
+            id,
+            &mut self.frequency_penalty,
+            0.0,
+            Self::FREQUENCY_PENALTY,
             0.01,
-            "Tail-Free Sampling Z",
-            "Tail free sampling is used to reduce the impact \
-            of less probable tokens from the output. A higher value (e.g., 2.0) \
-            will reduce the impact more, while a value of 1.0 disables this setting.",
+            tr("inference.frequency_penalty"),
+            tr("inference.frequency_penalty_help"),
         );
-        Self::edit_numeric(ui, &mut self.num_predict, 128, 1.0, "Number to Predict", "Maximum number of tokens to predict when generating text. (Default: 128, -1 = infinite generation, -2 = fill context)");
-        Self::edit_numeric(ui, &mut self.top_k, 40, 1.0, "Top-K", "Reduces the probability of generating nonsense. A higher value (e.g. 100) will give more diverse answers, while a lower value (e.g. 10) will be more conservative.");
-        Self::edit_numeric(ui, &mut self.top_p, 0.9, 0.01, "Top-P", "Works together with top-k. A higher value (e.g., 0.95) will lead to more diverse text, while a lower value (e.g., 0.5) will generate more focused and conservative text.");
+        Self::ollama_only(ui, backend, |ui| {
+            Self::edit_numeric(
+                ui,
+                id,
+                &mut self.num_batch,
+                512,
+                Self::NUM_BATCH,
+                1.0,
+                tr("inference.num_batch"),
+                tr("inference.num_batch_help"),
+            );
+            Self::edit_bool(
+                ui,
+                &mut self.penalize_newline,
+                true,
+                tr("inference.penalize_newline"),
+                tr("inference.penalize_newline_help"),
+            );
+            self.edit_keep_alive(ui);
+        });
     }
 }
 
@@ -739,58 +2355,1275 @@ fn help(ui: &mut egui::Ui, text: &str, add_contents: impl FnOnce(&mut egui::Ui))
     });
 }
 
-#[derive(serde::Deserialize, serde::Serialize, Clone)]
-pub struct Settings {
-    pub endpoint: String,
-    endpoint_error: String,
-    pub model_picker: ModelPicker,
-    pub inherit_chat_picker: bool,
+/// Manual HTTP proxy used for requests to the Ollama server. When disabled, the standard
+/// `HTTP_PROXY`/`HTTPS_PROXY`/`ALL_PROXY`/`NO_PROXY` environment variables are used.
+#[derive(Default, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(from = "SavedProxySettings", into = "SavedProxySettings")]
+pub struct ProxySettings {
+    pub enabled: bool,
+    pub url: String,
+    pub username: String,
+    pub password: String,
+    /// Whether the password is kept for the next start
+    pub remember_password: bool,
+    /// Whether the remembered password is in the OS keyring rather than the saved settings
+    pub in_keyring: bool,
+    /// Connect to local servers directly
+    pub bypass_localhost: bool,
 }
 
-const DEFAULT_HOST: &str = "http://127.0.0.1:11434";
+/// How [`ProxySettings`] are persisted, the password is only saved if the user opted in
+/// and there is no keyring to keep it in.
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+struct SavedProxySettings {
+    enabled: bool,
+    url: String,
+    username: String,
+    remember_password: bool,
+    in_keyring: bool,
+    password: Option<String>,
+    bypass_localhost: bool,
+}
 
-impl Default for Settings {
-    fn default() -> Self {
+impl From<SavedProxySettings> for ProxySettings {
+    fn from(saved: SavedProxySettings) -> Self {
         Self {
-            endpoint: DEFAULT_HOST.to_owned(),
-            model_picker: ModelPicker::default(),
-            inherit_chat_picker: true,
-            endpoint_error: String::new(),
+            enabled: saved.enabled,
+            url: saved.url,
+            username: saved.username,
+            // older versions only saved the password if it was remembered
+            remember_password: saved.remember_password || saved.password.is_some(),
+            // read from the keyring by `load_password` once the settings are loaded
+            password: saved.password.unwrap_or_default(),
+            in_keyring: saved.in_keyring,
+            bypass_localhost: saved.bypass_localhost,
         }
     }
 }
 
-impl Settings {
-    fn parse_endpoint(&self) -> Result<Url> {
-        let url = url::Url::parse(&self.endpoint)?;
-        if !url.has_host() {
-            return Err(anyhow::anyhow!("invalid host"));
+impl From<ProxySettings> for SavedProxySettings {
+    fn from(proxy: ProxySettings) -> Self {
+        Self {
+            enabled: proxy.enabled,
+            url: proxy.url,
+            username: proxy.username,
+            remember_password: proxy.remember_password,
+            in_keyring: proxy.in_keyring,
+            password: (proxy.remember_password && !proxy.in_keyring && !proxy.password.is_empty())
+                .then_some(proxy.password),
+            bypass_localhost: proxy.bypass_localhost,
         }
-        Ok(url)
     }
+}
 
-    #[inline]
-    pub fn make_ollama(&self) -> Ollama {
-        Ollama::from_url(
-            self.parse_endpoint()
-                .unwrap_or_else(|_| Url::parse(DEFAULT_HOST).unwrap()),
-        )
+impl ProxySettings {
+    /// Name of the keyring entry holding the password
+    const KEYRING_ENTRY: &'static str = "proxy-password";
+
+    /// Move the password into the OS keyring if it's remembered, keeping it with the
+    /// settings if that fails. Forgets it otherwise.
+    pub fn store_password(&mut self) {
+        let password = if self.remember_password {
+            self.password.as_str()
+        } else {
+            ""
+        };
+        match crate::secrets::store(Self::KEYRING_ENTRY, password) {
+            Ok(()) => self.in_keyring = self.remember_password,
+            Err(e) => {
+                if self.remember_password {
+                    log::warn!("keeping the proxy password with the settings: {e}");
+                }
+                self.in_keyring = false;
+            }
+        }
     }
 
-    pub fn show_modal(&mut self, modal: &Modal) {
-        modal.show(|ui| {
-            modal.title(ui, "Reset Settings");
+    /// Read the password from the OS keyring after the settings were loaded.
+    pub fn load_password(&mut self) {
+        if !self.in_keyring {
+            // passwords remembered before the keyring was used move into it
+            if self.remember_password && !self.password.is_empty() {
+                self.store_password();
+            }
+            return;
+        }
+        match crate::secrets::load(Self::KEYRING_ENTRY) {
+            Ok(password) => self.password = password.unwrap_or_default(),
+            Err(e) => log::error!("failed to read the proxy password from the keyring: {e}"),
+        }
+    }
+
+    fn make_proxy(&self) -> Result<reqwest::Proxy> {
+        let mut proxy = reqwest::Proxy::all(self.url.trim())?;
+        if !self.username.is_empty() {
+            proxy = proxy.basic_auth(&self.username, &self.password);
+        }
+        if self.bypass_localhost {
+            proxy = proxy.no_proxy(reqwest::NoProxy::from_string("localhost,127.0.0.1,::1"));
+        }
+        Ok(proxy)
+    }
+
+    /// Description of the proxy in use for error messages, `None` if the proxy is disabled.
+    pub fn description(&self) -> Option<ProxyInUse> {
+        if self.enabled {
+            Some(ProxyInUse {
+                description: self.url.trim().to_owned(),
+                manual: true,
+            })
+        } else {
+            [
+                "HTTPS_PROXY",
+                "https_proxy",
+                "ALL_PROXY",
+                "all_proxy",
+                "HTTP_PROXY",
+                "http_proxy",
+            ]
+            .into_iter()
+            .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+            .map(|url| ProxyInUse {
+                description: tr_args("settings.from_environment", &[&url]),
+                manual: false,
+            })
+        }
+    }
+}
+
+/// Proxy requests go through, see [`ProxySettings::description`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyInUse {
+    pub description: String,
+    /// Set up in the connection settings rather than taken from the environment
+    pub manual: bool,
+}
+
+/// How requests to the Ollama server authenticate, for servers behind a reverse proxy.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum AuthKind {
+    #[default]
+    None,
+    /// `Authorization: Bearer <token>`
+    Bearer,
+    /// `Authorization: Basic <username:password>`
+    Basic,
+}
+
+impl AuthKind {
+    const ALL: [Self; 3] = [Self::None, Self::Bearer, Self::Basic];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::None => tr("auth.none"),
+            Self::Bearer => tr("auth.bearer"),
+            Self::Basic => tr("auth.basic"),
+        }
+    }
+}
+
+/// Credentials sent in the `Authorization` header of every request to the Ollama server.
+/// The secret is kept in the OS keyring when possible and only saved with the settings if
+/// that fails.
+#[derive(Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(from = "SavedAuthSettings", into = "SavedAuthSettings")]
+pub struct AuthSettings {
+    pub kind: AuthKind,
+    pub username: String,
+    /// Bearer token or password
+    pub secret: String,
+    /// Whether the secret is in the OS keyring rather than the saved settings
+    pub in_keyring: bool,
+}
+
+/// How [`AuthSettings`] are persisted, without the secret if it's in the keyring.
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+struct SavedAuthSettings {
+    kind: AuthKind,
+    username: String,
+    in_keyring: bool,
+    secret: Option<String>,
+}
+
+impl From<SavedAuthSettings> for AuthSettings {
+    fn from(saved: SavedAuthSettings) -> Self {
+        Self {
+            kind: saved.kind,
+            username: saved.username,
+            // read from the keyring by `load_secret` once the settings are loaded
+            secret: saved.secret.unwrap_or_default(),
+            in_keyring: saved.in_keyring,
+        }
+    }
+}
+
+impl From<AuthSettings> for SavedAuthSettings {
+    fn from(auth: AuthSettings) -> Self {
+        Self {
+            kind: auth.kind,
+            username: auth.username,
+            in_keyring: auth.in_keyring,
+            secret: (!auth.in_keyring && !auth.secret.is_empty()).then_some(auth.secret),
+        }
+    }
+}
+
+impl AuthSettings {
+    /// Name of the keyring entry holding the secret
+    const KEYRING_ENTRY: &'static str = "ollama-auth";
+
+    /// Value of the `Authorization` header, `None` without credentials.
+    fn header(&self) -> Option<reqwest::header::HeaderValue> {
+        let value = match self.kind {
+            AuthKind::None => return None,
+            AuthKind::Bearer => format!("Bearer {}", self.secret.trim()),
+            AuthKind::Basic => {
+                let credentials = format!("{}:{}", self.username, self.secret);
+                let mut reader = base64_stream::ToBase64Reader::new(credentials.as_bytes());
+                let mut encoded = String::new();
+                std::io::Read::read_to_string(&mut reader, &mut encoded).ok()?;
+                format!("Basic {encoded}")
+            }
+        };
+        let mut value = reqwest::header::HeaderValue::from_str(&value)
+            .map_err(|e| log::error!("invalid credentials for the Authorization header: {e}"))
+            .ok()?;
+        value.set_sensitive(true);
+        Some(value)
+    }
+
+    /// Move the secret into the OS keyring, keeping it with the settings if that fails.
+    pub fn store_secret(&mut self) {
+        let secret = if self.kind == AuthKind::None {
+            ""
+        } else {
+            self.secret.as_str()
+        };
+        match crate::secrets::store(Self::KEYRING_ENTRY, secret) {
+            Ok(()) => self.in_keyring = true,
+            Err(e) => {
+                log::warn!("keeping the server credentials with the settings: {e}");
+                self.in_keyring = false;
+            }
+        }
+    }
+
+    /// Read the secret from the OS keyring after the settings were loaded.
+    pub fn load_secret(&mut self) {
+        if !self.in_keyring {
+            return;
+        }
+        match crate::secrets::load(Self::KEYRING_ENTRY) {
+            Ok(secret) => self.secret = secret.unwrap_or_default(),
+            Err(e) => log::error!("failed to read the server credentials from the keyring: {e}"),
+        }
+    }
+}
+
+/// Most verbose level written to the log file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    const ALL: [Self; 5] = [
+        Self::Error,
+        Self::Warn,
+        Self::Info,
+        Self::Debug,
+        Self::Trace,
+    ];
+
+    fn filter(self) -> log::LevelFilter {
+        match self {
+            Self::Error => log::LevelFilter::Error,
+            Self::Warn => log::LevelFilter::Warn,
+            Self::Info => log::LevelFilter::Info,
+            Self::Debug => log::LevelFilter::Debug,
+            Self::Trace => log::LevelFilter::Trace,
+        }
+    }
+}
+
+/// Log file for bug reports, written next to stderr and the log window.
+#[derive(Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct FileLogSettings {
+    pub enabled: bool,
+    /// Path of the log file, the default one in the app's data directory if empty
+    pub path: String,
+    pub level: LogLevel,
+    /// Path being typed, applied once the field loses focus so no files are created for
+    /// partial paths
+    #[serde(skip)]
+    path_draft: Option<String>,
+}
+
+impl FileLogSettings {
+    fn path(&self) -> Option<PathBuf> {
+        let path = self.path.trim();
+        if path.is_empty() {
+            logs::default_file_path().map(Path::to_path_buf)
+        } else {
+            Some(PathBuf::from(path))
+        }
+    }
+
+    /// Start, stop or redirect the log file as configured, cheap if nothing changed.
+    pub fn apply(&self) {
+        let path = self.enabled.then(|| self.path()).flatten();
+        logs::configure_file(path.as_deref(), self.level.filter());
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.add(toggle(&mut self.enabled));
+            help(ui, tr("settings.log_file_help"), |ui| {
+                ui.label(tr("settings.log_file"));
+            });
+        });
+        ui.add_enabled_ui(self.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("settings.log_level"));
+                egui::ComboBox::new("log_level_combobox", "")
+                    .selected_text(format!("{:?}", self.level))
+                    .show_ui(ui, |ui| {
+                        for level in LogLevel::ALL {
+                            ui.selectable_value(&mut self.level, level, format!("{level:?}"));
+                        }
+                    });
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr("settings.log_path"));
+                let default = logs::default_file_path()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default();
+                let mut draft = self.path_draft.take().unwrap_or_else(|| self.path.clone());
+                let response = ui.add(egui::TextEdit::singleline(&mut draft).hint_text(default));
+                if response.lost_focus() {
+                    self.path = draft.trim().to_owned();
+                } else if response.has_focus() {
+                    self.path_draft = Some(draft);
+                }
+            });
+        });
+        if self.enabled {
+            if let Some(e) = logs::file_error() {
+                ui.label(RichText::new(e).color(ui.visuals().error_fg_color));
+            }
+        }
+        let dir = self
+            .path()
+            .and_then(|p| p.parent().map(Path::to_path_buf))
+            .filter(|dir| dir.is_dir());
+        if ui
+            .add_enabled(
+                dir.is_some(),
+                egui::Button::new(tr("settings.open_log_folder")),
+            )
+            .clicked()
+        {
+            if let Some(dir) = dir {
+                if let Err(e) = logs::reveal(&dir) {
+                    log::error!("failed to open the log folder: {e:#}");
+                }
+            }
+        }
+    }
+}
+
+/// How long requests wait for the server before giving up, so a flaky connection can't
+/// leave a stream or the model picker hanging forever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Timeouts {
+    /// Time to establish a connection
+    pub connect_secs: u64,
+    /// Time until the first token of a reply, or the response to a model list or info
+    /// request. Loading a large model can take a while
+    pub first_token_secs: u64,
+    /// Time a reply may stall between two tokens
+    pub stall_secs: u64,
+    /// Send the request once more when a reply stalls, replacing the partial text
+    pub retry_stalled: bool,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            connect_secs: 10,
+            first_token_secs: 120,
+            stall_secs: 60,
+            retry_stalled: false,
+        }
+    }
+}
+
+impl Timeouts {
+    #[inline]
+    pub fn connect(&self) -> Duration {
+        Duration::from_secs(self.connect_secs)
+    }
+
+    #[inline]
+    pub fn first_token(&self) -> Duration {
+        Duration::from_secs(self.first_token_secs)
+    }
+
+    #[inline]
+    pub fn stall(&self) -> Duration {
+        Duration::from_secs(self.stall_secs)
+    }
+}
+
+/// HTTP status a failed request was answered with. Backend errors start with it, e.g.
+/// "401 Unauthorized: invalid token".
+pub fn error_status(error: &str) -> Option<reqwest::StatusCode> {
+    let code = error.split_whitespace().next()?;
+    let status = reqwest::StatusCode::from_bytes(code.as_bytes()).ok()?;
+    error.starts_with(&status.to_string()).then_some(status)
+}
+
+/// Whether a request failed because the server refused the credentials.
+pub fn is_auth_error(error: &str) -> bool {
+    error_status(error) == Some(reqwest::StatusCode::UNAUTHORIZED)
+}
+
+/// `error` followed by its sources, which hold the details of HTTP client errors.
+pub fn error_chain(error: &(dyn std::error::Error + 'static)) -> String {
+    let mut chain = error.to_string();
+    let mut source = error.source();
+    while let Some(e) = source {
+        chain += &format!(": {e}");
+        source = e.source();
+    }
+    chain
+}
+
+/// One line explaining a failed request in plain words, for the common failures.
+pub fn explain_request_error(error: &str) -> &'static str {
+    let lower = error.to_lowercase();
+    if is_auth_error(error) {
+        tr("chat.explain_auth")
+    } else if lower.contains("timed out") || lower.contains("timeout") {
+        tr("chat.explain_timeout")
+    } else if lower.contains("connection refused") {
+        tr("chat.explain_refused")
+    } else if lower.contains("model") && lower.contains("not found") {
+        tr("chat.explain_model_missing")
+    } else if [
+        "connection reset",
+        "connection closed",
+        "unexpected eof",
+        "decoding response",
+    ]
+    .iter()
+    .any(|pat| lower.contains(pat))
+    {
+        tr("chat.explain_connection_lost")
+    } else if lower.contains("proxy") {
+        tr("chat.explain_proxy")
+    } else {
+        tr("chat.explain_failed")
+    }
+}
+
+/// Make request errors that were likely caused by the proxy distinguishable from server errors.
+/// Failed connections only count when the proxy was set up by hand, proxies from the
+/// environment are skipped for hosts in `NO_PROXY` and then the server itself is unreachable.
+pub fn describe_request_error(error: String, proxy: Option<&ProxyInUse>) -> String {
+    let Some(proxy) = proxy else {
+        return error;
+    };
+    let lower = error.to_lowercase();
+    let names_proxy = ["proxy", "tunnel"].iter().any(|pat| lower.contains(pat));
+    let connect_failed = ["error sending request", "connection refused"]
+        .iter()
+        .any(|pat| lower.contains(pat));
+    if names_proxy || (proxy.manual && connect_failed) {
+        tr_args("settings.proxy_error", &[&proxy.description, &error])
+    } else {
+        error
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+#[serde(default)]
+pub struct Settings {
+    /// API chats and model lists are requested through
+    pub backend: BackendKind,
+    pub openai: OpenAiSettings,
+    /// OpenAI connection being edited, applied with the "Apply" button
+    #[serde(skip)]
+    openai_draft: Option<OpenAiSettings>,
+    pub endpoint: String,
+    /// Endpoint from `--host` or `OLLAMA_HOST`, used instead of `endpoint` until another one
+    /// is applied
+    #[serde(skip)]
+    pub endpoint_override: Option<String>,
+    /// (host, port) being edited, applied to `endpoint` with the "Apply" button
+    #[serde(skip)]
+    endpoint_draft: Option<(String, String)>,
+    pub proxy: ProxySettings,
+    pub auth: AuthSettings,
+    pub timeouts: Timeouts,
+    pub file_log: FileLogSettings,
+    /// Credentials being edited, applied with their "Apply" button
+    #[serde(skip)]
+    auth_draft: Option<AuthSettings>,
+    pub model_picker: ModelPicker,
+    pub inherit_chat_picker: bool,
+    pub model_choice: ModelChoice,
+    /// Number of days deleted chats are kept in the bin before being purged
+    pub retention_days: u32,
+    /// Pasted images larger than this on either side are downscaled
+    pub max_image_dimension: u32,
+    /// Largest text file that can be dropped into a chat, in KiB
+    pub max_attachment_kb: u32,
+    /// Seconds between saves of changed chats and settings
+    pub autosave_secs: u32,
+    pub theme: Theme,
+    pub language: Language,
+    /// Name shown on your messages, "You" if empty
+    pub user_name: String,
+    /// Show temperature and seed controls in the chat header
+    pub quick_settings: bool,
+    pub send_shortcut: SendShortcut,
+    pub keybindings: Keybindings,
+    /// Saved user prompts, reusable across chats
+    pub golden_prompts: Vec<GoldenPrompt>,
+    pub speech: SpeechSettings,
+    /// Path to the whisper model used for voice input
+    #[cfg(feature = "voice")]
+    pub whisper_model: String,
+    /// Voices of the TTS backend, `None` until listed
+    #[cfg(feature = "tts")]
+    #[serde(skip)]
+    voices: Option<Vec<tts::Voice>>,
+}
+
+/// Light or dark visuals.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Theme {
+    /// Whatever the OS uses, dark if unknown
+    #[default]
+    System,
+    Dark,
+    Light,
+}
+
+impl Theme {
+    const ALL: [Self; 3] = [Self::System, Self::Dark, Self::Light];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::System => tr("settings.theme_system"),
+            Self::Dark => tr("settings.theme_dark"),
+            Self::Light => tr("settings.theme_light"),
+        }
+    }
+
+    /// Whether to use dark visuals, given the theme reported by the OS.
+    pub fn is_dark(self, system: Option<eframe::Theme>) -> bool {
+        match self {
+            Self::System => system != Some(eframe::Theme::Light),
+            Self::Dark => true,
+            Self::Light => false,
+        }
+    }
+}
+
+/// Key combination that sends the chat input, the other one inserts a new line.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum SendShortcut {
+    /// Enter sends, Shift+Enter inserts a new line
+    #[default]
+    Enter,
+    /// Ctrl+Enter sends, Enter inserts a new line
+    CtrlEnter,
+}
+
+impl SendShortcut {
+    const ALL: [Self; 2] = [Self::Enter, Self::CtrlEnter];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Enter => tr("settings.send_enter"),
+            Self::CtrlEnter => tr("settings.send_ctrl_enter"),
+        }
+    }
+
+    pub fn send_hint(self) -> &'static str {
+        match self {
+            Self::Enter => "Enter",
+            Self::CtrlEnter => "Ctrl+Enter",
+        }
+    }
+
+    pub fn newline_hint(self) -> &'static str {
+        match self {
+            Self::Enter => "Shift+Enter",
+            Self::CtrlEnter => "Enter",
+        }
+    }
+
+    /// Shortcut the chat input treats as a new line
+    pub fn newline_shortcut(self) -> egui::KeyboardShortcut {
+        match self {
+            Self::Enter => egui::KeyboardShortcut::new(egui::Modifiers::SHIFT, egui::Key::Enter),
+            Self::CtrlEnter => egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::Enter),
+        }
+    }
+
+    /// Whether the send key combination was pressed. Modifiers must match exactly, so the
+    /// new line combination never sends.
+    pub fn send_pressed(self, input: &egui::InputState) -> bool {
+        input.key_pressed(egui::Key::Enter)
+            && match self {
+                Self::Enter => input.modifiers.is_none(),
+                Self::CtrlEnter => input.modifiers.command_only(),
+            }
+    }
+}
+
+/// Action that can be triggered with a remappable keyboard shortcut.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize, serde::Serialize,
+)]
+pub enum KeyAction {
+    NewChat,
+    CloseChat,
+    NextChat,
+    PreviousChat,
+    ToggleSidebar,
+    SearchChats,
+    FindInChat,
+    StopGeneration,
+    UndoDeletion,
+    ToggleLogs,
+}
+
+impl KeyAction {
+    pub const ALL: [Self; 10] = [
+        Self::NewChat,
+        Self::CloseChat,
+        Self::NextChat,
+        Self::PreviousChat,
+        Self::ToggleSidebar,
+        Self::SearchChats,
+        Self::FindInChat,
+        Self::StopGeneration,
+        Self::UndoDeletion,
+        Self::ToggleLogs,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::NewChat => tr("keys.new_chat"),
+            Self::CloseChat => tr("keys.close_chat"),
+            Self::NextChat => tr("keys.next_chat"),
+            Self::PreviousChat => tr("keys.previous_chat"),
+            Self::ToggleSidebar => tr("keys.toggle_sidebar"),
+            Self::SearchChats => tr("keys.search_chats"),
+            Self::FindInChat => tr("keys.find_in_chat"),
+            Self::StopGeneration => tr("keys.stop_generation"),
+            Self::UndoDeletion => tr("keys.undo_deletion"),
+            Self::ToggleLogs => tr("keys.toggle_logs"),
+        }
+    }
+
+    pub const fn default_shortcut(self) -> egui::KeyboardShortcut {
+        use egui::{Key, Modifiers};
+        let (modifiers, key) = match self {
+            Self::NewChat => (Modifiers::COMMAND, Key::N),
+            Self::CloseChat => (Modifiers::COMMAND, Key::W),
+            Self::NextChat => (Modifiers::COMMAND, Key::Tab),
+            Self::PreviousChat => (
+                Modifiers {
+                    shift: true,
+                    ..Modifiers::COMMAND
+                },
+                Key::Tab,
+            ),
+            Self::ToggleSidebar => (Modifiers::COMMAND, Key::B),
+            Self::SearchChats => (
+                Modifiers {
+                    shift: true,
+                    ..Modifiers::COMMAND
+                },
+                Key::F,
+            ),
+            Self::FindInChat => (Modifiers::COMMAND, Key::F),
+            Self::StopGeneration => (Modifiers::COMMAND, Key::Period),
+            Self::UndoDeletion => (Modifiers::COMMAND, Key::Z),
+            Self::ToggleLogs => (
+                Modifiers {
+                    shift: true,
+                    ..Modifiers::COMMAND
+                },
+                Key::L,
+            ),
+        };
+        egui::KeyboardShortcut::new(modifiers, key)
+    }
+}
+
+/// Shortcuts that are not remappable, a binding using one of them conflicts
+fn reserved_shortcuts() -> impl Iterator<Item = (egui::KeyboardShortcut, String)> {
+    use egui::Key;
+    [
+        Key::Num1,
+        Key::Num2,
+        Key::Num3,
+        Key::Num4,
+        Key::Num5,
+        Key::Num6,
+        Key::Num7,
+        Key::Num8,
+        Key::Num9,
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, key)| {
+        (
+            egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, key),
+            tr_args("keys.jump_to_chat", &[&(i + 1)]),
+        )
+    })
+}
+
+/// Keep Ctrl (Cmd on macOS), Alt and Shift of the held modifiers, so that bindings made
+/// with Ctrl on one platform use Cmd on another.
+fn normalize_modifiers(held: egui::Modifiers) -> egui::Modifiers {
+    let mut modifiers = if held.command {
+        egui::Modifiers::COMMAND
+    } else if held.ctrl {
+        egui::Modifiers::CTRL
+    } else {
+        egui::Modifiers::NONE
+    };
+    modifiers.alt = held.alt;
+    modifiers.shift = held.shift;
+    modifiers
+}
+
+/// Keyboard shortcuts of the [`KeyAction`]s. Only remapped actions are stored, the others
+/// use their default.
+#[derive(Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Keybindings {
+    remapped: BTreeMap<KeyAction, egui::KeyboardShortcut>,
+    /// Action waiting for its new key combination to be pressed
+    #[serde(skip)]
+    capturing: Option<KeyAction>,
+    /// Why the last pressed combination was refused
+    #[serde(skip)]
+    capture_error: Option<&'static str>,
+}
+
+impl Keybindings {
+    pub fn shortcut(&self, action: KeyAction) -> egui::KeyboardShortcut {
+        self.remapped
+            .get(&action)
+            .copied()
+            .unwrap_or_else(|| action.default_shortcut())
+    }
+
+    /// Whether the shortcut of `action` was pressed this frame, consuming the key press.
+    /// Nothing triggers while a new combination is being recorded.
+    pub fn pressed(&self, ctx: &egui::Context, action: KeyAction) -> bool {
+        self.capturing.is_none() && ctx.input_mut(|i| i.consume_shortcut(&self.shortcut(action)))
+    }
+
+    /// Shortcut of `action` as shown to the user, e.g. "Ctrl+N".
+    pub fn format(&self, ctx: &egui::Context, action: KeyAction) -> String {
+        ctx.format_shortcut(&self.shortcut(action))
+    }
+
+    /// Other actions and fixed shortcuts using the same combination as `action`.
+    fn conflicts(&self, action: KeyAction) -> Vec<String> {
+        let shortcut = self.shortcut(action);
+        KeyAction::ALL
+            .into_iter()
+            .filter(|&other| other != action && self.shortcut(other) == shortcut)
+            .map(|other| other.label().to_owned())
+            .chain(
+                reserved_shortcuts()
+                    .filter(|(reserved, _)| *reserved == shortcut)
+                    .map(|(_, name)| name),
+            )
+            .collect()
+    }
+
+    /// Record the next key press as the shortcut of the action being captured. Escape
+    /// without modifiers cancels, presses of modifier keys alone keep waiting.
+    fn capture(&mut self, ui: &egui::Ui) {
+        let Some(action) = self.capturing else {
+            return;
+        };
+        let pressed = ui.input_mut(|i| {
+            let mut pressed = None;
+            i.events.retain(|event| match event {
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } if pressed.is_none() => {
+                    pressed = Some(egui::KeyboardShortcut::new(
+                        normalize_modifiers(*modifiers),
+                        *key,
+                    ));
+                    false
+                }
+                _ => true,
+            });
+            pressed
+        });
+        let Some(shortcut) = pressed else {
+            // show the held modifiers as they change
+            ui.ctx().request_repaint();
+            return;
+        };
+        let is_function_key = shortcut
+            .logical_key
+            .name()
+            .strip_prefix('F')
+            .is_some_and(|n| n.parse::<u8>().is_ok());
+        if shortcut.modifiers.is_none() && shortcut.logical_key == egui::Key::Escape {
+            self.capturing = None;
+            self.capture_error = None;
+        } else if !(shortcut.modifiers.command
+            || shortcut.modifiers.ctrl
+            || shortcut.modifiers.alt
+            || is_function_key)
+        {
+            // plain keys would fire while typing
+            self.capture_error = Some(tr("keys.needs_modifier"));
+        } else {
+            if shortcut == action.default_shortcut() {
+                self.remapped.remove(&action);
+            } else {
+                self.remapped.insert(action, shortcut);
+            }
+            self.capturing = None;
+            self.capture_error = None;
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        self.capture(ui);
+        let ctx = ui.ctx().clone();
+        egui::Grid::new("keybindings_grid")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for action in KeyAction::ALL {
+                    ui.label(action.label());
+                    if self.capturing == Some(action) {
+                        let held = normalize_modifiers(ui.input(|i| i.modifiers));
+                        let held = egui::ModifierNames::NAMES
+                            .format(&held, matches!(ctx.os(), egui::os::OperatingSystem::Mac));
+                        let text = if held.is_empty() {
+                            tr("keys.press").to_owned()
+                        } else {
+                            format!("{held}+…")
+                        };
+                        if ui
+                            .add(egui::Button::new(text).selected(true))
+                            .on_hover_text(tr("keys.press_help"))
+                            .clicked()
+                        {
+                            self.capturing = None;
+                            self.capture_error = None;
+                        }
+                    } else {
+                        let conflicts = self.conflicts(action);
+                        let mut text = RichText::new(self.format(&ctx, action));
+                        if !conflicts.is_empty() {
+                            text = text.color(ui.visuals().error_fg_color);
+                        }
+                        let mut resp = ui.button(text);
+                        resp = if conflicts.is_empty() {
+                            resp.on_hover_text(tr("keys.change_help"))
+                        } else {
+                            resp.on_hover_text(tr_args("keys.conflict", &[&conflicts.join(", ")]))
+                        };
+                        if resp.clicked() {
+                            self.capturing = Some(action);
+                            self.capture_error = None;
+                        }
+                    }
+                    if self.remapped.contains_key(&action)
+                        && ui
+                            .small_button("↺")
+                            .on_hover_text(tr_args(
+                                "keys.reset_one",
+                                &[&ctx.format_shortcut(&action.default_shortcut())],
+                            ))
+                            .clicked()
+                    {
+                        self.remapped.remove(&action);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(error) = self.capture_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        if ui
+            .add_enabled(
+                !self.remapped.is_empty(),
+                egui::Button::new(tr("keys.reset_all")),
+            )
+            .clicked()
+        {
+            self.remapped.clear();
+            self.capturing = None;
+            self.capture_error = None;
+        }
+    }
+}
+
+/// Text-to-speech settings, applied to the shared TTS instance whenever they change
+#[derive(Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct SpeechSettings {
+    /// Id of the selected voice, `None` for the platform default
+    pub voice: Option<String>,
+    /// Speech rate, pitch and volume as a fraction of the backend's range (0 is the
+    /// minimum, 1 the maximum). `None` uses the backend's normal value.
+    pub rate: Option<f32>,
+    pub pitch: Option<f32>,
+    pub volume: Option<f32>,
+}
+
+impl SpeechSettings {
+    /// Map a normalized value onto the backend range `min..=max`.
+    #[cfg(feature = "tts")]
+    pub fn denormalize(value: Option<f32>, min: f32, max: f32, normal: f32) -> f32 {
+        value.map_or(normal, |v| min + v.clamp(0.0, 1.0) * (max - min))
+    }
+
+    /// Normalized position of `normal` within `min..=max`.
+    #[cfg(feature = "tts")]
+    fn normalize(min: f32, max: f32, normal: f32) -> f32 {
+        if max > min {
+            ((normal - min) / (max - min)).clamp(0.0, 1.0)
+        } else {
+            0.5
+        }
+    }
+
+    #[cfg(feature = "tts")]
+    fn edit_property(
+        ui: &mut egui::Ui,
+        value: &mut Option<f32>,
+        supported: bool,
+        (min, max, normal): (f32, f32, f32),
+        name: &str,
+    ) {
+        let label = ui.label(name);
+        if !supported {
+            label.on_hover_text(tr_args("settings.speech_unsupported", &[&name]));
+        }
+        ui.add_enabled_ui(supported, |ui| {
+            ui.horizontal(|ui| {
+                let mut fraction = value.unwrap_or_else(|| Self::normalize(min, max, normal));
+                if ui
+                    .add(
+                        egui::Slider::new(&mut fraction, 0.0..=1.0)
+                            .custom_formatter(|v, _| format!("{:.0}%", v * 100.0))
+                            .custom_parser(|s| {
+                                s.trim_end_matches('%')
+                                    .trim()
+                                    .parse::<f64>()
+                                    .ok()
+                                    .map(|v| v / 100.0)
+                            }),
+                    )
+                    .changed()
+                {
+                    *value = Some(fraction);
+                }
+                if ui
+                    .add_enabled(value.is_some(), egui::Button::new("↺").small())
+                    .on_hover_text(tr("settings.speech_reset"))
+                    .clicked()
+                {
+                    *value = None;
+                }
+            });
+        });
+        ui.end_row();
+    }
+}
+
+const DEFAULT_HOST: &str = "http://127.0.0.1:11434";
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            backend: BackendKind::default(),
+            openai: OpenAiSettings::default(),
+            openai_draft: None,
+            endpoint: DEFAULT_HOST.to_owned(),
+            model_picker: ModelPicker::default(),
+            inherit_chat_picker: true,
+            model_choice: ModelChoice::default(),
+            endpoint_override: None,
+            endpoint_draft: None,
+            proxy: ProxySettings {
+                bypass_localhost: true,
+                ..Default::default()
+            },
+            auth: AuthSettings::default(),
+            auth_draft: None,
+            timeouts: Timeouts::default(),
+            file_log: FileLogSettings::default(),
+            retention_days: 30,
+            max_image_dimension: 1344,
+            max_attachment_kb: 512,
+            autosave_secs: 30,
+            theme: Theme::default(),
+            language: Language::default(),
+            user_name: String::new(),
+            quick_settings: true,
+            send_shortcut: SendShortcut::default(),
+            keybindings: Keybindings::default(),
+            golden_prompts: Vec::new(),
+            speech: SpeechSettings::default(),
+            #[cfg(feature = "voice")]
+            whisper_model: String::new(),
+            #[cfg(feature = "tts")]
+            voices: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Copy for files that leave the app, like backups and exported settings, without
+    /// the API key, server credentials and proxy password.
+    pub fn without_secrets(&self) -> Self {
+        let mut settings = self.clone();
+        settings.openai.api_key.clear();
+        settings.openai_draft = None;
+        settings.auth.secret.clear();
+        settings.auth_draft = None;
+        settings.proxy.password.clear();
+        settings
+    }
+
+    /// Fill in the secrets missing from settings read from a file with the ones of
+    /// `current`, see [`Self::without_secrets`].
+    pub fn keep_secrets(&mut self, current: &Self) {
+        if self.openai.api_key.is_empty() {
+            self.openai.api_key.clone_from(&current.openai.api_key);
+        }
+        if self.auth.secret.is_empty() && self.auth.kind == current.auth.kind {
+            self.auth.secret.clone_from(&current.auth.secret);
+        }
+        if self.proxy.password.is_empty() && self.proxy.username == current.proxy.username {
+            self.proxy.password.clone_from(&current.proxy.password);
+        }
+    }
+
+    fn show_model_choice(&mut self, ui: &mut egui::Ui) {
+        let choice = &mut self.model_choice;
+        ui.horizontal(|ui| {
+            help(ui, tr("settings.startup_model_help"), |ui| {
+                ui.label(tr("settings.startup_model"));
+            });
+            if choice.default_model.is_empty() {
+                ui.weak(tr("settings.picked_automatically"));
+            } else {
+                ui.code(&choice.default_model);
+                if ui
+                    .small_button(tr("models.unset"))
+                    .on_hover_text(tr("models.unset_help"))
+                    .clicked()
+                {
+                    choice.default_model.clear();
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add(toggle(&mut choice.prefer_chat));
+            help(ui, tr("settings.prefer_chat_help"), |ui| {
+                ui.label(tr("settings.prefer_chat"));
+            });
+        });
+        ui.horizontal(|ui| {
+            let mut limited = choice.max_size_gb.is_some();
+            ui.add(toggle(&mut limited));
+            help(ui, tr("settings.size_limit_help"), |ui| {
+                ui.label(tr("settings.size_limit"));
+            });
+            if limited {
+                let limit = choice.max_size_gb.get_or_insert(8.0);
+                ui.add(
+                    egui::DragValue::new(limit)
+                        .clamp_range(0.5..=1024.0)
+                        .speed(0.5)
+                        .suffix(" GB"),
+                );
+            } else {
+                choice.max_size_gb = None;
+            }
+        });
+    }
+
+    /// The endpoint in use: the override if any, otherwise the saved one.
+    pub fn current_endpoint(&self) -> &str {
+        self.endpoint_override.as_deref().unwrap_or(&self.endpoint)
+    }
+
+    /// URL of the server chats are sent to.
+    pub fn server_url(&self) -> &str {
+        match self.backend {
+            BackendKind::Ollama => self.current_endpoint(),
+            BackendKind::OpenAi => &self.openai.base_url,
+        }
+    }
+
+    pub fn parse_endpoint(&self) -> Result<Url> {
+        let url = url::Url::parse(self.current_endpoint())?;
+        if !url.has_host() {
+            return Err(anyhow::anyhow!(tr("settings.invalid_host")));
+        }
+        Ok(url)
+    }
+
+    /// Split the endpoint into the (host, port) fields of the connection settings.
+    fn split_endpoint(&self) -> (String, String) {
+        let url = self
+            .parse_endpoint()
+            .unwrap_or_else(|_| Url::parse(DEFAULT_HOST).unwrap());
+        (
+            format!(
+                "{}://{}",
+                url.scheme(),
+                url.host_str().unwrap_or("127.0.0.1")
+            ),
+            url.port_or_known_default().unwrap_or(11434).to_string(),
+        )
+    }
+
+    /// Parse a host the way the Ollama CLI reads `OLLAMA_HOST`: the scheme defaults to http,
+    /// the host to 127.0.0.1 and the port to 11434, e.g. `example.com`, `:8080` or
+    /// `https://example.com`.
+    pub fn parse_ollama_host(value: &str) -> Result<String> {
+        let value = value.trim().trim_end_matches('/');
+        let (scheme, rest) = value.split_once("://").unwrap_or(("http", value));
+        let (host, port) = match rest.rsplit_once(':') {
+            // ipv6 addresses without a port, like [::1]
+            Some((_, port)) if port.ends_with(']') => (rest, None),
+            Some((host, port)) => (host, Some(port)),
+            None => (rest, None),
+        };
+        let host = if host.is_empty() { "127.0.0.1" } else { host };
+        let port = match port {
+            Some(port) => port.to_owned(),
+            // an explicit scheme implies its default port, like the Ollama CLI
+            None if value.contains("://") => Url::parse(&format!("{scheme}://{host}"))?
+                .port_or_known_default()
+                .unwrap_or(11434)
+                .to_string(),
+            None => "11434".to_owned(),
+        };
+        Self::endpoint_from_draft(&format!("{scheme}://{host}"), &port)
+    }
+
+    /// Build an endpoint URL from the host and port fields, the scheme defaults to http.
+    fn endpoint_from_draft(host: &str, port: &str) -> Result<String> {
+        let host = host.trim().trim_end_matches('/');
+        if host.is_empty() {
+            return Err(anyhow::anyhow!(tr("settings.host_empty")));
+        }
+        let port: u16 = port
+            .trim()
+            .parse()
+            .map_err(|_| anyhow::anyhow!(tr("settings.port_range")))?;
+        let host = if host.contains("://") {
+            host.to_owned()
+        } else {
+            format!("http://{host}")
+        };
+        let mut url = Url::parse(&host)?;
+        if !url.has_host() || url.path() != "/" {
+            return Err(anyhow::anyhow!(tr("settings.invalid_host")));
+        }
+        url.set_port(Some(port))
+            .map_err(|_| anyhow::anyhow!(tr("settings.invalid_port")))?;
+        Ok(url.as_str().trim_end_matches('/').to_owned())
+    }
+
+    fn client_builder(&self) -> reqwest::ClientBuilder {
+        // the default client already respects the proxy environment variables,
+        // a manual proxy replaces them
+        let mut builder = reqwest::Client::builder().connect_timeout(self.timeouts.connect());
+        if self.proxy.enabled {
+            match self.proxy.make_proxy() {
+                Ok(proxy) => builder = builder.proxy(proxy),
+                Err(e) => log::error!("invalid proxy `{}`: {e}", self.proxy.url),
+            }
+        }
+        builder
+    }
+
+    fn build_client(builder: reqwest::ClientBuilder) -> reqwest::Client {
+        builder.build().unwrap_or_else(|e| {
+            log::error!("failed to build http client: {e}");
+            reqwest::Client::new()
+        })
+    }
+
+    fn ollama_client_builder(&self) -> reqwest::ClientBuilder {
+        let mut builder = self.client_builder();
+        if let Some(value) = self.auth.header() {
+            let mut headers = reqwest::header::HeaderMap::new();
+            headers.insert(reqwest::header::AUTHORIZATION, value);
+            builder = builder.default_headers(headers);
+        }
+        builder
+    }
+
+    /// Client for requests to the Ollama server, sending its credentials with each one.
+    pub fn make_client(&self) -> reqwest::Client {
+        Self::build_client(self.ollama_client_builder())
+    }
+
+    /// Client of the Ollama server, for chats, model management and embeddings.
+    pub fn make_ollama_client(&self) -> OllamaClient {
+        let url = self
+            .parse_endpoint()
+            .unwrap_or_else(|_| Url::parse(DEFAULT_HOST).unwrap());
+        OllamaClient::new(url, self.make_client())
+    }
+
+    /// Client of the OpenAI-compatible server, `None` if Ollama is the backend.
+    pub fn make_openai(&self) -> Option<OpenAiClient> {
+        (self.backend == BackendKind::OpenAi)
+            .then(|| OpenAiClient::new(&self.openai, Self::build_client(self.client_builder())))
+    }
+
+    pub fn show_modal(&mut self, modal: &Modal) {
+        modal.show(|ui| {
+            modal.title(ui, tr("settings.reset_title"));
             modal.frame(ui, |ui| {
-                modal.body_and_icon(
-                    ui,
-                    "Are you sure you want to reset global settings? \
-                    This action cannot be undone!",
-                    Icon::Warning,
-                );
+                modal.body_and_icon(ui, tr("settings.reset_body"), Icon::Warning);
             });
             modal.buttons(ui, |ui| {
-                modal.button(ui, "no");
-                if modal.caution_button(ui, "yes").clicked() {
+                modal.button(ui, tr("common.no"));
+                if modal.caution_button(ui, tr("common.yes")).clicked() {
                     *self = Self::default();
                 }
             });
@@ -799,7 +3632,7 @@ impl Settings {
 
     async fn ask_save_settings(settings: Self) {
         let Some(file) = rfd::AsyncFileDialog::new()
-            .add_filter("JSON file", &["json"])
+            .add_filter(tr("sidebar.json_filter"), &["json"])
             .save_file()
             .await
         else {
@@ -817,82 +3650,692 @@ impl Settings {
             .map_err(|e| log::error!("failed to save settings: {e}"));
     }
 
+    #[cfg(feature = "tts")]
+    fn show_speech(&mut self, ui: &mut egui::Ui, tts: &SharedTts) {
+        let Some(tts) = tts else {
+            return;
+        };
+        let voices = self.voices.get_or_insert_with(|| {
+            let mut voices = tts
+                .read()
+                .voices()
+                .map_err(|e| log::warn!("failed to list TTS voices: {e}"))
+                .unwrap_or_default();
+            voices.sort_by_key(|v| (v.language().to_string(), v.name()));
+            voices
+        });
+
+        ui.heading(tr("settings.speech"));
+        // backends that can't list voices keep their default voice
+        if !voices.is_empty() {
+            ui.horizontal(|ui| {
+                ui.label(tr("settings.voice"));
+                let selected = self
+                    .speech
+                    .voice
+                    .as_ref()
+                    .and_then(|id| voices.iter().find(|v| v.id() == *id))
+                    .map_or_else(|| tr("settings.default_voice").to_owned(), |v| v.name());
+                egui::ComboBox::new("tts_voice_combobox", "")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.speech.voice,
+                            None,
+                            tr("settings.default_voice"),
+                        );
+                        let mut language = String::new();
+                        for voice in voices.iter() {
+                            let voice_language = voice.language().to_string();
+                            if voice_language != language {
+                                ui.separator();
+                                ui.label(RichText::new(&voice_language).small().weak());
+                                language = voice_language;
+                            }
+                            ui.selectable_value(
+                                &mut self.speech.voice,
+                                Some(voice.id()),
+                                voice.name(),
+                            );
+                        }
+                    });
+            });
+        }
+
+        let (features, rate, pitch, volume) = {
+            let tts = tts.read();
+            (
+                tts.supported_features(),
+                (tts.min_rate(), tts.max_rate(), tts.normal_rate()),
+                (tts.min_pitch(), tts.max_pitch(), tts.normal_pitch()),
+                (tts.min_volume(), tts.max_volume(), tts.normal_volume()),
+            )
+        };
+        egui::Grid::new("speech_settings_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                let speech = &mut self.speech;
+                SpeechSettings::edit_property(
+                    ui,
+                    &mut speech.rate,
+                    features.rate,
+                    rate,
+                    tr("settings.rate"),
+                );
+                SpeechSettings::edit_property(
+                    ui,
+                    &mut speech.pitch,
+                    features.pitch,
+                    pitch,
+                    tr("settings.pitch"),
+                );
+                SpeechSettings::edit_property(
+                    ui,
+                    &mut speech.volume,
+                    features.volume,
+                    volume,
+                    tr("settings.volume"),
+                );
+            });
+        ui.separator();
+    }
+
+    /// Rows of the connection grid for the OpenAI-compatible server.
+    fn show_openai_connection<R>(&mut self, ui: &mut egui::Ui, request_info: &mut R)
+    where
+        R: FnMut(RequestInfoType<'_>),
+    {
+        let mut draft = self
+            .openai_draft
+            .take()
+            .unwrap_or_else(|| self.openai.clone());
+        ui.label(tr("settings.base_url"));
+        ui.add(
+            egui::TextEdit::singleline(&mut draft.base_url).hint_text("http://127.0.0.1:8000/v1"),
+        );
+        ui.end_row();
+
+        ui.label(tr("settings.api_key"));
+        ui.add(
+            egui::TextEdit::singleline(&mut draft.api_key)
+                .password(true)
+                .hint_text(tr("common.optional")),
+        );
+        ui.end_row();
+
+        let url = Url::parse(draft.base_url.trim());
+        ui.label("");
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    url.is_ok() && draft != self.openai,
+                    egui::Button::new(tr("common.apply")),
+                )
+                .on_hover_text(tr("settings.apply_server_help"))
+                .clicked()
+            {
+                self.openai.clone_from(&draft);
+                request_info(RequestInfoType::Reconnect);
+            }
+            if let Err(e) = &url {
+                ui.label(RichText::new(e.to_string()).color(ui.visuals().error_fg_color));
+            }
+        });
+        ui.end_row();
+        self.openai_draft = Some(draft);
+    }
+
+    fn show_timeouts<R>(&mut self, ui: &mut egui::Ui, request_info: &mut R)
+    where
+        R: FnMut(RequestInfoType<'_>),
+    {
+        let timeouts = &mut self.timeouts;
+        help(ui, tr("settings.connect_timeout_help"), |ui| {
+            ui.label(tr("settings.connect_timeout"));
+        });
+        let response = ui.add(
+            egui::DragValue::new(&mut timeouts.connect_secs)
+                .clamp_range(1..=120)
+                .suffix(" s"),
+        );
+        // the client is rebuilt with the new timeout
+        if response.drag_stopped() || (response.changed() && !response.dragged()) {
+            request_info(RequestInfoType::Reconnect);
+        }
+        ui.end_row();
+
+        help(ui, tr("settings.first_token_timeout_help"), |ui| {
+            ui.label(tr("settings.first_token_timeout"));
+        });
+        ui.add(
+            egui::DragValue::new(&mut timeouts.first_token_secs)
+                .clamp_range(5..=1800)
+                .suffix(" s"),
+        );
+        ui.end_row();
+
+        help(ui, tr("settings.stall_timeout_help"), |ui| {
+            ui.label(tr("settings.stall_timeout"));
+        });
+        ui.add(
+            egui::DragValue::new(&mut timeouts.stall_secs)
+                .clamp_range(5..=600)
+                .suffix(" s"),
+        );
+        ui.end_row();
+
+        help(ui, tr("settings.retry_stalled_help"), |ui| {
+            ui.label(tr("settings.retry_stalled"));
+        });
+        ui.add(toggle(&mut timeouts.retry_stalled));
+        ui.end_row();
+    }
+
+    fn show_auth<R>(&mut self, ui: &mut egui::Ui, request_info: &mut R)
+    where
+        R: FnMut(RequestInfoType<'_>),
+    {
+        let mut draft = self.auth_draft.take().unwrap_or_else(|| self.auth.clone());
+        ui.label(tr("settings.auth"));
+        egui::ComboBox::from_id_source("auth_kind")
+            .selected_text(draft.kind.label())
+            .show_ui(ui, |ui| {
+                for kind in AuthKind::ALL {
+                    ui.selectable_value(&mut draft.kind, kind, kind.label());
+                }
+            });
+        ui.end_row();
+
+        if draft.kind == AuthKind::Basic {
+            ui.label(tr("settings.username"));
+            ui.add(egui::TextEdit::singleline(&mut draft.username));
+            ui.end_row();
+        }
+
+        if draft.kind != AuthKind::None {
+            ui.label(if draft.kind == AuthKind::Bearer {
+                tr("settings.auth_token")
+            } else {
+                tr("settings.password")
+            });
+            ui.add(egui::TextEdit::singleline(&mut draft.secret).password(true));
+            ui.end_row();
+        }
+
+        let changed =
+            draft.kind != self.auth.kind || (draft.kind != AuthKind::None && draft != self.auth);
+        if changed || self.auth.kind != AuthKind::None {
+            ui.label("");
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(changed, egui::Button::new(tr("common.apply")))
+                    .on_hover_text(tr("settings.auth_apply_help"))
+                    .clicked()
+                {
+                    draft.store_secret();
+                    self.auth.clone_from(&draft);
+                    request_info(RequestInfoType::Reconnect);
+                }
+                if !changed {
+                    ui.label(if self.auth.in_keyring {
+                        tr("settings.auth_in_keyring")
+                    } else {
+                        tr("settings.auth_in_settings")
+                    });
+                }
+            });
+            ui.end_row();
+        }
+        self.auth_draft = Some(draft);
+    }
+
     pub fn show<R>(
         &mut self,
         ui: &mut egui::Ui,
         models: Option<&[LocalModel]>,
+        tasks: &ModelTasks,
         request_info: &mut R,
         modal: &Modal,
+        #[cfg(feature = "tts")] tts: &SharedTts,
     ) where
         R: FnMut(RequestInfoType<'_>),
     {
-        ui.heading("Ollama");
-        ui.label("Connection settings");
+        ui.heading(tr("settings.server"));
+        ui.label(tr("settings.connection"));
         egui::Grid::new("settings_grid")
             .num_columns(2)
             .striped(true)
             .min_row_height(32.0)
             .show(ui, |ui| {
-                ui.label("Endpoint");
+                help(ui, tr("settings.backend_help"), |ui| {
+                    ui.label(tr("settings.backend"));
+                });
+                let backend = self.backend;
+                egui::ComboBox::from_id_source("backend_combobox")
+                    .selected_text(self.backend.label())
+                    .show_ui(ui, |ui| {
+                        for kind in BackendKind::ALL {
+                            ui.selectable_value(&mut self.backend, kind, kind.label());
+                        }
+                    });
+                if self.backend != backend {
+                    request_info(RequestInfoType::Reconnect);
+                }
+                ui.end_row();
+
+                if self.backend == BackendKind::OpenAi {
+                    self.show_openai_connection(ui, request_info);
+                }
+
+                let (mut host, mut port) = self
+                    .endpoint_draft
+                    .take()
+                    .unwrap_or_else(|| self.split_endpoint());
+                ui.label(tr("settings.host"));
+                ui.add(egui::TextEdit::singleline(&mut host).hint_text("http://127.0.0.1"));
+                ui.end_row();
+
+                ui.label(tr("settings.port"));
+                ui.add(
+                    egui::TextEdit::singleline(&mut port)
+                        .hint_text("11434")
+                        .desired_width(64.0),
+                );
+                ui.end_row();
+
+                let new_endpoint = Self::endpoint_from_draft(&host, &port);
+                let mut reset = false;
+                ui.label("");
                 ui.horizontal(|ui| {
-                    let textedit = egui::TextEdit::singleline(&mut self.endpoint)
-                        .hint_text(DEFAULT_HOST)
-                        .show(ui);
-                    if textedit.response.changed() {
-                        if let Err(e) = self.parse_endpoint() {
-                            self.endpoint_error = e.to_string();
-                        } else {
-                            self.endpoint_error.clear();
+                    let changed = new_endpoint
+                        .as_ref()
+                        .is_ok_and(|endpoint| endpoint != self.current_endpoint());
+                    if ui
+                        .add_enabled(changed, egui::Button::new(tr("common.apply")))
+                        .on_hover_text(tr("settings.apply_endpoint_help"))
+                        .clicked()
+                    {
+                        if let Ok(endpoint) = &new_endpoint {
+                            self.endpoint.clone_from(endpoint);
+                            self.endpoint_override = None;
+                            request_info(RequestInfoType::Reconnect);
                         }
                     }
-                    if self.endpoint != DEFAULT_HOST
-                        && ui.button("↺").on_hover_text("Reset to default").clicked()
+                    if self.current_endpoint() != DEFAULT_HOST
+                        && ui
+                            .button("↺")
+                            .on_hover_text(tr("common.reset_to_default"))
+                            .clicked()
                     {
-                        self.endpoint_error.clear();
                         self.endpoint = DEFAULT_HOST.to_owned();
+                        self.endpoint_override = None;
+                        reset = true;
+                        request_info(RequestInfoType::Reconnect);
                     }
-                    if !self.endpoint_error.is_empty() {
-                        ui.label(
-                            RichText::new(&self.endpoint_error).color(ui.visuals().error_fg_color),
-                        );
+                    match &new_endpoint {
+                        Err(e) => {
+                            ui.label(
+                                RichText::new(e.to_string()).color(ui.visuals().error_fg_color),
+                            );
+                        }
+                        Ok(endpoint) if endpoint != self.current_endpoint() => {
+                            ui.label(tr_args(
+                                "settings.connected_to",
+                                &[&self.current_endpoint()],
+                            ));
+                        }
+                        Ok(_) => {}
                     }
                 });
                 ui.end_row();
+                if !reset {
+                    self.endpoint_draft = Some((host, port));
+                }
+
+                if self.backend != BackendKind::OpenAi {
+                    self.show_auth(ui, request_info);
+                }
+
+                ui.label(tr("settings.proxy"));
+                ui.horizontal(|ui| {
+                    ui.add(toggle(&mut self.proxy.enabled));
+                    help(ui, tr("settings.manual_proxy_help"), |ui| {
+                        ui.label(tr("settings.manual_proxy"));
+                    });
+                });
+                ui.end_row();
+
+                if self.proxy.enabled {
+                    ui.label(tr("settings.proxy_url"));
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.proxy.url)
+                                .hint_text("http://proxy.example.com:8080"),
+                        );
+                        if let Err(e) = self.proxy.make_proxy() {
+                            ui.label(
+                                RichText::new(e.to_string()).color(ui.visuals().error_fg_color),
+                            );
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label(tr("settings.username"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.proxy.username)
+                            .hint_text(tr("common.optional")),
+                    );
+                    ui.end_row();
+
+                    ui.label(tr("settings.password"));
+                    ui.horizontal(|ui| {
+                        let password = ui.add(
+                            egui::TextEdit::singleline(&mut self.proxy.password)
+                                .password(true)
+                                .hint_text(tr("common.optional")),
+                        );
+                        let remember = ui
+                            .checkbox(&mut self.proxy.remember_password, tr("settings.remember"))
+                            .on_hover_text(tr("settings.remember_help"));
+                        if remember.changed()
+                            || (password.lost_focus() && self.proxy.remember_password)
+                        {
+                            self.proxy.store_password();
+                        }
+                        if self.proxy.remember_password && !self.proxy.in_keyring {
+                            ui.weak(tr("settings.auth_in_settings"));
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label(tr("settings.bypass_localhost"));
+                    ui.add(toggle(&mut self.proxy.bypass_localhost));
+                    ui.end_row();
+                }
+
+                self.show_timeouts(ui, request_info);
             });
 
         ui.separator();
 
-        ui.heading("Model");
-        ui.label("Default model for new chats");
+        ui.heading(tr("settings.model"));
+        ui.label(tr("settings.default_model"));
         ui.horizontal(|ui| {
             ui.add(toggle(&mut self.inherit_chat_picker));
-            help(ui, "Inherit model changes from chats", |ui| {
-                ui.label("Inherit from chats");
+            help(ui, tr("settings.inherit_help"), |ui| {
+                ui.label(tr("settings.inherit"));
             });
         });
         ui.add_space(2.0);
-        self.model_picker.show(ui, models, request_info);
+        self.model_picker.show(
+            ui,
+            egui::Id::new("global_model_picker"),
+            models,
+            tasks,
+            request_info,
+            true,
+        );
+        self.show_model_choice(ui);
+
+        ui.separator();
+
+        ui.heading(tr("settings.golden_prompts"));
+        golden::show_library(ui, &mut self.golden_prompts);
 
         ui.separator();
 
-        ui.heading("Miscellaneous");
+        #[cfg(feature = "tts")]
+        self.show_speech(ui, tts);
+
+        #[cfg(feature = "voice")]
+        {
+            ui.heading(tr("settings.voice_input"));
+            ui.horizontal(|ui| {
+                help(ui, tr("settings.whisper_model_help"), |ui| {
+                    ui.label(tr("settings.whisper_model"));
+                });
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.whisper_model)
+                        .hint_text("path/to/ggml-base.en.bin"),
+                );
+            });
+            ui.separator();
+        }
+
+        ui.heading(tr("keys.heading"));
+        self.keybindings.show(ui);
+
+        ui.separator();
+
+        ui.heading(tr("settings.logging"));
+        self.file_log.show(ui);
+
+        ui.separator();
+
+        ui.heading(tr("settings.misc"));
+
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.language"));
+            egui::ComboBox::new("language_combobox", "")
+                .selected_text(self.language.label())
+                .show_ui(ui, |ui| {
+                    for language in Language::ALL {
+                        ui.selectable_value(&mut self.language, language, language.label());
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.theme"));
+            egui::ComboBox::new("theme_combobox", "")
+                .selected_text(self.theme.label())
+                .show_ui(ui, |ui| {
+                    for theme in Theme::ALL {
+                        ui.selectable_value(&mut self.theme, theme, theme.label());
+                    }
+                });
+        });
+
+        ui.checkbox(&mut self.quick_settings, tr("settings.quick_settings"));
+
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.your_name"));
+            ui.add(
+                egui::TextEdit::singleline(&mut self.user_name)
+                    .hint_text(tr("common.you"))
+                    .desired_width(120.0),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.send_with"));
+            egui::ComboBox::new("send_shortcut_combobox", "")
+                .selected_text(self.send_shortcut.label())
+                .show_ui(ui, |ui| {
+                    for shortcut in SendShortcut::ALL {
+                        ui.selectable_value(&mut self.send_shortcut, shortcut, shortcut.label());
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            help(ui, tr("settings.retention_help"), |ui| {
+                ui.label(tr("settings.retention"));
+            });
+            ui.add(
+                egui::DragValue::new(&mut self.retention_days)
+                    .clamp_range(1..=365)
+                    .suffix(tr("settings.days")),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            help(ui, tr("settings.max_image_help"), |ui| {
+                ui.label(tr("settings.max_image"));
+            });
+            ui.add(
+                egui::DragValue::new(&mut self.max_image_dimension)
+                    .clamp_range(256..=8192)
+                    .suffix(" px"),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            help(ui, tr("settings.max_attachment_help"), |ui| {
+                ui.label(tr("settings.max_attachment"));
+            });
+            ui.add(
+                egui::DragValue::new(&mut self.max_attachment_kb)
+                    .clamp_range(1..=16384)
+                    .suffix(" KiB"),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            help(ui, tr("settings.autosave_help"), |ui| {
+                ui.label(tr("settings.autosave"));
+            });
+            ui.add(
+                egui::DragValue::new(&mut self.autosave_secs)
+                    .clamp_range(5..=600)
+                    .suffix(" s"),
+            );
+        });
 
-        ui.label("Reset global settings to defaults");
-        if ui.button("Reset").clicked() {
+        ui.label(tr("settings.reset_label"));
+        if ui.button(tr("common.reset")).clicked() {
             modal.open();
         }
 
-        ui.label("Save and load settings as JSON");
+        ui.label(tr("settings.save_load_label"));
         ui.horizontal(|ui| {
-            if ui.button("Save").clicked() {
-                let settings = self.clone();
+            if ui.button(tr("common.save")).clicked() {
+                let settings = self.without_secrets();
                 tokio::spawn(async move {
                     Self::ask_save_settings(settings).await;
                 });
             }
-            if ui.button("Load").clicked() {
+            if ui.button(tr("common.load")).clicked() {
                 request_info(RequestInfoType::LoadSettings);
             }
         });
+
+        ui.label(tr("settings.backup_label"));
+        ui.horizontal(|ui| {
+            if ui.button(tr("settings.export_backup")).clicked() {
+                request_info(RequestInfoType::ExportBackup);
+            }
+            if ui.button(tr("settings.restore_backup")).clicked() {
+                request_info(RequestInfoType::RestoreBackup);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auth_errors() {
+        assert!(is_auth_error("401 Unauthorized: invalid token"));
+        assert!(!is_auth_error("404 Not Found: model \"llama3\" not found"));
+        assert!(!is_auth_error(
+            "error sending request for url (http://127.0.0.1:4010/api/tags)"
+        ));
+        assert!(!is_auth_error(
+            "the reply mentioned 401 unauthorized requests"
+        ));
+    }
+
+    /// Picking another model while a setting is dragged (edit → switch → release) only
+    /// switches once the drag ended
+    #[test]
+    fn model_switch_waits_for_settings_edit() {
+        let ctx = egui::Context::default();
+        let settings_id = egui::Id::new("model_picker").with("settings");
+        let next_frame = || ctx.run(egui::RawInput::default(), |_| {});
+        let model = |name: &str| SelectedModel {
+            name: name.to_owned(),
+            ..Default::default()
+        };
+        let mut picker = ModelPicker {
+            selected: model("llama3"),
+            ..Default::default()
+        };
+
+        // a slider is dragged and another model is picked meanwhile
+        note_settings_edit(&ctx, settings_id);
+        picker.pending_selection = Some(model("gemma"));
+        next_frame();
+        picker.apply_pending_selection(&ctx, settings_id);
+        assert_eq!(picker.selected.name, "llama3");
+
+        // still dragging
+        note_settings_edit(&ctx, settings_id);
+        picker.settings.temperature = Some(0.3);
+        next_frame();
+        picker.apply_pending_selection(&ctx, settings_id);
+        assert_eq!(picker.selected.name, "llama3");
+
+        // released
+        next_frame();
+        picker.apply_pending_selection(&ctx, settings_id);
+        assert_eq!(picker.selected.name, "gemma");
+        assert!(picker.pending_selection.is_none());
+        assert_eq!(picker.settings.temperature, Some(0.3));
+
+        // edits in another picker's settings don't hold this one back
+        note_settings_edit(&ctx, egui::Id::new("other_picker").with("settings"));
+        picker.pending_selection = Some(model("phi3"));
+        next_frame();
+        picker.apply_pending_selection(&ctx, settings_id);
+        assert_eq!(picker.selected.name, "phi3");
+    }
+
+    #[test]
+    fn exported_settings_have_no_secrets() {
+        let mut settings = Settings::default();
+        settings.openai.api_key = "sk-api-key".to_owned();
+        settings.auth.kind = AuthKind::Bearer;
+        settings.auth.secret = "bearer-token".to_owned();
+        settings.proxy.username = "me".to_owned();
+        settings.proxy.password = "proxy-password".to_owned();
+        settings.proxy.remember_password = true;
+        assert!(serde_json::to_string(&settings)
+            .unwrap()
+            .contains("proxy-password"));
+
+        let json = serde_json::to_string(&settings.without_secrets()).unwrap();
+        for secret in ["sk-api-key", "bearer-token", "proxy-password"] {
+            assert!(!json.contains(secret), "{secret} was exported");
+        }
+
+        let mut restored: Settings = serde_json::from_str(&json).unwrap();
+        restored.keep_secrets(&settings);
+        assert_eq!(restored.openai.api_key, "sk-api-key");
+        assert_eq!(restored.auth.secret, "bearer-token");
+        assert_eq!(restored.proxy.password, "proxy-password");
+    }
+
+    #[test]
+    fn proxy_errors() {
+        let manual = ProxyInUse {
+            description: "http://proxy:3128".to_owned(),
+            manual: true,
+        };
+        let environment = ProxyInUse {
+            description: "http://proxy:3128 (from environment)".to_owned(),
+            manual: false,
+        };
+        let refused = "error sending request: tcp connect error: Connection refused";
+        let tunnel = "error sending request: unsuccessful tunnel";
+        assert!(describe_request_error(refused.to_owned(), Some(&manual)).starts_with("Proxy"));
+        assert_eq!(
+            describe_request_error(refused.to_owned(), Some(&environment)),
+            refused
+        );
+        assert!(describe_request_error(tunnel.to_owned(), Some(&environment)).starts_with("Proxy"));
+        assert_eq!(describe_request_error(tunnel.to_owned(), None), tunnel);
     }
 }